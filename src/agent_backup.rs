@@ -0,0 +1,201 @@
+//! Backups of installed agent files that an install or update is about to
+//! overwrite or delete, so local edits survive a reinstall. Each run that
+//! changes an edited file gets its own `<dest>/.hyprlayer-backup/<time>/`
+//! holding the previous contents at their usual relative paths.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory under a tool's destination that holds its backups.
+pub const BACKUP_DIR: &str = ".hyprlayer-backup";
+
+/// Backups kept per tool; older ones are pruned after each new backup.
+const KEEP_BACKUPS: usize = 5;
+
+/// The contents of installed files as they were before an install touched
+/// them.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+}
+
+/// A backup directory as listed by `list_backups`. `name` is its
+/// timestamp, which is what `restore` takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub files: Vec<PathBuf>,
+}
+
+impl Snapshot {
+    /// Read each of `paths` (relative to `dest`) that exists.
+    pub fn take<P: AsRef<Path>>(dest: &Path, paths: impl IntoIterator<Item = P>) -> Self {
+        let files = paths
+            .into_iter()
+            .filter_map(|rel| {
+                let rel = rel.as_ref();
+                let content = fs::read(dest.join(rel)).ok()?;
+                Some((rel.to_path_buf(), content))
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Copy every snapshotted file whose content has since changed, or
+    /// that was deleted, into a new backup under `dest`, then prune old
+    /// backups. Returns the backup, `None` when nothing changed.
+    pub fn save_changed(&self, dest: &Path) -> Result<Option<BackupInfo>> {
+        let changed: Vec<(&PathBuf, &Vec<u8>)> = self
+            .files
+            .iter()
+            .filter(|(rel, old)| fs::read(dest.join(rel)).ok().as_ref() != Some(*old))
+            .collect();
+        if changed.is_empty() {
+            return Ok(None);
+        }
+
+        let root = dest.join(BACKUP_DIR);
+        let stamp = chrono::Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
+        let mut name = stamp.clone();
+        let mut n = 1;
+        while root.join(&name).exists() {
+            name = format!("{stamp}-{n}");
+            n += 1;
+        }
+        let path = root.join(&name);
+        for (rel, content) in &changed {
+            let target = path.join(rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, content)
+                .with_context(|| format!("Failed to back up {}", rel.display()))?;
+        }
+        prune(&root, KEEP_BACKUPS)?;
+
+        Ok(Some(BackupInfo {
+            name,
+            path,
+            files: changed.into_iter().map(|(rel, _)| rel.clone()).collect(),
+        }))
+    }
+}
+
+/// Backups under `dest`, newest first.
+pub fn list_backups(dest: &Path) -> Result<Vec<BackupInfo>> {
+    let root = dest.join(BACKUP_DIR);
+    let mut backups = Vec::new();
+    for name in backup_names(&root)?.into_iter().rev() {
+        let path = root.join(&name);
+        let files = crate::agents::list_files_at(&path)?;
+        backups.push(BackupInfo { name, path, files });
+    }
+    Ok(backups)
+}
+
+/// Copy the files of backup `name` back into `dest`, returning their
+/// paths. The backup itself is kept.
+pub fn restore(dest: &Path, name: &str) -> Result<Vec<PathBuf>> {
+    let backup = list_backups(dest)?
+        .into_iter()
+        .find(|b| b.name == name)
+        .ok_or_else(|| anyhow::anyhow!("No backup named {name} in {}", dest.display()))?;
+    for rel in &backup.files {
+        let target = dest.join(rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(backup.path.join(rel), &target)
+            .with_context(|| format!("Failed to restore {}", rel.display()))?;
+    }
+    Ok(backup.files)
+}
+
+/// Backup directory names under `root`, oldest first. The timestamps sort
+/// chronologically.
+fn backup_names(root: &Path) -> Result<Vec<String>> {
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut names: Vec<String> = fs::read_dir(root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Delete all but the newest `keep` backups.
+fn prune(root: &Path, keep: usize) -> Result<()> {
+    let names = backup_names(root)?;
+    let excess = names.len().saturating_sub(keep);
+    for name in &names[..excess] {
+        fs::remove_dir_all(root.join(name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_changed_backs_up_only_changed_files() {
+        let dest = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dest.path().join("agents")).unwrap();
+        fs::write(dest.path().join("agents/a.md"), "mine").unwrap();
+        fs::write(dest.path().join("agents/b.md"), "same").unwrap();
+        fs::write(dest.path().join("agents/c.md"), "gone").unwrap();
+
+        let snapshot = Snapshot::take(
+            dest.path(),
+            ["agents/a.md", "agents/b.md", "agents/c.md", "agents/new.md"],
+        );
+        fs::write(dest.path().join("agents/a.md"), "upstream").unwrap();
+        fs::remove_file(dest.path().join("agents/c.md")).unwrap();
+        fs::write(dest.path().join("agents/new.md"), "new").unwrap();
+
+        let backup = snapshot.save_changed(dest.path()).unwrap().unwrap();
+        assert_eq!(
+            backup.files,
+            [PathBuf::from("agents/a.md"), PathBuf::from("agents/c.md")]
+        );
+        assert_eq!(
+            fs::read_to_string(backup.path.join("agents/a.md")).unwrap(),
+            "mine"
+        );
+
+        assert_eq!(restore(dest.path(), &backup.name).unwrap(), backup.files);
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/a.md")).unwrap(),
+            "mine"
+        );
+        assert!(dest.path().join("agents/c.md").is_file());
+
+        let unchanged = Snapshot::take(dest.path(), ["agents/a.md"]);
+        assert!(unchanged.save_changed(dest.path()).unwrap().is_none());
+        assert!(restore(dest.path(), "nope").is_err());
+    }
+
+    #[test]
+    fn save_changed_prunes_old_backups() {
+        let dest = tempfile::tempdir().unwrap();
+        let root = dest.path().join(BACKUP_DIR);
+        for i in 0..KEEP_BACKUPS {
+            fs::create_dir_all(root.join(format!("2000-01-0{i}T00-00-00"))).unwrap();
+        }
+        fs::write(dest.path().join("a.md"), "mine").unwrap();
+        let snapshot = Snapshot::take(dest.path(), ["a.md"]);
+        fs::write(dest.path().join("a.md"), "upstream").unwrap();
+
+        let backup = snapshot.save_changed(dest.path()).unwrap().unwrap();
+        let backups = list_backups(dest.path()).unwrap();
+        assert_eq!(backups.len(), KEEP_BACKUPS);
+        assert_eq!(backups[0].name, backup.name);
+        assert!(!root.join("2000-01-00T00-00-00").exists());
+    }
+}
@@ -1,28 +1,199 @@
 use anyhow::{Context, Result};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::fs;
-use std::io::Write;
+use std::io::IsTerminal;
 use std::path::{MAIN_SEPARATOR_STR as SEP, Path, PathBuf};
-use std::process::Command;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use crate::agent_backup::{self, BACKUP_DIR, BackupInfo, Snapshot};
+use crate::config::{BackendConfig, ThoughtsConfig};
+use crate::http::{self, HttpError};
 
 const REPO: &str = "BrightBlock/hyprlayer-cli";
 const BRANCH: &str = "master";
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// GitHub's hosts for the API, single raw files and source tarballs.
+const GITHUB_API_URL: &str = "https://api.github.com";
+const GITHUB_RAW_URL: &str = "https://raw.githubusercontent.com";
+const GITHUB_CODELOAD_URL: &str = "https://codeload.github.com";
+
+#[cfg(test)]
+thread_local! {
+    /// Serves every GitHub host for tests on this thread, as set by
+    /// `tests::with_github`.
+    static GITHUB_OVERRIDE: std::cell::RefCell<Option<String>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// `path` on the GitHub `host`.
+fn github_url(host: &str, path: &str) -> String {
+    #[cfg(test)]
+    if let Some(base) = GITHUB_OVERRIDE.with_borrow(Clone::clone) {
+        return format!("{base}{path}");
+    }
+    format!("{host}{path}")
+}
+
+/// VS Code builds whose `User` directory holds Copilot's prompts and
+/// agents, in order of preference when more than one is installed.
+const VSCODE_VARIANTS: [&str; 3] = ["Code", "Code - Insiders", "VSCodium"];
+
+/// `ai.copilotDest` from the config, for VS Code installs in non-standard
+/// places. Set once at startup by `load_dest_override` (and by `configure
+/// --dest`), since `AgentTool` has no config of its own.
+static COPILOT_DEST: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Use `dest` instead of VS Code's User directory for Copilot's files.
+pub fn set_copilot_dest(dest: Option<PathBuf>) {
+    *COPILOT_DEST.write().unwrap_or_else(|e| e.into_inner()) = dest;
+}
+
+/// Apply the `ai.copilotDest` of the config at `config_path`, or of the
+/// default config, if it sets one.
+pub fn load_dest_override(config_path: Option<&Path>) {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => match crate::config::get_default_config_path() {
+            Ok(path) => path,
+            Err(_) => return,
+        },
+    };
+    let dest = crate::config::HyprlayerConfig::load(&path)
+        .ok()
+        .and_then(|config| config.ai?.copilot_dest);
+    if let Some(dest) = dest {
+        set_copilot_dest(Some(crate::config::expand_path(&dest)));
+    }
+}
+
+/// The `User` directory of the first VS Code variant installed under
+/// `config_dir`, or stable VS Code's when none is.
+fn vscode_user_dir(config_dir: &Path) -> PathBuf {
+    VSCODE_VARIANTS
+        .iter()
+        .map(|variant| config_dir.join(variant).join("User"))
+        .find(|dir| dir.is_dir())
+        .unwrap_or_else(|| config_dir.join("Code").join("User"))
+}
+
+/// `dir` as shown to users: under `~` (`%APPDATA%` on Windows) when it is,
+/// with a trailing separator.
+fn display_dir(dir: &Path) -> String {
+    #[cfg(target_os = "windows")]
+    let (base, prefix) = (dirs::config_dir(), "%APPDATA%");
+    #[cfg(not(target_os = "windows"))]
+    let (base, prefix) = (dirs::home_dir(), "~");
+    match base.and_then(|base| dir.strip_prefix(base).ok().map(Path::to_path_buf)) {
+        Some(rest) => format!("{prefix}{SEP}{}{SEP}", rest.display()),
+        None => format!("{}{SEP}", dir.display()),
+    }
+}
+
+/// Caps each single-file fetch so a stalled connection on the startup
+/// auto-reinstall path can't hang the user's command indefinitely.
+const FILE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentTool {
     Claude,
     Copilot,
+    #[value(name = "opencode")]
     OpenCode,
+    Codex,
+    Windsurf,
+}
+
+/// Where `AgentTool::install` writes Codex's `AGENTS.md` instructions.
+/// Other tools ignore it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionsTarget<'a> {
+    /// `~/.codex/AGENTS.md`, read in every project.
+    Global,
+    /// `AGENTS.md` at the root of this repository (`--project`).
+    Project(&'a Path),
+    /// Refresh `~/.codex/AGENTS.md` only if an earlier install wrote to it.
+    /// Used by the startup auto-reinstall, which can't know which project
+    /// a `--project` install went to.
+    Existing,
+}
+
+/// Where a bundle is installed, as recorded in its manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMode {
+    /// The tool's own directory, read in every project.
+    Global,
+    /// The tool's directory inside a repository (`--project`), committed
+    /// with it so teammates get the same files.
+    Project,
+}
+
+/// How `AgentTool::install` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// Skip progress output.
+    pub quiet: bool,
+    /// Back up installed files the install changes (see `agent_backup`).
+    pub backup: bool,
+}
+
+/// Where `AgentTool::install` takes the agent files from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentSource {
+    /// GitHub, at `master` or the pinned ref.
+    GitHub,
+    /// A local checkout holding the per-tool directories (`claude/`,
+    /// `opencode/`, ...), for machines that can't reach GitHub.
+    Dir(PathBuf),
+    /// A `.tar.gz` of such a checkout, e.g. GitHub's source tarball.
+    Archive(PathBuf),
+}
+
+impl AgentSource {
+    /// The source for a `--source` value: GitHub without one, else the
+    /// directory or archive at `path`.
+    pub fn from_arg(path: Option<&str>) -> Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::GitHub);
+        };
+        let path = crate::config::expand_path(path);
+        if path.is_dir() {
+            Ok(Self::Dir(path))
+        } else if path.is_file() {
+            Ok(Self::Archive(path))
+        } else {
+            Err(anyhow::anyhow!(
+                "Agent files source not found at {}",
+                path.display()
+            ))
+        }
+    }
 }
 
+/// Markers around the hyprlayer section of an `AGENTS.md`, so installs
+/// replace only that section and leave the user's own instructions alone.
+const INSTRUCTIONS_START: &str = "<!-- hyprlayer:start -->";
+const INSTRUCTIONS_END: &str = "<!-- hyprlayer:end -->";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum OpenCodeProvider {
     GithubCopilot,
     Anthropic,
     Abacus,
+    /// A provider OpenCode knows under `prefix`, such as an
+    /// OpenAI-compatible gateway, with models entered at configure time.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        prefix: String,
+        sonnet_model: String,
+        opus_model: String,
+    },
 }
 
 impl fmt::Display for OpenCodeProvider {
@@ -31,12 +202,14 @@ impl fmt::Display for OpenCodeProvider {
             Self::GithubCopilot => write!(f, "GitHub Copilot"),
             Self::Anthropic => write!(f, "Anthropic"),
             Self::Abacus => write!(f, "Abacus"),
+            Self::Custom { prefix, .. } => write!(f, "custom ({prefix})"),
         }
     }
 }
 
 impl OpenCodeProvider {
-    /// All available providers for selection prompts
+    /// The built-in providers for selection prompts; `Custom` is offered
+    /// separately.
     pub const ALL: &[OpenCodeProvider] = &[
         OpenCodeProvider::GithubCopilot,
         OpenCodeProvider::Anthropic,
@@ -50,6 +223,7 @@ impl OpenCodeProvider {
             Self::GithubCopilot => "github-copilot/claude-sonnet-4.5",
             Self::Anthropic => "anthropic/claude-sonnet-4-5",
             Self::Abacus => "abacus/claude-sonnet-4-6",
+            Self::Custom { sonnet_model, .. } => sonnet_model,
         }
     }
 
@@ -60,6 +234,7 @@ impl OpenCodeProvider {
             Self::GithubCopilot => "github-copilot/claude-opus-4.5",
             Self::Anthropic => "anthropic/claude-opus-4-5",
             Self::Abacus => "abacus/claude-opus-4-6",
+            Self::Custom { opus_model, .. } => opus_model,
         }
     }
 
@@ -67,12 +242,14 @@ impl OpenCodeProvider {
     /// Abacus routes to its highest-reasoning codex variant for a true
     /// cross-model second opinion; GitHub Copilot uses gpt-5-codex (the
     /// codex variant exposed through Copilot Chat); Anthropic stays on
-    /// claude-opus-4-5 because the Anthropic API is Claude-only.
+    /// claude-opus-4-5 because the Anthropic API is Claude-only. A custom
+    /// provider reviews with its opus model.
     pub fn default_adversarial_model(&self) -> &str {
         match self {
             Self::GithubCopilot => "github-copilot/gpt-5-codex",
             Self::Anthropic => "anthropic/claude-opus-4-5",
             Self::Abacus => "abacus/gpt-5.3-codex-xhigh",
+            Self::Custom { opus_model, .. } => opus_model,
         }
     }
 
@@ -82,7 +259,171 @@ impl OpenCodeProvider {
             Self::GithubCopilot => "github-copilot",
             Self::Anthropic => "anthropic",
             Self::Abacus => "abacus",
+            Self::Custom { prefix, .. } => prefix,
+        }
+    }
+}
+
+/// How a file in the destination directory compares with the remote bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    /// Shipped by the remote bundle but missing locally.
+    Added,
+    /// Present locally under a bundle directory but no longer shipped.
+    /// Reinstalling leaves these files in place.
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// How much of a tool's bundle is present in its destination directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallState {
+    NotInstalled,
+    /// `missing` of the `total` expected files are gone, e.g. after an
+    /// interrupted install or a hand-deleted file.
+    Partial {
+        missing: usize,
+        total: usize,
+    },
+    Installed,
+}
+
+impl InstallState {
+    /// The `installState` value in `ai status --json`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NotInstalled => "notInstalled",
+            Self::Partial { .. } => "partial",
+            Self::Installed => "installed",
+        }
+    }
+}
+
+/// One file from `AgentTool::diff_with_remote`. Contents are `None` on the
+/// side where the file is missing, or on both sides for non-UTF-8 files.
+#[derive(Debug)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub status: DiffStatus,
+    pub local_content: Option<String>,
+    pub remote_content: Option<String>,
+}
+
+/// The ref `AgentTool::resolve_ref` picked for a download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedRef {
+    /// The branch or tag, as shown to the user and recorded in the manifest.
+    pub name: String,
+    /// The commit to download, when the commits API could resolve it. This
+    /// is what install records as the installed SHA.
+    pub sha: Option<String>,
+    /// The release tag was missing, so `name` is `master` instead.
+    pub fell_back: bool,
+}
+
+impl ResolvedRef {
+    /// What to ask GitHub for: the resolved commit if known, so every
+    /// request of one install sees the same tree, else the ref by name.
+    pub fn git_ref(&self) -> &str {
+        self.sha.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// What `AgentTool::update` did, by file.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct UpdateSummary {
+    pub updated: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+impl fmt::Display for UpdateSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} updated, {} added, {} unchanged",
+            self.updated, self.added, self.unchanged
+        )?;
+        if self.removed > 0 {
+            write!(f, ", {} removed", self.removed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Written to each tool's destination directory: every file hyprlayer
+/// installed there, relative to it, with the GitHub blob SHA it was
+/// downloaded at. `update` compares these with the remote listing, and
+/// `uninstall` removes exactly these files.
+const MANIFEST_FILE: &str = ".hyprlayer-manifest.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    files: BTreeMap<PathBuf, String>,
+    /// The branch or tag the files were downloaded from; absent for
+    /// installs from a local source.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    git_ref: Option<String>,
+    /// The tool's `repo_dir` name. Absent in manifests from before it was
+    /// recorded, as is `installed_at`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    /// When the manifest was last saved, in unix seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    installed_at: Option<i64>,
+    /// Absent in manifests from before project installs, which were all
+    /// global.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mode: Option<InstallMode>,
+}
+
+impl Manifest {
+    fn for_files(files: &[RemoteFile], git_ref: &str) -> Self {
+        Self {
+            files: files
+                .iter()
+                .map(|f| (f.path.clone(), f.sha.clone()))
+                .collect(),
+            git_ref: Some(git_ref.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Drop the `failed` downloads, so the next `update` fetches them.
+    fn without(mut self, failed: &[FailedDownload]) -> Self {
+        for failure in failed {
+            self.files.remove(&failure.path);
+        }
+        self
+    }
+
+    /// The manifest in `dest`. Installs from before manifests existed, or
+    /// an unreadable one, give an empty manifest so every file is fetched.
+    fn load(dest: &Path) -> Self {
+        fs::read_to_string(dest.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the manifest for `tool` into `dest`, stamped with the current
+    /// time. Written to a temporary file and renamed into place, so two
+    /// installs racing can't leave a torn manifest; the last one wins.
+    fn save(mut self, tool: &AgentTool, dest: &Path) -> Result<()> {
+        self.tool = Some(tool.repo_dir().to_string());
+        self.installed_at = Some(chrono::Utc::now().timestamp());
+        self.mode.get_or_insert(InstallMode::Global);
+        let path = dest.join(MANIFEST_FILE);
+        let tmp = dest.join(format!("{MANIFEST_FILE}.tmp.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string_pretty(&self)?)?;
+        if let Err(e) = fs::rename(&tmp, &path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e).with_context(|| format!("Failed to write {}", path.display()));
         }
+        Ok(())
     }
 }
 
@@ -92,13 +433,21 @@ impl fmt::Display for AgentTool {
             Self::Claude => write!(f, "Claude Code"),
             Self::Copilot => write!(f, "GitHub Copilot"),
             Self::OpenCode => write!(f, "OpenCode"),
+            Self::Codex => write!(f, "OpenAI Codex"),
+            Self::Windsurf => write!(f, "Windsurf"),
         }
     }
 }
 
 impl AgentTool {
     /// All available variants, for use in selection prompts
-    pub const ALL: &[AgentTool] = &[AgentTool::Claude, AgentTool::Copilot, AgentTool::OpenCode];
+    pub const ALL: &[AgentTool] = &[
+        AgentTool::Claude,
+        AgentTool::Copilot,
+        AgentTool::OpenCode,
+        AgentTool::Codex,
+        AgentTool::Windsurf,
+    ];
 
     /// The directory name in the repo that contains this tool's agent files
     pub(crate) fn repo_dir(&self) -> &str {
@@ -106,6 +455,8 @@ impl AgentTool {
             Self::Claude => "claude",
             Self::Copilot => "copilot",
             Self::OpenCode => "opencode",
+            Self::Codex => "codex",
+            Self::Windsurf => "windsurf",
         }
     }
 
@@ -117,36 +468,84 @@ impl AgentTool {
                 Ok(home.join(".claude"))
             }
             Self::Copilot => {
+                if let Some(dest) = COPILOT_DEST
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .clone()
+                {
+                    return Ok(dest);
+                }
+                // `~/.config` on Linux, `~/Library/Application Support` on
+                // macOS and `%APPDATA%` on Windows.
                 let config = dirs::config_dir()
                     .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
-                Ok(config.join("Code").join("User"))
+                Ok(vscode_user_dir(&config))
             }
             Self::OpenCode => {
                 let home = dirs::home_dir()
                     .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
                 Ok(home.join(".config").join("opencode"))
             }
+            Self::Codex => {
+                let home = dirs::home_dir()
+                    .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+                Ok(home.join(".codex"))
+            }
+            // Global rules live in `memories/`, workflows in
+            // `global_workflows/`, both under `~/.codeium/windsurf`.
+            Self::Windsurf => {
+                let home = dirs::home_dir()
+                    .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+                Ok(home.join(".codeium").join("windsurf"))
+            }
+        }
+    }
+
+    /// This tool's directory in the repository at `root`, for `--project`
+    /// installs. `None` for tools that only read their global directory;
+    /// Codex's project install is just its `AGENTS.md` (see
+    /// `InstructionsTarget::Project`).
+    pub fn project_dir(&self, root: &Path) -> Option<PathBuf> {
+        match self {
+            Self::Claude => Some(root.join(".claude")),
+            Self::OpenCode => Some(root.join(".opencode")),
+            Self::Copilot | Self::Codex | Self::Windsurf => None,
         }
     }
 
+    /// Whether `--project` changes where this tool is installed.
+    pub fn supports_project(&self) -> bool {
+        *self == Self::Codex || self.project_dir(Path::new("")).is_some()
+    }
+
+    /// The project install in the current repository, with its manifest,
+    /// if there is one.
+    fn project_install(&self) -> Option<(PathBuf, Manifest)> {
+        let root = crate::config::get_current_repo_path().ok()?;
+        let dest = self.project_dir(&root)?;
+        let manifest = Manifest::load(&dest);
+        (manifest.mode == Some(InstallMode::Project)).then_some((dest, manifest))
+    }
+
     /// Display the destination directory for user-facing messages
     pub fn dest_display(&self) -> String {
         match self {
             Self::Claude => format!("~{SEP}.claude{SEP}"),
-            #[cfg(target_os = "linux")]
-            Self::Copilot => format!("~{SEP}.config{SEP}Code{SEP}User{SEP}"),
-            #[cfg(target_os = "macos")]
-            Self::Copilot => {
-                format!("~{SEP}Library{SEP}Application Support{SEP}Code{SEP}User{SEP}")
-            }
-            #[cfg(target_os = "windows")]
-            Self::Copilot => format!("%APPDATA%{SEP}Code{SEP}User{SEP}"),
+            // Depends on the VS Code variant and any `--dest` override.
+            Self::Copilot => match self.dest_dir() {
+                Ok(dest) => display_dir(&dest),
+                Err(_) => format!("Code{SEP}User{SEP}"),
+            },
             Self::OpenCode => format!("~{SEP}.config{SEP}opencode{SEP}"),
+            Self::Codex => format!("~{SEP}.codex{SEP}"),
+            #[cfg(not(target_os = "windows"))]
+            Self::Windsurf => format!("~{SEP}.codeium{SEP}windsurf{SEP}"),
+            #[cfg(target_os = "windows")]
+            Self::Windsurf => format!("%USERPROFILE%{SEP}.codeium{SEP}windsurf{SEP}"),
         }
     }
 
-    /// Check if agent files appear to be installed already.
-    /// Returns true if the destination directory contains the expected subdirectories.
+    /// Whether every expected agent file is installed.
     pub fn is_installed(&self) -> bool {
         let Ok(dest) = self.dest_dir() else {
             return false;
@@ -154,6 +553,14 @@ impl AgentTool {
         self.is_installed_at(&dest)
     }
 
+    /// How much of the bundle is installed; see `install_state_at`.
+    pub fn install_state(&self) -> InstallState {
+        let Ok(dest) = self.dest_dir() else {
+            return InstallState::NotInstalled;
+        };
+        self.install_state_at(&dest)
+    }
+
     /// Looser variant: does any prior install exist at `dest_dir`, even if
     /// it predates the current sentinel-file set? Used by the auto-reinstall
     /// gate so that exactly the stale installs that need refreshing get
@@ -170,52 +577,207 @@ impl AgentTool {
         // Per-tool structural directories that have been part of every
         // shipped bundle. If both exist, *something* was installed here
         // by a previous `hyprlayer ai configure`.
-        let (a, b) = match self {
-            Self::Claude => ("skills", "agents"),
-            Self::OpenCode => ("commands", "agents"),
-            Self::Copilot => ("prompts", "agents"),
+        let dirs: &[&str] = match self {
+            Self::Claude => &["skills", "agents"],
+            Self::OpenCode => &["commands", "agents"],
+            Self::Copilot => &["prompts", "agents"],
+            // Codex has no sub-agents; its instructions may live elsewhere.
+            Self::Codex => &["prompts"],
+            Self::Windsurf => &["global_workflows", "memories"],
         };
-        dest.join(a).is_dir() && dest.join(b).is_dir()
+        dirs.iter().all(|dir| dest.join(dir).is_dir())
     }
 
-    /// Test-friendly variant of `is_installed` that takes an explicit destination path.
-    ///
-    /// Checks for sentinel files unique to the current bundle of
-    /// commands/skills/agents. An older install with the right top-level
-    /// directories but missing newly added files reports not-installed, so
-    /// `configure --no-force` re-runs and provisions the new bundle. Bump
-    /// these whenever we ship a top-level file existing users should pick up.
     fn is_installed_at(&self, dest: &Path) -> bool {
+        self.install_state_at(dest) == InstallState::Installed
+    }
+
+    /// Compare `dest` with the files the install manifest lists plus this
+    /// tool's sentinel files. Only files we ship count, so directories
+    /// like Copilot's `prompts/` that other software creates don't make a
+    /// tool look installed. None present is not installed, some is partial.
+    fn install_state_at(&self, dest: &Path) -> InstallState {
+        let mut expected: BTreeSet<PathBuf> = Manifest::load(dest).files.into_keys().collect();
+        expected.extend(self.sentinel_files().iter().map(PathBuf::from));
+        let total = expected.len();
+        let missing = expected
+            .iter()
+            .filter(|path| !dest.join(path).is_file())
+            .count();
+        match missing {
+            0 => InstallState::Installed,
+            m if m == total => InstallState::NotInstalled,
+            missing => InstallState::Partial { missing, total },
+        }
+    }
+
+    /// The files the install manifest lists that are present in `dest`.
+    /// Installs from before manifests fall back to every file under the
+    /// sentinel files' top-level directories, so the tool's own settings
+    /// and session history never show up.
+    fn installed_files_at(&self, dest: &Path) -> Result<Vec<PathBuf>> {
+        let manifest = Manifest::load(dest);
+        if !manifest.files.is_empty() {
+            return Ok(manifest
+                .files
+                .into_keys()
+                .filter(|path| dest.join(path).is_file())
+                .collect());
+        }
+        let bundle_dirs: BTreeSet<&str> = self
+            .sentinel_files()
+            .iter()
+            .filter_map(|file| file.split('/').next())
+            .collect();
+        let mut files = Vec::new();
+        for dir in bundle_dirs {
+            files.extend(
+                list_files_at(&dest.join(dir))?
+                    .into_iter()
+                    .map(|path| Path::new(dir).join(path)),
+            );
+        }
+        Ok(files)
+    }
+
+    /// Files unique to the current bundle of commands/skills/agents. An
+    /// older install with the right top-level directories but missing newly
+    /// added files isn't fully installed, so `configure --no-force` re-runs
+    /// and provisions the new bundle. Bump these whenever we ship a
+    /// top-level file existing users should pick up.
+    fn sentinel_files(&self) -> [&'static str; 2] {
         match self {
-            Self::Claude => {
-                dest.join("skills/code_review/SKILL.md").is_file()
-                    && dest.join("agents/codebase-locator.md").is_file()
-            }
-            Self::OpenCode => {
-                dest.join("commands/code_review.md").is_file()
-                    && dest.join("agents/codebase-locator.md").is_file()
-            }
-            Self::Copilot => {
-                dest.join("prompts/code_review.prompt.md").is_file()
-                    && dest.join("agents/codebase-locator.agent.md").is_file()
-            }
+            Self::Claude => ["skills/code_review/SKILL.md", "agents/codebase-locator.md"],
+            Self::OpenCode => ["commands/code_review.md", "agents/codebase-locator.md"],
+            Self::Copilot => [
+                "prompts/code_review.prompt.md",
+                "agents/codebase-locator.agent.md",
+            ],
+            Self::Codex => ["prompts/code_review.md", "prompts/research_codebase.md"],
+            Self::Windsurf => [
+                "global_workflows/code_review.md",
+                "global_workflows/research_codebase.md",
+            ],
+        }
+    }
+
+    /// Where Codex's hyprlayer instructions are installed: `"global"`,
+    /// `"project"` (in `project_root`), `"both"`, or `None`. Always `None`
+    /// for other tools.
+    pub fn instructions_mode(&self, project_root: Option<&Path>) -> Option<&'static str> {
+        if *self != Self::Codex {
+            return None;
+        }
+        let global = self
+            .dest_dir()
+            .is_ok_and(|dest| has_instructions(&dest.join("AGENTS.md")));
+        instructions_mode_at(global, project_root)
+    }
+
+    /// The files hyprlayer installed in the destination directory, relative
+    /// to it and sorted (see `installed_files_at`). A missing destination
+    /// yields an empty list.
+    pub fn list_installed_files(&self) -> Result<Vec<PathBuf>> {
+        self.installed_files_at(&self.dest_dir()?)
+    }
+
+    /// Backups made by earlier installs of this tool, newest first.
+    pub fn backups(&self) -> Result<Vec<BackupInfo>> {
+        agent_backup::list_backups(&self.dest_dir()?)
+    }
+
+    /// Put the files of backup `name` back in place, returning their paths.
+    pub fn restore_backup(&self, name: &str) -> Result<Vec<PathBuf>> {
+        agent_backup::restore(&self.dest_dir()?, name)
+    }
+
+    /// Compare the installed files with the current remote bundle, as
+    /// `install` would write it (OpenCode placeholders filled in from
+    /// `placeholders`, files taken from `pin_ref` or the release tag).
+    /// Sorted by path.
+    pub fn diff_with_remote(
+        &self,
+        placeholders: Option<&PlaceholderSet>,
+        pin_ref: Option<&str>,
+    ) -> Result<Vec<FileDiff>> {
+        let dest = self.dest_dir()?;
+        let resolved = self.resolve_ref(pin_ref);
+        let git_ref = resolved.git_ref();
+
+        let mut listing = Vec::new();
+        list_remote_files(self.repo_dir(), git_ref, Path::new(""), &mut listing)?;
+        // Codex's AGENTS.md is merged into the user's own, so it never
+        // matches the bundle byte for byte.
+        if *self == Self::Codex {
+            listing.retain(|file| file.path != Path::new("AGENTS.md"));
         }
+        let remote = listing
+            .into_iter()
+            .map(|RemoteFile { path, url, .. }| {
+                let mut body = http::get_bytes(&url, FILE_TIMEOUT)?;
+                if let Some(placeholders) = placeholders
+                    && let Ok(text) = std::str::from_utf8(&body)
+                    && let Some(updated) = self.fill_template(&path, text, placeholders)?
+                {
+                    body = updated.into_bytes();
+                }
+                Ok((path, body))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        compare_with_remote(&dest, remote)
     }
 
     /// Print status information for this agent tool.
     /// OpenCode includes provider and model details from config.
     pub fn print_status(&self, config: &crate::config::AiConfig) {
+        use chrono::TimeZone;
         use colored::Colorize;
 
         println!("  AI Tool: {}", self.to_string().cyan());
 
-        let status = if self.is_installed() {
-            "installed".green()
-        } else {
-            "not installed".red()
+        let status = match self.install_state() {
+            InstallState::Installed => "installed".green(),
+            InstallState::Partial { missing, total } => {
+                format!("partially installed ({missing} of {total} files missing)").yellow()
+            }
+            InstallState::NotInstalled => "not installed".red(),
         };
         println!("  Status: {}", status);
+        if matches!(self.install_state(), InstallState::Partial { .. }) {
+            println!(
+                "  {}",
+                "Run 'hyprlayer ai update' to fetch the missing files.".bright_black()
+            );
+        }
         println!("  Location: {}", self.dest_display().cyan());
+        if let Some(git_ref) = self.installed_ref() {
+            println!("  Ref: {}", git_ref.cyan());
+        }
+        let manifest = self.manifest();
+        if !manifest.files.is_empty() {
+            let when = manifest
+                .installed_at
+                .and_then(|t| chrono::Local.timestamp_opt(t, 0).single())
+                .map(|t| format!(" on {}", t.format("%Y-%m-%d %H:%M")))
+                .unwrap_or_default();
+            println!("  Installed: {} files{}", manifest.files.len(), when);
+        }
+        if let Some((dest, manifest)) = self.project_install() {
+            let state = self.install_state_at(&dest);
+            println!(
+                "  Project: {} ({}, {} files)",
+                dest.display().to_string().cyan(),
+                state.as_str(),
+                manifest.files.len()
+            );
+        }
+        if let Some(pin) = &config.agent_pin_ref {
+            println!("  Pinned to: {}", pin.cyan());
+            if let Some(warning) = crate::version::pin_warning(pin) {
+                println!("  {}", warning.yellow());
+            }
+        }
 
         match self {
             Self::OpenCode => {
@@ -230,187 +792,1162 @@ impl AgentTool {
                         .unwrap_or_else(|| "not set".to_string())
                         .cyan()
                 );
+                let provider = config.opencode_provider.as_ref();
+                let model = |configured: &Option<String>,
+                             default: fn(&OpenCodeProvider) -> &str| {
+                    match (configured, provider) {
+                        (Some(model), _) => model.clone(),
+                        (None, Some(provider)) => format!("{} (default)", default(provider)),
+                        (None, None) => "not set".to_string(),
+                    }
+                };
                 println!(
                     "    Sonnet Model: {}",
-                    config
-                        .opencode_sonnet_model
-                        .as_deref()
-                        .unwrap_or("not set")
-                        .cyan()
+                    model(
+                        &config.opencode_sonnet_model,
+                        OpenCodeProvider::default_sonnet_model
+                    )
+                    .cyan()
                 );
                 println!(
                     "    Opus Model: {}",
-                    config
-                        .opencode_opus_model
-                        .as_deref()
-                        .unwrap_or("not set")
-                        .cyan()
+                    model(
+                        &config.opencode_opus_model,
+                        OpenCodeProvider::default_opus_model
+                    )
+                    .cyan()
+                );
+            }
+            Self::Codex => {
+                let project_root = crate::config::get_current_repo_path().ok();
+                let mode = self.instructions_mode(project_root.as_deref());
+                println!(
+                    "  Instructions: {}",
+                    match mode {
+                        Some("global") => format!("global (~{SEP}.codex{SEP}AGENTS.md)").cyan(),
+                        Some("project") => "project (AGENTS.md in this repository)".cyan(),
+                        Some(_) => "global and project".cyan(),
+                        None => "not installed".red(),
+                    }
                 );
             }
-            Self::Claude | Self::Copilot => {}
+            Self::Claude | Self::Copilot | Self::Windsurf => {}
         }
     }
 
     /// Return status as JSON-serializable struct for --json output.
     pub fn status_json(&self, config: &crate::config::AiConfig) -> serde_json::Value {
-        match self {
+        let mut value = match self {
             Self::OpenCode => serde_json::json!({
                 "agentTool": self.to_string(),
                 "installed": self.is_installed(),
+                "installState": self.install_state().as_str(),
                 "location": self.dest_display(),
+                "installedRef": self.installed_ref(),
                 "opencodeProvider": config.opencode_provider.as_ref().map(|p| p.to_string()),
                 "opencodeSonnetModel": config.opencode_sonnet_model.clone(),
                 "opencodeOpusModel": config.opencode_opus_model.clone(),
+                "agentPinRef": config.agent_pin_ref.clone(),
+            }),
+            Self::Codex => serde_json::json!({
+                "agentTool": self.to_string(),
+                "installed": self.is_installed(),
+                "installState": self.install_state().as_str(),
+                "location": self.dest_display(),
+                "installedRef": self.installed_ref(),
+                "instructions": self.instructions_mode(
+                    crate::config::get_current_repo_path().ok().as_deref()
+                ),
+                "agentPinRef": config.agent_pin_ref.clone(),
             }),
-            Self::Claude | Self::Copilot => serde_json::json!({
+            Self::Claude | Self::Copilot | Self::Windsurf => serde_json::json!({
                 "agentTool": self.to_string(),
                 "installed": self.is_installed(),
+                "installState": self.install_state().as_str(),
                 "location": self.dest_display(),
+                "installedRef": self.installed_ref(),
+                "agentPinRef": config.agent_pin_ref.clone(),
             }),
+        };
+        let manifest = self.manifest();
+        if let Some(map) = value.as_object_mut() {
+            map.insert(
+                "installedFileCount".to_string(),
+                manifest.files.len().into(),
+            );
+            map.insert("installedAt".to_string(), manifest.installed_at.into());
+            let project = self.project_install().map(|(dest, manifest)| {
+                serde_json::json!({
+                    "location": dest.display().to_string(),
+                    "installState": self.install_state_at(&dest).as_str(),
+                    "installedRef": manifest.git_ref,
+                    "installedFileCount": manifest.files.len(),
+                    "installedAt": manifest.installed_at,
+                })
+            });
+            map.insert("project".to_string(), project.into());
         }
+        value
     }
 
-    /// Download agent files from GitHub and install to the destination.
+    /// Install the agent files from `source` to the destination.
     ///
-    /// Returns `Some(sha)` when we successfully captured the per-tool
-    /// `master` commit SHA *before* the download (the next 24h auto-check
-    /// uses this as the freshness baseline). Returns `None` when the
-    /// commits API was unreachable but the downloads succeeded — the
-    /// install is still good, but we have no SHA to cache, so the next
+    /// From GitHub, files come from the release tag matching this CLI
+    /// (`master` when that tag doesn't exist). Returns `Some(sha)` when we
+    /// successfully captured the per-tool commit SHA *before* the download (the next 24h
+    /// auto-check uses this as the freshness baseline). Returns `None`
+    /// when the commits API was unreachable but the downloads succeeded —
+    /// the install is still good, but we have no SHA to cache, so the next
     /// auto-check will treat the bundle as stale and refresh again. We
     /// don't fail the whole install on commits-API rate-limits because
     /// `hyprlayer ai configure` / `ai reinstall` must continue to work
-    /// even when only the commits endpoint is throttled.
+    /// even when only the commits endpoint is throttled. A local source
+    /// has no commit to record and also returns `None`.
+    ///
+    /// With `pin_ref` set, GitHub files come from that ref instead and the
+    /// ref itself is returned as the installed SHA. `instructions` picks
+    /// where Codex's `AGENTS.md` goes. With `options.backup`, installed
+    /// files whose content the install changes are backed up first.
     pub fn install(
         &self,
-        opencode_provider: Option<&OpenCodeProvider>,
-        quiet: bool,
+        source: &AgentSource,
+        placeholders: Option<&PlaceholderSet>,
+        pin_ref: Option<&str>,
+        instructions: InstructionsTarget,
+        options: InstallOptions,
+    ) -> Result<Option<String>> {
+        self.install_at(
+            &self.dest_dir()?,
+            source,
+            placeholders,
+            pin_ref,
+            instructions,
+            options,
+        )
+    }
+
+    /// `install`, but into this tool's directory in the repository at
+    /// `root` (see `project_dir`), with the manifest marking it a project
+    /// install.
+    pub fn install_project(
+        &self,
+        root: &Path,
+        source: &AgentSource,
+        placeholders: Option<&PlaceholderSet>,
+        pin_ref: Option<&str>,
+        options: InstallOptions,
     ) -> Result<Option<String>> {
+        let dest = self
+            .project_dir(root)
+            .ok_or_else(|| anyhow::anyhow!("{self} has no project directory"))?;
+        let sha = self.install_at(
+            &dest,
+            source,
+            placeholders,
+            pin_ref,
+            InstructionsTarget::Global,
+            options,
+        )?;
+        let mut manifest = Manifest::load(&dest);
+        manifest.mode = Some(InstallMode::Project);
+        manifest.save(self, &dest)?;
+        Ok(sha)
+    }
+
+    /// Test-friendly body of `install`.
+    fn install_at(
+        &self,
+        dest: &Path,
+        source: &AgentSource,
+        placeholders: Option<&PlaceholderSet>,
+        pin_ref: Option<&str>,
+        instructions: InstructionsTarget,
+        options: InstallOptions,
+    ) -> Result<Option<String>> {
+        let quiet = options.quiet;
+        let checkout = match source {
+            AgentSource::GitHub => None,
+            AgentSource::Dir(dir) => Some(dir.clone()),
+            AgentSource::Archive(archive) => {
+                let file = fs::File::open(archive)
+                    .with_context(|| format!("Failed to open {}", archive.display()))?;
+                let extracted = extract_tarball(file, None)
+                    .with_context(|| format!("Failed to extract {}", archive.display()))?;
+                return self.install_at(
+                    dest,
+                    &AgentSource::Dir(extracted.path().to_path_buf()),
+                    placeholders,
+                    pin_ref,
+                    instructions,
+                    options,
+                );
+            }
+        };
+        fs::create_dir_all(dest)?;
+
+        let Some(checkout) = checkout else {
+            // Recording a post-download SHA could mask `master`-advances
+            // that happen mid-install — next-day's check would then compare
+            // against an at-or-newer cache and skip the necessary re-sync.
+            let resolved = self.resolve_ref(pin_ref);
+            let git_ref = resolved.git_ref();
+
+            if !quiet {
+                println!("Downloading {} agent files from {}...", self, resolved.name);
+                print_ref(&resolved, pin_ref);
+            }
+            // One tarball request instead of a Contents API listing per
+            // directory and a curl per file.
+            match fetch_tarball(git_ref)
+                .and_then(|body| extract_tarball(&body[..], Some(self.repo_dir())))
+            {
+                Ok(extracted) => {
+                    self.install_from_checkout(
+                        dest,
+                        extracted.path(),
+                        Some(&resolved.name),
+                        placeholders,
+                        instructions,
+                        options,
+                    )?;
+                }
+                Err(e) => {
+                    if !quiet {
+                        use colored::Colorize;
+                        println!(
+                            "  {}",
+                            format!("Tarball download failed ({e:#}); downloading file by file")
+                                .yellow()
+                        );
+                    }
+                    let files = self.list_remote_files(git_ref)?;
+                    let snapshot = options
+                        .backup
+                        .then(|| Snapshot::take(dest, files.iter().map(|f| &f.path)));
+                    let progress = FileProgress::new(files.len(), quiet);
+                    let failed = download_files(&files, dest, &progress);
+                    Manifest::for_files(&files, &resolved.name)
+                        .without(&failed)
+                        .save(self, dest)?;
+                    progress.finish(&format!("Downloaded {} files", files.len() - failed.len()));
+
+                    let agents_md = || self.fetch_agents_md(git_ref);
+                    self.finish_install(dest, agents_md, placeholders, instructions, quiet)?;
+                    save_backup(snapshot, dest, quiet)?;
+                    if !failed.is_empty() {
+                        return Err(failed_downloads_error(self, files.len(), &failed));
+                    }
+                }
+            }
+            return Ok(resolved.sha);
+        };
+
+        if !quiet {
+            println!(
+                "Copying {} agent files from {}...",
+                self,
+                checkout.display()
+            );
+            if let Some(pin) = pin_ref {
+                println!("  Ignoring pin {pin}: files come from the local source");
+            }
+        }
+        self.install_from_checkout(dest, &checkout, None, placeholders, instructions, options)?;
+        Ok(None)
+    }
+
+    /// Copy this tool's files from a checkout of the repository (or an
+    /// unpacked tarball of one) to `dest`, then `finish_install`.
+    /// `git_ref` is the ref a downloaded tarball came from, recorded in the
+    /// manifest; `None` for a local source.
+    fn install_from_checkout(
+        &self,
+        dest: &Path,
+        checkout: &Path,
+        git_ref: Option<&str>,
+        placeholders: Option<&PlaceholderSet>,
+        instructions: InstructionsTarget,
+        options: InstallOptions,
+    ) -> Result<()> {
+        let quiet = options.quiet;
+        let tool_dir = locate_tool_dir(checkout, self.repo_dir())?;
+        let files = self.list_local_files(&tool_dir)?;
+        let snapshot = options.backup.then(|| Snapshot::take(dest, &files));
+        let mut manifest = Manifest {
+            git_ref: git_ref.map(str::to_string),
+            ..Manifest::default()
+        };
+        let progress = FileProgress::new(files.len(), quiet);
+        for rel in &files {
+            progress.start(rel);
+            let source = tool_dir.join(rel);
+            let target = dest.join(rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &target)
+                .with_context(|| format!("Failed to copy {}", rel.display()))?;
+            // The same blob SHA GitHub lists, so a later `ai update` only
+            // fetches what changed since.
+            let sha = git2::Oid::hash_file(git2::ObjectType::Blob, &source)?;
+            manifest.files.insert(rel.clone(), sha.to_string());
+            progress.done();
+        }
+        manifest.save(self, dest)?;
+        let verb = if git_ref.is_some() {
+            "Downloaded"
+        } else {
+            "Copied"
+        };
+        progress.finish(&format!("{} {} files", verb, files.len()));
+
+        let agents_md = || {
+            let path = tool_dir.join("AGENTS.md");
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        self.finish_install(dest, agents_md, placeholders, instructions, quiet)?;
+        save_backup(snapshot, dest, quiet)
+    }
+
+    /// Like `install`, but only download files whose GitHub blob SHA
+    /// differs from the one recorded at the last install (or that are
+    /// missing locally), and delete installed files the bundle no longer
+    /// ships. Files hyprlayer didn't install are never touched. With
+    /// `backup`, changed files are backed up as in `install`.
+    pub fn update(
+        &self,
+        placeholders: Option<&PlaceholderSet>,
+        pin_ref: Option<&str>,
+        instructions: InstructionsTarget,
+        backup: bool,
+    ) -> Result<(Option<String>, UpdateSummary)> {
         let dest = self.dest_dir()?;
         fs::create_dir_all(&dest)?;
 
-        // Recording a post-download SHA could mask `master`-advances that
-        // happen mid-install — next-day's check would then compare against
-        // an at-or-newer cache and skip the necessary re-sync.
-        let sha = fetch_repo_dir_sha(self.repo_dir()).ok();
-        let git_ref = sha.as_deref().unwrap_or(BRANCH);
+        // Resolved before listing for the same reason as in `install`.
+        let resolved = self.resolve_ref(pin_ref);
+        let git_ref = resolved.git_ref();
+
+        println!("Updating {} agent files from {}...", self, resolved.name);
+        print_ref(&resolved, pin_ref);
+        let files = self.list_remote_files(git_ref)?;
+        let manifest = Manifest::load(&dest);
+        let (stale, removed, summary) = plan_update(&dest, &manifest, &files);
+        let snapshot = backup.then(|| {
+            let paths = stale.iter().map(|f| &f.path).chain(&removed);
+            Snapshot::take(&dest, paths)
+        });
+
+        let progress = FileProgress::new(stale.len(), false);
+        let failed = download_files(&stale, &dest, &progress);
+        for path in &removed {
+            let full = dest.join(path);
+            match fs::remove_file(&full) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(e).with_context(|| format!("Failed to remove {}", full.display()));
+                }
+                _ => {}
+            }
+            // Drop the directory too if that was its last file.
+            if let Some(parent) = full.parent() {
+                let _ = fs::remove_dir(parent);
+            }
+        }
+        Manifest::for_files(&files, &resolved.name)
+            .without(&failed)
+            .save(self, &dest)?;
+        progress.finish(&summary.to_string());
+
+        let agents_md = || self.fetch_agents_md(git_ref);
+        self.finish_install(&dest, agents_md, placeholders, instructions, false)?;
+        save_backup(snapshot, &dest, false)?;
+        if !failed.is_empty() {
+            return Err(failed_downloads_error(self, stale.len(), &failed));
+        }
+        Ok((resolved.sha, summary))
+    }
 
-        if !quiet {
-            println!("Downloading {} agent files...", self);
+    /// Decide where to download from. A pin is used as given. Otherwise
+    /// the release tag matching this CLI is preferred, so an older binary
+    /// never gets agent files written for a newer one, falling back to
+    /// `master` when the tag (or this tool's directory in it) is missing.
+    pub(crate) fn resolve_ref(&self, pin_ref: Option<&str>) -> ResolvedRef {
+        if let Some(pin) = pin_ref {
+            return ResolvedRef {
+                name: pin.to_string(),
+                sha: Some(pin.to_string()),
+                fell_back: false,
+            };
         }
-        let mut count = 0;
-        download_directory(self.repo_dir(), git_ref, &dest, &mut count, quiet)?;
-        if !quiet {
-            println!("  {:<60}", format!("Downloaded {} files", count));
+        let tag = release_tag();
+        match fetch_repo_dir_sha(self.repo_dir(), &tag) {
+            Ok(Some(sha)) => ResolvedRef {
+                name: tag,
+                sha: Some(sha),
+                fell_back: false,
+            },
+            Ok(None) => ResolvedRef {
+                name: BRANCH.to_string(),
+                sha: fetch_repo_dir_sha(self.repo_dir(), BRANCH).ok().flatten(),
+                fell_back: true,
+            },
+            // The commits API being unreachable says nothing about the tag;
+            // download it by name and let that fail if it's really missing.
+            Err(_) => ResolvedRef {
+                name: tag,
+                sha: None,
+                fell_back: false,
+            },
+        }
+    }
+
+    /// Whether `master` has changes to this tool's files that `git_ref`
+    /// doesn't, by the last commit touching its directory on each. `None`
+    /// when GitHub doesn't say (unreachable, rate-limited, unknown ref).
+    pub(crate) fn has_updates_since(&self, git_ref: &str) -> Option<bool> {
+        let at_ref = fetch_repo_dir_sha(self.repo_dir(), git_ref).ok()??;
+        let latest = fetch_repo_dir_sha(self.repo_dir(), BRANCH).ok()??;
+        Some(at_ref != latest)
+    }
+
+    /// The branch or tag the installed files were downloaded from, as
+    /// recorded in the manifest. `None` for local-source or older installs.
+    pub fn installed_ref(&self) -> Option<String> {
+        self.manifest().git_ref
+    }
+
+    /// The install manifest, empty when there is none.
+    fn manifest(&self) -> Manifest {
+        self.dest_dir()
+            .map(|dest| Manifest::load(&dest))
+            .unwrap_or_default()
+    }
+
+    /// Remove exactly the files the install manifest lists, any
+    /// directories that leaves empty, and the manifest itself. Files
+    /// hyprlayer didn't install are never touched. Returns the removed
+    /// files, relative to the destination directory.
+    pub fn uninstall(&self) -> Result<Vec<PathBuf>> {
+        self.uninstall_at(&self.dest_dir()?)
+    }
+
+    /// Test-friendly body of `uninstall`.
+    fn uninstall_at(&self, dest: &Path) -> Result<Vec<PathBuf>> {
+        let manifest = Manifest::load(dest);
+        if manifest.files.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No install manifest in {}, so which files hyprlayer installed there is unknown. \
+                 Run 'hyprlayer ai reinstall' to record one, or remove the files by hand.",
+                dest.display()
+            ));
+        }
+        let mut removed = Vec::new();
+        for path in manifest.files.into_keys() {
+            let full = dest.join(&path);
+            match fs::remove_file(&full) {
+                Ok(()) => removed.push(path),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    return Err(e).with_context(|| format!("Failed to remove {}", full.display()));
+                }
+            }
+            // Drop directories the file leaves empty, up to `dest`.
+            let mut dir = full.parent();
+            while let Some(parent) = dir
+                && parent != dest
+                && fs::remove_dir(parent).is_ok()
+            {
+                dir = parent.parent();
+            }
+        }
+        fs::remove_file(dest.join(MANIFEST_FILE))?;
+        Ok(removed)
+    }
+
+    /// Every file of the remote bundle at `git_ref`, relative to the
+    /// destination directory. Codex installs only its prompts; `AGENTS.md`
+    /// is merged separately by `finish_install`.
+    fn list_remote_files(&self, git_ref: &str) -> Result<Vec<RemoteFile>> {
+        let mut files = Vec::new();
+        if *self == Self::Codex {
+            let prompts = format!("{}/prompts", self.repo_dir());
+            list_remote_files(&prompts, git_ref, Path::new("prompts"), &mut files)?;
+        } else {
+            list_remote_files(self.repo_dir(), git_ref, Path::new(""), &mut files)?;
+        }
+        Ok(files)
+    }
+
+    /// Like `list_remote_files`, for the tool's directory in a local
+    /// checkout.
+    fn list_local_files(&self, tool_dir: &Path) -> Result<Vec<PathBuf>> {
+        if *self != Self::Codex {
+            return list_files_at(tool_dir);
+        }
+        Ok(list_files_at(&tool_dir.join("prompts"))?
+            .into_iter()
+            .map(|rel| Path::new("prompts").join(rel))
+            .collect())
+    }
+
+    /// Codex's `AGENTS.md` from GitHub at `git_ref`.
+    fn fetch_agents_md(&self, git_ref: &str) -> Result<String> {
+        let url = github_url(
+            GITHUB_RAW_URL,
+            &format!("/{REPO}/{git_ref}/{}/AGENTS.md", self.repo_dir()),
+        );
+        String::from_utf8(http::get_bytes(&url, FILE_TIMEOUT)?)
+            .context("Codex AGENTS.md is not UTF-8 text")
+    }
+
+    /// The steps after the bundle files are in place: write Codex's
+    /// instructions (read through `agents_md` only when they're written)
+    /// and fill in placeholders.
+    fn finish_install(
+        &self,
+        dest: &Path,
+        agents_md: impl FnOnce() -> Result<String>,
+        placeholders: Option<&PlaceholderSet>,
+        instructions: InstructionsTarget,
+        quiet: bool,
+    ) -> Result<()> {
+        if *self == Self::Codex {
+            let global = dest.join("AGENTS.md");
+            let target = match instructions {
+                InstructionsTarget::Global => Some(global),
+                InstructionsTarget::Project(root) => Some(root.join("AGENTS.md")),
+                InstructionsTarget::Existing => has_instructions(&global).then_some(global),
+            };
+            if let Some(target) = target {
+                let mut text = agents_md()?;
+                if let Some(filled) = placeholders.and_then(|p| p.substitute(&text)) {
+                    text = filled;
+                }
+                write_instructions(&target, &text)?;
+                if !quiet {
+                    println!("  Updated {}", target.display());
+                }
+            }
         }
 
-        if matches!(self, AgentTool::OpenCode)
-            && let Some(provider) = opencode_provider
-        {
+        if let Some(placeholders) = placeholders {
             if !quiet {
-                println!("Configuring models for {}...", provider);
+                println!("Filling in agent file placeholders...");
             }
-            let updated = update_opencode_models(&dest, provider)?;
+            let updated = self.fill_placeholders(dest, placeholders)?;
             if !quiet {
-                println!("  {:<60}", format!("Updated {} files", updated));
+                println!("  Updated {} files", updated);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill in placeholders in the installed files. OpenCode's agents and
+    /// commands also get their YAML and TOML model keys rewritten (see
+    /// `update_opencode_models`); other tools only have `{{NAME}}`
+    /// placeholders filled in, in the files the install manifest lists.
+    fn fill_placeholders(&self, dest: &Path, placeholders: &PlaceholderSet) -> Result<usize> {
+        if *self == Self::OpenCode {
+            return update_opencode_models(dest, placeholders);
+        }
+        Manifest::load(dest)
+            .files
+            .keys()
+            .filter(|rel| is_model_file(rel))
+            .try_fold(0, |count, rel| {
+                let path = dest.join(rel);
+                let updated = rewrite_file(&path, placeholders, |content| {
+                    Ok(placeholders.substitute(content))
+                })?;
+                Ok::<_, anyhow::Error>(count + usize::from(updated))
+            })
+    }
+
+    /// `content` of the bundle file at `rel` as `fill_placeholders` leaves
+    /// it, or `None` if it's left alone.
+    fn fill_template(
+        &self,
+        rel: &Path,
+        content: &str,
+        placeholders: &PlaceholderSet,
+    ) -> Result<Option<String>> {
+        match self {
+            Self::OpenCode if has_model_placeholders(rel) => {
+                rewrite_models(rel, content, placeholders)
+            }
+            Self::OpenCode => Ok(None),
+            _ if is_model_file(rel) => Ok(placeholders.substitute(content)),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// The `repo_dir` directory of a local checkout. GitHub's source tarballs
+/// wrap the tree in one `<owner>-<repo>-<sha>/` directory, so that is
+/// looked through too.
+fn locate_tool_dir(checkout: &Path, repo_dir: &str) -> Result<PathBuf> {
+    let direct = checkout.join(repo_dir);
+    if direct.is_dir() {
+        return Ok(direct);
+    }
+    let subdirs: Vec<PathBuf> = fs::read_dir(checkout)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir())
+        .collect();
+    if let [only] = subdirs.as_slice()
+        && only.join(repo_dir).is_dir()
+    {
+        return Ok(only.join(repo_dir));
+    }
+    Err(anyhow::anyhow!(
+        "No {}/ directory in {}",
+        repo_dir,
+        checkout.display()
+    ))
+}
+
+/// GitHub's source tarball of the repository at `git_ref`.
+fn fetch_tarball(git_ref: &str) -> Result<Vec<u8>> {
+    let url = github_url(GITHUB_CODELOAD_URL, &format!("/{REPO}/tar.gz/{git_ref}"));
+    Ok(http::get_bytes_retrying(&url, Duration::from_secs(120))?)
+}
+
+/// Unpack a `.tar.gz` into a fresh, randomly named temporary directory,
+/// removed when the returned `TempDir` is dropped. With `repo_dir`, only
+/// that directory of the checkout is kept, whether or not the tree is
+/// wrapped in a top-level directory as GitHub's tarballs are.
+fn extract_tarball(
+    archive: impl std::io::Read,
+    repo_dir: Option<&str>,
+) -> Result<tempfile::TempDir> {
+    let dir = tempfile::Builder::new()
+        .prefix("hyprlayer-agents-")
+        .tempdir()?;
+
+    let mut tarball = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tarball.entries()? {
+        let mut entry = entry?;
+        let wanted = repo_dir.is_none_or(|repo_dir| {
+            let path = entry.path().map(|p| p.into_owned()).unwrap_or_default();
+            path.components().take(2).any(|c| c.as_os_str() == repo_dir)
+        });
+        // `unpack_in` refuses paths that would escape `dir`.
+        if wanted {
+            entry.unpack_in(dir.path())?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Print the `--ref`/`agentPinRef` notice, or the warning that the
+/// release tag is missing, shown before downloading.
+fn print_ref(resolved: &ResolvedRef, pin_ref: Option<&str>) {
+    use colored::Colorize;
+    if let Some(pin) = pin_ref {
+        println!("  Pinned to {}", pin.cyan());
+        if let Some(warning) = crate::version::pin_warning(pin) {
+            println!("  {}", warning.yellow());
+        }
+    } else if resolved.fell_back {
+        println!(
+            "  {}",
+            format!(
+                "No agent files tagged {}; using {} instead, which may not match this version",
+                release_tag(),
+                BRANCH
+            )
+            .yellow()
+        );
+    }
+}
+
+fn instructions_mode_at(global: bool, project_root: Option<&Path>) -> Option<&'static str> {
+    let project = project_root.is_some_and(|root| has_instructions(&root.join("AGENTS.md")));
+    match (global, project) {
+        (true, true) => Some("both"),
+        (true, false) => Some("global"),
+        (false, true) => Some("project"),
+        (false, false) => None,
+    }
+}
+
+/// Whether `path` holds a hyprlayer instructions section.
+fn has_instructions(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(INSTRUCTIONS_START))
+}
+
+/// `existing` with its hyprlayer section replaced by `instructions`, or
+/// with the section appended when there is none yet.
+fn merge_instructions(existing: &str, instructions: &str) -> String {
+    let section = format!(
+        "{INSTRUCTIONS_START}\n{}\n{INSTRUCTIONS_END}\n",
+        instructions.trim_end()
+    );
+    if let Some(start) = existing.find(INSTRUCTIONS_START)
+        && let Some(end) = existing[start..].find(INSTRUCTIONS_END)
+    {
+        let rest = &existing[start + end + INSTRUCTIONS_END.len()..];
+        return format!(
+            "{}{section}{}",
+            &existing[..start],
+            rest.strip_prefix('\n').unwrap_or(rest)
+        );
+    }
+    if existing.trim().is_empty() {
+        return section;
+    }
+    format!("{}\n\n{section}", existing.trim_end())
+}
+
+fn write_instructions(path: &Path, instructions: &str) -> Result<()> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, merge_instructions(&existing, instructions))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Test-friendly body of `AgentTool::diff_with_remote`. Local files only
+/// count as `Removed` inside the bundle's top-level directories, so
+/// unrelated files in the tool's config dir (settings, history) are ignored.
+fn compare_with_remote(dest: &Path, remote: Vec<(PathBuf, Vec<u8>)>) -> Result<Vec<FileDiff>> {
+    let bundle_dirs: std::collections::HashSet<_> = remote
+        .iter()
+        .filter(|(path, _)| path.components().count() > 1)
+        .filter_map(|(path, _)| path.components().next())
+        .map(|c| c.as_os_str().to_os_string())
+        .collect();
+    let remote_paths: std::collections::HashSet<_> =
+        remote.iter().map(|(path, _)| path.clone()).collect();
+
+    let mut diffs = Vec::new();
+    for (path, body) in remote {
+        let local = fs::read(dest.join(&path)).ok();
+        let status = match &local {
+            None => DiffStatus::Added,
+            Some(local) if *local == body => DiffStatus::Unchanged,
+            Some(_) => DiffStatus::Modified,
+        };
+        let local_content = local.and_then(|l| String::from_utf8(l).ok());
+        let remote_content = String::from_utf8(body).ok();
+        let binary =
+            remote_content.is_none() || (status != DiffStatus::Added && local_content.is_none());
+        diffs.push(FileDiff {
+            path,
+            status,
+            local_content: local_content.filter(|_| !binary),
+            remote_content: remote_content.filter(|_| !binary),
+        });
+    }
+
+    for path in list_files_at(dest)? {
+        let in_bundle_dir = path
+            .components()
+            .next()
+            .is_some_and(|c| bundle_dirs.contains(c.as_os_str()));
+        if in_bundle_dir && !remote_paths.contains(&path) {
+            let local_content = fs::read_to_string(dest.join(&path)).ok();
+            diffs.push(FileDiff {
+                path,
+                status: DiffStatus::Removed,
+                local_content,
+                remote_content: None,
+            });
+        }
+    }
+
+    diffs.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(diffs)
+}
+
+/// Save the files in `snapshot` that an install changed (see
+/// `Snapshot::save_changed`) and say where they went. Shown even when
+/// `quiet`, on stderr, since the startup auto-reinstall is otherwise silent.
+fn save_backup(snapshot: Option<Snapshot>, dest: &Path, quiet: bool) -> Result<()> {
+    use colored::Colorize;
+    let Some(backup) = snapshot
+        .map(|s| s.save_changed(dest))
+        .transpose()?
+        .flatten()
+    else {
+        return Ok(());
+    };
+    let message = format!(
+        "  Backed up {} changed file(s) to {}",
+        backup.files.len(),
+        backup.path.display()
+    );
+    if quiet {
+        eprintln!("{}", message.yellow());
+    } else {
+        println!("{}", message.yellow());
+    }
+    Ok(())
+}
+
+/// Every file under `dest`, relative to it and sorted. Skips `.git` and
+/// the install backups.
+pub(crate) fn list_files_at(dest: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if entry.file_name() != ".git" && entry.file_name() != BACKUP_DIR {
+                    walk(root, &path, out)?;
+                }
+            } else if let Ok(rel) = path.strip_prefix(root) {
+                out.push(rel.to_path_buf());
             }
         }
-
-        Ok(sha)
+        Ok(())
+    }
+
+    if !dest.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    walk(dest, dest, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+/// The bundle SHA recorded for several tools installed together: each
+/// tool's SHA in order, without repeats, joined with `+`. One tool records
+/// its own SHA unchanged. `None` when any SHA is unknown.
+pub fn combined_sha(shas: impl IntoIterator<Item = Option<String>>) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for sha in shas {
+        let sha = sha?;
+        if !parts.contains(&sha) {
+            parts.push(sha);
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("+"))
+}
+
+/// The tag agent files are downloaded from by default: the release this
+/// binary was built from.
+pub(crate) fn release_tag() -> String {
+    format!("v{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Fetch the latest commit SHA at `git_ref` that touched `repo_path`.
+/// `Ok(None)` when `git_ref` doesn't exist or doesn't contain `repo_path`.
+pub(crate) fn fetch_repo_dir_sha(repo_path: &str, git_ref: &str) -> Result<Option<String>> {
+    let url = github_url(
+        GITHUB_API_URL,
+        &format!("/repos/{REPO}/commits?path={repo_path}&sha={git_ref}&per_page=1"),
+    );
+    let json = match http::get_json(&url, Duration::from_secs(5)) {
+        Ok(json) => json,
+        // 422 is GitHub's answer for an unknown `sha`.
+        Err(HttpError::Status {
+            status: 404 | 422, ..
+        }) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if is_empty_array(&json) {
+        return Ok(None);
+    }
+    parse_repo_dir_sha(&json, repo_path).map(Some)
+}
+
+fn is_empty_array(json: &str) -> bool {
+    serde_json::from_str::<Vec<serde_json::Value>>(json).is_ok_and(|entries| entries.is_empty())
+}
+
+fn parse_repo_dir_sha(json: &str, repo_path: &str) -> Result<String> {
+    // GitHub returns an object with `message` on errors (e.g. 403
+    // rate-limited); detect that before assuming the array shape.
+    if let Ok(err) = serde_json::from_str::<GitHubError>(json)
+        && let Some(message) = err.message
+    {
+        return Err(anyhow::anyhow!(
+            "GitHub commits API error for '{}': {}",
+            repo_path,
+            message
+        ));
+    }
+
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(json).context("Failed to parse GitHub commits API response")?;
+    entries
+        .first()
+        .and_then(|e| e.get("sha"))
+        .and_then(|s| s.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow::anyhow!("GitHub returned no commits for '{}'", repo_path))
+}
+
+/// Downloads of one file while its content doesn't match the listed blob
+/// SHA, e.g. after a truncated body or a captive portal's HTML page.
+const VERIFY_ATTEMPTS: u32 = 3;
+
+/// A file `download_files` couldn't fetch, even with retries.
+#[derive(Debug)]
+struct FailedDownload {
+    path: PathBuf,
+    error: String,
+}
+
+/// Progress through a known number of files: a bar on a terminal, a line
+/// per file otherwise so logs stay readable, and nothing when quiet.
+struct FileProgress {
+    bar: Option<ProgressBar>,
+    lines: bool,
+}
+
+impl FileProgress {
+    fn new(total: usize, quiet: bool) -> Self {
+        if quiet {
+            return Self {
+                bar: None,
+                lines: false,
+            };
+        }
+        if !std::io::stdout().is_terminal() {
+            return Self {
+                bar: None,
+                lines: true,
+            };
+        }
+        let bar = ProgressBar::with_draw_target(Some(total as u64), ProgressDrawTarget::stdout());
+        bar.set_style(
+            ProgressStyle::with_template("  [{bar:30}] {pos}/{len} {wide_msg}")
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        Self {
+            bar: Some(bar),
+            lines: false,
+        }
+    }
+
+    /// Show `path` as the file being worked on.
+    fn start(&self, path: &Path) {
+        if let Some(bar) = &self.bar {
+            bar.set_message(path.display().to_string());
+        } else if self.lines {
+            println!("  {}", path.display());
+        }
+    }
+
+    /// Count the file last passed to `start` as done.
+    fn done(&self) {
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+
+    /// Clear the bar and print `summary` in its place.
+    fn finish(&self, summary: &str) {
+        if let Some(bar) = &self.bar {
+            bar.finish_and_clear();
+        }
+        if self.bar.is_some() || self.lines {
+            println!("  {summary}");
+        }
+    }
+}
+
+/// Download each of `files` into `dest`, reporting to `progress`. Each file is retried on temporary network errors and when its content
+/// doesn't match its blob SHA; one that still fails doesn't stop the rest,
+/// and is returned instead.
+///
+/// The URLs come from one `list_remote_files` walk pinned to a single
+/// `git_ref`, which prevents a mid-install `master` advance from producing
+/// a torn install where some files come from commit A and others from
+/// commit B.
+fn download_files<F: std::borrow::Borrow<RemoteFile>>(
+    files: &[F],
+    dest: &Path,
+    progress: &FileProgress,
+) -> Vec<FailedDownload> {
+    let mut failed = Vec::new();
+    for file in files {
+        let file = file.borrow();
+        progress.start(&file.path);
+        if let Err(e) = download_verified(file, dest) {
+            failed.push(FailedDownload {
+                path: file.path.clone(),
+                error: format!("{e:#}"),
+            });
+        }
+        progress.done();
+    }
+    failed
+}
+
+/// Download `file` into `dest`, checking the body against `file.sha`. Only
+/// a complete 2xx body with the right blob SHA is written, so an error
+/// page, a truncated download or a portal's login page never lands as the
+/// file.
+fn download_verified(file: &RemoteFile, dest: &Path) -> Result<()> {
+    let mut actual = String::new();
+    for _ in 0..VERIFY_ATTEMPTS {
+        let body = http::get_bytes_retrying(&file.url, FILE_TIMEOUT)?;
+        actual = git_blob_sha(&body)?;
+        // Listings without a SHA can't be checked.
+        if file.sha.is_empty() || actual == file.sha {
+            let target = dest.join(&file.path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, body)?;
+            return Ok(());
+        }
+    }
+    Err(anyhow::anyhow!(
+        "corrupt download: content has blob SHA {} but GitHub lists {}",
+        actual,
+        file.sha
+    ))
+}
+
+/// Git's blob SHA for `content`, the SHA-1 of `blob <len>\0` followed by
+/// the content, which is what the Contents API lists as `sha`.
+fn git_blob_sha(content: &[u8]) -> Result<String> {
+    Ok(git2::Oid::hash_object(git2::ObjectType::Blob, content)?.to_string())
+}
+
+/// The error for a partial download: which files are missing and how to
+/// fetch just those.
+fn failed_downloads_error(
+    tool: &AgentTool,
+    attempted: usize,
+    failed: &[FailedDownload],
+) -> anyhow::Error {
+    let files: String = failed
+        .iter()
+        .map(|f| format!("\n  {}: {}", f.path.display(), f.error))
+        .collect();
+    anyhow::anyhow!(
+        "{} of {} {} agent files failed to download:{}\n\
+         Run 'hyprlayer ai update' to retry just those.",
+        failed.len(),
+        attempted,
+        tool,
+        files
+    )
+}
+
+/// Split `remote` into the files `update` must download (missing locally,
+/// or at a different blob SHA than `manifest` records) and the manifest
+/// entries no longer shipped, which it deletes.
+fn plan_update<'a>(
+    dest: &Path,
+    manifest: &Manifest,
+    remote: &'a [RemoteFile],
+) -> (Vec<&'a RemoteFile>, Vec<PathBuf>, UpdateSummary) {
+    let mut summary = UpdateSummary::default();
+    let mut stale = Vec::new();
+    for file in remote {
+        if !dest.join(&file.path).is_file() {
+            summary.added += 1;
+        } else if manifest.files.get(&file.path) == Some(&file.sha) {
+            summary.unchanged += 1;
+            continue;
+        } else {
+            summary.updated += 1;
+        }
+        stale.push(file);
     }
+
+    let shipped: std::collections::HashSet<&Path> =
+        remote.iter().map(|f| f.path.as_path()).collect();
+    let removed: Vec<PathBuf> = manifest
+        .files
+        .keys()
+        .filter(|path| !shipped.contains(path.as_path()))
+        .cloned()
+        .collect();
+    summary.removed = removed.len();
+    (stale, removed, summary)
 }
 
-/// Fetch the latest `master` commit SHA that touched `repo_path`.
-pub(crate) fn fetch_repo_dir_sha(repo_path: &str) -> Result<String> {
-    let url = format!(
-        "https://api.github.com/repos/{REPO}/commits?path={repo_path}&sha={BRANCH}&per_page=1"
+/// List one directory of the repo at `git_ref` via the GitHub Contents API.
+fn fetch_directory_entries(repo_path: &str, git_ref: &str) -> Result<Vec<GitHubEntry>> {
+    let api_url = github_url(
+        GITHUB_API_URL,
+        &format!("/repos/{REPO}/contents/{repo_path}?ref={git_ref}"),
     );
-    let json = curl_get_json(&url, Some(5))?;
-    parse_repo_dir_sha(&json, repo_path)
+
+    let json = match http::get_json(&api_url, Duration::from_secs(15)) {
+        // A tool directory that hasn't been published yet, or a pinned ref
+        // that doesn't exist.
+        Err(e @ HttpError::Status { status: 404, .. }) => {
+            return Err(anyhow::anyhow!(
+                "Agent files for '{}' are not available on GitHub at {}; check the ref and path ({})",
+                repo_path,
+                git_ref,
+                e
+            ));
+        }
+        result => result?,
+    };
+    parse_directory_entries(&json, repo_path)
 }
 
-fn parse_repo_dir_sha(json: &str, repo_path: &str) -> Result<String> {
-    // GitHub returns an object with `message` on errors (e.g. 403
-    // rate-limited); detect that before assuming the array shape.
+fn parse_directory_entries(json: &str, repo_path: &str) -> Result<Vec<GitHubEntry>> {
+    // The API returns a JSON object with a "message" field on errors (e.g.
+    // 404 for a tool directory that hasn't been published yet)
     if let Ok(err) = serde_json::from_str::<GitHubError>(json)
         && let Some(message) = err.message
     {
         return Err(anyhow::anyhow!(
-            "GitHub commits API error for '{}': {}",
+            "Agent files for '{}' are not available on GitHub ({})",
             repo_path,
             message
         ));
     }
 
-    let entries: Vec<serde_json::Value> =
-        serde_json::from_str(json).context("Failed to parse GitHub commits API response")?;
-    entries
-        .first()
-        .and_then(|e| e.get("sha"))
-        .and_then(|s| s.as_str())
-        .map(|s| s.to_string())
-        .ok_or_else(|| anyhow::anyhow!("GitHub returned no commits for '{}'", repo_path))
+    serde_json::from_str(json).context("Failed to parse GitHub API response")
 }
 
-/// Download a directory from the repo using the GitHub Contents API.
-/// Recursively fetches subdirectories and downloads each file individually.
-///
-/// `git_ref` is the resolved commit SHA (or branch name) to pin every
-/// listing + raw fetch to. Pinning across the recursion prevents a
-/// mid-install `master` advance from producing a torn install where
-/// some files come from commit A and others from commit B.
-fn download_directory(
+/// One file of a tool's remote bundle.
+#[derive(Debug, Clone)]
+struct RemoteFile {
+    /// Relative to the tool's destination directory.
+    path: PathBuf,
+    /// Already pinned to the `ref` the listing was requested at.
+    url: String,
+    /// Git blob SHA, which changes exactly when the content does.
+    sha: String,
+}
+
+/// Collect every file under `repo_path` with `rel` prepended to its path
+/// below `repo_path`, recursing into subdirectories.
+fn list_remote_files(
     repo_path: &str,
     git_ref: &str,
-    dest: &Path,
-    count: &mut usize,
-    quiet: bool,
+    rel: &Path,
+    out: &mut Vec<RemoteFile>,
 ) -> Result<()> {
-    let api_url = format!("https://api.github.com/repos/{REPO}/contents/{repo_path}?ref={git_ref}");
-
-    let json = curl_get_json(&api_url, Some(15))?;
-
-    // The API returns a JSON object with a "message" field on errors (e.g. 404)
-    if let Ok(err) = serde_json::from_str::<GitHubError>(&json)
-        && let Some(message) = err.message
-    {
-        return Err(anyhow::anyhow!(
-            "Agent files for '{}' are not available on GitHub ({})",
-            repo_path,
-            message
-        ));
-    }
-
-    let entries: Vec<GitHubEntry> =
-        serde_json::from_str(&json).context("Failed to parse GitHub API response")?;
-
-    for entry in entries {
-        let dest_path = dest.join(&entry.name);
+    for entry in fetch_directory_entries(repo_path, git_ref)? {
+        let rel_path = rel.join(&entry.name);
         match entry.entry_type.as_str() {
             "file" => {
-                // The contents API's `download_url` is already pinned to
-                // the `?ref=<git_ref>` we requested, so reusing it keeps
-                // the whole download tied to a single SHA.
                 let url = entry
                     .download_url
                     .ok_or_else(|| anyhow::anyhow!("No download URL for {}", entry.path))?;
-                if !quiet {
-                    print!("  {:<60}\r", entry.path);
-                    std::io::stdout().flush().ok();
-                }
-                curl_download_file(&url, &dest_path)?;
-                *count += 1;
-            }
-            "dir" => {
-                // No explicit `create_dir_all` here — `curl_download_file`
-                // creates each file's parent on demand, which covers this
-                // subdir as soon as we download anything into it.
-                download_directory(&entry.path, git_ref, &dest_path, count, quiet)?;
+                out.push(RemoteFile {
+                    path: rel_path,
+                    url,
+                    sha: entry.sha,
+                });
             }
+            "dir" => list_remote_files(&entry.path, git_ref, &rel_path, out)?,
             _ => {} // skip symlinks, submodules, etc.
         }
     }
-
     Ok(())
 }
 
@@ -419,120 +1956,321 @@ struct GitHubError {
     message: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize)]
 struct GitHubEntry {
     name: String,
     path: String,
     #[serde(rename = "type")]
     entry_type: String,
     download_url: Option<String>,
+    #[serde(default)]
+    sha: String,
 }
 
-/// GET a URL and return the response body as a string.
-/// Optionally applies a timeout (in seconds) via curl's `--max-time`.
-pub(crate) fn curl_get_json(url: &str, timeout_secs: Option<u32>) -> Result<String> {
-    let timeout_str = timeout_secs.map(|s| s.to_string());
-    let mut args = vec![
-        "-sL",
-        "-H",
-        "Accept: application/vnd.github.v3+json",
-        "-H",
-        "User-Agent: hyprlayer-cli",
-    ];
-    if let Some(ref t) = timeout_str {
-        args.extend(["--max-time", t]);
+/// Values for the `{{NAME}}` placeholders in OpenCode agent/command
+/// templates, keyed by `NAME`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaceholderSet {
+    values: HashMap<String, String>,
+}
+
+impl PlaceholderSet {
+    pub fn insert(&mut self, name: &str, value: impl Into<String>) {
+        self.values.insert(name.to_string(), value.into());
     }
-    args.push(url);
 
-    let output = Command::new("curl")
-        .args(&args)
-        .output()
-        .context("curl not found — install curl to download agent files")?;
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
 
-    if !output.status.success() {
-        anyhow::bail!("GitHub API request failed");
+    /// Use the configured `opencodeSonnetModel`/`opencodeOpusModel` in
+    /// place of the provider's defaults, where set.
+    pub fn with_models(mut self, sonnet: Option<&str>, opus: Option<&str>) -> Self {
+        if let Some(model) = sonnet {
+            self.insert("SONNET_MODEL", model);
+        }
+        if let Some(model) = opus {
+            self.insert("OPUS_MODEL", model);
+        }
+        self
     }
 
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
-}
+    /// Names of the `{{NAME}}` placeholders in `content` without a value,
+    /// sorted. Only upper-case names count, so other `{{...}}` text isn't
+    /// taken for a placeholder.
+    fn unknown(&self, content: &str) -> BTreeSet<String> {
+        static PLACEHOLDER: std::sync::LazyLock<regex::Regex> =
+            std::sync::LazyLock::new(|| regex::Regex::new(r"\{\{([A-Z][A-Z0-9_]*)\}\}").unwrap());
+        PLACEHOLDER
+            .captures_iter(content)
+            .map(|c| c[1].to_string())
+            .filter(|name| self.get(name).is_none())
+            .collect()
+    }
 
-/// Download a single file to disk.
-///
-/// `--fail-with-body` makes curl exit non-zero on HTTP 4xx/5xx so a 404
-/// HTML page or rate-limit JSON envelope can never be persisted as a
-/// fake "agent file." `--max-time` caps the per-file fetch so a stalled
-/// connection on the startup auto-reinstall path can't hang the user's
-/// command indefinitely.
-fn curl_download_file(url: &str, dest: &Path) -> Result<()> {
-    if let Some(parent) = dest.parent() {
-        fs::create_dir_all(parent)?;
+    /// `content` with every known placeholder replaced in a single pass, or
+    /// `None` if it contains none. Unknown `{{...}}` sequences are kept, and
+    /// substituted values are never expanded again.
+    fn substitute(&self, content: &str) -> Option<String> {
+        let mut out = String::with_capacity(content.len());
+        let mut rest = content;
+        let mut replaced = false;
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let value = after
+                .find("}}")
+                .and_then(|end| Some((end, self.get(&after[..end])?)));
+            match value {
+                Some((end, value)) => {
+                    out.push_str(value);
+                    rest = &after[end + 2..];
+                    replaced = true;
+                }
+                None => {
+                    out.push_str("{{");
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        replaced.then_some(out)
     }
+}
+
+/// Placeholders for `provider`'s models (`SONNET_MODEL`, `OPUS_MODEL`,
+/// `ADVERSARIAL_MODEL`, `PROVIDER`) on top of `thoughts_placeholder_set`.
+pub fn build_placeholder_set(
+    config: Option<&ThoughtsConfig>,
+    provider: &OpenCodeProvider,
+) -> PlaceholderSet {
+    let mut set = thoughts_placeholder_set(config);
+    set.insert("SONNET_MODEL", provider.default_sonnet_model());
+    set.insert("OPUS_MODEL", provider.default_opus_model());
+    set.insert("ADVERSARIAL_MODEL", provider.default_adversarial_model());
+    set.insert("PROVIDER", provider.provider_prefix());
+    set
+}
 
-    let dest_str = dest.display().to_string();
-    let status = Command::new("curl")
-        .args([
-            "-sSL",
-            "--fail-with-body",
-            "--max-time",
-            "30",
-            "-o",
-            &dest_str,
-            url,
-        ])
-        .status()
-        .context("curl not found")?;
-
-    if !status.success() {
-        // Don't leave a partial / error-page body on disk.
-        let _ = fs::remove_file(dest);
-        return Err(anyhow::anyhow!("Failed to download {}", dest.display()));
+/// The placeholders every tool's files may use: `DATE` and, when thoughts
+/// are configured, `USER` (also `USERNAME`), `THOUGHTS_DIR` (also
+/// `THOUGHTS_REPO`), `REPOS_DIR` and `GLOBAL_DIR` from the default
+/// backend. The directories are only known for git and Obsidian.
+pub fn thoughts_placeholder_set(config: Option<&ThoughtsConfig>) -> PlaceholderSet {
+    let mut set = PlaceholderSet::default();
+    set.insert("DATE", chrono::Local::now().format("%Y-%m-%d").to_string());
+
+    let Some(config) = config else {
+        return set;
+    };
+    if !config.user.is_empty() {
+        set.insert("USER", config.user.as_str());
+        set.insert("USERNAME", config.user.as_str());
     }
-    Ok(())
+    let dirs = match &config.backend {
+        BackendConfig::Git(g) => Some((&g.thoughts_repo, &g.repos_dir, &g.global_dir)),
+        BackendConfig::Obsidian(o) => Some((&o.vault_path, &o.repos_dir, &o.global_dir)),
+        BackendConfig::Notion(_) | BackendConfig::Anytype(_) => None,
+    };
+    if let Some((root, repos_dir, global_dir)) = dirs {
+        set.insert("THOUGHTS_DIR", root.as_str());
+        set.insert("THOUGHTS_REPO", root.as_str());
+        set.insert("REPOS_DIR", repos_dir.as_str());
+        set.insert("GLOBAL_DIR", global_dir.as_str());
+    }
+    set
 }
 
-/// Template placeholders used in OpenCode agent/command files
-const SONNET_MODEL_PLACEHOLDER: &str = "{{SONNET_MODEL}}";
-const OPUS_MODEL_PLACEHOLDER: &str = "{{OPUS_MODEL}}";
-const ADVERSARIAL_MODEL_PLACEHOLDER: &str = "{{ADVERSARIAL_MODEL}}";
+/// File types whose placeholders are filled in.
+const MODEL_FILE_EXTENSIONS: [&str; 4] = ["md", "yml", "yaml", "toml"];
 
-/// Replace model placeholders in a file with provider-specific values.
-/// Returns true if any replacements were made.
-fn replace_model_placeholders(path: &Path, provider: &OpenCodeProvider) -> Result<bool> {
-    let content = fs::read_to_string(path)?;
+/// Fill in placeholders in a file (see `rewrite_models`). Returns true if
+/// any replacements were made.
+fn apply_placeholders(path: &Path, placeholders: &PlaceholderSet) -> Result<bool> {
+    rewrite_file(path, placeholders, |content| {
+        rewrite_models(path, content, placeholders)
+    })
+}
 
-    if !content.contains(SONNET_MODEL_PLACEHOLDER)
-        && !content.contains(OPUS_MODEL_PLACEHOLDER)
-        && !content.contains(ADVERSARIAL_MODEL_PLACEHOLDER)
-    {
+/// Replace the file at `path` with what `rewrite` makes of its content,
+/// if anything. Returns true if the file changed. Placeholders
+/// `placeholders` has no value for are left as they are, with a warning.
+/// A file that isn't UTF-8 is left alone with a warning rather than
+/// failing the whole install.
+fn rewrite_file(
+    path: &Path,
+    placeholders: &PlaceholderSet,
+    rewrite: impl FnOnce(&str) -> Result<Option<String>>,
+) -> Result<bool> {
+    use colored::Colorize;
+    let Ok(content) = String::from_utf8(fs::read(path)?) else {
+        eprintln!(
+            "{}",
+            format!("Skipping {}: not a UTF-8 text file", path.display()).yellow()
+        );
         return Ok(false);
-    }
-
-    let updated = content
-        .replace(SONNET_MODEL_PLACEHOLDER, provider.default_sonnet_model())
-        .replace(OPUS_MODEL_PLACEHOLDER, provider.default_opus_model())
-        .replace(
-            ADVERSARIAL_MODEL_PLACEHOLDER,
-            provider.default_adversarial_model(),
+    };
+    let unknown = placeholders.unknown(&content);
+    if !unknown.is_empty() {
+        let names: Vec<String> = unknown
+            .iter()
+            .map(|name| format!("{{{{{name}}}}}"))
+            .collect();
+        eprintln!(
+            "{}",
+            format!(
+                "{}: no value for {}; left as is",
+                path.display(),
+                names.join(", ")
+            )
+            .yellow()
         );
+    }
+    let Some(updated) = rewrite(&content)? else {
+        return Ok(false);
+    };
 
     fs::write(path, updated)?;
     Ok(true)
 }
 
-/// Update all model placeholders in OpenCode agent/command files.
-/// Files use {{SONNET_MODEL}}, {{OPUS_MODEL}}, and {{ADVERSARIAL_MODEL}} placeholders.
-fn update_opencode_models(dest_dir: &Path, provider: &OpenCodeProvider) -> Result<usize> {
-    let dirs = ["agents", "commands"];
+/// `content` of the file at `path` with placeholders filled in, or `None`
+/// if nothing changed. Placeholders win for every file type; YAML and TOML
+/// files without placeholders get their model keys rewritten.
+fn rewrite_models(
+    path: &Path,
+    content: &str,
+    placeholders: &PlaceholderSet,
+) -> Result<Option<String>> {
+    if let Some(updated) = placeholders.substitute(content) {
+        return Ok(Some(updated));
+    }
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yml" | "yaml") => {
+            let mut value: serde_yaml::Value = serde_yaml::from_str(content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            if !replace_yaml_models(&mut value, placeholders) {
+                return Ok(None);
+            }
+            Ok(Some(serde_yaml::to_string(&value)?))
+        }
+        Some("toml") => {
+            let mut table: toml::Table = toml::from_str(content)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            if !replace_toml_models(&mut table, placeholders) {
+                return Ok(None);
+            }
+            Ok(Some(toml::to_string_pretty(&table)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Model for a config key, or `None` for keys that don't name one. A plain
+/// `model` keeps its current tier: opus if its value mentions opus,
+/// otherwise sonnet.
+fn model_for_key<'a>(
+    key: &str,
+    current: &str,
+    placeholders: &'a PlaceholderSet,
+) -> Option<&'a str> {
+    match key {
+        "sonnet_model" => placeholders.get("SONNET_MODEL"),
+        "opus_model" => placeholders.get("OPUS_MODEL"),
+        "model" if current.contains("opus") => placeholders.get("OPUS_MODEL"),
+        "model" => placeholders.get("SONNET_MODEL"),
+        _ => None,
+    }
+}
+
+/// Set `current` to the model for `key`, if it names one. Returns
+/// `Some(changed)` for model keys and `None` for everything else.
+fn set_model(key: &str, current: &mut String, placeholders: &PlaceholderSet) -> Option<bool> {
+    let model = model_for_key(key, current, placeholders)?;
+    let changed = current != model;
+    *current = model.to_string();
+    Some(changed)
+}
 
-    dirs.iter()
-        .filter_map(|dir| {
-            let path = dest_dir.join(dir);
-            path.is_dir().then_some(path)
-        })
-        .flat_map(|dir| fs::read_dir(dir).into_iter().flatten().flatten())
-        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "md"))
-        .try_fold(0, |count, entry| {
-            let updated = replace_model_placeholders(&entry.path(), provider)?;
+/// Rewrite model keys at any depth. Returns whether anything changed.
+fn replace_yaml_models(value: &mut serde_yaml::Value, placeholders: &PlaceholderSet) -> bool {
+    let mut changed = false;
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map.iter_mut() {
+                let set = match (key.as_str(), &mut *value) {
+                    (Some(key), serde_yaml::Value::String(current)) => {
+                        set_model(key, current, placeholders)
+                    }
+                    _ => None,
+                };
+                changed |= set.unwrap_or_else(|| replace_yaml_models(value, placeholders));
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                changed |= replace_yaml_models(item, placeholders);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// TOML counterpart of `replace_yaml_models`.
+fn replace_toml_models(table: &mut toml::Table, placeholders: &PlaceholderSet) -> bool {
+    fn walk(value: &mut toml::Value, placeholders: &PlaceholderSet) -> bool {
+        match value {
+            toml::Value::Table(table) => replace_toml_models(table, placeholders),
+            toml::Value::Array(items) => {
+                let mut changed = false;
+                for item in items {
+                    changed |= walk(item, placeholders);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    let mut changed = false;
+    for (key, value) in table.iter_mut() {
+        let set = match value {
+            toml::Value::String(current) => set_model(key, current, placeholders),
+            _ => None,
+        };
+        changed |= set.unwrap_or_else(|| walk(value, placeholders));
+    }
+    changed
+}
+
+fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MODEL_FILE_EXTENSIONS.contains(&ext))
+}
+
+/// Whether `update_opencode_models` rewrites the file at `rel` (relative to
+/// the destination directory): a model file anywhere under `agents/` or
+/// `commands/`.
+fn has_model_placeholders(rel: &Path) -> bool {
+    let mut parts = rel.components();
+    let dir = parts.next().map(|c| c.as_os_str());
+    let in_dir = parts.next().is_some();
+    in_dir && dir.is_some_and(|d| d == "agents" || d == "commands") && is_model_file(rel)
+}
+
+/// Fill in placeholders in OpenCode agent/command files, including those
+/// in subdirectories such as `commands/research/` (see
+/// `build_placeholder_set`); YAML and TOML configs may instead set
+/// `model`, `sonnet_model` or `opus_model` keys. Returns how many files
+/// changed.
+fn update_opencode_models(dest_dir: &Path, placeholders: &PlaceholderSet) -> Result<usize> {
+    list_files_at(dest_dir)?
+        .into_iter()
+        .filter(|rel| has_model_placeholders(rel))
+        .try_fold(0, |count, rel| {
+            let updated = apply_placeholders(&dest_dir.join(rel), placeholders)?;
             Ok::<_, anyhow::Error>(count + usize::from(updated))
         })
 }
@@ -541,6 +2279,11 @@ fn update_opencode_models(dest_dir: &Path, provider: &OpenCodeProvider) -> Resul
 mod tests {
     use super::*;
 
+    const QUIET: InstallOptions = InstallOptions {
+        quiet: true,
+        backup: false,
+    };
+
     /// Create `path` (and any missing parent dirs) as an empty stub file.
     fn touch(path: &Path) {
         if let Some(parent) = path.parent() {
@@ -636,6 +2379,51 @@ mod tests {
         }
     }
 
+    /// `dest_display` with its `~`, `%APPDATA%` or `%USERPROFILE%` prefix
+    /// expanded again.
+    fn expand_display(display: &str) -> PathBuf {
+        let display = display.trim_end_matches(SEP);
+        let (base, rest) = if let Some(rest) = display.strip_prefix('~') {
+            (dirs::home_dir().unwrap(), rest)
+        } else if let Some(rest) = display.strip_prefix("%APPDATA%") {
+            (dirs::config_dir().unwrap(), rest)
+        } else if let Some(rest) = display.strip_prefix("%USERPROFILE%") {
+            (dirs::home_dir().unwrap(), rest)
+        } else {
+            return PathBuf::from(display);
+        };
+        base.join(rest.trim_start_matches(SEP))
+    }
+
+    #[test]
+    fn dest_display_matches_dest_dir() {
+        for tool in AgentTool::ALL {
+            assert_eq!(
+                expand_display(&tool.dest_display()),
+                tool.dest_dir().unwrap(),
+                "{tool}"
+            );
+        }
+    }
+
+    #[test]
+    fn vscode_user_dir_prefers_installed_variant() {
+        let config = tempfile::tempdir().unwrap();
+        let stable = config.path().join("Code").join("User");
+        assert_eq!(vscode_user_dir(config.path()), stable);
+
+        let codium = config.path().join("VSCodium").join("User");
+        fs::create_dir_all(&codium).unwrap();
+        assert_eq!(vscode_user_dir(config.path()), codium);
+
+        let insiders = config.path().join("Code - Insiders").join("User");
+        fs::create_dir_all(&insiders).unwrap();
+        assert_eq!(vscode_user_dir(config.path()), insiders);
+
+        fs::create_dir_all(&stable).unwrap();
+        assert_eq!(vscode_user_dir(config.path()), stable);
+    }
+
     #[test]
     fn dest_display_claude_contains_claude_dir() {
         let display = AgentTool::Claude.dest_display();
@@ -667,6 +2455,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dest_display_windsurf_contains_codeium_dir() {
+        let display = AgentTool::Windsurf.dest_display();
+        assert!(
+            display.contains(&format!(".codeium{SEP}windsurf")),
+            "Expected .codeium{}windsurf in: {}",
+            SEP,
+            display
+        );
+    }
+
+    #[test]
+    fn agent_tool_serializes_to_lowercase() {
+        let json = serde_json::to_string(&AgentTool::Windsurf).unwrap();
+        assert_eq!(json, "\"windsurf\"");
+        let tool: AgentTool = serde_json::from_str("\"windsurf\"").unwrap();
+        assert_eq!(tool, AgentTool::Windsurf);
+        assert!(AgentTool::ALL.contains(&AgentTool::Windsurf));
+    }
+
+    #[test]
+    fn combined_sha_joins_distinct_shas() {
+        let sha = |s: &str| Some(s.to_string());
+        assert_eq!(combined_sha([sha("abc")]), sha("abc"));
+        assert_eq!(
+            combined_sha([sha("abc"), sha("def"), sha("abc")]),
+            sha("abc+def")
+        );
+        assert_eq!(combined_sha([sha("v1.0"), sha("v1.0")]), sha("v1.0"));
+        assert_eq!(combined_sha([sha("abc"), None]), None);
+        assert_eq!(combined_sha(Vec::new()), None);
+    }
+
+    #[test]
+    fn parse_directory_entries_reports_missing_tool_dir() {
+        let json = r#"{"message": "Not Found", "documentation_url": "https://docs.github.com", "status": "404"}"#;
+        let err = parse_directory_entries(json, "windsurf").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Agent files for 'windsurf' are not available on GitHub (Not Found)"
+        );
+
+        let json = r#"[{"name": "a.md", "path": "windsurf/a.md", "type": "file", "download_url": "https://x/a.md"}]"#;
+        let entries = parse_directory_entries(json, "windsurf").unwrap();
+        assert_eq!(entries[0].path, "windsurf/a.md");
+    }
+
     #[test]
     fn opencode_provider_serializes_to_kebab_case() {
         let json = serde_json::to_string(&OpenCodeProvider::GithubCopilot).unwrap();
@@ -679,6 +2514,35 @@ mod tests {
         assert_eq!(json, "\"abacus\"");
     }
 
+    #[test]
+    fn custom_opencode_provider_round_trips_and_fills_placeholders() {
+        let provider = OpenCodeProvider::Custom {
+            prefix: "gateway".to_string(),
+            sonnet_model: "gateway/sonnet-x".to_string(),
+            opus_model: "gateway/opus-x".to_string(),
+        };
+        let json = serde_json::to_value(&provider).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"custom": {
+                "prefix": "gateway",
+                "sonnetModel": "gateway/sonnet-x",
+                "opusModel": "gateway/opus-x",
+            }})
+        );
+        assert_eq!(
+            serde_json::from_value::<OpenCodeProvider>(json).unwrap(),
+            provider
+        );
+        assert_eq!(provider.to_string(), "custom (gateway)");
+
+        let set = build_placeholder_set(None, &provider);
+        assert_eq!(set.get("PROVIDER"), Some("gateway"));
+        assert_eq!(set.get("SONNET_MODEL"), Some("gateway/sonnet-x"));
+        assert_eq!(set.get("OPUS_MODEL"), Some("gateway/opus-x"));
+        assert_eq!(set.get("ADVERSARIAL_MODEL"), Some("gateway/opus-x"));
+    }
+
     #[test]
     fn opencode_provider_deserializes_from_kebab_case() {
         let provider: OpenCodeProvider = serde_json::from_str("\"github-copilot\"").unwrap();
@@ -744,81 +2608,308 @@ mod tests {
             "anthropic/claude-opus-4-5"
         );
         assert_eq!(
-            OpenCodeProvider::Abacus.default_adversarial_model(),
-            "abacus/gpt-5.3-codex-xhigh"
+            OpenCodeProvider::Abacus.default_adversarial_model(),
+            "abacus/gpt-5.3-codex-xhigh"
+        );
+    }
+
+    #[test]
+    fn opencode_provider_prefixes() {
+        assert_eq!(
+            OpenCodeProvider::GithubCopilot.provider_prefix(),
+            "github-copilot"
+        );
+        assert_eq!(OpenCodeProvider::Anthropic.provider_prefix(), "anthropic");
+        assert_eq!(OpenCodeProvider::Abacus.provider_prefix(), "abacus");
+    }
+
+    #[test]
+    fn apply_placeholders_replaces_sonnet() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_sonnet_placeholder");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let file_path = temp_dir.join("test_agent.md");
+
+        let content = "---\nmodel: {{SONNET_MODEL}}\n---\n# Agent";
+        fs::write(&file_path, content).unwrap();
+
+        let updated = apply_placeholders(
+            &file_path,
+            &build_placeholder_set(None, &OpenCodeProvider::GithubCopilot),
+        )
+        .unwrap();
+        assert!(updated);
+
+        let result = fs::read_to_string(&file_path).unwrap();
+        assert!(result.contains("model: github-copilot/claude-sonnet-4.5"));
+        assert!(!result.contains("{{SONNET_MODEL}}"));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn apply_placeholders_replaces_opus() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_opus_placeholder");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let file_path = temp_dir.join("research.md");
+
+        let content = "---\nmodel: {{OPUS_MODEL}}\n---\n# Research";
+        fs::write(&file_path, content).unwrap();
+
+        let updated = apply_placeholders(
+            &file_path,
+            &build_placeholder_set(None, &OpenCodeProvider::Abacus),
+        )
+        .unwrap();
+        assert!(updated);
+
+        let result = fs::read_to_string(&file_path).unwrap();
+        assert!(result.contains("model: abacus/claude-opus-4-6"));
+        assert!(!result.contains("{{OPUS_MODEL}}"));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn apply_placeholders_replaces_adversarial() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_adversarial_placeholder");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let file_path = temp_dir.join("adversarial-reviewer.md");
+
+        let content = "---\nmodel: {{ADVERSARIAL_MODEL}}\n---\n# Adversarial";
+        fs::write(&file_path, content).unwrap();
+
+        let updated = apply_placeholders(
+            &file_path,
+            &build_placeholder_set(None, &OpenCodeProvider::Abacus),
+        )
+        .unwrap();
+        assert!(updated);
+
+        let result = fs::read_to_string(&file_path).unwrap();
+        assert!(result.contains("model: abacus/gpt-5.3-codex-xhigh"));
+        assert!(!result.contains("{{ADVERSARIAL_MODEL}}"));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn apply_placeholders_rewrites_yaml_model_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("reviewer.yaml");
+        fs::write(
+            &file_path,
+            "name: reviewer\nmodel: anthropic/claude-opus-4-1\nsteps:\n  - sonnet_model: old\n",
+        )
+        .unwrap();
+
+        let provider = OpenCodeProvider::GithubCopilot;
+        assert!(apply_placeholders(&file_path, &build_placeholder_set(None, &provider)).unwrap());
+
+        let value: serde_yaml::Value =
+            serde_yaml::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+        assert_eq!(value["name"].as_str(), Some("reviewer"));
+        assert_eq!(value["model"].as_str(), Some(provider.default_opus_model()));
+        assert_eq!(
+            value["steps"][0]["sonnet_model"].as_str(),
+            Some(provider.default_sonnet_model())
+        );
+
+        // Already up to date: nothing to rewrite.
+        assert!(!apply_placeholders(&file_path, &build_placeholder_set(None, &provider)).unwrap());
+    }
+
+    #[test]
+    fn apply_placeholders_fills_yaml_template_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("agent.yml");
+        fs::write(&file_path, "# keep this comment\nmodel: {{SONNET_MODEL}}\n").unwrap();
+
+        let provider = OpenCodeProvider::Anthropic;
+        assert!(apply_placeholders(&file_path, &build_placeholder_set(None, &provider)).unwrap());
+        let result = fs::read_to_string(&file_path).unwrap();
+        assert!(result.starts_with("# keep this comment\n"));
+        assert!(result.contains(provider.default_sonnet_model()));
+    }
+
+    #[test]
+    fn apply_placeholders_rewrites_toml_model_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("agent.toml");
+        fs::write(
+            &file_path,
+            "title = \"plan\"\nmodel = \"x/sonnet\"\n\n[tiers]\nopus_model = \"old\"\n",
+        )
+        .unwrap();
+
+        let provider = OpenCodeProvider::Abacus;
+        assert!(apply_placeholders(&file_path, &build_placeholder_set(None, &provider)).unwrap());
+
+        let table: toml::Table = toml::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+        assert_eq!(table["title"].as_str(), Some("plan"));
+        assert_eq!(
+            table["model"].as_str(),
+            Some(provider.default_sonnet_model())
+        );
+        assert_eq!(
+            table["tiers"]["opus_model"].as_str(),
+            Some(provider.default_opus_model())
+        );
+    }
+
+    #[test]
+    fn apply_placeholders_fills_toml_template_placeholders() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("agent.toml");
+        fs::write(&file_path, "opus_model = \"{{OPUS_MODEL}}\"\n").unwrap();
+
+        let provider = OpenCodeProvider::Anthropic;
+        assert!(apply_placeholders(&file_path, &build_placeholder_set(None, &provider)).unwrap());
+        let result = fs::read_to_string(&file_path).unwrap();
+        assert_eq!(
+            result,
+            format!("opus_model = \"{}\"\n", provider.default_opus_model())
+        );
+    }
+
+    #[test]
+    fn placeholder_set_substitutes_in_a_single_pass() {
+        let mut set = PlaceholderSet::default();
+        set.insert("USERNAME", "{{OPUS_MODEL}}");
+        set.insert("OPUS_MODEL", "opus");
+
+        assert_eq!(
+            set.substitute("by {{USERNAME}} on {{OPUS_MODEL}}, {{UNKNOWN}} {{"),
+            Some("by {{OPUS_MODEL}} on opus, {{UNKNOWN}} {{".to_string())
         );
+        assert_eq!(set.substitute("{{UNKNOWN}} only"), None);
     }
 
     #[test]
-    fn opencode_provider_prefixes() {
+    fn build_placeholder_set_reads_thoughts_config() {
+        let provider = OpenCodeProvider::Anthropic;
+        let bare = build_placeholder_set(None, &provider);
         assert_eq!(
-            OpenCodeProvider::GithubCopilot.provider_prefix(),
-            "github-copilot"
+            bare.get("SONNET_MODEL"),
+            Some(provider.default_sonnet_model())
         );
-        assert_eq!(OpenCodeProvider::Anthropic.provider_prefix(), "anthropic");
-        assert_eq!(OpenCodeProvider::Abacus.provider_prefix(), "abacus");
+        assert_eq!(bare.get("PROVIDER"), Some("anthropic"));
+        assert!(bare.get("DATE").is_some());
+        assert!(bare.get("USERNAME").is_none());
+
+        let thoughts = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(crate::config::GitConfig {
+                thoughts_repo: "~/thoughts".to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            ..Default::default()
+        };
+        let set = build_placeholder_set(Some(&thoughts), &provider);
+        assert_eq!(set.get("USER"), Some("alice"));
+        assert_eq!(set.get("USERNAME"), Some("alice"));
+        assert_eq!(set.get("THOUGHTS_DIR"), Some("~/thoughts"));
+        assert_eq!(set.get("THOUGHTS_REPO"), Some("~/thoughts"));
+        assert_eq!(set.get("REPOS_DIR"), Some("repos"));
+        assert_eq!(set.get("GLOBAL_DIR"), Some("global"));
+
+        let paths_only = thoughts_placeholder_set(Some(&thoughts));
+        assert_eq!(paths_only.get("REPOS_DIR"), Some("repos"));
+        assert!(paths_only.get("SONNET_MODEL").is_none());
     }
 
     #[test]
-    fn replace_model_placeholders_replaces_sonnet() {
-        let temp_dir = std::env::temp_dir().join("hyprlayer_test_sonnet_placeholder");
-        fs::create_dir_all(&temp_dir).unwrap();
-        let file_path = temp_dir.join("test_agent.md");
-
-        let content = "---\nmodel: {{SONNET_MODEL}}\n---\n# Agent";
-        fs::write(&file_path, content).unwrap();
-
-        let updated =
-            replace_model_placeholders(&file_path, &OpenCodeProvider::GithubCopilot).unwrap();
-        assert!(updated);
-
-        let result = fs::read_to_string(&file_path).unwrap();
-        assert!(result.contains("model: github-copilot/claude-sonnet-4.5"));
-        assert!(!result.contains("{{SONNET_MODEL}}"));
+    fn placeholder_set_lists_unknown_placeholders() {
+        let set = thoughts_placeholder_set(None);
+        assert_eq!(
+            set.unknown("{{DATE}} {{REPOS_DIR}} {{ .Values }} {{lower}} {{REPOS_DIR}} {{A_1}}"),
+            BTreeSet::from(["A_1".to_string(), "REPOS_DIR".to_string()])
+        );
+    }
 
-        fs::remove_dir_all(&temp_dir).ok();
+    fn path_thoughts() -> ThoughtsConfig {
+        ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(crate::config::GitConfig {
+                thoughts_repo: "~/notes".to_string(),
+                repos_dir: "projects".to_string(),
+                global_dir: "shared-global".to_string(),
+            }),
+            ..Default::default()
+        }
     }
 
     #[test]
-    fn replace_model_placeholders_replaces_opus() {
-        let temp_dir = std::env::temp_dir().join("hyprlayer_test_opus_placeholder");
-        fs::create_dir_all(&temp_dir).unwrap();
-        let file_path = temp_dir.join("research.md");
-
-        let content = "---\nmodel: {{OPUS_MODEL}}\n---\n# Research";
-        fs::write(&file_path, content).unwrap();
-
-        let updated = replace_model_placeholders(&file_path, &OpenCodeProvider::Abacus).unwrap();
-        assert!(updated);
-
-        let result = fs::read_to_string(&file_path).unwrap();
-        assert!(result.contains("model: abacus/claude-opus-4-6"));
-        assert!(!result.contains("{{OPUS_MODEL}}"));
+    fn update_opencode_models_fills_model_and_path_placeholders_together() {
+        let dest = tempfile::tempdir().unwrap();
+        let command = dest.path().join("commands/research.md");
+        fs::create_dir_all(command.parent().unwrap()).unwrap();
+        fs::write(
+            &command,
+            "---\nmodel: {{OPUS_MODEL}}\n---\n\
+             Write to {{THOUGHTS_DIR}}/{{REPOS_DIR}}/app/{{USER}}/research/ \
+             or {{GLOBAL_DIR}}/, keeping {{MYSTERY}}.\n",
+        )
+        .unwrap();
 
-        fs::remove_dir_all(&temp_dir).ok();
+        let thoughts = path_thoughts();
+        let placeholders = build_placeholder_set(Some(&thoughts), &OpenCodeProvider::Anthropic);
+        assert_eq!(
+            update_opencode_models(dest.path(), &placeholders).unwrap(),
+            1
+        );
+        assert_eq!(
+            fs::read_to_string(&command).unwrap(),
+            "---\nmodel: anthropic/claude-opus-4-5\n---\n\
+             Write to ~/notes/projects/app/alice/research/ \
+             or shared-global/, keeping {{MYSTERY}}.\n"
+        );
     }
 
     #[test]
-    fn replace_model_placeholders_replaces_adversarial() {
-        let temp_dir = std::env::temp_dir().join("hyprlayer_test_adversarial_placeholder");
-        fs::create_dir_all(&temp_dir).unwrap();
-        let file_path = temp_dir.join("adversarial-reviewer.md");
-
-        let content = "---\nmodel: {{ADVERSARIAL_MODEL}}\n---\n# Adversarial";
-        fs::write(&file_path, content).unwrap();
-
-        let updated = replace_model_placeholders(&file_path, &OpenCodeProvider::Abacus).unwrap();
-        assert!(updated);
-
-        let result = fs::read_to_string(&file_path).unwrap();
-        assert!(result.contains("model: abacus/gpt-5.3-codex-xhigh"));
-        assert!(!result.contains("{{ADVERSARIAL_MODEL}}"));
-
-        fs::remove_dir_all(&temp_dir).ok();
+    fn install_fills_path_placeholders_for_every_tool() {
+        let checkout = tempfile::tempdir().unwrap();
+        let write = |path: &Path, content: &str| {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            &checkout.path().join("claude/commands/research.md"),
+            "Save to thoughts/{{USER}}/ in {{THOUGHTS_DIR}}/{{REPOS_DIR}}",
+        );
+        write(
+            &checkout.path().join("claude/agents/locator.md"),
+            "model: sonnet",
+        );
+        let dest = tempfile::tempdir().unwrap();
+        // Not installed by hyprlayer, so never rewritten.
+        let mine = dest.path().join("commands/mine.md");
+        write(&mine, "{{USER}}");
+
+        let thoughts = path_thoughts();
+        AgentTool::Claude
+            .install_at(
+                dest.path(),
+                &AgentSource::Dir(checkout.path().to_path_buf()),
+                Some(&thoughts_placeholder_set(Some(&thoughts))),
+                None,
+                InstructionsTarget::Existing,
+                QUIET,
+            )
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.path().join("commands/research.md")).unwrap(),
+            "Save to thoughts/alice/ in ~/notes/projects"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/locator.md")).unwrap(),
+            "model: sonnet"
+        );
+        assert_eq!(fs::read_to_string(&mine).unwrap(), "{{USER}}");
     }
 
     #[test]
-    fn replace_model_placeholders_skips_files_without_placeholders() {
+    fn apply_placeholders_skips_files_without_placeholders() {
         let temp_dir = std::env::temp_dir().join("hyprlayer_test_no_placeholder");
         fs::create_dir_all(&temp_dir).unwrap();
         let file_path = temp_dir.join("no_placeholder.md");
@@ -826,7 +2917,11 @@ mod tests {
         let content = "---\ndescription: No model field\n---\n# Test";
         fs::write(&file_path, content).unwrap();
 
-        let updated = replace_model_placeholders(&file_path, &OpenCodeProvider::Anthropic).unwrap();
+        let updated = apply_placeholders(
+            &file_path,
+            &build_placeholder_set(None, &OpenCodeProvider::Anthropic),
+        )
+        .unwrap();
         assert!(!updated);
 
         let result = fs::read_to_string(&file_path).unwrap();
@@ -864,8 +2959,23 @@ mod tests {
         )
         .unwrap();
 
-        let count = update_opencode_models(&temp_dir, &OpenCodeProvider::GithubCopilot).unwrap();
-        assert_eq!(count, 2); // Only files with placeholders
+        // Command nested a directory down, with a placeholder
+        fs::create_dir_all(commands_dir.join("research")).unwrap();
+        fs::write(
+            commands_dir.join("research/deep.md"),
+            "---\nmodel: {{SONNET_MODEL}}\n---\n# Deep research",
+        )
+        .unwrap();
+
+        // Binary file with a model extension (skipped, not an error)
+        fs::write(commands_dir.join("research/blob.md"), [0xff, 0xfe, 0x00]).unwrap();
+
+        let count = update_opencode_models(
+            &temp_dir,
+            &build_placeholder_set(None, &OpenCodeProvider::GithubCopilot),
+        )
+        .unwrap();
+        assert_eq!(count, 3); // Only files with placeholders
 
         let agent = fs::read_to_string(agents_dir.join("analyzer.md")).unwrap();
         assert!(agent.contains("model: github-copilot/claude-sonnet-4.5"));
@@ -873,6 +2983,13 @@ mod tests {
         let research = fs::read_to_string(commands_dir.join("research.md")).unwrap();
         assert!(research.contains("model: github-copilot/claude-opus-4.5"));
 
+        let nested = fs::read_to_string(commands_dir.join("research/deep.md")).unwrap();
+        assert!(nested.contains("model: github-copilot/claude-sonnet-4.5"));
+        assert_eq!(
+            fs::read(commands_dir.join("research/blob.md")).unwrap(),
+            [0xff, 0xfe, 0x00]
+        );
+
         fs::remove_dir_all(&temp_dir).ok();
     }
 
@@ -893,7 +3010,11 @@ mod tests {
         )
         .unwrap();
 
-        let count = update_opencode_models(&temp_dir, &OpenCodeProvider::Abacus).unwrap();
+        let count = update_opencode_models(
+            &temp_dir,
+            &build_placeholder_set(None, &OpenCodeProvider::Abacus),
+        )
+        .unwrap();
         assert_eq!(count, 2);
 
         let adversarial = fs::read_to_string(agents_dir.join("adversarial-reviewer.md")).unwrap();
@@ -926,7 +3047,7 @@ mod tests {
             fs::create_dir_all(&agents_dir).unwrap();
             fs::write(agents_dir.join("adversarial-reviewer.md"), &template_body).unwrap();
 
-            update_opencode_models(&temp_dir, provider).unwrap();
+            update_opencode_models(&temp_dir, &build_placeholder_set(None, provider)).unwrap();
 
             let resolved = fs::read_to_string(agents_dir.join("adversarial-reviewer.md")).unwrap();
             assert!(
@@ -946,6 +3067,727 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compare_with_remote_classifies_files() {
+        let dest = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dest.path().join("agents")).unwrap();
+        fs::write(dest.path().join("agents/same.md"), "same\n").unwrap();
+        fs::write(dest.path().join("agents/edited.md"), "mine\n").unwrap();
+        fs::write(dest.path().join("agents/retired.md"), "old\n").unwrap();
+        fs::write(dest.path().join("settings.json"), "{}").unwrap();
+
+        let remote = vec![
+            (PathBuf::from("agents/same.md"), b"same\n".to_vec()),
+            (PathBuf::from("agents/edited.md"), b"theirs\n".to_vec()),
+            (PathBuf::from("agents/new.md"), b"new\n".to_vec()),
+        ];
+        let diffs = compare_with_remote(dest.path(), remote).unwrap();
+        let summary: Vec<(&str, DiffStatus)> = diffs
+            .iter()
+            .map(|d| (d.path.to_str().unwrap(), d.status))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("agents/edited.md", DiffStatus::Modified),
+                ("agents/new.md", DiffStatus::Added),
+                ("agents/retired.md", DiffStatus::Removed),
+                ("agents/same.md", DiffStatus::Unchanged),
+            ]
+        );
+        assert_eq!(diffs[0].local_content.as_deref(), Some("mine\n"));
+        assert_eq!(diffs[0].remote_content.as_deref(), Some("theirs\n"));
+        assert!(diffs[1].local_content.is_none());
+    }
+
+    #[test]
+    fn uninstall_removes_only_manifest_files() {
+        let dest = tempfile::tempdir().unwrap();
+        let touch = |path: &str| {
+            let path = dest.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "x").unwrap();
+        };
+        touch("agents/a.md");
+        touch("agents/mine.md");
+        touch("commands/nested/c.md");
+        let mut manifest = Manifest::default();
+        for path in ["agents/a.md", "agents/gone.md", "commands/nested/c.md"] {
+            manifest
+                .files
+                .insert(PathBuf::from(path), "sha".to_string());
+        }
+        manifest.save(&AgentTool::Claude, dest.path()).unwrap();
+
+        let removed = AgentTool::Claude.uninstall_at(dest.path()).unwrap();
+        assert_eq!(
+            removed,
+            [
+                PathBuf::from("agents/a.md"),
+                PathBuf::from("commands/nested/c.md")
+            ]
+        );
+        assert!(dest.path().join("agents/mine.md").exists());
+        assert!(!dest.path().join("commands").exists());
+        assert!(!dest.path().join(MANIFEST_FILE).exists());
+
+        // Without a manifest nothing is removed.
+        let err = AgentTool::Claude.uninstall_at(dest.path()).unwrap_err();
+        assert!(err.to_string().contains("No install manifest"));
+        assert!(dest.path().join("agents/mine.md").exists());
+    }
+
+    #[test]
+    fn install_from_local_dir_copies_files_and_fills_placeholders() {
+        let checkout = tempfile::tempdir().unwrap();
+        let write = |rel: &str, content: &str| {
+            let path = checkout.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "opencode/agents/analyzer.md",
+            "---\nmodel: {{SONNET_MODEL}}\n---\n",
+        );
+        write("opencode/commands/commit.md", "# Commit");
+        write("codex/prompts/commit.md", "# Commit");
+        write("codex/AGENTS.md", "Use thoughts/.");
+
+        let dest = tempfile::tempdir().unwrap();
+        let source = AgentSource::Dir(checkout.path().to_path_buf());
+        let placeholders = build_placeholder_set(None, &OpenCodeProvider::GithubCopilot);
+        let sha = AgentTool::OpenCode
+            .install_at(
+                dest.path(),
+                &source,
+                Some(&placeholders),
+                None,
+                InstructionsTarget::Global,
+                QUIET,
+            )
+            .unwrap();
+        assert_eq!(sha, None);
+        assert_eq!(
+            list_files_at(dest.path()).unwrap(),
+            [
+                PathBuf::from(MANIFEST_FILE),
+                PathBuf::from("agents/analyzer.md"),
+                PathBuf::from("commands/commit.md"),
+            ]
+        );
+        let analyzer = fs::read_to_string(dest.path().join("agents/analyzer.md")).unwrap();
+        assert!(analyzer.contains("model: github-copilot/claude-sonnet-4.5"));
+        let manifest = Manifest::load(dest.path());
+        assert_eq!(manifest.files.len(), 2);
+        // Local sources have no ref to record.
+        assert_eq!(manifest.git_ref, None);
+        assert_eq!(manifest.tool.as_deref(), Some("opencode"));
+        assert!(manifest.installed_at.is_some());
+
+        let codex_dest = tempfile::tempdir().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        AgentTool::Codex
+            .install_at(
+                codex_dest.path(),
+                &source,
+                None,
+                None,
+                InstructionsTarget::Project(project.path()),
+                QUIET,
+            )
+            .unwrap();
+        assert!(codex_dest.path().join("prompts/commit.md").is_file());
+        let agents_md = fs::read_to_string(project.path().join("AGENTS.md")).unwrap();
+        assert!(agents_md.contains("Use thoughts/."));
+    }
+
+    #[test]
+    fn install_project_writes_into_the_repository_and_records_the_mode() {
+        let checkout = tempfile::tempdir().unwrap();
+        let path = checkout.path().join("opencode/agents/analyzer.md");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "---\nmodel: {{SONNET_MODEL}}\n---\n").unwrap();
+
+        let root = tempfile::tempdir().unwrap();
+        let source = AgentSource::Dir(checkout.path().to_path_buf());
+        let placeholders = build_placeholder_set(None, &OpenCodeProvider::Anthropic);
+        AgentTool::OpenCode
+            .install_project(root.path(), &source, Some(&placeholders), None, QUIET)
+            .unwrap();
+        let dest = root.path().join(".opencode");
+        let analyzer = fs::read_to_string(dest.join("agents/analyzer.md")).unwrap();
+        assert!(!analyzer.contains("{{SONNET_MODEL}}"));
+        assert_eq!(Manifest::load(&dest).mode, Some(InstallMode::Project));
+
+        assert!(AgentTool::Claude.supports_project());
+        assert!(AgentTool::Codex.supports_project());
+        assert!(!AgentTool::Copilot.supports_project());
+        let err = AgentTool::Copilot
+            .install_project(root.path(), &source, None, None, QUIET)
+            .unwrap_err();
+        assert!(err.to_string().contains("no project directory"));
+    }
+
+    #[test]
+    fn reinstall_backs_up_locally_edited_files() {
+        let checkout = tempfile::tempdir().unwrap();
+        let write = |rel: &str, content: &str| {
+            let path = checkout.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "opencode/agents/analyzer.md",
+            "---\nmodel: {{SONNET_MODEL}}\n---\n",
+        );
+        write("opencode/commands/commit.md", "# Commit");
+
+        let dest = tempfile::tempdir().unwrap();
+        let source = AgentSource::Dir(checkout.path().to_path_buf());
+        let placeholders = build_placeholder_set(None, &OpenCodeProvider::Anthropic);
+        let install = |backup| {
+            AgentTool::OpenCode
+                .install_at(
+                    dest.path(),
+                    &source,
+                    Some(&placeholders),
+                    None,
+                    InstructionsTarget::Global,
+                    InstallOptions {
+                        quiet: true,
+                        backup,
+                    },
+                )
+                .unwrap()
+        };
+        install(true);
+        assert!(!dest.path().join(BACKUP_DIR).exists());
+
+        fs::write(dest.path().join("commands/commit.md"), "# My commit").unwrap();
+        install(true);
+        // The analyzer's filled-in placeholders don't count as a change.
+        let backups = agent_backup::list_backups(dest.path()).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].files, [PathBuf::from("commands/commit.md")]);
+        assert_eq!(
+            fs::read_to_string(backups[0].path.join("commands/commit.md")).unwrap(),
+            "# My commit"
+        );
+        assert!(
+            !list_files_at(dest.path())
+                .unwrap()
+                .iter()
+                .any(|p| p.starts_with(BACKUP_DIR))
+        );
+
+        fs::write(dest.path().join("commands/commit.md"), "# Mine again").unwrap();
+        install(false);
+        assert_eq!(agent_backup::list_backups(dest.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn locate_tool_dir_looks_inside_a_tarball_wrapper() {
+        let checkout = tempfile::tempdir().unwrap();
+        touch(
+            &checkout
+                .path()
+                .join("BrightBlock-hyprlayer-cli-abc123/claude/agents/a.md"),
+        );
+        assert_eq!(
+            locate_tool_dir(checkout.path(), "claude").unwrap(),
+            checkout
+                .path()
+                .join("BrightBlock-hyprlayer-cli-abc123/claude")
+        );
+        assert!(locate_tool_dir(checkout.path(), "codex").is_err());
+    }
+
+    #[test]
+    fn extract_tarball_keeps_only_the_tool_dir() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, content) in [
+            ("BrightBlock-hyprlayer-cli-abc123/claude/agents/a.md", "# A"),
+            (
+                "BrightBlock-hyprlayer-cli-abc123/claude/commands/b.md",
+                "# B",
+            ),
+            (
+                "BrightBlock-hyprlayer-cli-abc123/opencode/agents/c.md",
+                "# C",
+            ),
+            ("BrightBlock-hyprlayer-cli-abc123/src/main.rs", ""),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, content.as_bytes())
+                .unwrap();
+        }
+        let tarball = builder.into_inner().unwrap().finish().unwrap();
+
+        let extracted = extract_tarball(&tarball[..], Some("claude")).unwrap();
+        let unpacked = list_files_at(extracted.path());
+        let dest = tempfile::tempdir().unwrap();
+        let installed = AgentTool::Claude.install_from_checkout(
+            dest.path(),
+            extracted.path(),
+            Some("v1.2.3"),
+            None,
+            InstructionsTarget::Global,
+            QUIET,
+        );
+        let extracted_path = extracted.path().to_path_buf();
+        drop(extracted);
+        assert!(!extracted_path.exists());
+
+        assert_eq!(
+            unpacked.unwrap(),
+            [
+                PathBuf::from("BrightBlock-hyprlayer-cli-abc123/claude/agents/a.md"),
+                PathBuf::from("BrightBlock-hyprlayer-cli-abc123/claude/commands/b.md"),
+            ]
+        );
+        installed.unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/a.md")).unwrap(),
+            "# A"
+        );
+        // Git's blob SHA for "# A", as the Contents API would list it.
+        let manifest = Manifest::load(dest.path());
+        assert_eq!(
+            manifest.files[Path::new("agents/a.md")],
+            git2::Oid::hash_object(git2::ObjectType::Blob, b"# A")
+                .unwrap()
+                .to_string()
+        );
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.git_ref.as_deref(), Some("v1.2.3"));
+
+        assert!(extract_tarball(&b"not a tarball"[..], Some("claude")).is_err());
+    }
+
+    #[test]
+    fn download_files_collects_failures_and_keeps_going() {
+        use crate::http::test_server::{json_response, serve};
+        let (base, server) = serve(vec![
+            json_response("404 Not Found", r#"{"message":"Not Found"}"#),
+            json_response("200 OK", "# B"),
+        ]);
+        let file = |name: &str| RemoteFile {
+            path: PathBuf::from(format!("agents/{name}")),
+            url: format!("{base}/{name}"),
+            sha: git_blob_sha(b"# B").unwrap(),
+        };
+        let files = [file("a.md"), file("b.md")];
+        let dest = tempfile::tempdir().unwrap();
+
+        let failed = download_files(&files, dest.path(), &FileProgress::new(files.len(), true));
+        server.join().unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, PathBuf::from("agents/a.md"));
+        assert!(!dest.path().join("agents/a.md").exists());
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/b.md")).unwrap(),
+            "# B"
+        );
+
+        let manifest = Manifest::for_files(&files, "v1.0.0").without(&failed);
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            [&PathBuf::from("agents/b.md")]
+        );
+        let message = failed_downloads_error(&AgentTool::Claude, 2, &failed).to_string();
+        assert!(message.starts_with("1 of 2 Claude Code agent files failed to download:"));
+        assert!(message.contains("agents/a.md: HTTP 404"));
+    }
+
+    /// Run `f` with every GitHub URL on this thread pointing at `base`.
+    fn with_github<T>(base: &str, f: impl FnOnce() -> T) -> T {
+        GITHUB_OVERRIDE.set(Some(base.to_string()));
+        let result = f();
+        GITHUB_OVERRIDE.set(None);
+        result
+    }
+
+    /// A Contents API entry: a file served from `{base}/raw/{path}` when
+    /// there's `content`, otherwise a directory.
+    fn github_entry(base: &str, path: &str, content: Option<&str>) -> serde_json::Value {
+        let name = path.rsplit('/').next().unwrap();
+        match content {
+            Some(content) => serde_json::json!({
+                "name": name,
+                "path": path,
+                "type": "file",
+                "download_url": format!("{base}/raw/{path}"),
+                "sha": git_blob_sha(content.as_bytes()).unwrap(),
+            }),
+            None => serde_json::json!({
+                "name": name,
+                "path": path,
+                "type": "dir",
+                "download_url": null,
+            }),
+        }
+    }
+
+    fn install_from_github(dest: &Path, placeholders: &PlaceholderSet) -> Result<Option<String>> {
+        AgentTool::OpenCode.install_at(
+            dest,
+            &AgentSource::GitHub,
+            Some(placeholders),
+            Some("v9.9.9"),
+            InstructionsTarget::Existing,
+            QUIET,
+        )
+    }
+
+    #[test]
+    fn install_from_github_walks_nested_directories() {
+        use crate::http::test_server::{json_response, serve_with};
+        let deep = "---\nmodel: {{OPUS_MODEL}}\n---\n# Deep research\n";
+        let plan = "---\nmodel: {{SONNET_MODEL}}\n---\n# Plan\n";
+        let readme = "# OpenCode agents\n";
+        let (base, server) = serve_with(|base| {
+            let listing = |entries: &[serde_json::Value]| {
+                json_response("200 OK", &serde_json::to_string(entries).unwrap())
+            };
+            vec![
+                json_response("404 Not Found", "Not Found"),
+                listing(&[
+                    github_entry(base, "opencode/commands", None),
+                    github_entry(base, "opencode/README.md", Some(readme)),
+                ]),
+                listing(&[
+                    github_entry(base, "opencode/commands/research", None),
+                    github_entry(base, "opencode/commands/plan.md", Some(plan)),
+                ]),
+                listing(&[github_entry(
+                    base,
+                    "opencode/commands/research/deep.md",
+                    Some(deep),
+                )]),
+                json_response("200 OK", deep),
+                json_response("200 OK", plan),
+                json_response("200 OK", readme),
+            ]
+        });
+        let dest = tempfile::tempdir().unwrap();
+        let placeholders = build_placeholder_set(None, &OpenCodeProvider::Anthropic);
+
+        let sha = with_github(&base, || install_from_github(dest.path(), &placeholders)).unwrap();
+        let requests = server.join().unwrap();
+        assert_eq!(sha.as_deref(), Some("v9.9.9"));
+        let contents = "/repos/BrightBlock/hyprlayer-cli/contents/opencode";
+        assert_eq!(
+            requests.iter().map(|r| r.path.as_str()).collect::<Vec<_>>(),
+            [
+                "/BrightBlock/hyprlayer-cli/tar.gz/v9.9.9",
+                &format!("{contents}?ref=v9.9.9"),
+                &format!("{contents}/commands?ref=v9.9.9"),
+                &format!("{contents}/commands/research?ref=v9.9.9"),
+                "/raw/opencode/commands/research/deep.md",
+                "/raw/opencode/commands/plan.md",
+                "/raw/opencode/README.md",
+            ]
+        );
+
+        assert_eq!(
+            list_files_at(dest.path()).unwrap(),
+            [
+                PathBuf::from(MANIFEST_FILE),
+                PathBuf::from("README.md"),
+                PathBuf::from("commands/plan.md"),
+                PathBuf::from("commands/research/deep.md"),
+            ]
+        );
+        let deep = fs::read_to_string(dest.path().join("commands/research/deep.md")).unwrap();
+        assert!(deep.contains("model: anthropic/claude-opus-4-5"));
+        let plan = fs::read_to_string(dest.path().join("commands/plan.md")).unwrap();
+        assert!(plan.contains("model: anthropic/claude-sonnet-4-5"));
+        let manifest = Manifest::load(dest.path());
+        assert_eq!(manifest.git_ref.as_deref(), Some("v9.9.9"));
+        assert_eq!(manifest.files.len(), 3);
+    }
+
+    #[test]
+    fn install_from_github_reports_a_missing_tool_directory() {
+        use crate::http::test_server::{json_response, serve};
+        let (base, server) = serve(vec![
+            json_response("404 Not Found", "Not Found"),
+            json_response(
+                "404 Not Found",
+                r#"{"message": "Not Found", "documentation_url": "https://docs.github.com"}"#,
+            ),
+        ]);
+        let dest = tempfile::tempdir().unwrap();
+        let placeholders = build_placeholder_set(None, &OpenCodeProvider::Anthropic);
+
+        let err =
+            with_github(&base, || install_from_github(dest.path(), &placeholders)).unwrap_err();
+        server.join().unwrap();
+        let err = err.to_string();
+        assert!(
+            err.starts_with(
+                "Agent files for 'opencode' are not available on GitHub at v9.9.9; check the ref and path (HTTP 404 from "
+            ),
+            "{err}"
+        );
+        assert!(err.contains(&format!("{base}/repos/")), "{err}");
+        assert!(err.ends_with(": Not Found)"), "{err}");
+        assert!(!dest.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn has_updates_since_compares_the_ref_with_master() {
+        use crate::http::test_server::{json_response, serve};
+        let commit = |sha: &str| json_response("200 OK", &format!(r#"[{{"sha":"{sha}"}}]"#));
+        let (base, server) = serve(vec![
+            commit("aaa"),
+            commit("bbb"),
+            commit("aaa"),
+            commit("aaa"),
+            json_response(
+                "422 Unprocessable Entity",
+                r#"{"message":"No commit found"}"#,
+            ),
+        ]);
+
+        let checks: Vec<Option<bool>> = with_github(&base, || {
+            (0..3)
+                .map(|_| AgentTool::Claude.has_updates_since("v1.0.0"))
+                .collect()
+        });
+        let requests = server.join().unwrap();
+        assert_eq!(checks, [Some(true), Some(false), None]);
+        assert_eq!(
+            requests[1].path,
+            "/repos/BrightBlock/hyprlayer-cli/commits?path=claude&sha=master&per_page=1"
+        );
+    }
+
+    #[test]
+    fn git_blob_sha_matches_git() {
+        // `git hash-object` of an empty file and of "hello\n".
+        assert_eq!(
+            git_blob_sha(b"").unwrap(),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            git_blob_sha(b"hello\n").unwrap(),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
+
+    #[test]
+    fn download_files_retries_and_reports_corrupt_files() {
+        use crate::http::test_server::{json_response, serve};
+        let portal = "<html>Please log in</html>";
+        let (base, server) = serve(vec![
+            json_response("200 OK", "# A\n"),
+            json_response("200 OK", "# A\n\nAll of it\n"),
+            json_response("200 OK", portal),
+            json_response("200 OK", portal),
+            json_response("200 OK", portal),
+        ]);
+        let file = |name: &str| RemoteFile {
+            path: PathBuf::from(format!("agents/{name}")),
+            url: format!("{base}/{name}"),
+            sha: git_blob_sha(b"# A\n\nAll of it\n").unwrap(),
+        };
+        let files = [file("a.md"), file("b.md")];
+        let dest = tempfile::tempdir().unwrap();
+
+        let failed = download_files(&files, dest.path(), &FileProgress::new(files.len(), true));
+        server.join().unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.path().join("agents/a.md")).unwrap(),
+            "# A\n\nAll of it\n"
+        );
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].path, PathBuf::from("agents/b.md"));
+        assert!(!dest.path().join("agents/b.md").exists());
+        let message = failed_downloads_error(&AgentTool::Claude, 2, &failed).to_string();
+        assert!(message.contains(&format!(
+            "agents/b.md: corrupt download: content has blob SHA {} but GitHub lists {}",
+            git_blob_sha(portal.as_bytes()).unwrap(),
+            files[1].sha
+        )));
+    }
+
+    #[test]
+    fn plan_update_fetches_only_changed_and_missing_files() {
+        let dest = tempfile::tempdir().unwrap();
+        touch(&dest.path().join("agents/same.md"));
+        touch(&dest.path().join("agents/changed.md"));
+        touch(&dest.path().join("agents/retired.md"));
+        touch(&dest.path().join("agents/mine.md"));
+
+        let remote_file = |path: &str, sha: &str| RemoteFile {
+            path: PathBuf::from(path),
+            url: format!("https://example.com/{path}"),
+            sha: sha.to_string(),
+        };
+        let old = [
+            remote_file("agents/same.md", "a"),
+            remote_file("agents/changed.md", "b"),
+            remote_file("agents/retired.md", "c"),
+            remote_file("agents/deleted.md", "d"),
+        ];
+        Manifest::for_files(&old, "master")
+            .save(&AgentTool::Claude, dest.path())
+            .unwrap();
+
+        let remote = [
+            remote_file("agents/same.md", "a"),
+            remote_file("agents/changed.md", "b2"),
+            remote_file("agents/deleted.md", "d"),
+            remote_file("agents/new.md", "e"),
+        ];
+        let manifest = Manifest::load(dest.path());
+        let (stale, removed, summary) = plan_update(dest.path(), &manifest, &remote);
+
+        let stale: Vec<&Path> = stale.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(
+            stale,
+            [
+                Path::new("agents/changed.md"),
+                Path::new("agents/deleted.md"),
+                Path::new("agents/new.md")
+            ]
+        );
+        // Only files hyprlayer installed are ever removed.
+        assert_eq!(removed, [PathBuf::from("agents/retired.md")]);
+        assert_eq!(
+            summary.to_string(),
+            "1 updated, 2 added, 1 unchanged, 1 removed"
+        );
+
+        // Without a manifest every installed file is re-fetched.
+        let (stale, removed, summary) = plan_update(dest.path(), &Manifest::default(), &remote);
+        assert_eq!(stale.len(), 4);
+        assert!(removed.is_empty());
+        assert_eq!(summary.to_string(), "2 updated, 2 added, 0 unchanged");
+    }
+
+    #[test]
+    fn has_model_placeholders_matches_update_opencode_models() {
+        assert!(has_model_placeholders(Path::new("agents/reviewer.md")));
+        assert!(has_model_placeholders(Path::new("commands/plan.md")));
+        assert!(has_model_placeholders(Path::new("agents/nested/x.md")));
+        assert!(has_model_placeholders(Path::new(
+            "commands/research/deep.md"
+        )));
+        assert!(!has_model_placeholders(Path::new("agents")));
+        assert!(!has_model_placeholders(Path::new("skills/plan.md")));
+        assert!(!has_model_placeholders(Path::new("agents/notes.txt")));
+        assert!(has_model_placeholders(Path::new("agents/reviewer.yaml")));
+        assert!(has_model_placeholders(Path::new("commands/plan.toml")));
+    }
+
+    #[test]
+    fn list_files_at_walks_dest_dir_and_skips_git() {
+        let dest = tempfile::tempdir().unwrap();
+        touch(&dest.path().join("agents/codebase-locator.md"));
+        touch(&dest.path().join("skills/code_review/SKILL.md"));
+        touch(&dest.path().join("settings.json"));
+        touch(&dest.path().join(".git/HEAD"));
+
+        let files = list_files_at(dest.path()).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("agents/codebase-locator.md"),
+                PathBuf::from("settings.json"),
+                PathBuf::from("skills/code_review/SKILL.md"),
+            ]
+        );
+
+        assert!(
+            list_files_at(&dest.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn installed_files_are_scoped_to_the_manifest() {
+        let dest = tempfile::tempdir().unwrap();
+        let tool = AgentTool::Claude;
+        touch(&dest.path().join("agents/codebase-locator.md"));
+        touch(&dest.path().join("skills/code_review/SKILL.md"));
+        touch(&dest.path().join("settings.json"));
+        touch(&dest.path().join("projects/session.jsonl"));
+
+        // No manifest yet: the bundle's directories only.
+        assert_eq!(
+            tool.installed_files_at(dest.path()).unwrap(),
+            [
+                PathBuf::from("agents/codebase-locator.md"),
+                PathBuf::from("skills/code_review/SKILL.md"),
+            ]
+        );
+
+        let mut manifest = Manifest::default();
+        for path in ["agents/codebase-locator.md", "commands/gone.md"] {
+            manifest
+                .files
+                .insert(PathBuf::from(path), "sha".to_string());
+        }
+        manifest.save(&tool, dest.path()).unwrap();
+        assert_eq!(
+            tool.installed_files_at(dest.path()).unwrap(),
+            [PathBuf::from("agents/codebase-locator.md")]
+        );
+    }
+
+    #[test]
+    fn install_state_distinguishes_missing_partial_and_full_installs() {
+        let dest = tempfile::tempdir().unwrap();
+        let tool = AgentTool::Copilot;
+
+        // VS Code creates these for its own prompts and agents.
+        fs::create_dir_all(dest.path().join("prompts")).unwrap();
+        fs::create_dir_all(dest.path().join("agents")).unwrap();
+        assert_eq!(
+            tool.install_state_at(dest.path()),
+            InstallState::NotInstalled
+        );
+
+        touch(&dest.path().join("prompts/code_review.prompt.md"));
+        assert_eq!(
+            tool.install_state_at(dest.path()),
+            InstallState::Partial {
+                missing: 1,
+                total: 2
+            }
+        );
+
+        touch(&dest.path().join("agents/codebase-locator.agent.md"));
+        assert_eq!(tool.install_state_at(dest.path()), InstallState::Installed);
+
+        // Files the manifest lists count too.
+        let mut manifest = Manifest::default();
+        for path in ["prompts/code_review.prompt.md", "prompts/commit.prompt.md"] {
+            manifest
+                .files
+                .insert(PathBuf::from(path), "sha".to_string());
+        }
+        manifest.save(&tool, dest.path()).unwrap();
+        assert_eq!(
+            tool.install_state_at(dest.path()),
+            InstallState::Partial {
+                missing: 1,
+                total: 3
+            }
+        );
+        assert!(!tool.is_installed_at(dest.path()));
+    }
+
     #[test]
     fn claude_is_installed_requires_skills() {
         let temp_root = std::env::temp_dir().join("hyprlayer_test_claude_is_installed");
@@ -1075,6 +3917,62 @@ mod tests {
         fs::remove_dir_all(&temp_root).ok();
     }
 
+    #[test]
+    fn codex_install_checks_prompts_only() {
+        let root = tempfile::tempdir().unwrap();
+        let dest = root.path().join("codex");
+        fs::create_dir_all(dest.join("prompts")).unwrap();
+        assert!(AgentTool::Codex.has_existing_install_at(&dest));
+        assert!(!AgentTool::Codex.is_installed_at(&dest));
+
+        touch(&dest.join("prompts/code_review.md"));
+        touch(&dest.join("prompts/research_codebase.md"));
+        assert!(AgentTool::Codex.is_installed_at(&dest));
+        assert_eq!(AgentTool::Claude.instructions_mode(None), None);
+    }
+
+    #[test]
+    fn merge_instructions_keeps_user_content() {
+        let fresh = merge_instructions("", "# Hyprlayer\n");
+        assert_eq!(
+            fresh,
+            format!("{INSTRUCTIONS_START}\n# Hyprlayer\n{INSTRUCTIONS_END}\n")
+        );
+
+        let appended = merge_instructions("# Mine\n", "v1");
+        assert!(appended.starts_with("# Mine\n\n"));
+        assert!(appended.contains("v1"));
+
+        let replaced = merge_instructions(&format!("{appended}More of mine\n"), "v2");
+        assert_eq!(
+            replaced,
+            format!("# Mine\n\n{INSTRUCTIONS_START}\nv2\n{INSTRUCTIONS_END}\nMore of mine\n")
+        );
+    }
+
+    #[test]
+    fn instructions_mode_reports_where_agents_md_was_written() {
+        let root = tempfile::tempdir().unwrap();
+        let agents_md = root.path().join("AGENTS.md");
+        assert_eq!(instructions_mode_at(false, Some(root.path())), None);
+        assert_eq!(instructions_mode_at(true, None), Some("global"));
+
+        fs::write(&agents_md, "# Mine\n").unwrap();
+        assert_eq!(instructions_mode_at(false, Some(root.path())), None);
+
+        write_instructions(&agents_md, "# Hyprlayer").unwrap();
+        assert!(
+            fs::read_to_string(&agents_md)
+                .unwrap()
+                .starts_with("# Mine")
+        );
+        assert_eq!(
+            instructions_mode_at(false, Some(root.path())),
+            Some("project")
+        );
+        assert_eq!(instructions_mode_at(true, Some(root.path())), Some("both"));
+    }
+
     #[test]
     fn update_opencode_models_with_different_providers() {
         let temp_dir = std::env::temp_dir().join("hyprlayer_test_providers");
@@ -1088,7 +3986,11 @@ mod tests {
         )
         .unwrap();
 
-        update_opencode_models(&temp_dir, &OpenCodeProvider::Anthropic).unwrap();
+        update_opencode_models(
+            &temp_dir,
+            &build_placeholder_set(None, &OpenCodeProvider::Anthropic),
+        )
+        .unwrap();
 
         let result = fs::read_to_string(commands_dir.join("test.md")).unwrap();
         assert!(result.contains("model: anthropic/claude-sonnet-4-5"));
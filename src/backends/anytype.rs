@@ -26,7 +26,9 @@ impl ThoughtsBackend for AnytypeBackend {
             ));
         }
 
-        crate::hooks::setup_git_hooks(ctx.code_repo, false)?;
+        if ctx.install_hooks {
+            crate::hooks::setup_git_hooks(ctx.code_repo, false, 0)?;
+        }
 
         common::warn_stale_thoughts_dir(ctx.code_repo, "Anytype content lives in the app");
 
@@ -101,16 +103,39 @@ impl ThoughtsBackend for AnytypeBackend {
 
 fn register_anytype_mcp(agent: AgentTool, env_var: &str) -> Result<()> {
     match agent {
-        AgentTool::Claude => run_mcp_add("claude", &["--scope", "user"], "Claude Code", env_var),
-        AgentTool::OpenCode => run_mcp_add("opencode", &[], "OpenCode", env_var),
+        AgentTool::Claude => {
+            run_mcp_add("claude", &["--scope", "user"], "-e", "Claude Code", env_var)
+        }
+        AgentTool::OpenCode => run_mcp_add("opencode", &[], "-e", "OpenCode", env_var),
+        AgentTool::Codex => run_mcp_add("codex", &[], "--env", "OpenAI Codex", env_var),
         AgentTool::Copilot => {
-            print_copilot_mcp_snippet(env_var);
+            print_mcp_snippet(
+                "GitHub Copilot: paste this into your VS Code settings.json (under \
+                 the \"github.copilot.mcp.servers\" key):",
+                env_var,
+            );
+            Ok(())
+        }
+        AgentTool::Windsurf => {
+            print_mcp_snippet(
+                "Windsurf: paste this into ~/.codeium/windsurf/mcp_config.json \
+                 (under the \"mcpServers\" key):",
+                env_var,
+            );
             Ok(())
         }
     }
 }
 
-fn run_mcp_add(cli: &str, extra_args: &[&str], label: &str, env_var: &str) -> Result<()> {
+/// `<cli> mcp add` for the Anytype server. `env_flag` is the CLI's option
+/// for passing an environment variable (`-e`, or `--env` for Codex).
+fn run_mcp_add(
+    cli: &str,
+    extra_args: &[&str],
+    env_flag: &str,
+    label: &str,
+    env_var: &str,
+) -> Result<()> {
     let env_pair = super::common::resolve_mcp_env_pair(env_var)?;
     let mut cmd = Command::new(cli);
     cmd.arg("mcp").arg("add");
@@ -118,7 +143,7 @@ fn run_mcp_add(cli: &str, extra_args: &[&str], label: &str, env_var: &str) -> Re
         cmd.arg(a);
     }
     cmd.arg(ANYTYPE_MCP_NAME)
-        .arg("-e")
+        .arg(env_flag)
         .arg(&env_pair)
         .arg("--")
         .arg(ANYTYPE_MCP_COMMAND);
@@ -145,14 +170,10 @@ fn run_mcp_add(cli: &str, extra_args: &[&str], label: &str, env_var: &str) -> Re
     Ok(())
 }
 
-fn print_copilot_mcp_snippet(env_var: &str) {
+/// For tools without an `mcp add` CLI, print a config snippet to paste in.
+fn print_mcp_snippet(intro: &str, env_var: &str) {
     println!();
-    println!(
-        "{}",
-        "GitHub Copilot: paste this into your VS Code settings.json (under \
-         the \"github.copilot.mcp.servers\" key):"
-            .yellow()
-    );
+    println!("{}", intro.yellow());
     let args_json: Vec<String> = ANYTYPE_MCP_ARGS
         .iter()
         .map(|a| format!("\"{}\"", a))
@@ -175,13 +196,14 @@ fn print_copilot_mcp_snippet(env_var: &str) {
 /// Probe the agent's CLI for Anytype MCP registration. Returns:
 /// - `Some(true)` if anytype appears in the MCP list
 /// - `Some(false)` if the probe succeeded but anytype is absent
-/// - `None` if we couldn't probe (Copilot; CLI missing; non-zero exit) —
+/// - `None` if we couldn't probe (Copilot or Windsurf; CLI missing; non-zero exit) —
 ///   callers treat this as "unknown".
 fn probe_anytype_mcp(agent: AgentTool) -> Option<bool> {
     let cli = match agent {
         AgentTool::Claude => "claude",
         AgentTool::OpenCode => "opencode",
-        AgentTool::Copilot => return None,
+        AgentTool::Codex => "codex",
+        AgentTool::Copilot | AgentTool::Windsurf => return None,
     };
     let output = Command::new(cli).args(["mcp", "list"]).output().ok()?;
     if !output.status.success() {
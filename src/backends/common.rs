@@ -1,10 +1,10 @@
-#[cfg(windows)]
-use anyhow::Context;
 use anyhow::Result;
 use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
+use crate::platform;
+
 /// Build the `KEY=VALUE` pair to pass as `<cli> mcp add -e <pair>`.
 ///
 /// `Command::new` does not invoke a shell, and `claude mcp add -e` stores its
@@ -81,50 +81,18 @@ pub fn setup_symlinks_into(root: &Path, code_repo: &Path, dirs: &FilesystemDirs)
     create_symlinks(&thoughts_dir, &repo_thoughts_path, &global_path, dirs.user)
 }
 
-#[cfg(unix)]
-fn create_symlinks(
-    thoughts_dir: &Path,
-    repo_thoughts_path: &Path,
-    global_path: &Path,
-    user: &str,
-) -> Result<()> {
-    std::os::unix::fs::symlink(repo_thoughts_path.join(user), thoughts_dir.join(user))?;
-    std::os::unix::fs::symlink(
-        repo_thoughts_path.join("shared"),
-        thoughts_dir.join("shared"),
-    )?;
-    std::os::unix::fs::symlink(global_path, thoughts_dir.join("global"))?;
-    Ok(())
-}
-
-#[cfg(windows)]
 fn create_symlinks(
     thoughts_dir: &Path,
     repo_thoughts_path: &Path,
     global_path: &Path,
     user: &str,
 ) -> Result<()> {
-    use std::os::windows::fs::symlink_dir;
-
-    let create = |target: &Path, link: &Path| -> Result<()> {
-        symlink_dir(target, link).with_context(|| {
-            format!(
-                "Failed to create symlink. On Windows, symlinks require either:\n\
-                 1. Run as Administrator, or\n\
-                 2. Enable Developer Mode in Settings > Update & Security > For developers\n\n\
-                 Target: {}\nLink: {}",
-                target.display(),
-                link.display()
-            )
-        })
-    };
-
-    create(&repo_thoughts_path.join(user), &thoughts_dir.join(user))?;
-    create(
+    platform::create_dir_link(&repo_thoughts_path.join(user), &thoughts_dir.join(user))?;
+    platform::create_dir_link(
         &repo_thoughts_path.join("shared"),
         &thoughts_dir.join("shared"),
     )?;
-    create(global_path, &thoughts_dir.join("global"))?;
+    platform::create_dir_link(global_path, &thoughts_dir.join("global"))?;
     Ok(())
 }
 
@@ -3,6 +3,7 @@ use std::path::Path;
 
 use crate::agents::AgentTool;
 use crate::config::{BackendKind, EffectiveConfig};
+use crate::secrets::SecretsPolicy;
 
 pub mod anytype;
 pub mod common;
@@ -17,6 +18,41 @@ pub struct BackendContext<'a> {
     /// The active AI tool, when configured. Only backends that register MCP
     /// servers (notion, anytype) need this; others ignore it.
     pub agent_tool: Option<AgentTool>,
+    /// `init --remote` / `--branch`. Only the git backend uses this.
+    pub git_setup: GitSetup,
+    /// False after `init --no-hooks`: leave the code repository's git hooks
+    /// alone.
+    pub install_hooks: bool,
+    /// Maximum random delay in seconds before the post-commit auto-sync.
+    /// Only the git backend installs that hook.
+    pub sync_jitter: u64,
+    /// How the git backend's sync handles files that look like they hold
+    /// secrets.
+    pub secrets: SecretsPolicy,
+}
+
+/// Remote and initial branch for the git backend's thoughts repository.
+#[derive(Debug, Default, Clone)]
+pub struct GitSetup {
+    pub remote: Option<String>,
+    pub branch: Option<String>,
+}
+
+impl GitSetup {
+    pub fn is_empty(&self) -> bool {
+        self.remote.is_none() && self.branch.is_none()
+    }
+
+    /// Reject `--remote` and `--branch` for a backend other than git.
+    pub fn check(&self, backend_kind: BackendKind) -> Result<()> {
+        if backend_kind != BackendKind::Git && !self.is_empty() {
+            return Err(anyhow::anyhow!(
+                "--remote and --branch only apply to the git backend, not {}",
+                backend_kind
+            ));
+        }
+        Ok(())
+    }
 }
 
 impl<'a> BackendContext<'a> {
@@ -25,6 +61,10 @@ impl<'a> BackendContext<'a> {
             code_repo,
             effective,
             agent_tool: None,
+            git_setup: GitSetup::default(),
+            install_hooks: true,
+            sync_jitter: 0,
+            secrets: SecretsPolicy::Confirm,
         }
     }
 
@@ -32,6 +72,26 @@ impl<'a> BackendContext<'a> {
         self.agent_tool = agent_tool;
         self
     }
+
+    pub fn with_git_setup(mut self, git_setup: GitSetup) -> Self {
+        self.git_setup = git_setup;
+        self
+    }
+
+    pub fn with_hooks(mut self, install_hooks: bool) -> Self {
+        self.install_hooks = install_hooks;
+        self
+    }
+
+    pub fn with_sync_jitter(mut self, sync_jitter: u64) -> Self {
+        self.sync_jitter = sync_jitter;
+        self
+    }
+
+    pub fn with_secrets_policy(mut self, secrets: SecretsPolicy) -> Self {
+        self.secrets = secrets;
+        self
+    }
 }
 
 pub struct StatusReport {
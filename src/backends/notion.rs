@@ -23,7 +23,9 @@ impl ThoughtsBackend for NotionBackend {
             ));
         }
 
-        crate::hooks::setup_git_hooks(ctx.code_repo, false)?;
+        if ctx.install_hooks {
+            crate::hooks::setup_git_hooks(ctx.code_repo, false, 0)?;
+        }
 
         common::warn_stale_thoughts_dir(ctx.code_repo, "Notion content lives in the database");
 
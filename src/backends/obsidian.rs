@@ -52,7 +52,9 @@ impl ThoughtsBackend for ObsidianBackend {
         common::setup_directory_structure_at(&root, &dirs)?;
         common::setup_symlinks_into(&root, ctx.code_repo, &dirs)?;
 
-        crate::hooks::setup_git_hooks(ctx.code_repo, false)?;
+        if ctx.install_hooks {
+            crate::hooks::setup_git_hooks(ctx.code_repo, false, 0)?;
+        }
         Ok(())
     }
 
@@ -1,28 +1,60 @@
 use anyhow::Result;
-use clap::Args;
-use std::fs;
-use std::path::PathBuf;
+use clap::{ArgAction, Args};
+use colored::Colorize;
+use std::path::{Path, PathBuf};
 
-use crate::config::{BackendKind, HyprlayerConfig, expand_path, get_default_config_path};
+use super::{ConfigAction, RebaseAction};
+use crate::agents::AgentTool;
+use crate::config::{
+    BackendKind, ConfigSources, EffectiveConfig, HyprlayerConfig, expand_path,
+    get_current_repo_path, get_default_config_path,
+};
+use crate::git_ops::GitRepo;
 
 /// Common config file argument shared across commands
 #[derive(Debug, Clone, Args)]
 pub struct ConfigArgs {
-    #[arg(long, help = "Path to config file")]
-    pub config_file: Option<String>,
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Path to config file (repeat or comma-separate to layer several; later files win)"
+    )]
+    pub config_file: Vec<String>,
+    #[arg(
+        long = "set",
+        value_name = "KEY=VALUE",
+        action = ArgAction::Append,
+        help = "Override a config value for this run only, e.g. thoughts.thoughtsRepo=/tmp/t (repeatable)"
+    )]
+    pub overrides: Vec<String>,
 }
 
 impl ConfigArgs {
-    /// Resolve the config file path (from arg or default)
+    /// Resolve the config file path (from arg or default). With several
+    /// files this is the last, highest-precedence one — the file writes go to.
     pub fn path(&self) -> Result<PathBuf> {
         self.config_file
-            .as_ref()
+            .last()
             .map_or_else(get_default_config_path, |p| Ok(expand_path(p)))
     }
 
-    /// Load existing config, error if not found or incomplete
+    /// Apply `change` to the config file writes go to, under its lock (see
+    /// `HyprlayerConfig::update`). Only that file is read and saved, so
+    /// other `--config-file` layers never get copied into it.
+    pub fn update<T>(&self, change: impl FnOnce(&mut HyprlayerConfig) -> Result<T>) -> Result<T> {
+        HyprlayerConfig::update(&self.path()?, change)
+    }
+
+    /// Load existing config, error if not found or incomplete. Several
+    /// `--config-file`s are merged left to right, then `--set` overrides
+    /// applied; neither is saved.
     pub fn load(&self) -> Result<HyprlayerConfig> {
-        let config = self.load_if_exists()?.ok_or_else(|| {
+        Ok(self.load_sourced()?.0)
+    }
+
+    /// `load`, plus where values came from (see `load_sourced_if_exists`).
+    pub fn load_sourced(&self) -> Result<(HyprlayerConfig, ConfigSources)> {
+        let (config, sources) = self.load_sourced_if_exists()?.ok_or_else(|| {
             anyhow::anyhow!("No configuration found. Run 'hyprlayer thoughts init' first.")
         })?;
         if config
@@ -34,30 +66,120 @@ impl ConfigArgs {
                 "Thoughts not fully configured. Run 'hyprlayer thoughts init' to complete setup."
             ));
         }
-        Ok(config)
+        Ok((config, sources))
+    }
+
+    /// `load`, plus the effective thoughts settings for the current
+    /// directory's repository.
+    pub fn load_with_profile(&self) -> Result<(HyprlayerConfig, EffectiveConfig)> {
+        self.load_with_profile_for(&get_current_repo_path()?.display().to_string())
+    }
+
+    /// `load`, plus the effective thoughts settings for the repository at
+    /// `repo_path`.
+    pub fn load_with_profile_for(
+        &self,
+        repo_path: &str,
+    ) -> Result<(HyprlayerConfig, EffectiveConfig)> {
+        let config = self.load()?;
+        // `load` fails unless thoughts are configured.
+        let effective = config
+            .thoughts
+            .as_ref()
+            .unwrap()
+            .effective_config_for(repo_path);
+        Ok((config, effective))
     }
 
-    /// Load config if exists, returns None if config file doesn't exist
+    /// The git thoughts repository `command` works on, and its expanded
+    /// path: the current repository's effective backend, or the default
+    /// backend outside a mapped repository. Errors unless that backend is git.
+    pub fn load_git_thoughts_repo(&self, command: &str) -> Result<(PathBuf, GitRepo)> {
+        let config = self.load()?;
+        let current_repo = get_current_repo_path()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        // `load` fails unless thoughts are configured.
+        let effective = config
+            .thoughts
+            .as_ref()
+            .unwrap()
+            .effective_config_for(&current_repo);
+        let git = effective.backend.as_git().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{command} needs a git thoughts backend, not {}",
+                effective.backend.kind()
+            )
+        })?;
+        let thoughts_repo = expand_path(&git.thoughts_repo);
+        let repo = GitRepo::open(&thoughts_repo)?;
+        Ok((thoughts_repo, repo))
+    }
+
+    /// Load config if it exists, merging several `--config-file`s and
+    /// applying `--set` as `load` does. `None` when a single config file
+    /// doesn't exist; with several, each must.
     pub fn load_if_exists(&self) -> Result<Option<HyprlayerConfig>> {
-        let path = self.path()?;
-        if !path.exists() {
+        Ok(self.load_sourced_if_exists()?.map(|(config, _)| config))
+    }
+
+    /// `load_if_exists`, plus which later `--config-file` or `--set` each
+    /// value the first file doesn't supply came from.
+    pub fn load_sourced_if_exists(&self) -> Result<Option<(HyprlayerConfig, ConfigSources)>> {
+        let loaded = if self.config_file.len() > 1 {
+            let paths: Vec<PathBuf> = self.config_file.iter().map(|p| expand_path(p)).collect();
+            let refs: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+            Some(HyprlayerConfig::load_merged(&refs)?)
+        } else {
+            self.load_target()?
+                .map(|config| (config, ConfigSources::default()))
+        };
+        let Some((mut config, mut sources)) = loaded else {
             return Ok(None);
+        };
+        for entry in &self.overrides {
+            let (key, value) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("--set expects KEY=VALUE, got \"{entry}\""))?;
+            let before = config.clone();
+            if !config.apply_override(key, value)? {
+                eprintln!(
+                    "{}",
+                    format!("Warning: ignoring --set {key}: no such config key").yellow()
+                );
+            }
+            sources.record(&before, &config, "--set")?;
         }
-        HyprlayerConfig::load(&path).map(Some)
+        Ok(Some((config, sources)))
+    }
+
+    /// Error when `--set` was given to `command`, which edits or shows the
+    /// config file itself, so an override for this run has nothing to act on.
+    pub fn reject_overrides(&self, command: &str) -> Result<()> {
+        if self.overrides.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "--set doesn't apply to '{command}', which works on the config file itself. Use 'hyprlayer thoughts config set' to change a value."
+        ))
     }
 
-    /// Load raw JSON config, error if not found
-    pub fn load_raw(&self) -> Result<(PathBuf, serde_json::Value)> {
+    /// Load only the config file writes go to, if it exists: without the
+    /// other `--config-file` layers or `--set`, for commands that build on
+    /// it and write it back.
+    pub fn load_target(&self) -> Result<Option<HyprlayerConfig>> {
         let path = self.path()?;
         if !path.exists() {
-            return Err(anyhow::anyhow!("No thoughts configuration found"));
+            return Ok(None);
         }
-        let content = fs::read_to_string(&path)?;
-        let value = serde_json::from_str(&content)?;
-        Ok((path, value))
+        HyprlayerConfig::load(&path).map(Some)
     }
 }
 
+/// Every prompt `init` asks has a flag. `--yes` never prompts: on a first
+/// setup it needs `--directory` and `--user`, and takes defaults for
+/// anything else (`--thoughts-repo`, `--repos-dir` and `--global-dir` for
+/// the git backend).
 #[derive(Debug, Args)]
 #[command(name = "init", about = "Initialize thoughts for current repository")]
 pub struct InitArgs {
@@ -68,10 +190,33 @@ pub struct InitArgs {
         help = "Specify the repository directory name (skips interactive prompt)"
     )]
     pub directory: Option<String>,
+    #[arg(
+        long,
+        help = "Your username in the thoughts repository (skips the prompt; default: $HYPRLAYER_USER)"
+    )]
+    pub user: Option<String>,
     #[arg(long, help = "Use a specific thoughts profile")]
     pub profile: Option<String>,
     #[arg(long, value_enum, help = "Storage backend for thoughts")]
     pub backend: Option<BackendKind>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Thoughts repository path (git backend; implies --backend git)"
+    )]
+    pub thoughts_repo: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Directory name for repository-specific thoughts (default: repos)"
+    )]
+    pub repos_dir: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Directory name for global thoughts (default: global)"
+    )]
+    pub global_dir: Option<String>,
     #[arg(
         long,
         help = "Obsidian vault path (required when --backend obsidian with --yes)"
@@ -105,12 +250,46 @@ pub struct InitArgs {
                 Ignored for notion (uses agent tool's connector)."
     )]
     pub api_token_env: Option<String>,
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Add URL as the thoughts repository's origin and push to it (git backend)"
+    )]
+    pub remote: Option<String>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Initial branch for a newly created thoughts repository (git backend)"
+    )]
+    pub branch: Option<String>,
+    #[arg(
+        long,
+        conflicts_with = "hooks",
+        help = "Don't install git hooks, now or on later inits"
+    )]
+    pub no_hooks: bool,
+    #[arg(long, help = "Install git hooks again after --no-hooks")]
+    pub hooks: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        conflicts_with = "no_hooks",
+        help = "Wait a random 0..=SECONDS before each auto-sync, to spread out syncs from many machines (0 disables)"
+    )]
+    pub sync_jitter: Option<u64>,
     #[arg(
         long,
         short = 'y',
-        help = "Run without interactive prompts (requires existing config and --directory)"
+        help = "Run without interactive prompts (requires --directory, and --user on first setup)"
     )]
     pub yes: bool,
+    #[arg(
+        long,
+        help = "List the directories already in the thoughts repository (candidates for --directory) and exit"
+    )]
+    pub list_existing: bool,
+    #[arg(long, requires = "list_existing", help = "Output as JSON")]
+    pub json: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -123,6 +302,14 @@ pub struct InitArgs {
 pub struct UninitArgs {
     #[arg(long, help = "Force removal even if not in configuration")]
     pub force: bool,
+    #[arg(
+        long,
+        conflicts_with = "keep_hooks",
+        help = "Also remove the git hooks hyprlayer installed"
+    )]
+    pub remove_hooks: bool,
+    #[arg(long, help = "Leave the git hooks installed without a warning")]
+    pub keep_hooks: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -132,6 +319,420 @@ pub struct UninitArgs {
 pub struct SyncArgs {
     #[arg(short, long, help = "Commit message for sync")]
     pub message: Option<String>,
+    #[arg(
+        long,
+        help = "Fail instead of asking when files look like they contain secrets"
+    )]
+    pub no_interactive: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Sync this thoughts repository directly, without a code repository"
+    )]
+    pub thoughts_repo: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "tui",
+    about = "Browse, edit and sync thoughts in a terminal UI"
+)]
+pub struct TuiArgs {
+    #[arg(
+        long,
+        help = "Browse this profile's thoughts instead of the current repository's"
+    )]
+    pub profile: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// Which part of the thoughts tree `thoughts open` opens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OpenScope {
+    User,
+    Shared,
+    Global,
+    All,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "open",
+    about = "Open thoughts for the current repository in an editor or file manager"
+)]
+pub struct OpenArgs {
+    #[arg(
+        long,
+        value_enum,
+        default_value = "user",
+        help = "Your notes, shared notes, global notes, or the whole thoughts repo"
+    )]
+    pub scope: OpenScope,
+    #[arg(
+        long,
+        help = "Open this profile's thoughts instead of the current repository's"
+    )]
+    pub profile: Option<String>,
+    #[arg(long, help = "Open in the system file manager instead of $EDITOR")]
+    pub file_manager: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "search", about = "Search thoughts for the current repository")]
+pub struct SearchArgs {
+    #[arg(help = "Words that must all appear on a line, or a pattern with --regex")]
+    pub query: String,
+    #[arg(long, help = "Use the search index instead of scanning files")]
+    pub index: bool,
+    #[arg(
+        long,
+        help = "Treat the query as a case-insensitive regex matched against words"
+    )]
+    pub regex: bool,
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Only files modified on or after DATE (YYYY-MM-DD, or relative like 1week, 30days)"
+    )]
+    pub after: Option<String>,
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Only files modified before DATE (YYYY-MM-DD, or relative like 1week, 30days)"
+    )]
+    pub before: Option<String>,
+    #[arg(
+        long,
+        help = "Search every profile's thoughts, not just the current repository's"
+    )]
+    pub all_profiles: bool,
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// External search tool for `thoughts grep`, in detection order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GrepTool {
+    Rg,
+    Ag,
+    Grep,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "grep",
+    about = "Search thoughts/searchable with ripgrep, ag or grep",
+    after_help = "Arguments after `--` are passed to the tool verbatim, e.g.\n  hyprlayer thoughts grep TODO -- --type md -C 2"
+)]
+pub struct GrepArgs {
+    pub pattern: String,
+    #[arg(
+        long,
+        value_enum,
+        help = "Use this tool instead of the first one found"
+    )]
+    pub tool: Option<GrepTool>,
+    #[arg(short, long, help = "Print which tool is used")]
+    pub verbose: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "build", about = "Rebuild the search index from scratch")]
+pub struct IndexBuildArgs {
+    #[arg(long, help = "Index this profile's thoughts instead of the default")]
+    pub profile: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "add",
+    about = "Add a git worktree of the current repository with its thoughts linked"
+)]
+pub struct WorktreeAddArgs {
+    #[arg(help = "Directory for the new worktree")]
+    pub path: String,
+    #[arg(help = "Branch to check out, created from HEAD if it doesn't exist")]
+    pub branch: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "list",
+    about = "List the current repository's git worktrees and whether each has thoughts"
+)]
+pub struct WorktreeListArgs {
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "search", about = "Search every indexed thoughts file")]
+pub struct IndexSearchArgs {
+    #[arg(help = "Words that must all appear on a line, or a pattern with --regex")]
+    pub query: String,
+    #[arg(
+        long,
+        help = "Treat the query as a case-insensitive regex matched against words"
+    )]
+    pub regex: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "use-profile",
+    about = "Switch the current repository to a different thoughts profile"
+)]
+pub struct UseProfileArgs {
+    #[arg(help = "Profile to switch to, or 'default' for the default backend")]
+    pub name: String,
+    #[arg(
+        long = "move",
+        group = "content",
+        help = "Move existing notes to the new thoughts repo"
+    )]
+    pub move_content: bool,
+    #[arg(
+        long,
+        group = "content",
+        help = "Copy existing notes, keeping the originals"
+    )]
+    pub copy: bool,
+    #[arg(
+        long,
+        group = "content",
+        help = "Leave existing notes in the old thoughts repo"
+    )]
+    pub leave: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// Output formats for `thoughts export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// A static site with an index, one page per thought and search
+    Html,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "export", about = "Export thoughts as a browsable HTML site")]
+pub struct ExportArgs {
+    #[arg(long, value_enum, default_value = "html", help = "Output format")]
+    pub format: ExportFormat,
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        help = "Output directory, or file with --zip (default: ./thoughts-site/ or ./thoughts-site.zip)"
+    )]
+    pub output: Option<String>,
+    #[arg(long, help = "Write a single zip file instead of a directory")]
+    pub zip: bool,
+    #[arg(
+        long,
+        help = "Export this profile's thoughts instead of the current repository's"
+    )]
+    pub profile: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// Note formats `thoughts import` understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Obsidian,
+}
+
+/// Where `thoughts import` puts notes, under the repository's `thoughts/`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportScope {
+    /// thoughts/<user>
+    User,
+    /// thoughts/shared
+    Shared,
+    /// thoughts/global/<user>
+    Global,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "import",
+    about = "Import notes from another tool into thoughts for the current repository"
+)]
+pub struct ImportArgs {
+    #[arg(
+        required_unless_present = "obsidian_vault",
+        requires = "format",
+        help = "Directory to import"
+    )]
+    pub source: Option<String>,
+    #[arg(long, value_enum, help = "Format of the source directory")]
+    pub format: Option<ImportFormat>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["source", "format"],
+        help = "Import an Obsidian vault (same as <SOURCE> --format obsidian)"
+    )]
+    pub obsidian_vault: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Put every note in this scope instead of asking per top-level directory"
+    )]
+    pub scope: Option<ImportScope>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "apply-patch",
+    about = "Apply a git format-patch file to the thoughts repository"
+)]
+pub struct ApplyPatchArgs {
+    #[arg(long, help = "Patch file to apply (reads stdin when omitted)")]
+    pub path: Option<String>,
+    #[arg(
+        long,
+        conflicts_with = "path",
+        help = "Abandon a conflicted patch and restore the previous state"
+    )]
+    pub abort: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "encrypt",
+    about = "Encrypt a thoughts file with a passphrase (age), or decrypt it again"
+)]
+pub struct EncryptArgs {
+    #[arg(
+        long,
+        help = "File to encrypt to <file>.age, or the .age file to decrypt"
+    )]
+    pub file: String,
+    #[arg(long, help = "Decrypt instead of encrypting")]
+    pub decrypt: bool,
+    #[arg(
+        long,
+        conflicts_with = "decrypt",
+        help = "Encrypt even though the plaintext is already committed (it stays in git history)"
+    )]
+    pub force: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "audit",
+    about = "List the sync commits in the thoughts repository"
+)]
+pub struct AuditArgs {
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Only syncs on or after DATE (YYYY-MM-DD, or relative like 1week, 30days)"
+    )]
+    pub since: Option<String>,
+    #[arg(long, default_value_t = 50, help = "Show at most this many syncs")]
+    pub limit: usize,
+    #[arg(
+        long,
+        help = "Check each commit with git verify-commit and flag unsigned ones"
+    )]
+    pub verify_signatures: bool,
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// Which part of the repository's thoughts `thoughts diff` shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DiffScope {
+    /// thoughts/<user>
+    User,
+    /// thoughts/shared
+    Shared,
+    /// thoughts/global
+    Global,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "diff",
+    about = "Show how the current repository's thoughts changed between two commits"
+)]
+pub struct DiffArgs {
+    #[arg(help = "Commit, or a date (YYYY-MM-DD, 1week) meaning the last commit before it")]
+    pub ref1: String,
+    #[arg(default_value = "HEAD", help = "Commit or date to compare against")]
+    pub ref2: String,
+    #[arg(
+        long,
+        value_enum,
+        help = "Only your notes, shared notes, or global notes (default: the repository's notes)"
+    )]
+    pub scope: Option<DiffScope>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "rebase",
+    about = "Continue, skip or abort a stopped rebase of the thoughts repo, or start an interactive one"
+)]
+pub struct RebaseArgs {
+    #[command(subcommand)]
+    pub action: Option<RebaseAction>,
+    #[arg(
+        long,
+        value_name = "BASE",
+        num_args = 0..=1,
+        default_missing_value = "@{upstream}",
+        help = "Interactively rebase commits since BASE (default: the upstream branch), e.g. to squash old sync commits"
+    )]
+    pub interactive: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "merge",
+    about = "Merge a branch into the current thoughts branch"
+)]
+pub struct MergeArgs {
+    #[arg(help = "Branch to merge (local or remote-tracking, e.g. origin/main)")]
+    pub branch: String,
+    #[arg(long, help = "Record the merge as a single-parent commit")]
+    pub squash: bool,
+    #[arg(short, long, help = "Commit message for the merge commit")]
+    pub message: Option<String>,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -139,6 +740,16 @@ pub struct SyncArgs {
 #[derive(Debug, Args)]
 #[command(name = "status", about = "Show status of thoughts repository")]
 pub struct StatusArgs {
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[arg(long, help = "List the current repository's git worktrees")]
+    pub worktrees: bool,
+    #[arg(
+        long,
+        conflicts_with = "worktrees",
+        help = "Summarize every mapped repository instead of the current one"
+    )]
+    pub all_repos: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -146,10 +757,91 @@ pub struct StatusArgs {
 #[derive(Debug, Args)]
 #[command(name = "config", about = "View or edit thoughts configuration")]
 pub struct ConfigArgsCmd {
+    #[command(subcommand)]
+    pub action: Option<ConfigAction>,
     #[arg(long, help = "Open configuration in editor")]
     pub edit: bool,
+    #[arg(
+        long,
+        help = "Show what the next save changes; with --edit, review the edit before saving it"
+    )]
+    pub diff: bool,
     #[arg(long, help = "Output configuration as JSON")]
     pub json: bool,
+    #[arg(
+        long,
+        requires = "json",
+        help = "Include tokens, keys and other secrets in --json output"
+    )]
+    pub show_sensitive: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "effective",
+    about = "Show the resolved configuration for the current repository"
+)]
+pub struct ConfigEffectiveArgs {
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "export",
+    about = "Export portable settings (user, backend, profiles, AI tool) without repo mappings"
+)]
+pub struct ConfigExportArgs {
+    #[arg(short, long, help = "Write the bundle to a file instead of stdout")]
+    pub output: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "import",
+    about = "Merge settings from an exported bundle into the local config"
+)]
+pub struct ConfigImportArgs {
+    /// Path to a bundle written by `thoughts config export`
+    pub file: String,
+    #[arg(
+        long,
+        conflicts_with = "keep_existing",
+        help = "Replace conflicting local values without prompting"
+    )]
+    pub overwrite: bool,
+    #[arg(long, help = "Keep conflicting local values without prompting")]
+    pub keep_existing: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "set", about = "Set a configuration value")]
+pub struct ConfigSetArgs {
+    /// Key to set: `agent-pin-ref` (alias `ai.agentPinRef`), `disableUpdateCheck` or
+    /// `update-check-interval` (alias `updateCheckIntervalHours`, in hours; 0 checks every run) or
+    /// `proxy` (alias `thoughts.proxy`)
+    pub key: String,
+    /// New value; `null` clears it
+    pub value: String,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "unset", about = "Clear an optional configuration value")]
+pub struct ConfigUnsetArgs {
+    /// Dotted key path, e.g. `ai.agentTool`, `backend.vaultSubpath`, `profiles.work`
+    pub key: String,
+    #[arg(long, help = "Unset a profile even if repositories still use it")]
+    pub force: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -164,6 +856,33 @@ pub struct ProfileCreateArgs {
     pub repos_dir: Option<String>,
     #[arg(long, help = "Global directory name")]
     pub global_dir: Option<String>,
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        conflicts_with = "from_default",
+        help = "Copy directory names from an existing profile"
+    )]
+    pub from: Option<String>,
+    #[arg(long, help = "Copy directory names from the default thoughts config")]
+    pub from_default: bool,
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Clone an existing thoughts repository instead of creating an empty one"
+    )]
+    pub remote: Option<String>,
+    #[arg(
+        long,
+        help = "Allow a thoughts repository already used by the default config or another profile"
+    )]
+    pub allow_shared_repo: bool,
+    #[arg(
+        long,
+        help = "Never prompt; fail listing any settings not given by flags or --from"
+    )]
+    pub non_interactive: bool,
+    #[arg(long, help = "Print the created profile as JSON")]
+    pub json: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -183,6 +902,11 @@ pub struct ProfileShowArgs {
     pub name: String,
     #[arg(long, help = "Output as JSON")]
     pub json: bool,
+    #[arg(
+        long,
+        help = "Check the profile's thoughts repository and exit non-zero on failure"
+    )]
+    pub validate: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -193,6 +917,133 @@ pub struct ProfileDeleteArgs {
     pub name: String,
     #[arg(long, help = "Force deletion even if in use")]
     pub force: bool,
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        help = "Reassign repositories using this profile to PROFILE (or 'default') before deleting"
+    )]
+    pub migrate_to: Option<String>,
+    #[arg(
+        long,
+        requires = "migrate_to",
+        help = "Move migrated repositories' notes into the target thoughts repo and commit both repos"
+    )]
+    pub move_content: bool,
+    #[arg(long, help = "Also delete the profile's thoughts repository from disk")]
+    pub remove_repo: bool,
+    #[arg(
+        long,
+        short = 'y',
+        requires = "remove_repo",
+        help = "Skip the confirmation prompt for --remove-repo"
+    )]
+    pub yes: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(name = "edit", about = "Update an existing thoughts profile")]
+pub struct ProfileEditArgs {
+    pub name: String,
+    #[arg(long, help = "Thoughts repository path")]
+    pub repo: Option<String>,
+    #[arg(long, help = "Repos directory name")]
+    pub repos_dir: Option<String>,
+    #[arg(long, help = "Global directory name")]
+    pub global_dir: Option<String>,
+    #[arg(
+        long,
+        help = "Allow a thoughts repository already used by the default config or another profile"
+    )]
+    pub allow_shared_repo: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "rename",
+    about = "Rename a thoughts profile and update the repositories mapped to it"
+)]
+pub struct ProfileRenameArgs {
+    pub old: String,
+    pub new: String,
+    #[arg(long, help = "Output as JSON")]
+    pub json: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "set-default",
+    about = "Use a profile for repositories initialized without --profile"
+)]
+pub struct ProfileSetDefaultArgs {
+    pub name: String,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "unset-default",
+    about = "Initialize new repositories with the default backend again"
+)]
+pub struct ProfileUnsetDefaultArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ProfileFormat {
+    Json,
+    Toml,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "export",
+    about = "Print a profile definition that teammates can import"
+)]
+pub struct ProfileExportArgs {
+    pub name: String,
+    #[arg(short, long, help = "Write the profile to a file instead of stdout")]
+    pub output: Option<String>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Output format (default: toml for a .toml --output, json otherwise)"
+    )]
+    pub format: Option<ProfileFormat>,
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Remote to clone on import (default: the thoughts repository's origin)"
+    )]
+    pub remote: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "import",
+    about = "Create a profile from an exported definition"
+)]
+pub struct ProfileImportArgs {
+    #[arg(value_name = "FILE_OR_URL", help = "Exported profile (JSON or TOML)")]
+    pub source: String,
+    #[arg(long, help = "Import under a different profile name")]
+    pub name: Option<String>,
+    #[arg(long, help = "Replace an existing profile with the same name")]
+    pub force: bool,
+    #[arg(
+        long,
+        help = "Allow a thoughts repository already used by the default config or another profile"
+    )]
+    pub allow_shared_repo: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -207,6 +1058,78 @@ pub struct ProfileDeleteArgs {
 pub struct AiConfigureArgs {
     #[arg(long, help = "Force reconfiguration even if already set up")]
     pub force: bool,
+    #[arg(
+        long = "tool",
+        value_enum,
+        value_name = "TOOL",
+        help = "AI tool to install, repeatable (skips the selection prompt)"
+    )]
+    pub tools: Vec<AgentTool>,
+    #[arg(
+        long,
+        help = "Install into the current repository: .claude/ and .opencode/ for Claude Code and OpenCode, AGENTS.md for OpenAI Codex"
+    )]
+    pub project: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Install agent files from a local checkout or .tar.gz instead of GitHub"
+    )]
+    pub source: Option<String>,
+    #[arg(
+        long = "ref",
+        value_name = "REF",
+        conflicts_with = "source",
+        help = "Download agent files from this branch or tag and keep following it (sets ai.agentPinRef)"
+    )]
+    pub git_ref: Option<String>,
+    #[arg(
+        long,
+        value_name = "MODEL",
+        help = "OpenCode: model for most commands and agents instead of the provider's default"
+    )]
+    pub sonnet_model: Option<String>,
+    #[arg(
+        long,
+        value_name = "MODEL",
+        help = "OpenCode: model for research and planning commands instead of the provider's default"
+    )]
+    pub opus_model: Option<String>,
+    #[arg(
+        long,
+        help = "Don't back up locally edited agent files before overwriting them"
+    )]
+    pub no_backup: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "GitHub Copilot: install into PATH instead of VS Code's User directory"
+    )]
+    pub dest: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+/// Which of OpenCode's two model placeholders `ai set-model` changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ModelTier {
+    Sonnet,
+    Opus,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "set-model",
+    about = "Override the model OpenCode agent files use for a tier"
+)]
+pub struct AiSetModelArgs {
+    #[arg(value_enum, help = "Model tier to override")]
+    pub tier: ModelTier,
+    #[arg(
+        value_name = "MODEL",
+        help = "Model ID, e.g. anthropic/claude-sonnet-4-6, or 'default' for the provider's default"
+    )]
+    pub model: String,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -216,6 +1139,13 @@ pub struct AiConfigureArgs {
 pub struct AiStatusArgs {
     #[arg(long, help = "Output as JSON")]
     pub json: bool,
+    #[arg(short, long, help = "List every installed agent file")]
+    pub verbose: bool,
+    #[arg(
+        long,
+        help = "Report every supported tool, including ones not configured"
+    )]
+    pub all: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -223,6 +1153,97 @@ pub struct AiStatusArgs {
 #[derive(Debug, Args)]
 #[command(name = "reinstall", about = "Reinstall AI agent files")]
 pub struct AiReinstallArgs {
+    #[arg(long, help = "Show which files would change without reinstalling")]
+    pub dry_run: bool,
+    #[arg(
+        long,
+        help = "Install into the current repository: .claude/ and .opencode/ for Claude Code and OpenCode, AGENTS.md for OpenAI Codex"
+    )]
+    pub project: bool,
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with = "dry_run",
+        help = "Install agent files from a local checkout or .tar.gz instead of GitHub"
+    )]
+    pub source: Option<String>,
+    #[arg(
+        long = "ref",
+        value_name = "REF",
+        conflicts_with = "source",
+        help = "Download agent files from this branch or tag and keep following it (sets ai.agentPinRef)"
+    )]
+    pub git_ref: Option<String>,
+    #[arg(
+        long,
+        help = "Don't back up locally edited agent files before overwriting them"
+    )]
+    pub no_backup: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "update",
+    about = "Download only the AI agent files that changed upstream"
+)]
+pub struct AiUpdateArgs {
+    #[arg(long, help = "Re-download every file, like 'ai reinstall'")]
+    pub force: bool,
+    #[arg(
+        long,
+        help = "OpenAI Codex: put AGENTS.md in the current repository instead of ~/.codex"
+    )]
+    pub project: bool,
+    #[arg(
+        long,
+        help = "Don't back up locally edited agent files before overwriting them"
+    )]
+    pub no_backup: bool,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "restore-backup",
+    about = "List agent file backups made by installs, or restore one"
+)]
+pub struct AiRestoreBackupArgs {
+    #[arg(
+        value_name = "BACKUP",
+        help = "Backup to restore (lists backups when omitted)"
+    )]
+    pub name: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "uninstall",
+    about = "Remove the agent files hyprlayer installed, as listed in each tool's install manifest"
+)]
+pub struct AiUninstallArgs {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Debug, Args)]
+#[command(
+    name = "diff",
+    about = "Compare installed agent files with the latest remote versions"
+)]
+pub struct AiDiffArgs {
+    #[arg(long, overrides_with = "show", help = "Only show per-file line counts")]
+    pub stat: bool,
+    #[arg(
+        long,
+        overrides_with = "stat",
+        help = "Print line diffs for the changed files (the default; overrides an earlier --stat)"
+    )]
+    pub show: bool,
     #[command(flatten)]
     pub config: ConfigArgs,
 }
@@ -274,3 +1295,11 @@ pub struct CodexStreamArgs {
     #[arg(long)]
     pub no_tool_calls: bool,
 }
+
+#[derive(Debug, Args)]
+#[command(name = "version", about = "Show the installed hyprlayer version")]
+pub struct VersionArgs {
+    /// Also show the release notes for this version
+    #[arg(long)]
+    pub changelog: bool,
+}
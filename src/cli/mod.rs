@@ -3,13 +3,23 @@ pub mod commands;
 use clap::{Parser, Subcommand};
 pub use commands::*;
 
-const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GIT_COMMIT"), ")");
+pub const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("GIT_COMMIT"), ")");
 
 #[derive(Parser, Debug)]
 #[command(name = "hyprlayer")]
 #[command(version = VERSION)]
 #[command(about = "Manage developer thoughts and notes", long_about = None)]
-pub enum Cli {
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+    /// Wait for the startup update check instead of running it in the
+    /// background (for testing the notification).
+    #[arg(long, global = true, hide = true)]
+    pub sync_version_check: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
     /// Manage developer thoughts and notes
     Thoughts {
         #[command(subcommand)]
@@ -30,39 +40,79 @@ pub enum Cli {
         #[command(subcommand)]
         command: CodexCommands,
     },
+    Version(VersionArgs),
 }
 
-impl Cli {
+impl Commands {
     /// The `ConfigArgs` of whichever leaf subcommand was selected, or
     /// `None` for subcommands that don't read config (e.g. `codex stream`,
     /// a stdin/stdout filter). Used by startup checks to honor
     /// `--config-file` and per-config `disableUpdateCheck` settings.
     pub fn config_args(&self) -> Option<&ConfigArgs> {
         match self {
-            Cli::Thoughts { command } => Some(match command {
+            Commands::Thoughts { command } => Some(match command {
                 ThoughtsCommands::Init(a) => &a.config,
                 ThoughtsCommands::Uninit(a) => &a.config,
                 ThoughtsCommands::Sync(a) => &a.config,
                 ThoughtsCommands::Status(a) => &a.config,
-                ThoughtsCommands::Config(a) => &a.config,
+                ThoughtsCommands::Tui(a) => &a.config,
+                ThoughtsCommands::Open(a) => &a.config,
+                ThoughtsCommands::Search(a) => &a.config,
+                ThoughtsCommands::Grep(a) => &a.config,
+                ThoughtsCommands::UseProfile(a) => &a.config,
+                ThoughtsCommands::ApplyPatch(a) => &a.config,
+                ThoughtsCommands::Merge(a) => &a.config,
+                ThoughtsCommands::Encrypt(a) => &a.config,
+                ThoughtsCommands::Audit(a) => &a.config,
+                ThoughtsCommands::Diff(a) => &a.config,
+                ThoughtsCommands::Rebase(a) => &a.config,
+                ThoughtsCommands::Import(a) => &a.config,
+                ThoughtsCommands::Export(a) => &a.config,
+                ThoughtsCommands::Index { command } => match command {
+                    IndexCommands::Build(a) => &a.config,
+                    IndexCommands::Search(a) => &a.config,
+                },
+                ThoughtsCommands::Worktree { command } => match command {
+                    WorktreeCommands::Add(a) => &a.config,
+                    WorktreeCommands::List(a) => &a.config,
+                },
+                ThoughtsCommands::Config(a) => match &a.action {
+                    Some(ConfigAction::Effective(e)) => &e.config,
+                    Some(ConfigAction::Export(e)) => &e.config,
+                    Some(ConfigAction::Import(i)) => &i.config,
+                    Some(ConfigAction::Set(s)) => &s.config,
+                    Some(ConfigAction::Unset(u)) => &u.config,
+                    None => &a.config,
+                },
                 ThoughtsCommands::Profile { command } => match command {
                     ProfileCommands::Create(a) => &a.config,
                     ProfileCommands::List(a) => &a.config,
                     ProfileCommands::Show(a) => &a.config,
                     ProfileCommands::Delete(a) => &a.config,
+                    ProfileCommands::Rename(a) => &a.config,
+                    ProfileCommands::Edit(a) => &a.config,
+                    ProfileCommands::SetDefault(a) => &a.config,
+                    ProfileCommands::UnsetDefault(a) => &a.config,
+                    ProfileCommands::Export(a) => &a.config,
+                    ProfileCommands::Import(a) => &a.config,
                 },
             }),
-            Cli::Ai { command } => Some(match command {
+            Commands::Ai { command } => Some(match command {
                 AiCommands::Configure(a) => &a.config,
                 AiCommands::Status(a) => &a.config,
                 AiCommands::Reinstall(a) => &a.config,
+                AiCommands::Update(a) => &a.config,
+                AiCommands::Diff(a) => &a.config,
+                AiCommands::SetModel(a) => &a.config,
+                AiCommands::RestoreBackup(a) => &a.config,
+                AiCommands::Uninstall(a) => &a.config,
             }),
-            Cli::Storage { command } => Some(match command {
+            Commands::Storage { command } => Some(match command {
                 StorageCommands::Info(a) => &a.config,
                 StorageCommands::SetDatabaseId(a) => &a.config,
                 StorageCommands::SetTypeId(a) => &a.config,
             }),
-            Cli::Codex { .. } => None,
+            Commands::Codex { .. } | Commands::Version(_) => None,
         }
     }
 }
@@ -72,15 +122,43 @@ pub enum AiCommands {
     Configure(AiConfigureArgs),
     Status(AiStatusArgs),
     Reinstall(AiReinstallArgs),
+    Update(AiUpdateArgs),
+    Diff(AiDiffArgs),
+    SetModel(AiSetModelArgs),
+    RestoreBackup(AiRestoreBackupArgs),
+    Uninstall(AiUninstallArgs),
 }
 
 #[derive(Subcommand, Debug)]
 pub enum ThoughtsCommands {
-    Init(InitArgs),
+    Init(Box<InitArgs>),
     Uninit(UninitArgs),
     Sync(SyncArgs),
     Status(StatusArgs),
+    Tui(TuiArgs),
+    Open(OpenArgs),
+    Search(SearchArgs),
+    Grep(GrepArgs),
+    UseProfile(UseProfileArgs),
+    ApplyPatch(ApplyPatchArgs),
+    Merge(MergeArgs),
+    Encrypt(EncryptArgs),
+    Audit(AuditArgs),
+    Diff(DiffArgs),
+    Rebase(RebaseArgs),
+    Import(ImportArgs),
+    Export(ExportArgs),
+    /// Manage the persistent thoughts search index
+    Index {
+        #[command(subcommand)]
+        command: IndexCommands,
+    },
     Config(ConfigArgsCmd),
+    /// Create git worktrees with thoughts linked, or list them
+    Worktree {
+        #[command(subcommand)]
+        command: WorktreeCommands,
+    },
     /// Manage thoughts profiles
     Profile {
         #[command(subcommand)]
@@ -88,12 +166,49 @@ pub enum ThoughtsCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum IndexCommands {
+    Build(IndexBuildArgs),
+    Search(IndexSearchArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorktreeCommands {
+    Add(WorktreeAddArgs),
+    List(WorktreeListArgs),
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    Effective(ConfigEffectiveArgs),
+    Export(ConfigExportArgs),
+    Import(ConfigImportArgs),
+    Set(ConfigSetArgs),
+    Unset(ConfigUnsetArgs),
+}
+
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebaseAction {
+    /// Continue after resolving conflicts
+    Continue,
+    /// Give up and return to where the rebase started
+    Abort,
+    /// Drop the commit that stopped the rebase and carry on
+    Skip,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum ProfileCommands {
     Create(ProfileCreateArgs),
     List(ProfileListArgs),
     Show(ProfileShowArgs),
     Delete(ProfileDeleteArgs),
+    Rename(ProfileRenameArgs),
+    Edit(ProfileEditArgs),
+    SetDefault(ProfileSetDefaultArgs),
+    UnsetDefault(ProfileUnsetDefaultArgs),
+    Export(ProfileExportArgs),
+    Import(ProfileImportArgs),
 }
 
 #[derive(Subcommand, Debug)]
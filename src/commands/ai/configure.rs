@@ -1,97 +1,257 @@
 use anyhow::Result;
-use dialoguer::{Select, theme::ColorfulTheme};
+use colored::Colorize;
+use dialoguer::{Confirm, Input, MultiSelect, Select, theme::ColorfulTheme};
 
-use crate::agents::{AgentTool, OpenCodeProvider};
+use crate::agents::{
+    AgentSource, AgentTool, InstallState, OpenCodeProvider, PlaceholderSet, build_placeholder_set,
+    thoughts_placeholder_set,
+};
 use crate::cli::AiConfigureArgs;
-use crate::commands::ai::record_install;
+use crate::commands::ai::{install_tools, project_root, record_install, tool_list};
 use crate::config::HyprlayerConfig;
 
 pub fn configure(args: AiConfigureArgs) -> Result<()> {
-    let AiConfigureArgs { force, config } = args;
+    let AiConfigureArgs {
+        force,
+        tools,
+        project,
+        source,
+        git_ref,
+        sonnet_model,
+        opus_model,
+        no_backup,
+        dest,
+        config,
+    } = args;
+    config.reject_overrides("ai configure")?;
+    let source = AgentSource::from_arg(source.as_deref())?;
     let config_path = config.path()?;
 
     let mut hyprlayer_config = load_or_create_minimal_config(&config_path)?;
+    // Saved with the install so the daily check keeps following this ref.
+    if let Some(git_ref) = git_ref {
+        hyprlayer_config.ai_mut().agent_pin_ref = Some(git_ref);
+    }
+    if let Some(dest) = dest {
+        let dest = std::path::absolute(crate::config::expand_path(&dest))?;
+        crate::agents::set_copilot_dest(Some(dest.clone()));
+        hyprlayer_config.ai_mut().copilot_dest = Some(dest.display().to_string());
+    }
 
-    let existing_agent = hyprlayer_config
+    let existing_tools = hyprlayer_config
         .ai
         .as_ref()
-        .and_then(|ai| ai.agent_tool.as_ref());
-
-    if let (Some(agent), false) = (existing_agent, force) {
-        if !agent.is_installed() {
-            let agent = *agent;
-            let opencode_provider = hyprlayer_config
-                .ai
-                .as_ref()
-                .and_then(|ai| ai.opencode_provider.as_ref())
-                .cloned();
-            let sha = agent.install(opencode_provider.as_ref(), false)?;
-            record_install(&mut hyprlayer_config, &config_path, sha)?;
+        .map(|ai| ai.agent_tools.clone())
+        .unwrap_or_default();
+
+    if !existing_tools.is_empty() && !force {
+        let mut missing = Vec::new();
+        let mut declined = false;
+        for tool in existing_tools.iter().copied() {
+            match tool.install_state() {
+                InstallState::Installed => {}
+                InstallState::NotInstalled => missing.push(tool),
+                InstallState::Partial {
+                    missing: gone,
+                    total,
+                } => {
+                    println!(
+                        "{}",
+                        format!("{tool} is partially installed ({gone} of {total} files missing)")
+                            .yellow()
+                    );
+                    if Confirm::with_theme(&ColorfulTheme::default())
+                        .with_prompt("Complete the install?")
+                        .default(true)
+                        .interact()?
+                    {
+                        missing.push(tool);
+                    } else {
+                        declined = true;
+                    }
+                }
+            }
+        }
+        if declined && missing.is_empty() {
+            return Ok(());
+        }
+        if !missing.is_empty() {
+            let project_root = project_root(&missing, project)?;
+            let placeholders = placeholders(&hyprlayer_config);
+            let pin_ref = hyprlayer_config.agent_pin_ref().map(str::to_string);
+            let sha = install_tools(
+                &missing,
+                &source,
+                Some(&placeholders),
+                pin_ref.as_deref(),
+                project_root.as_deref(),
+                !no_backup,
+            )?;
+            record_install(&config_path, sha, |target| target.ai = hyprlayer_config.ai)?;
             return Ok(());
         }
         return Err(anyhow::anyhow!(
             "Already configured: {}. Use --force to reconfigure.",
-            agent
+            tool_list(&existing_tools)
         ));
     }
 
     let theme = ColorfulTheme::default();
-    let agent_tool = prompt_for_agent_tool(&theme)?;
-
-    let (opencode_provider, opencode_sonnet_model, opencode_opus_model) =
-        if agent_tool == AgentTool::OpenCode {
-            let provider = prompt_for_opencode_provider(&theme)?;
-            (
-                Some(provider.clone()),
-                Some(provider.default_sonnet_model().to_string()),
-                Some(provider.default_opus_model().to_string()),
-            )
+    let agent_tools = if tools.is_empty() {
+        prompt_for_agent_tools(&theme, &existing_tools)?
+    } else {
+        dedup_tools(tools)
+    };
+    let project_root = project_root(&agent_tools, project)?;
+    let uses_opencode = agent_tools.contains(&AgentTool::OpenCode);
+    if !uses_opencode && (sonnet_model.is_some() || opus_model.is_some()) {
+        return Err(anyhow::anyhow!(
+            "--sonnet-model and --opus-model only apply to OpenCode"
+        ));
+    }
+
+    // Models are only stored when overridden, so the provider's defaults
+    // keep tracking new releases.
+    let (opencode_provider, opencode_sonnet_model, opencode_opus_model) = if uses_opencode {
+        let provider =
+            prompt_for_opencode_provider(&theme, sonnet_model.as_deref(), opus_model.as_deref())?;
+        // A custom provider holds its models itself.
+        if matches!(provider, OpenCodeProvider::Custom { .. }) {
+            (Some(provider), None, None)
         } else {
-            (None, None, None)
-        };
+            (Some(provider), sonnet_model, opus_model)
+        }
+    } else {
+        (None, None, None)
+    };
 
     let ai = hyprlayer_config.ai_mut();
-    ai.agent_tool = Some(agent_tool);
+    ai.agent_tools = agent_tools.clone();
     ai.opencode_provider = opencode_provider;
     ai.opencode_sonnet_model = opencode_sonnet_model;
     ai.opencode_opus_model = opencode_opus_model;
 
-    hyprlayer_config.save(&config_path)?;
+    HyprlayerConfig::update(&config_path, |target| {
+        target.ai = hyprlayer_config.ai.clone();
+        Ok(())
+    })?;
 
-    let opencode_provider_ref = hyprlayer_config
-        .ai
-        .as_ref()
-        .and_then(|ai| ai.opencode_provider.as_ref())
-        .cloned();
-    let sha = agent_tool.install(opencode_provider_ref.as_ref(), false)?;
-    record_install(&mut hyprlayer_config, &config_path, sha)?;
+    let placeholders = placeholders(&hyprlayer_config);
+    let pin_ref = hyprlayer_config.agent_pin_ref().map(str::to_string);
+    let sha = install_tools(
+        &agent_tools,
+        &source,
+        Some(&placeholders),
+        pin_ref.as_deref(),
+        project_root.as_deref(),
+        !no_backup,
+    )?;
+    record_install(&config_path, sha, |_| {})?;
 
     Ok(())
 }
 
-fn prompt_for_agent_tool(theme: &ColorfulTheme) -> Result<AgentTool> {
+/// Placeholders for the top-level `ai` settings: the thoughts paths, plus
+/// the OpenCode models with any overrides in place of the provider's
+/// defaults.
+fn placeholders(config: &HyprlayerConfig) -> PlaceholderSet {
+    let ai = config.ai.as_ref();
+    match ai.and_then(|ai| ai.opencode_provider.as_ref()) {
+        Some(provider) => build_placeholder_set(config.thoughts.as_ref(), provider).with_models(
+            ai.and_then(|ai| ai.opencode_sonnet_model.as_deref()),
+            ai.and_then(|ai| ai.opencode_opus_model.as_deref()),
+        ),
+        None => thoughts_placeholder_set(config.thoughts.as_ref()),
+    }
+}
+
+/// `--tool` values in the order given, without repeats.
+fn dedup_tools(tools: Vec<AgentTool>) -> Vec<AgentTool> {
+    let mut out = Vec::new();
+    for tool in tools {
+        if !out.contains(&tool) {
+            out.push(tool);
+        }
+    }
+    out
+}
+
+/// Multi-select the AI tools to install, with `current` pre-checked (or
+/// the first tool on a fresh setup). Loops until at least one is chosen.
+fn prompt_for_agent_tools(theme: &ColorfulTheme, current: &[AgentTool]) -> Result<Vec<AgentTool>> {
     let options: Vec<String> = AgentTool::ALL.iter().map(|t| t.to_string()).collect();
-    let selection = Select::with_theme(theme)
-        .with_prompt("Which AI tool do you use?")
-        .items(&options)
-        .default(0)
-        .interact()?;
+    let defaults: Vec<bool> = AgentTool::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, tool)| {
+            if current.is_empty() {
+                i == 0
+            } else {
+                current.contains(tool)
+            }
+        })
+        .collect();
 
-    Ok(AgentTool::ALL[selection])
+    loop {
+        let selection = MultiSelect::with_theme(theme)
+            .with_prompt("Which AI tools do you use? (space to toggle)")
+            .items(&options)
+            .defaults(&defaults)
+            .interact()?;
+        if !selection.is_empty() {
+            return Ok(selection.into_iter().map(|i| AgentTool::ALL[i]).collect());
+        }
+        println!("{}", "Select at least one AI tool.".red());
+    }
 }
 
-fn prompt_for_opencode_provider(theme: &ColorfulTheme) -> Result<OpenCodeProvider> {
-    let options: Vec<String> = OpenCodeProvider::ALL
+/// Pick a built-in provider, or enter a custom one's prefix and models
+/// (taken from `--sonnet-model`/`--opus-model` when given).
+fn prompt_for_opencode_provider(
+    theme: &ColorfulTheme,
+    sonnet_model: Option<&str>,
+    opus_model: Option<&str>,
+) -> Result<OpenCodeProvider> {
+    let mut options: Vec<String> = OpenCodeProvider::ALL
         .iter()
         .map(|p| p.to_string())
         .collect();
+    options.push("Custom (enter a provider prefix and models)".to_string());
     let selection = Select::with_theme(theme)
         .with_prompt("Which OpenCode provider do you want to use?")
         .items(&options)
         .default(0)
         .interact()?;
+    if let Some(provider) = OpenCodeProvider::ALL.get(selection) {
+        return Ok(provider.clone());
+    }
 
-    Ok(OpenCodeProvider::ALL[selection].clone())
+    let prefix: String = Input::with_theme(theme)
+        .with_prompt("Provider prefix, as configured in OpenCode")
+        .interact_text()?;
+    let prefix = prefix.trim().to_string();
+    let model = |prompt: &str, given: Option<&str>, tier: &str| -> Result<String> {
+        if let Some(model) = given {
+            return Ok(model.to_string());
+        }
+        Ok(Input::with_theme(theme)
+            .with_prompt(prompt)
+            .default(format!("{prefix}/{tier}"))
+            .interact_text()?)
+    };
+    Ok(OpenCodeProvider::Custom {
+        sonnet_model: model(
+            "Model for most commands and agents",
+            sonnet_model,
+            "claude-sonnet-4-5",
+        )?,
+        opus_model: model(
+            "Model for research and planning",
+            opus_model,
+            "claude-opus-4-5",
+        )?,
+        prefix,
+    })
 }
 
 fn load_or_create_minimal_config(config_path: &std::path::Path) -> Result<HyprlayerConfig> {
@@ -100,3 +260,20 @@ fn load_or_create_minimal_config(config_path: &std::path::Path) -> Result<Hyprla
     }
     Ok(HyprlayerConfig::default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_tools_keeps_first_occurrence() {
+        assert_eq!(
+            dedup_tools(vec![
+                AgentTool::OpenCode,
+                AgentTool::Claude,
+                AgentTool::OpenCode
+            ]),
+            [AgentTool::OpenCode, AgentTool::Claude]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::agents::{AgentTool, DiffStatus, FileDiff, PlaceholderSet};
+use crate::cli::AiDiffArgs;
+use crate::commands::ai::effective_tools;
+use crate::text_diff::{DiffLine, change_counts, diff_lines};
+
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+pub fn diff(args: AiDiffArgs) -> Result<()> {
+    // `--show` is the default; clap already lets the later of it and
+    // `--stat` win.
+    let AiDiffArgs {
+        stat,
+        show: _,
+        config,
+    } = args;
+
+    let hyprlayer_config = config.load_if_exists()?.ok_or_else(|| {
+        anyhow::anyhow!("No configuration found. Run 'hyprlayer ai configure' first.")
+    })?;
+    let (agent_tools, placeholders) = effective_tools(&hyprlayer_config)?;
+
+    for (i, agent_tool) in agent_tools.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        diff_tool(
+            *agent_tool,
+            Some(&placeholders),
+            hyprlayer_config.agent_pin_ref(),
+            stat,
+        )?;
+    }
+    Ok(())
+}
+
+fn diff_tool(
+    agent_tool: AgentTool,
+    placeholders: Option<&PlaceholderSet>,
+    pin_ref: Option<&str>,
+    stat: bool,
+) -> Result<()> {
+    println!(
+        "Comparing {} agent files in {} with {}...",
+        agent_tool,
+        agent_tool.dest_display(),
+        pin_ref.map_or_else(
+            || "the latest release".to_string(),
+            |pin| format!("pinned ref {pin}")
+        )
+    );
+    let diffs = agent_tool.diff_with_remote(placeholders, pin_ref)?;
+
+    if stat {
+        print_stat(&diffs);
+        return Ok(());
+    }
+
+    for diff in diffs.iter().filter(|d| d.status != DiffStatus::Unchanged) {
+        println!();
+        println!("{} {}", status_marker(diff.status), diff.path.display());
+        match (&diff.local_content, &diff.remote_content) {
+            (Some(local), Some(remote)) => print_hunks(&diff_lines(local, remote)),
+            (None, None) => println!("{}", "  (binary file)".bright_black()),
+            _ => {}
+        }
+    }
+    println!();
+    print_summary(&diffs);
+    Ok(())
+}
+
+/// Per-file `+added -removed` line counts for every file that differs,
+/// followed by the status totals.
+pub(crate) fn print_stat(diffs: &[FileDiff]) {
+    for diff in diffs.iter().filter(|d| d.status != DiffStatus::Unchanged) {
+        let (added, removed) = line_counts(diff);
+        println!(
+            "  {} {}  {} {}",
+            status_marker(diff.status),
+            diff.path.display(),
+            format!("+{added}").green(),
+            format!("-{removed}").red()
+        );
+    }
+    print_summary(diffs);
+}
+
+fn print_summary(diffs: &[FileDiff]) {
+    let count = |status| diffs.iter().filter(|d| d.status == status).count();
+    let (modified, added, removed) = (
+        count(DiffStatus::Modified),
+        count(DiffStatus::Added),
+        count(DiffStatus::Removed),
+    );
+    if modified + added + removed == 0 {
+        println!("{}", "Installed files match the remote bundle.".green());
+        return;
+    }
+    println!(
+        "{} modified, {} added, {} removed, {} unchanged",
+        modified,
+        added,
+        removed,
+        count(DiffStatus::Unchanged)
+    );
+    if removed > 0 {
+        println!(
+            "{}",
+            "Removed files are no longer shipped; reinstalling leaves them in place."
+                .bright_black()
+        );
+    }
+}
+
+fn status_marker(status: DiffStatus) -> colored::ColoredString {
+    match status {
+        DiffStatus::Added => "A".green(),
+        DiffStatus::Removed => "D".red(),
+        DiffStatus::Modified => "M".yellow(),
+        DiffStatus::Unchanged => " ".normal(),
+    }
+}
+
+fn line_counts(diff: &FileDiff) -> (usize, usize) {
+    let lines = |content: &Option<String>| content.as_deref().map_or(0, |c| c.lines().count());
+    match diff.status {
+        DiffStatus::Added => (lines(&diff.remote_content), 0),
+        DiffStatus::Removed => (0, lines(&diff.local_content)),
+        DiffStatus::Modified => match (&diff.local_content, &diff.remote_content) {
+            (Some(local), Some(remote)) => change_counts(&diff_lines(local, remote)),
+            _ => (0, 0),
+        },
+        DiffStatus::Unchanged => (0, 0),
+    }
+}
+
+/// Print changed lines with `CONTEXT_LINES` of surrounding context,
+/// separating non-adjacent hunks with `...`.
+fn print_hunks(lines: &[DiffLine]) {
+    let visible = hunk_mask(lines);
+    let mut skipped = false;
+    for (line, show) in lines.iter().zip(visible) {
+        if !show {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            println!("{}", "  ...".bright_black());
+            skipped = false;
+        }
+        match line {
+            DiffLine::Same(text) => println!("   {text}"),
+            DiffLine::Removed(text) => println!("{}", format!("  -{text}").red()),
+            DiffLine::Added(text) => println!("{}", format!("  +{text}").green()),
+        }
+    }
+}
+
+/// Which lines fall within `CONTEXT_LINES` of a change.
+fn hunk_mask(lines: &[DiffLine]) -> Vec<bool> {
+    let mut visible = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if !matches!(line, DiffLine::Same(_)) {
+            let start = i.saturating_sub(CONTEXT_LINES);
+            let end = (i + CONTEXT_LINES + 1).min(lines.len());
+            visible[start..end].iter_mut().for_each(|v| *v = true);
+        }
+    }
+    visible
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn hunk_mask_keeps_context_around_changes() {
+        let old = (1..=10)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let new = old.replace("\n5\n", "\nfive\n");
+        let diff = diff_lines(&old, &new);
+        let shown: Vec<&DiffLine> = diff
+            .iter()
+            .zip(hunk_mask(&diff))
+            .filter_map(|(line, show)| show.then_some(line))
+            .collect();
+        assert_eq!(shown.len(), 8);
+        assert_eq!(shown[0], &DiffLine::Same("2"));
+        assert_eq!(shown[7], &DiffLine::Same("8"));
+    }
+
+    #[test]
+    fn line_counts_by_status() {
+        let file = |status, local: Option<&str>, remote: Option<&str>| FileDiff {
+            path: PathBuf::from("agents/a.md"),
+            status,
+            local_content: local.map(str::to_string),
+            remote_content: remote.map(str::to_string),
+        };
+        assert_eq!(
+            line_counts(&file(DiffStatus::Added, None, Some("a\nb\n"))),
+            (2, 0)
+        );
+        assert_eq!(
+            line_counts(&file(DiffStatus::Removed, Some("a\n"), None)),
+            (0, 1)
+        );
+        assert_eq!(
+            line_counts(&file(
+                DiffStatus::Modified,
+                Some("a\nb\n"),
+                Some("a\nc\nd\n")
+            )),
+            (2, 1)
+        );
+    }
+}
@@ -1,29 +1,163 @@
 pub mod configure;
+pub mod diff;
 pub mod reinstall;
+pub mod restore_backup;
+pub mod set_model;
 pub mod status;
+pub mod uninstall;
+pub mod update;
 
 use anyhow::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use crate::config::HyprlayerConfig;
+use crate::agents::{
+    AgentSource, AgentTool, InstallOptions, InstructionsTarget, PlaceholderSet,
+    build_placeholder_set, combined_sha, thoughts_placeholder_set,
+};
+use crate::config::{HyprlayerConfig, get_current_repo_path};
+use crate::git_ops::GitRepo;
+
+/// Agent tools in effect for the current repository (see
+/// `HyprlayerConfig::effective_agent_tools_for_repo`), and the placeholders
+/// for their files: the thoughts paths, plus the OpenCode models when a
+/// provider is set (or the configured overrides of them).
+pub(crate) fn effective_tools(
+    config: &HyprlayerConfig,
+) -> Result<(Vec<AgentTool>, PlaceholderSet)> {
+    let repo_path = get_current_repo_path()
+        .ok()
+        .map(|p| p.display().to_string());
+    let repo_path = repo_path.as_deref();
+    let agent_tools = config.effective_agent_tools_for_repo(repo_path).to_vec();
+    if agent_tools.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No AI tool configured. Run 'hyprlayer ai configure' first."
+        ));
+    }
+    let (sonnet, opus) = config.effective_models_for_repo(repo_path);
+    let placeholders = match config.effective_opencode_provider_for_repo(repo_path) {
+        Some(p) => build_placeholder_set(config.thoughts.as_ref(), p).with_models(sonnet, opus),
+        None => thoughts_placeholder_set(config.thoughts.as_ref()),
+    };
+    Ok((agent_tools, placeholders))
+}
+
+/// `tools` as a comma-separated list for messages.
+pub(crate) fn tool_list(tools: &[AgentTool]) -> String {
+    tools
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The repository root for `--project`, which Claude Code, OpenCode and
+/// OpenAI Codex support. `None` without the flag.
+pub(crate) fn project_root(agent_tools: &[AgentTool], project: bool) -> Result<Option<PathBuf>> {
+    if !project {
+        return Ok(None);
+    }
+    if !agent_tools.iter().any(AgentTool::supports_project) {
+        return Err(anyhow::anyhow!(
+            "--project only applies to Claude Code, OpenCode and OpenAI Codex, not {}",
+            tool_list(agent_tools)
+        ));
+    }
+    let root = get_current_repo_path()?;
+    if !GitRepo::is_repo(&root) {
+        return Err(anyhow::anyhow!(
+            "--project must be run from the root of a git repository"
+        ));
+    }
+    Ok(Some(root))
+}
+
+/// Where `install` puts Codex's instructions for an optional `--project`
+/// root.
+fn instructions_target(project_root: Option<&Path>) -> InstructionsTarget<'_> {
+    project_root.map_or(InstructionsTarget::Global, InstructionsTarget::Project)
+}
+
+/// Install each of `agent_tools` in turn from `source`, returning the
+/// combined bundle SHA to record (see `combined_sha`). With a
+/// `project_root`, tools that have a project directory are installed there
+/// instead; the daily check only follows global installs, so their SHAs
+/// aren't part of the result. With `backup`, locally edited files are
+/// backed up before they're overwritten.
+pub(crate) fn install_tools(
+    agent_tools: &[AgentTool],
+    source: &AgentSource,
+    placeholders: Option<&PlaceholderSet>,
+    pin_ref: Option<&str>,
+    project_root: Option<&Path>,
+    backup: bool,
+) -> Result<Option<String>> {
+    let mut shas = Vec::new();
+    let options = InstallOptions {
+        quiet: false,
+        backup,
+    };
+    for tool in agent_tools {
+        if let Some(root) = project_root
+            && tool.project_dir(root).is_some()
+        {
+            tool.install_project(root, source, placeholders, pin_ref, options)?;
+            continue;
+        }
+        shas.push(tool.install(
+            source,
+            placeholders,
+            pin_ref,
+            instructions_target(project_root),
+            options,
+        )?);
+    }
+    Ok(combined_sha(shas))
+}
+
+/// `install_tools`, but through `AgentTool::update`, so only files that
+/// changed upstream are downloaded.
+pub(crate) fn update_tools(
+    agent_tools: &[AgentTool],
+    placeholders: Option<&PlaceholderSet>,
+    pin_ref: Option<&str>,
+    project_root: Option<&Path>,
+    backup: bool,
+) -> Result<Option<String>> {
+    let mut shas = Vec::new();
+    for tool in agent_tools {
+        let (sha, _) = tool.update(
+            placeholders,
+            pin_ref,
+            instructions_target(project_root),
+            backup,
+        )?;
+        shas.push(sha);
+    }
+    Ok(combined_sha(shas))
+}
 
 /// Persist the SHA after a successful `AgentTool::install` and clear
 /// `last_agent_check` so the next startup-time check re-evaluates
-/// immediately instead of waiting for the throttle window.
+/// immediately instead of waiting for the throttle window. `change` makes
+/// any other edits that go with the install, in the same `update`.
 ///
 /// `sha = None` (commits API was unreachable) leaves the cached SHA
 /// untouched but still clears the throttle, so the next startup check
 /// will retry the SHA fetch.
 pub(crate) fn record_install(
-    config: &mut HyprlayerConfig,
     config_path: &Path,
     sha: Option<String>,
+    change: impl FnOnce(&mut HyprlayerConfig),
 ) -> Result<()> {
-    if sha.is_some() {
-        config.agents_installed_sha = sha;
-    }
-    config.last_agent_check = None;
-    config.save(config_path)
+    HyprlayerConfig::update(config_path, |config| {
+        change(config);
+        if sha.is_some() {
+            config.agents_installed_sha = sha;
+        }
+        config.last_agent_check = None;
+        Ok(())
+    })
 }
 
 #[cfg(test)]
@@ -37,17 +171,14 @@ mod tests {
         fs::create_dir_all(&temp_dir).unwrap();
         let config_path = temp_dir.join("config.json");
 
-        let mut cfg = HyprlayerConfig {
+        let cfg = HyprlayerConfig {
             agents_installed_sha: Some("old".to_string()),
             last_agent_check: Some(1_700_000_000),
             ..Default::default()
         };
         cfg.save(&config_path).unwrap();
 
-        record_install(&mut cfg, &config_path, Some("new".to_string())).unwrap();
-
-        assert_eq!(cfg.agents_installed_sha.as_deref(), Some("new"));
-        assert!(cfg.last_agent_check.is_none());
+        record_install(&config_path, Some("new".to_string()), |_| {}).unwrap();
 
         let reloaded = HyprlayerConfig::load(&config_path).unwrap();
         assert_eq!(reloaded.agents_installed_sha.as_deref(), Some("new"));
@@ -62,17 +193,22 @@ mod tests {
         fs::create_dir_all(&temp_dir).unwrap();
         let config_path = temp_dir.join("config.json");
 
-        let mut cfg = HyprlayerConfig {
+        let cfg = HyprlayerConfig {
             agents_installed_sha: Some("existing".to_string()),
             last_agent_check: Some(1_700_000_000),
             ..Default::default()
         };
         cfg.save(&config_path).unwrap();
 
-        record_install(&mut cfg, &config_path, None).unwrap();
+        record_install(&config_path, None, |config| {
+            config.ai_mut().agent_pin_ref = Some("v1".to_string());
+        })
+        .unwrap();
 
-        assert_eq!(cfg.agents_installed_sha.as_deref(), Some("existing"));
-        assert!(cfg.last_agent_check.is_none());
+        let reloaded = HyprlayerConfig::load(&config_path).unwrap();
+        assert_eq!(reloaded.agents_installed_sha.as_deref(), Some("existing"));
+        assert!(reloaded.last_agent_check.is_none());
+        assert_eq!(reloaded.agent_pin_ref(), Some("v1"));
 
         fs::remove_dir_all(&temp_dir).ok();
     }
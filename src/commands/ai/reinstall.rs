@@ -1,28 +1,55 @@
 use anyhow::Result;
 
+use crate::agents::AgentSource;
 use crate::cli::AiReinstallArgs;
-use crate::commands::ai::record_install;
+use crate::commands::ai::diff::print_stat;
+use crate::commands::ai::{effective_tools, install_tools, project_root, record_install};
 
 pub fn reinstall(args: AiReinstallArgs) -> Result<()> {
-    let AiReinstallArgs { config } = args;
+    let AiReinstallArgs {
+        dry_run,
+        project,
+        source,
+        git_ref,
+        no_backup,
+        config,
+    } = args;
+    let source = AgentSource::from_arg(source.as_deref())?;
     let config_path = config.path()?;
 
-    let mut hyprlayer_config = config.load().map_err(|_| {
+    let hyprlayer_config = config.load().map_err(|_| {
         anyhow::anyhow!("No configuration found. Run 'hyprlayer ai configure' first.")
     })?;
+    let pin_ref = git_ref
+        .clone()
+        .or_else(|| hyprlayer_config.agent_pin_ref().map(str::to_string));
 
-    let (agent_tool, opencode_provider) = {
-        let ai_config = hyprlayer_config.ai.as_ref().ok_or_else(|| {
-            anyhow::anyhow!("No AI tool configured. Run 'hyprlayer ai configure' first.")
-        })?;
-        let agent_tool = ai_config.agent_tool.ok_or_else(|| {
-            anyhow::anyhow!("No AI tool configured. Run 'hyprlayer ai configure' first.")
-        })?;
-        (agent_tool, ai_config.opencode_provider.clone())
-    };
+    let (agent_tools, placeholders) = effective_tools(&hyprlayer_config)?;
+    let project_root = project_root(&agent_tools, project)?;
 
-    let sha = agent_tool.install(opencode_provider.as_ref(), false)?;
-    record_install(&mut hyprlayer_config, &config_path, sha)?;
+    if dry_run {
+        for agent_tool in &agent_tools {
+            let diffs = agent_tool.diff_with_remote(Some(&placeholders), pin_ref.as_deref())?;
+            println!("Reinstalling {} agent files would:", agent_tool);
+            print_stat(&diffs);
+        }
+        return Ok(());
+    }
+
+    let sha = install_tools(
+        &agent_tools,
+        &source,
+        Some(&placeholders),
+        pin_ref.as_deref(),
+        project_root.as_deref(),
+        !no_backup,
+    )?;
+    // Saved with the install so the daily check keeps following this ref.
+    record_install(&config_path, sha, |target| {
+        if let Some(git_ref) = git_ref {
+            target.ai_mut().agent_pin_ref = Some(git_ref);
+        }
+    })?;
 
     Ok(())
 }
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::AiRestoreBackupArgs;
+use crate::commands::ai::effective_tools;
+
+pub fn restore_backup(args: AiRestoreBackupArgs) -> Result<()> {
+    let AiRestoreBackupArgs { name, config } = args;
+
+    let hyprlayer_config = config.load_if_exists()?.ok_or_else(|| {
+        anyhow::anyhow!("No configuration found. Run 'hyprlayer ai configure' first.")
+    })?;
+    let (agent_tools, _) = effective_tools(&hyprlayer_config)?;
+
+    let Some(name) = name else {
+        for (i, agent_tool) in agent_tools.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("{} ({}):", agent_tool, agent_tool.dest_display().cyan());
+            let backups = agent_tool.backups()?;
+            if backups.is_empty() {
+                println!("  {}", "No backups".bright_black());
+            }
+            for backup in backups {
+                println!("  {}  {} file(s)", backup.name.cyan(), backup.files.len());
+            }
+        }
+        return Ok(());
+    };
+
+    let mut restored_any = false;
+    for agent_tool in &agent_tools {
+        if !agent_tool.backups()?.iter().any(|b| b.name == name) {
+            continue;
+        }
+        let files = agent_tool.restore_backup(&name)?;
+        println!(
+            "{}",
+            format!(
+                "Restored {} {} file(s) from backup {}",
+                files.len(),
+                agent_tool,
+                name
+            )
+            .green()
+        );
+        for file in files {
+            println!("  {}", file.display());
+        }
+        restored_any = true;
+    }
+    if !restored_any {
+        return Err(anyhow::anyhow!(
+            "No backup named {name}. Run 'hyprlayer ai restore-backup' to list them."
+        ));
+    }
+    Ok(())
+}
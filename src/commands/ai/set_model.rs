@@ -0,0 +1,71 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::{AiSetModelArgs, ModelTier};
+use crate::config::HyprlayerConfig;
+
+pub fn set_model(args: AiSetModelArgs) -> Result<()> {
+    let AiSetModelArgs {
+        tier,
+        model,
+        config,
+    } = args;
+    config.reject_overrides("ai set-model")?;
+    if config.load_if_exists()?.is_none() {
+        return Err(anyhow::anyhow!(
+            "No configuration found. Run 'hyprlayer ai configure' first."
+        ));
+    }
+
+    let message = config.update(|target| Ok(set_tier(target, tier, &model)))?;
+    println!("{}", message.green());
+    println!(
+        "{}",
+        "Run 'hyprlayer ai reinstall' to apply it to the installed agent files.".bright_black()
+    );
+    Ok(())
+}
+
+/// Store `model` as the override for `tier` (`default` clears it) and
+/// describe the change.
+fn set_tier(config: &mut HyprlayerConfig, tier: ModelTier, model: &str) -> String {
+    let ai = config.ai_mut();
+    let (slot, name) = match tier {
+        ModelTier::Sonnet => (&mut ai.opencode_sonnet_model, "Sonnet"),
+        ModelTier::Opus => (&mut ai.opencode_opus_model, "Opus"),
+    };
+    if model == "default" {
+        *slot = None;
+        format!("{name} model reset to the provider's default")
+    } else {
+        *slot = Some(model.to_string());
+        format!("{name} model set to {model}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::{OpenCodeProvider, build_placeholder_set};
+
+    #[test]
+    fn overrides_replace_the_provider_defaults_until_reset() {
+        let mut cfg = HyprlayerConfig::default();
+        cfg.ai_mut().opencode_provider = Some(OpenCodeProvider::Anthropic);
+        set_tier(&mut cfg, ModelTier::Sonnet, "anthropic/claude-sonnet-9");
+
+        let ai = cfg.ai.as_ref().unwrap();
+        let set = build_placeholder_set(None, &OpenCodeProvider::Anthropic).with_models(
+            ai.opencode_sonnet_model.as_deref(),
+            ai.opencode_opus_model.as_deref(),
+        );
+        assert_eq!(set.get("SONNET_MODEL"), Some("anthropic/claude-sonnet-9"));
+        assert_eq!(
+            set.get("OPUS_MODEL"),
+            Some(OpenCodeProvider::Anthropic.default_opus_model())
+        );
+
+        set_tier(&mut cfg, ModelTier::Sonnet, "default");
+        assert_eq!(cfg.ai.unwrap().opencode_sonnet_model, None);
+    }
+}
@@ -3,8 +3,9 @@ use chrono_humanize::{Accuracy, HumanTime, Tense};
 use colored::Colorize;
 use std::time::{Duration, UNIX_EPOCH};
 
+use crate::agents::AgentTool;
 use crate::cli::AiStatusArgs;
-use crate::config::HyprlayerConfig;
+use crate::config::{AiConfig, HyprlayerConfig, get_current_repo_path, redact_json};
 
 fn print_not_configured(json: bool) -> Result<()> {
     if json {
@@ -20,23 +21,60 @@ fn print_not_configured(json: bool) -> Result<()> {
 }
 
 pub fn status(args: AiStatusArgs) -> Result<()> {
-    let AiStatusArgs { json, config } = args;
+    let AiStatusArgs {
+        json,
+        verbose,
+        all,
+        config,
+    } = args;
     let config_path = config.path()?;
+    let loaded = config.load_if_exists()?;
 
-    let Some(hyprlayer_config) = config.load_if_exists()? else {
+    // A repository mapped to a profile with its own `ai` block reports that
+    // profile's settings; outside a repository only the top level applies.
+    let repo_path = get_current_repo_path()
+        .ok()
+        .map(|p| p.display().to_string());
+
+    if all {
+        let ai_config = loaded
+            .as_ref()
+            .and_then(|c| c.effective_ai_for_repo(repo_path.as_deref()))
+            .unwrap_or_default();
+        return print_all_tools(&ai_config, json);
+    }
+
+    let Some(hyprlayer_config) = loaded else {
         return print_not_configured(json);
     };
-
-    let Some(ref ai_config) = hyprlayer_config.ai else {
+    let Some(ai_config) = hyprlayer_config.effective_ai_for_repo(repo_path.as_deref()) else {
         return print_not_configured(json);
     };
 
-    let Some(ref agent_tool) = ai_config.agent_tool else {
+    if ai_config.agent_tools.is_empty() {
         return print_not_configured(json);
-    };
+    }
 
     if json {
-        let mut value = agent_tool.status_json(ai_config);
+        // The primary tool's fields stay at the top level, as they were
+        // before several tools could be configured; `tools` lists them all.
+        let mut tools = Vec::new();
+        for agent_tool in &ai_config.agent_tools {
+            let mut value = agent_tool.status_json(&ai_config);
+            if verbose && let Some(map) = value.as_object_mut() {
+                let files = agent_tool
+                    .list_installed_files()?
+                    .iter()
+                    .map(|p| serde_json::Value::String(p.display().to_string()))
+                    .collect();
+                map.insert(
+                    "installedFiles".to_string(),
+                    serde_json::Value::Array(files),
+                );
+            }
+            tools.push(value);
+        }
+        let mut value = tools[0].clone();
         if let Some(map) = value.as_object_mut() {
             map.insert(
                 "agentsInstalledSha".to_string(),
@@ -53,12 +91,22 @@ pub fn status(args: AiStatusArgs) -> Result<()> {
                     .map(|t| serde_json::Value::Number(t.into()))
                     .unwrap_or(serde_json::Value::Null),
             );
+            map.insert("tools".to_string(), serde_json::Value::Array(tools));
         }
+        redact_json(&mut value);
         println!("{}", serde_json::to_string_pretty(&value)?);
         return Ok(());
     }
 
-    agent_tool.print_status(ai_config);
+    for (i, agent_tool) in ai_config.agent_tools.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        agent_tool.print_status(&ai_config);
+        if verbose {
+            print_installed_files(agent_tool)?;
+        }
+    }
     print_bundle_freshness(&hyprlayer_config);
 
     println!();
@@ -70,6 +118,67 @@ pub fn status(args: AiStatusArgs) -> Result<()> {
     Ok(())
 }
 
+/// `ai status --all`: one line per supported tool, configured or not.
+fn print_all_tools(ai_config: &AiConfig, json: bool) -> Result<()> {
+    if json {
+        let mut value = all_tools_json(ai_config);
+        redact_json(&mut value);
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    for agent_tool in AgentTool::ALL {
+        let name = format!("{:<16}", agent_tool.to_string());
+        let state = if agent_tool.is_installed() {
+            format!("{:<15}", "installed").green()
+        } else {
+            format!("{:<15}", "not installed").bright_black()
+        };
+        let configured = if ai_config.agent_tools.contains(agent_tool) {
+            format!("  {}", "(configured)".yellow())
+        } else {
+            String::new()
+        };
+        println!(
+            "  {}{}{}{}",
+            name.cyan(),
+            state,
+            agent_tool.dest_display(),
+            configured
+        );
+    }
+    Ok(())
+}
+
+/// Each tool's `status_json` with a `configured` flag, in `AgentTool::ALL`
+/// order.
+fn all_tools_json(ai_config: &AiConfig) -> serde_json::Value {
+    let tools = AgentTool::ALL
+        .iter()
+        .map(|agent_tool| {
+            let mut value = agent_tool.status_json(ai_config);
+            if let Some(map) = value.as_object_mut() {
+                map.insert(
+                    "configured".to_string(),
+                    serde_json::Value::Bool(ai_config.agent_tools.contains(agent_tool)),
+                );
+            }
+            value
+        })
+        .collect();
+    serde_json::Value::Array(tools)
+}
+
+fn print_installed_files(agent_tool: &AgentTool) -> Result<()> {
+    let files = agent_tool.list_installed_files()?;
+    println!();
+    println!("  Installed files ({}):", files.len());
+    for file in &files {
+        println!("    {}", file.display().to_string().bright_black());
+    }
+    Ok(())
+}
+
 /// Render the cached bundle SHA + last-check timestamp under the per-tool
 /// status block. Skipped entirely when no SHA is cached, so users who
 /// configured an AI tool but haven't yet hit an auto-reinstall window
@@ -95,3 +204,24 @@ fn print_bundle_freshness(config: &HyprlayerConfig) {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_tools_json_flags_configured_tools() {
+        let ai_config = AiConfig {
+            agent_tools: vec![AgentTool::OpenCode],
+            ..Default::default()
+        };
+        let value = all_tools_json(&ai_config);
+        let tools = value.as_array().unwrap();
+        assert_eq!(tools.len(), AgentTool::ALL.len());
+        for (tool, json) in AgentTool::ALL.iter().zip(tools) {
+            assert_eq!(json["agentTool"], tool.to_string());
+            assert_eq!(json["configured"], *tool == AgentTool::OpenCode);
+            assert!(json["installed"].is_boolean());
+        }
+    }
+}
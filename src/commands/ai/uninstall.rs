@@ -0,0 +1,52 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::AiUninstallArgs;
+use crate::commands::ai::effective_tools;
+
+pub fn uninstall(args: AiUninstallArgs) -> Result<()> {
+    let AiUninstallArgs { config } = args;
+
+    let hyprlayer_config = config.load_if_exists()?.ok_or_else(|| {
+        anyhow::anyhow!("No configuration found. Run 'hyprlayer ai configure' first.")
+    })?;
+    let (agent_tools, _) = effective_tools(&hyprlayer_config)?;
+
+    let mut uninstalled = Vec::new();
+    let mut failed = false;
+    for agent_tool in &agent_tools {
+        match agent_tool.uninstall() {
+            Ok(files) => {
+                println!(
+                    "{}",
+                    format!(
+                        "Removed {} {} file(s) from {}",
+                        files.len(),
+                        agent_tool,
+                        agent_tool.dest_display()
+                    )
+                    .green()
+                );
+                uninstalled.push(*agent_tool);
+            }
+            Err(e) => {
+                eprintln!("{}", format!("{agent_tool}: {e:#}").yellow());
+                failed = true;
+            }
+        }
+    }
+
+    // Otherwise the daily agent check would reinstall them.
+    if !uninstalled.is_empty() {
+        config.update(|target| {
+            if let Some(ai) = target.ai.as_mut() {
+                ai.agent_tools.retain(|tool| !uninstalled.contains(tool));
+            }
+            Ok(())
+        })?;
+    }
+    if failed {
+        return Err(anyhow::anyhow!("Some agent files were not uninstalled"));
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anyhow::Result;
+
+use crate::cli::{AiReinstallArgs, AiUpdateArgs};
+use crate::commands::ai::reinstall::reinstall;
+use crate::commands::ai::{effective_tools, project_root, record_install, update_tools};
+
+pub fn update(args: AiUpdateArgs) -> Result<()> {
+    let AiUpdateArgs {
+        force,
+        project,
+        no_backup,
+        config,
+    } = args;
+    if force {
+        return reinstall(AiReinstallArgs {
+            dry_run: false,
+            project,
+            source: None,
+            git_ref: None,
+            no_backup,
+            config,
+        });
+    }
+    let config_path = config.path()?;
+
+    let hyprlayer_config = config.load().map_err(|_| {
+        anyhow::anyhow!("No configuration found. Run 'hyprlayer ai configure' first.")
+    })?;
+
+    let (agent_tools, placeholders) = effective_tools(&hyprlayer_config)?;
+    let project_root = project_root(&agent_tools, project)?;
+
+    let pin_ref = hyprlayer_config.agent_pin_ref().map(str::to_string);
+    let sha = update_tools(
+        &agent_tools,
+        Some(&placeholders),
+        pin_ref.as_deref(),
+        project_root.as_deref(),
+        !no_backup,
+    )?;
+    record_install(&config_path, sha, |_| {})?;
+
+    Ok(())
+}
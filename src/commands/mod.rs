@@ -2,3 +2,4 @@ pub mod ai;
 pub mod codex;
 pub mod storage;
 pub mod thoughts;
+pub mod version;
@@ -4,7 +4,9 @@ use serde_json::{Value, json};
 
 use crate::backends::schema::schema_as_json_value;
 use crate::cli::StorageInfoArgs;
-use crate::config::{BackendConfig, EffectiveConfig, expand_path, get_current_repo_path};
+use crate::config::{
+    BackendConfig, EffectiveConfig, expand_path, get_current_repo_path, redact_json,
+};
 
 fn expand_display(s: &str) -> String {
     expand_path(s).display().to_string()
@@ -27,7 +29,8 @@ pub fn info(args: StorageInfoArgs) -> Result<()> {
         .unwrap_or_else(default_effective);
 
     if as_json {
-        let payload = build_json(&effective, &current_repo_str);
+        let mut payload = build_json(&effective, &current_repo_str);
+        redact_json(&mut payload);
         println!("{}", serde_json::to_string_pretty(&payload)?);
         return Ok(());
     }
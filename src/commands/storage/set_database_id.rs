@@ -1,29 +1,27 @@
 use anyhow::Result;
 
 use crate::cli::StorageSetDatabaseIdArgs;
-use crate::config::{HyprlayerConfig, get_current_repo_path};
+use crate::config::get_current_repo_path;
 
 pub fn set_database_id(args: StorageSetDatabaseIdArgs) -> Result<()> {
     let StorageSetDatabaseIdArgs { id, config } = args;
+    config.reject_overrides("storage set-database-id")?;
 
     if id.trim().is_empty() {
         return Err(anyhow::anyhow!("Database ID cannot be empty"));
     }
 
-    let config_path = config.path()?;
-    let mut hyprlayer_config = HyprlayerConfig::load(&config_path)?;
-
     let current_repo = get_current_repo_path()?;
     let current_repo_str = current_repo.display().to_string();
 
-    let thoughts = hyprlayer_config.thoughts.as_mut().ok_or_else(|| {
-        anyhow::anyhow!("No thoughts configuration found. Run 'hyprlayer thoughts init' first.")
-    })?;
-
-    let backend = thoughts.active_backend_mut(&current_repo_str)?;
-    backend.require_notion_mut("set-database-id")?.database_id = Some(id);
-    hyprlayer_config.save(&config_path)?;
-    Ok(())
+    config.update(|target| {
+        let thoughts = target.thoughts.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("No thoughts configuration found. Run 'hyprlayer thoughts init' first.")
+        })?;
+        let backend = thoughts.active_backend_mut(&current_repo_str)?;
+        backend.require_notion_mut("set-database-id")?.database_id = Some(id);
+        Ok(())
+    })
 }
 
 #[cfg(test)]
@@ -31,7 +29,9 @@ mod tests {
     use super::*;
     use crate::cli::ConfigArgs;
     use crate::commands::storage::test_util::with_cwd;
-    use crate::config::{BackendConfig, GitConfig, NotionConfig, RepoMapping, ThoughtsConfig};
+    use crate::config::{
+        BackendConfig, GitConfig, HyprlayerConfig, NotionConfig, RepoMapping, ThoughtsConfig,
+    };
     use tempfile::TempDir;
 
     fn seed_notion_config(path: &std::path::Path, current_repo_str: &str) -> anyhow::Result<()> {
@@ -50,6 +50,13 @@ mod tests {
                 .into_iter()
                 .collect(),
                 profiles: Default::default(),
+                default_profile: None,
+                hooks_enabled: true,
+                sync_jitter_max_seconds: None,
+                encrypted_files: Vec::new(),
+                skip_secrets_scan: false,
+                proxy: None,
+                push_with_git_cli: false,
             }),
             ..Default::default()
         };
@@ -74,6 +81,13 @@ mod tests {
                 .into_iter()
                 .collect(),
                 profiles: Default::default(),
+                default_profile: None,
+                hooks_enabled: true,
+                sync_jitter_max_seconds: None,
+                encrypted_files: Vec::new(),
+                skip_secrets_scan: false,
+                proxy: None,
+                push_with_git_cli: false,
             }),
             ..Default::default()
         };
@@ -93,7 +107,8 @@ mod tests {
             let err = set_database_id(StorageSetDatabaseIdArgs {
                 id: "db-123".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap_err();
@@ -113,7 +128,8 @@ mod tests {
             set_database_id(StorageSetDatabaseIdArgs {
                 id: "db-123".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap();
@@ -137,7 +153,8 @@ mod tests {
             let err = set_database_id(StorageSetDatabaseIdArgs {
                 id: "   ".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap_err();
@@ -1,29 +1,27 @@
 use anyhow::Result;
 
 use crate::cli::StorageSetTypeIdArgs;
-use crate::config::{HyprlayerConfig, get_current_repo_path};
+use crate::config::get_current_repo_path;
 
 pub fn set_type_id(args: StorageSetTypeIdArgs) -> Result<()> {
     let StorageSetTypeIdArgs { id, config } = args;
+    config.reject_overrides("storage set-type-id")?;
 
     if id.trim().is_empty() {
         return Err(anyhow::anyhow!("Type ID cannot be empty"));
     }
 
-    let config_path = config.path()?;
-    let mut hyprlayer_config = HyprlayerConfig::load(&config_path)?;
-
     let current_repo = get_current_repo_path()?;
     let current_repo_str = current_repo.display().to_string();
 
-    let thoughts = hyprlayer_config.thoughts.as_mut().ok_or_else(|| {
-        anyhow::anyhow!("No thoughts configuration found. Run 'hyprlayer thoughts init' first.")
-    })?;
-
-    let backend = thoughts.active_backend_mut(&current_repo_str)?;
-    backend.require_anytype_mut("set-type-id")?.type_id = Some(id);
-    hyprlayer_config.save(&config_path)?;
-    Ok(())
+    config.update(|target| {
+        let thoughts = target.thoughts.as_mut().ok_or_else(|| {
+            anyhow::anyhow!("No thoughts configuration found. Run 'hyprlayer thoughts init' first.")
+        })?;
+        let backend = thoughts.active_backend_mut(&current_repo_str)?;
+        backend.require_anytype_mut("set-type-id")?.type_id = Some(id);
+        Ok(())
+    })
 }
 
 #[cfg(test)]
@@ -31,7 +29,9 @@ mod tests {
     use super::*;
     use crate::cli::ConfigArgs;
     use crate::commands::storage::test_util::with_cwd;
-    use crate::config::{AnytypeConfig, BackendConfig, GitConfig, RepoMapping, ThoughtsConfig};
+    use crate::config::{
+        AnytypeConfig, BackendConfig, GitConfig, HyprlayerConfig, RepoMapping, ThoughtsConfig,
+    };
     use tempfile::TempDir;
 
     fn seed_anytype_config(path: &std::path::Path, current_repo_str: &str) -> anyhow::Result<()> {
@@ -51,6 +51,13 @@ mod tests {
                 .into_iter()
                 .collect(),
                 profiles: Default::default(),
+                default_profile: None,
+                hooks_enabled: true,
+                sync_jitter_max_seconds: None,
+                encrypted_files: Vec::new(),
+                skip_secrets_scan: false,
+                proxy: None,
+                push_with_git_cli: false,
             }),
             ..Default::default()
         };
@@ -75,6 +82,13 @@ mod tests {
                 .into_iter()
                 .collect(),
                 profiles: Default::default(),
+                default_profile: None,
+                hooks_enabled: true,
+                sync_jitter_max_seconds: None,
+                encrypted_files: Vec::new(),
+                skip_secrets_scan: false,
+                proxy: None,
+                push_with_git_cli: false,
             }),
             ..Default::default()
         };
@@ -94,7 +108,8 @@ mod tests {
             let err = set_type_id(StorageSetTypeIdArgs {
                 id: "type-123".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap_err();
@@ -114,7 +129,8 @@ mod tests {
             set_type_id(StorageSetTypeIdArgs {
                 id: "type-123".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap();
@@ -138,7 +154,8 @@ mod tests {
             let err = set_type_id(StorageSetTypeIdArgs {
                 id: "   ".to_string(),
                 config: ConfigArgs {
-                    config_file: Some(cfg_path.display().to_string()),
+                    config_file: vec![cfg_path.display().to_string()],
+                    overrides: Vec::new(),
                 },
             })
             .unwrap_err();
@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::io::Read;
+
+use crate::cli::ApplyPatchArgs;
+use crate::config::expand_path;
+use crate::git_ops::GitRepo;
+
+pub fn apply_patch(args: ApplyPatchArgs) -> Result<()> {
+    let ApplyPatchArgs {
+        path,
+        abort,
+        config,
+    } = args;
+
+    let (thoughts_repo, repo) = config.load_git_thoughts_repo("thoughts apply-patch")?;
+
+    if abort {
+        repo.abort_apply()?;
+        println!("{}", "Aborted patch application".green());
+        return Ok(());
+    }
+
+    let patch = match path {
+        Some(path) => {
+            let path = expand_path(&path);
+            fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?
+        }
+        None => {
+            let mut patch = String::new();
+            std::io::stdin()
+                .read_to_string(&mut patch)
+                .context("Failed to read patch from stdin")?;
+            patch
+        }
+    };
+    if patch.trim().is_empty() {
+        return Err(anyhow::anyhow!("Patch is empty"));
+    }
+
+    if let Err(e) = repo.apply_patch(&patch) {
+        if !repo.apply_in_progress() {
+            return Err(e);
+        }
+        print_conflict_help(&repo, &thoughts_repo.display().to_string())?;
+        return Err(anyhow::anyhow!("Patch did not apply cleanly"));
+    }
+
+    println!(
+        "{}",
+        format!("Applied patch to {}", thoughts_repo.display()).green()
+    );
+    println!("  Commit: {}", repo.get_last_commit()?.cyan());
+    Ok(())
+}
+
+fn print_conflict_help(repo: &GitRepo, thoughts_repo: &str) -> Result<()> {
+    let conflicts = repo.conflicted_files()?;
+    if conflicts.is_empty() {
+        println!(
+            "{}",
+            "The patch does not apply to the current thoughts:".red()
+        );
+    } else {
+        println!("{}", "Conflicting files:".red());
+        for file in &conflicts {
+            println!("  {}", file.yellow());
+        }
+    }
+    println!();
+    println!("To resolve manually:");
+    println!("  cd {}", thoughts_repo);
+    println!("  # fix the files, then stage them with: git add <file>");
+    println!("  git am --continue");
+    println!(
+        "{}",
+        "Or give up with: hyprlayer thoughts apply-patch --abort".bright_black()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_ops::tests::repo_with_identity;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    fn repo_with_note(dir: &Path, content: &str) {
+        repo_with_identity(dir, None);
+        fs::write(dir.join("note.md"), content).unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "base"]);
+    }
+
+    #[test]
+    fn apply_patch_commits_or_reports_conflicts() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, clean, diverged) = (
+            root.path().join("source"),
+            root.path().join("clean"),
+            root.path().join("diverged"),
+        );
+        for dir in [&source, &clean, &diverged] {
+            fs::create_dir(dir).unwrap();
+            repo_with_note(dir, "one\n");
+        }
+        fs::write(source.join("note.md"), "one\ntwo\n").unwrap();
+        git(&source, &["commit", "-qam", "Add two"]);
+        let patch = git(&source, &["format-patch", "-1", "--stdout"]);
+
+        let repo = GitRepo::open(&clean).unwrap();
+        repo.apply_patch(&patch).unwrap();
+        assert!(repo.get_last_commit().unwrap().contains("Add two"));
+        assert_eq!(
+            fs::read_to_string(clean.join("note.md")).unwrap(),
+            "one\ntwo\n"
+        );
+
+        fs::write(diverged.join("note.md"), "uno\n").unwrap();
+        git(&diverged, &["commit", "-qam", "Translate"]);
+        let repo = GitRepo::open(&diverged).unwrap();
+        assert!(repo.apply_patch(&patch).is_err());
+        assert!(repo.apply_in_progress());
+
+        repo.abort_apply().unwrap();
+        assert!(!repo.apply_in_progress());
+        assert!(repo.get_last_commit().unwrap().contains("Translate"));
+    }
+}
@@ -0,0 +1,205 @@
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use colored::Colorize;
+use serde::Serialize;
+
+use crate::cli::AuditArgs;
+use crate::git_ops::{CommitInfo, GitRepo};
+use crate::search_index;
+
+/// One sync commit in the thoughts repository. `timestamp` is in unix
+/// seconds; `signed` is only present with `--verify-signatures`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub commit_id: String,
+    pub author: String,
+    pub timestamp: i64,
+    pub message: String,
+    pub files_changed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signed: Option<bool>,
+}
+
+pub fn audit(args: AuditArgs) -> Result<()> {
+    let AuditArgs {
+        since,
+        limit,
+        verify_signatures,
+        json,
+        config,
+    } = args;
+
+    let (_, repo) = config.load_git_thoughts_repo("thoughts audit")?;
+
+    let since = since
+        .map(|d| search_index::parse_date(&d, Local::now()))
+        .transpose()?;
+    let entries = audit_entries(&repo, since, limit, verify_signatures)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("{}", "No sync commits found".bright_black());
+        return Ok(());
+    }
+    print_table(&entries);
+    Ok(())
+}
+
+/// Whether `commit` was made by `thoughts sync`, by hand or from the
+/// post-commit hook.
+fn is_sync_commit(commit: &CommitInfo) -> bool {
+    commit.summary.starts_with("Sync") || commit.summary.starts_with("Auto-sync")
+}
+
+/// Up to `limit` sync commits from HEAD, newest first, made at or after
+/// `since` (unix seconds).
+fn audit_entries(
+    repo: &GitRepo,
+    since: Option<i64>,
+    limit: usize,
+    verify_signatures: bool,
+) -> Result<Vec<AuditEntry>> {
+    let since = since.unwrap_or(i64::MIN);
+    repo.log_matching(limit, |c| is_sync_commit(c) && c.time >= since)?
+        .into_iter()
+        .map(|commit| {
+            let signed = verify_signatures
+                .then(|| repo.verify_commit(&commit.id))
+                .transpose()?;
+            Ok(AuditEntry {
+                files_changed: repo.files_changed(&commit.id)?,
+                commit_id: commit.id,
+                author: commit.author,
+                timestamp: commit.time,
+                message: commit.summary,
+                signed,
+            })
+        })
+        .collect()
+}
+
+fn print_table(entries: &[AuditEntry]) {
+    let author_width = entries
+        .iter()
+        .map(|e| e.author.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("AUTHOR".len());
+    let verified = entries.iter().any(|e| e.signed.is_some());
+
+    let mut header = format!(
+        "{:<8} {:<16} {:<author_width$} {:>5} ",
+        "COMMIT", "DATE", "AUTHOR", "FILES"
+    );
+    if verified {
+        header.push_str(&format!("{:<9}", "SIGNED"));
+    }
+    header.push_str("MESSAGE");
+    println!("{}", header.bold());
+
+    for entry in entries {
+        let date = Local
+            .timestamp_opt(entry.timestamp, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let signed = match entry.signed {
+            Some(true) => format!("{:<9}", "yes").green().to_string(),
+            Some(false) => format!("{:<9}", "UNSIGNED").red().to_string(),
+            None => String::new(),
+        };
+        println!(
+            "{} {:<16} {:<author_width$} {:>5} {}{}",
+            format!("{:<8}", &entry.commit_id[..7]).yellow(),
+            date,
+            entry.author,
+            entry.files_changed,
+            signed,
+            entry.message
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git_ops::tests::repo_with_identity;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        git_at(dir, args, "@1700000000 +0000");
+    }
+
+    /// Run git with `date` as both author and committer date.
+    fn git_at(dir: &Path, args: &[&str], date: &str) {
+        let output = Command::new("git")
+            .args(args)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    fn commit(dir: &Path, files: &[&str], message: &str, date: &str) {
+        for file in files {
+            fs::write(dir.join(file), message).unwrap();
+        }
+        git(dir, &["add", "."]);
+        git_at(dir, &["commit", "-qm", message, "--no-gpg-sign"], date);
+    }
+
+    #[test]
+    fn audit_entries_keeps_only_sync_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        repo_with_identity(root, None);
+        commit(
+            root,
+            &["a.md"],
+            "Initial thoughts repository setup",
+            "@1700001000 +0000",
+        );
+        commit(
+            root,
+            &["a.md", "b.md"],
+            "Sync thoughts - old",
+            "@1700002000 +0000",
+        );
+        commit(root, &["c.md"], "Edit by hand", "@1700003000 +0000");
+        commit(
+            root,
+            &["d.md"],
+            "Auto-sync with commit: fix",
+            "@1700004000 +0000",
+        );
+        let repo = GitRepo::open(root).unwrap();
+
+        let entries = audit_entries(&repo, None, 10, false).unwrap();
+        let messages: Vec<&str> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            ["Auto-sync with commit: fix", "Sync thoughts - old"]
+        );
+        assert_eq!(entries[1].files_changed, 2);
+        assert_eq!(entries[1].author, "Test");
+        assert_eq!(entries[1].timestamp, 1_700_002_000);
+        assert_eq!(entries[1].signed, None);
+
+        let recent = audit_entries(&repo, Some(1_700_003_000), 10, true).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].signed, Some(false));
+        assert_eq!(audit_entries(&repo, None, 1, false).unwrap().len(), 1);
+
+        let json = serde_json::to_value(&recent[0]).unwrap();
+        assert_eq!(json["filesChanged"], 1);
+        assert_eq!(json["signed"], false);
+    }
+}
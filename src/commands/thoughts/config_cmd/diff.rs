@@ -0,0 +1,249 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::{Confirm, theme::ColorfulTheme};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::{HyprlayerConfig, REDACTED, redact_json};
+
+/// One `key: value` line that differs between two configs. Keys are
+/// dot-separated JSON paths; a missing side is `None`.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Change {
+    key: String,
+    old_value: Option<Value>,
+    new_value: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffReport<'a> {
+    changed: &'a [Change],
+}
+
+/// `config --diff`. With `edit`, open a copy of the config in `editor`,
+/// show what the edit changes and save it only once confirmed. Without,
+/// compare the file with how hyprlayer will next save it: keys it ignores
+/// show as removed and defaults it fills in as added.
+pub fn diff(
+    config_path: &Path,
+    editor: &str,
+    edit: bool,
+    json: bool,
+    show_sensitive: bool,
+) -> Result<()> {
+    if !config_path.exists() {
+        return Err(anyhow::anyhow!(
+            "No configuration found at {}",
+            config_path.display()
+        ));
+    }
+    let original = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read {}", config_path.display()))?;
+
+    if !edit {
+        let (parsed, _) = HyprlayerConfig::parse(&original)?;
+        let changes = diff_json(&original, &serde_json::to_string(&parsed)?, show_sensitive)?;
+        return print_changes(&changes, json);
+    }
+
+    let copy = std::env::temp_dir().join(format!("hyprlayer-config-{}.json", std::process::id()));
+    fs::write(&copy, &original)?;
+    let status = Command::new(editor).arg(&copy).status()?;
+    if !status.success() {
+        fs::remove_file(&copy).ok();
+        return Err(anyhow::anyhow!("{} exited with {}", editor, status));
+    }
+
+    let edited = fs::read_to_string(&copy)?;
+    let parsed = match HyprlayerConfig::parse(&edited) {
+        Ok((parsed, _)) => parsed,
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Edited config is not valid ({:#}); nothing was saved. Your copy is at {}",
+                e,
+                copy.display()
+            ));
+        }
+    };
+    fs::remove_file(&copy).ok();
+
+    // Diff against what `save` writes, so the review shows exactly what
+    // lands on disk.
+    let changes = diff_json(&original, &serde_json::to_string(&parsed)?, show_sensitive)?;
+    print_changes(&changes, json)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    if Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Save these changes?")
+        .default(true)
+        .interact()?
+    {
+        parsed.save(config_path)?;
+        eprintln!("{}", "Configuration saved".green());
+    } else {
+        eprintln!("{}", "Changes discarded".yellow());
+    }
+    Ok(())
+}
+
+fn print_changes(changes: &[Change], json: bool) -> Result<()> {
+    if json {
+        let report = DiffReport { changed: changes };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    if changes.is_empty() {
+        println!("{}", "No changes".bright_black());
+        return Ok(());
+    }
+    for change in changes {
+        if let Some(old) = &change.old_value {
+            println!("{}", format!("- {}: {}", change.key, old).red());
+        }
+        if let Some(new) = &change.new_value {
+            println!("{}", format!("+ {}: {}", change.key, new).green());
+        }
+    }
+    Ok(())
+}
+
+/// Compare two config JSON documents line by line, as sorted `key: value`
+/// pairs. Secrets are compared unredacted but reported as `[REDACTED]`
+/// unless `show_sensitive`.
+fn diff_json(old: &str, new: &str, show_sensitive: bool) -> Result<Vec<Change>> {
+    let old: Value = serde_json::from_str(old).context("Failed to parse config file")?;
+    let new: Value = serde_json::from_str(new).context("Failed to parse config file")?;
+    let (old_lines, new_lines) = (flatten(&old), flatten(&new));
+    let shown = |value: &Value| {
+        let mut value = value.clone();
+        if !show_sensitive {
+            redact_json(&mut value);
+        }
+        flatten(&value)
+    };
+    let (old_shown, new_shown) = (shown(&old), shown(&new));
+
+    // A redacted object collapses to one string, so its fields are missing
+    // from the shown lines.
+    let display = |lines: &BTreeMap<String, Value>, shown: &BTreeMap<String, Value>, key: &str| {
+        lines.get(key)?;
+        Some(
+            shown
+                .get(key)
+                .cloned()
+                .unwrap_or_else(|| Value::String(REDACTED.to_string())),
+        )
+    };
+
+    let keys: BTreeSet<&String> = old_lines.keys().chain(new_lines.keys()).collect();
+    Ok(keys
+        .into_iter()
+        .filter(|key| old_lines.get(*key) != new_lines.get(*key))
+        .map(|key| Change {
+            key: key.clone(),
+            old_value: display(&old_lines, &old_shown, key),
+            new_value: display(&new_lines, &new_shown, key),
+        })
+        .collect())
+}
+
+/// Every leaf of `value` keyed by its dot-separated path. Empty objects
+/// and arrays count as leaves so adding or removing one shows up.
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn walk(value: &Value, prefix: &str, out: &mut BTreeMap<String, Value>) {
+        let join = |key: &str| {
+            if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{prefix}.{key}")
+            }
+        };
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, child) in map {
+                    walk(child, &join(key), out);
+                }
+            }
+            Value::Array(items) if !items.is_empty() => {
+                for (i, item) in items.iter().enumerate() {
+                    walk(item, &format!("{prefix}[{i}]"), out);
+                }
+            }
+            _ => {
+                out.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    walk(value, "", &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_json_reports_changed_added_and_removed_keys() {
+        let old = json!({
+            "thoughts": {"user": "alice", "backend": {"kind": "git", "reposDir": "repos"}},
+            "ai": {"apiKey": "old-secret"}
+        });
+        let new = json!({
+            "thoughts": {"user": "bob", "backend": {"kind": "git"}, "hooksEnabled": false},
+            "ai": {"apiKey": "new-secret"}
+        });
+        let changes = diff_json(&old.to_string(), &new.to_string(), false).unwrap();
+        let keys: Vec<&str> = changes.iter().map(|c| c.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            [
+                "ai.apiKey",
+                "thoughts.backend.reposDir",
+                "thoughts.hooksEnabled",
+                "thoughts.user"
+            ]
+        );
+        assert_eq!(changes[0].old_value, Some(json!(REDACTED)));
+        assert_eq!(changes[1].new_value, None);
+        assert_eq!(changes[2].old_value, None);
+        assert_eq!(changes[3].new_value, Some(json!("bob")));
+
+        let changes = diff_json(&old.to_string(), &new.to_string(), true).unwrap();
+        assert_eq!(changes[0].new_value, Some(json!("new-secret")));
+
+        let json = serde_json::to_value(DiffReport { changed: &changes }).unwrap();
+        assert_eq!(json["changed"][3]["oldValue"], "alice");
+    }
+
+    #[test]
+    fn standalone_diff_shows_keys_hyprlayer_ignores() {
+        let mut config = HyprlayerConfig::default();
+        config.thoughts_mut().user = "alice".to_string();
+        let mut value = serde_json::to_value(&config).unwrap();
+        value["thoughts"]["typo"] = json!(true);
+        let original = value.to_string();
+
+        let (parsed, _) = HyprlayerConfig::parse(&original).unwrap();
+        let changes =
+            diff_json(&original, &serde_json::to_string(&parsed).unwrap(), false).unwrap();
+        assert_eq!(
+            changes,
+            [Change {
+                key: "thoughts.typo".to_string(),
+                old_value: Some(json!(true)),
+                new_value: None,
+            }]
+        );
+    }
+}
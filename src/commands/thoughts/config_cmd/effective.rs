@@ -0,0 +1,391 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::path::PathBuf;
+
+use crate::cli::ConfigEffectiveArgs;
+use crate::config::{
+    BackendConfig, ConfigSources, ThoughtsConfig, expand_path, get_current_repo_path, json_pointer,
+    redact_json,
+};
+
+/// A resolved value plus where it came from, so users can tell a profile
+/// override apart from the top-level default, a later `--config-file` or
+/// `--set`.
+#[derive(Debug, Serialize)]
+struct Sourced {
+    value: String,
+    source: String,
+}
+
+impl Sourced {
+    fn new(value: impl Into<String>, source: &str) -> Self {
+        Self {
+            value: value.into(),
+            source: source.to_string(),
+        }
+    }
+}
+
+/// Absolute targets of the three `<code_repo>/thoughts/` symlinks.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LinkTargets {
+    user: PathBuf,
+    shared: PathBuf,
+    global: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EffectiveReport {
+    repo_path: String,
+    backend: Sourced,
+    settings: Vec<(&'static str, Sourced)>,
+    mapped_name: Option<Sourced>,
+    profile: Option<Sourced>,
+    user: Sourced,
+    link_targets: Option<LinkTargets>,
+    /// Set when the mapping names a profile that no longer exists and
+    /// resolution silently fell back to the top-level backend.
+    missing_profile: Option<String>,
+}
+
+pub fn effective(args: ConfigEffectiveArgs) -> Result<()> {
+    let ConfigEffectiveArgs { json, config } = args;
+    let (hyprlayer_config, sources) = config.load_sourced()?;
+    let thoughts = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let current_repo = get_current_repo_path()?;
+    let report = build_report(thoughts, &sources, &current_repo.display().to_string());
+
+    if json {
+        let mut value = report_json(&report);
+        redact_json(&mut value);
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    print_report(&report);
+    Ok(())
+}
+
+fn build_report(
+    thoughts: &ThoughtsConfig,
+    sources: &ConfigSources,
+    repo_path: &str,
+) -> EffectiveReport {
+    let effective = thoughts.effective_config_for(repo_path);
+    let mapping = thoughts.repo_mappings.get(repo_path);
+
+    let (backend_pointer, backend_source) = match &effective.profile_name {
+        Some(name) => (
+            format!("{}/backend", json_pointer("/thoughts/profiles", name)),
+            format!("profile \"{name}\""),
+        ),
+        None => ("/thoughts/backend".to_string(), "default".to_string()),
+    };
+    let mapping_pointer = json_pointer("/thoughts/repoMappings", repo_path);
+    // A later `--config-file` or `--set` wins over where the value would
+    // otherwise come from.
+    let sourced = |value: &str, pointer: &str, fallback: &str| {
+        Sourced::new(value, sources.get(pointer).unwrap_or(fallback))
+    };
+    let missing_profile = mapping
+        .and_then(|m| m.profile())
+        .filter(|name| !thoughts.profiles.contains_key(*name))
+        .map(|s| s.to_string());
+
+    let settings = backend_settings(&effective.backend)
+        .into_iter()
+        .map(|(key, value)| {
+            let pointer = json_pointer(&backend_pointer, key);
+            (key, sourced(&value, &pointer, &backend_source))
+        })
+        .collect();
+
+    let link_targets = effective
+        .mapped_name
+        .as_deref()
+        .and_then(|mapped| link_targets(&effective.backend, mapped, &effective.user));
+
+    EffectiveReport {
+        repo_path: repo_path.to_string(),
+        backend: sourced(
+            effective.backend.kind().as_str(),
+            &json_pointer(&backend_pointer, "kind"),
+            &backend_source,
+        ),
+        settings,
+        mapped_name: effective
+            .mapped_name
+            .as_deref()
+            .map(|n| sourced(n, &mapping_pointer, "repo mapping")),
+        profile: effective
+            .profile_name
+            .as_deref()
+            .map(|p| sourced(p, &mapping_pointer, "repo mapping")),
+        user: sourced(&effective.user, "/thoughts/user", "default"),
+        link_targets,
+        missing_profile,
+    }
+}
+
+/// Per-backend settings as `(camelCase key, value)` pairs, in display order.
+fn backend_settings(backend: &BackendConfig) -> Vec<(&'static str, String)> {
+    match backend {
+        BackendConfig::Git(g) => vec![
+            ("thoughtsRepo", g.thoughts_repo.clone()),
+            ("reposDir", g.repos_dir.clone()),
+            ("globalDir", g.global_dir.clone()),
+        ],
+        BackendConfig::Obsidian(o) => {
+            let mut out = vec![("vaultPath", o.vault_path.clone())];
+            if let Some(sub) = &o.vault_subpath {
+                out.push(("vaultSubpath", sub.clone()));
+            }
+            out.push(("reposDir", o.repos_dir.clone()));
+            out.push(("globalDir", o.global_dir.clone()));
+            out
+        }
+        BackendConfig::Notion(n) => {
+            let mut out = vec![("parentPageId", n.parent_page_id.clone())];
+            if let Some(db) = &n.database_id {
+                out.push(("databaseId", db.clone()));
+            }
+            out
+        }
+        BackendConfig::Anytype(a) => {
+            let mut out = vec![("spaceId", a.space_id.clone())];
+            if let Some(t) = &a.type_id {
+                out.push(("typeId", t.clone()));
+            }
+            if let Some(env) = &a.api_token_env {
+                out.push(("apiTokenEnv", env.clone()));
+            }
+            out
+        }
+    }
+}
+
+/// Only filesystem backends lay out an on-disk tree with symlink targets.
+fn link_targets(backend: &BackendConfig, mapped: &str, user: &str) -> Option<LinkTargets> {
+    let (root, repos_dir, global_dir) = match backend {
+        BackendConfig::Git(g) => (expand_path(&g.thoughts_repo), &g.repos_dir, &g.global_dir),
+        BackendConfig::Obsidian(o) => (o.obsidian_root()?, &o.repos_dir, &o.global_dir),
+        BackendConfig::Notion(_) | BackendConfig::Anytype(_) => return None,
+    };
+    let repo_thoughts = root.join(repos_dir).join(mapped);
+    Some(LinkTargets {
+        user: repo_thoughts.join(user),
+        shared: repo_thoughts.join("shared"),
+        global: root.join(global_dir),
+    })
+}
+
+fn report_json(report: &EffectiveReport) -> Value {
+    let settings: serde_json::Map<String, Value> = report
+        .settings
+        .iter()
+        .map(|(k, v)| (k.to_string(), json!(v)))
+        .collect();
+    json!({
+        "repoPath": report.repo_path,
+        "backend": report.backend,
+        "settings": settings,
+        "mappedName": report.mapped_name,
+        "profile": report.profile,
+        "user": report.user,
+        "linkTargets": report.link_targets,
+        "missingProfile": report.missing_profile,
+    })
+}
+
+fn print_report(report: &EffectiveReport) {
+    let row = |label: &str, s: &Sourced| {
+        println!(
+            "  {label}: {} {}",
+            s.value.cyan(),
+            format!("({})", s.source).bright_black()
+        );
+    };
+
+    println!("{}", "Effective Configuration:".yellow());
+    println!("  Repository: {}", report.repo_path.cyan());
+    row("Backend", &report.backend);
+    for (key, value) in &report.settings {
+        row(key, value);
+    }
+    match &report.profile {
+        Some(p) => row("Profile", p),
+        None => println!("  Profile: {}", "(none)".bright_black()),
+    }
+    row("User", &report.user);
+    match &report.mapped_name {
+        Some(m) => row("Mapped name", m),
+        None => println!(
+            "  Mapped name: {}",
+            "(current repository not mapped)".bright_black()
+        ),
+    }
+
+    if let Some(name) = &report.missing_profile {
+        println!();
+        println!(
+            "{}",
+            format!(
+                "Warning: repo mapping references profile \"{name}\", which does not exist. \
+                 Falling back to the default configuration."
+            )
+            .yellow()
+        );
+    }
+
+    if let Some(targets) = &report.link_targets {
+        println!();
+        println!("{}", "Link Targets:".yellow());
+        for (link, target) in [
+            (report.user.value.as_str(), &targets.user),
+            ("shared", &targets.shared),
+            ("global", &targets.global),
+        ] {
+            println!(
+                "  thoughts/{link} → {}",
+                target.display().to_string().cyan()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GitConfig, HyprlayerConfig, NotionConfig, ProfileConfig, RepoMapping};
+
+    fn config_with_work_profile() -> ThoughtsConfig {
+        let mut cfg = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: "/t/default".to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            ..Default::default()
+        };
+        cfg.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Git(GitConfig {
+                    thoughts_repo: "/t/work".to_string(),
+                    repos_dir: "projects".to_string(),
+                    global_dir: "shared-global".to_string(),
+                }),
+                ai: None,
+            },
+        );
+        cfg
+    }
+
+    #[test]
+    fn report_attributes_profile_settings_and_link_targets() {
+        let mut cfg = config_with_work_profile();
+        cfg.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("work".to_string())),
+        );
+
+        let report = build_report(&cfg, &ConfigSources::default(), "/code/app");
+        assert_eq!(report.backend.source, "profile \"work\"");
+        let (key, repo) = &report.settings[0];
+        assert_eq!(*key, "thoughtsRepo");
+        assert_eq!(repo.value, "/t/work");
+        assert_eq!(repo.source, "profile \"work\"");
+        assert_eq!(report.user.source, "default");
+        assert_eq!(report.mapped_name.as_ref().unwrap().value, "app");
+
+        let targets = report.link_targets.unwrap();
+        assert_eq!(targets.user, PathBuf::from("/t/work/projects/app/alice"));
+        assert_eq!(targets.shared, PathBuf::from("/t/work/projects/app/shared"));
+        assert_eq!(targets.global, PathBuf::from("/t/work/shared-global"));
+    }
+
+    #[test]
+    fn report_flags_mapping_to_missing_profile() {
+        let mut cfg = config_with_work_profile();
+        cfg.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("gone".to_string())),
+        );
+
+        let report = build_report(&cfg, &ConfigSources::default(), "/code/app");
+        assert_eq!(report.backend.source, "default");
+        assert_eq!(report.missing_profile.as_deref(), Some("gone"));
+        assert!(report.profile.is_none());
+    }
+
+    #[test]
+    fn report_has_no_link_targets_for_unmapped_or_remote_backends() {
+        let cfg = config_with_work_profile();
+        assert!(
+            build_report(&cfg, &ConfigSources::default(), "/unmapped")
+                .link_targets
+                .is_none()
+        );
+
+        let mut notion = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Notion(NotionConfig {
+                parent_page_id: "p1".to_string(),
+                database_id: None,
+            }),
+            ..Default::default()
+        };
+        notion
+            .repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+        let report = build_report(&notion, &ConfigSources::default(), "/code/app");
+        assert!(report.link_targets.is_none());
+
+        let value = report_json(&report);
+        assert_eq!(value["settings"]["parentPageId"]["value"], "p1");
+        assert_eq!(value["backend"]["source"], "default");
+    }
+
+    #[test]
+    fn report_attributes_later_config_files_and_set_overrides() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path().join("base.json");
+        let local = temp_dir.path().join("local.json");
+        let mut cfg = config_with_work_profile();
+        cfg.repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+        HyprlayerConfig {
+            thoughts: Some(cfg),
+            ..Default::default()
+        }
+        .save(&base)
+        .unwrap();
+        std::fs::write(
+            &local,
+            r#"{"version":3,"thoughts":{"user":"alice","backend":{"kind":"git","thoughtsRepo":"/t/local","reposDir":"repos","globalDir":"global"}}}"#,
+        )
+        .unwrap();
+
+        let args = crate::cli::ConfigArgs {
+            config_file: vec![base.display().to_string(), local.display().to_string()],
+            overrides: vec!["thoughts.user=bob".to_string()],
+        };
+        let (loaded, sources) = args.load_sourced().unwrap();
+        let report = build_report(loaded.thoughts.as_ref().unwrap(), &sources, "/code/app");
+
+        assert_eq!(report.user.value, "bob");
+        assert_eq!(report.user.source, "--set");
+        let (_, repo) = &report.settings[0];
+        assert_eq!(repo.value, "/t/local");
+        assert_eq!(repo.source, format!("config file {}", local.display()));
+        let (_, repos_dir) = &report.settings[1];
+        assert_eq!(repos_dir.source, "default");
+        assert_eq!(report.mapped_name.unwrap().source, "repo mapping");
+    }
+}
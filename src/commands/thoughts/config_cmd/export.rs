@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::ConfigExportArgs;
+use crate::config::{AiConfig, BackendConfig, HyprlayerConfig, ProfileConfig, expand_path};
+
+/// Bumped whenever the bundle shape changes incompatibly.
+pub const BUNDLE_FORMAT: u32 = 1;
+
+/// Machine-independent slice of a config. Repo mappings are deliberately
+/// absent: they key on absolute checkout paths that differ per machine.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    pub hyprlayer_export: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<BackendConfig>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai: Option<AiConfig>,
+}
+
+impl ConfigBundle {
+    pub fn from_config(config: &HyprlayerConfig) -> Self {
+        let home = dirs::home_dir();
+        let portable = |backend: &BackendConfig| portable_backend(backend, home.as_deref());
+
+        let thoughts = config.thoughts.as_ref();
+        Self {
+            hyprlayer_export: BUNDLE_FORMAT,
+            user: thoughts.map(|t| t.user.clone()).filter(|u| !u.is_empty()),
+            backend: thoughts
+                .filter(|t| t.is_thoughts_configured())
+                .map(|t| portable(&t.backend)),
+            profiles: thoughts
+                .map(|t| {
+                    t.profiles
+                        .iter()
+                        .map(|(name, p)| {
+                            let profile = ProfileConfig {
+                                backend: portable(&p.backend),
+                                ai: p.ai.clone(),
+                            };
+                            (name.clone(), profile)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            ai: config.ai.clone(),
+        }
+    }
+}
+
+/// Rewrite absolute paths under `$HOME` as `~/…` so they resolve on a
+/// machine with a different username.
+pub(crate) fn portable_backend(backend: &BackendConfig, home: Option<&Path>) -> BackendConfig {
+    let mut backend = backend.clone();
+    let path = match &mut backend {
+        BackendConfig::Git(g) => &mut g.thoughts_repo,
+        BackendConfig::Obsidian(o) => &mut o.vault_path,
+        BackendConfig::Notion(_) | BackendConfig::Anytype(_) => return backend,
+    };
+    if let Some(home) = home
+        && let Ok(rest) = expand_path(path).strip_prefix(home)
+    {
+        *path = if rest.as_os_str().is_empty() {
+            "~".to_string()
+        } else {
+            format!("~/{}", rest.display())
+        };
+    }
+    backend
+}
+
+pub fn export(args: ConfigExportArgs) -> Result<()> {
+    let ConfigExportArgs { output, config } = args;
+    let hyprlayer_config = config.load_if_exists()?.ok_or_else(|| {
+        anyhow::anyhow!("No configuration found. Run 'hyprlayer thoughts init' first.")
+    })?;
+
+    let bundle = ConfigBundle::from_config(&hyprlayer_config);
+    let json = serde_json::to_string_pretty(&bundle)?;
+
+    match output {
+        Some(path) => {
+            let path = expand_path(&path);
+            fs::write(&path, format!("{json}\n"))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{}",
+                format!("Exported settings to {}", path.display()).green()
+            );
+            eprintln!(
+                "{}",
+                "Repository mappings are not included; run 'hyprlayer thoughts init' in each repository after importing."
+                    .bright_black()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NotionConfig, RepoMapping, ThoughtsConfig, tests::git_backend};
+    use std::path::PathBuf;
+
+    #[test]
+    fn portable_backend_rewrites_paths_under_home() {
+        let home = PathBuf::from("/home/alice");
+        let rewritten = portable_backend(&git_backend("/home/alice/thoughts"), Some(&home));
+        assert_eq!(rewritten.as_git().unwrap().thoughts_repo, "~/thoughts");
+
+        let outside = portable_backend(&git_backend("/srv/thoughts"), Some(&home));
+        assert_eq!(outside.as_git().unwrap().thoughts_repo, "/srv/thoughts");
+
+        let notion = BackendConfig::Notion(NotionConfig {
+            parent_page_id: "p1".to_string(),
+            database_id: None,
+        });
+        assert_eq!(portable_backend(&notion, Some(&home)), notion);
+    }
+
+    #[test]
+    fn bundle_omits_repo_mappings() {
+        let mut thoughts = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: git_backend("/srv/thoughts"),
+            ..Default::default()
+        };
+        thoughts
+            .repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+        thoughts.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_backend("/srv/work"),
+                ai: None,
+            },
+        );
+        let config = HyprlayerConfig {
+            thoughts: Some(thoughts),
+            ..Default::default()
+        };
+
+        let bundle = ConfigBundle::from_config(&config);
+        let json = serde_json::to_value(&bundle).unwrap();
+        assert_eq!(json["hyprlayerExport"], BUNDLE_FORMAT);
+        assert_eq!(json["user"], "alice");
+        assert!(json.get("repoMappings").is_none());
+        assert_eq!(
+            json["profiles"]["work"]["backend"]["thoughtsRepo"],
+            "/srv/work"
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::{Select, theme::ColorfulTheme};
+use serde_json::Value;
+use std::fs;
+
+use super::export::{BUNDLE_FORMAT, ConfigBundle};
+use crate::cli::ConfigImportArgs;
+use crate::config::{HyprlayerConfig, expand_path};
+
+/// One bundle value that differs from (or is absent in) the local config.
+#[derive(Debug)]
+struct Change {
+    path: Vec<String>,
+    local: Option<Value>,
+    incoming: Value,
+}
+
+impl Change {
+    fn key(&self) -> String {
+        self.path.join(".")
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct MergeSummary {
+    added: usize,
+    replaced: usize,
+    kept: usize,
+}
+
+pub fn import(args: ConfigImportArgs) -> Result<()> {
+    let ConfigImportArgs {
+        file,
+        overwrite,
+        keep_existing,
+        config,
+    } = args;
+    config.reject_overrides("thoughts config import")?;
+
+    let bundle_path = expand_path(&file);
+    let content = fs::read_to_string(&bundle_path)
+        .with_context(|| format!("Failed to read {}", bundle_path.display()))?;
+    let bundle: ConfigBundle = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a hyprlayer config export", bundle_path.display()))?;
+
+    let config_path = config.path()?;
+    let local = if config_path.exists() {
+        HyprlayerConfig::load(&config_path)?
+    } else {
+        HyprlayerConfig::default()
+    };
+    let before = serde_json::to_value(&local)?;
+
+    let (merged, summary) = merge(local, &bundle, |change| {
+        if overwrite {
+            return Ok(true);
+        }
+        if keep_existing {
+            return Ok(false);
+        }
+        prompt_conflict(change)
+    })?;
+
+    if summary.added + summary.replaced == 0 {
+        println!(
+            "{}",
+            "Nothing to import: local config already matches.".green()
+        );
+        return Ok(());
+    }
+
+    // Conflicts were resolved without the config lock; don't overwrite a
+    // config another process changed in the meantime.
+    config.update(|target| {
+        if serde_json::to_value(&*target)? != before {
+            return Err(anyhow::anyhow!(
+                "{} changed during the import; run it again",
+                config_path.display()
+            ));
+        }
+        *target = merged;
+        Ok(())
+    })?;
+    println!(
+        "{}",
+        format!(
+            "Imported {} new and {} replaced setting(s), kept {} local value(s)",
+            summary.added, summary.replaced, summary.kept
+        )
+        .green()
+    );
+    println!(
+        "{}",
+        "Repository mappings were left untouched.".bright_black()
+    );
+    Ok(())
+}
+
+fn prompt_conflict(change: &Change) -> Result<bool> {
+    let show = |v: &Value| serde_json::to_string(v).unwrap_or_default();
+    println!();
+    println!("{}", format!("Conflict at {}", change.key()).yellow());
+    if let Some(local) = &change.local {
+        println!("  local:    {}", show(local).cyan());
+    }
+    println!("  imported: {}", show(&change.incoming).cyan());
+
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("Which value should be kept?")
+        .items(&["Keep existing", "Use imported"])
+        .default(0)
+        .interact()?;
+    Ok(selection == 1)
+}
+
+/// Merge `bundle` into `local`. Values missing locally are added; for
+/// values that differ, `take_incoming` decides. Repo mappings are never
+/// touched. The result must pass `ThoughtsConfig::validate`, otherwise
+/// nothing is returned so the caller never writes a broken config.
+fn merge(
+    local: HyprlayerConfig,
+    bundle: &ConfigBundle,
+    mut take_incoming: impl FnMut(&Change) -> Result<bool>,
+) -> Result<(HyprlayerConfig, MergeSummary)> {
+    if bundle.hyprlayer_export != BUNDLE_FORMAT {
+        return Err(anyhow::anyhow!(
+            "Unsupported export format {} (expected {})",
+            bundle.hyprlayer_export,
+            BUNDLE_FORMAT
+        ));
+    }
+
+    let mut root = serde_json::to_value(&local)?;
+    let mut summary = MergeSummary::default();
+
+    for change in plan(&root, bundle)? {
+        let apply = match change.local {
+            None => {
+                summary.added += 1;
+                true
+            }
+            Some(_) if take_incoming(&change)? => {
+                summary.replaced += 1;
+                true
+            }
+            Some(_) => {
+                summary.kept += 1;
+                false
+            }
+        };
+        if apply {
+            set_path(&mut root, &change.path, change.incoming);
+        }
+    }
+
+    let merged: HyprlayerConfig = serde_json::from_value(root)?;
+    if let Some(thoughts) = &merged.thoughts {
+        let problems = thoughts.validate();
+        if !problems.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Imported config would be invalid; nothing was written:\n  - {}",
+                problems.join("\n  - ")
+            ));
+        }
+    }
+    Ok((merged, summary))
+}
+
+/// List every bundle leaf that isn't already identical locally, in a
+/// stable order: user, backend, profiles by name, then AI fields.
+fn plan(root: &Value, bundle: &ConfigBundle) -> Result<Vec<Change>> {
+    let mut incoming: Vec<(Vec<String>, Value)> = Vec::new();
+    let path = |parts: &[&str]| parts.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    if let Some(user) = &bundle.user {
+        incoming.push((path(&["thoughts", "user"]), Value::from(user.as_str())));
+    }
+    if let Some(backend) = &bundle.backend {
+        incoming.push((
+            path(&["thoughts", "backend"]),
+            serde_json::to_value(backend)?,
+        ));
+    }
+    for (name, profile) in &bundle.profiles {
+        incoming.push((
+            path(&["thoughts", "profiles", name]),
+            serde_json::to_value(profile)?,
+        ));
+    }
+    if let Some(ai) = &bundle.ai
+        && let Value::Object(fields) = serde_json::to_value(ai)?
+    {
+        for (field, value) in fields.into_iter().filter(|(_, v)| !v.is_null()) {
+            incoming.push((path(&["ai", &field]), value));
+        }
+    }
+
+    Ok(incoming
+        .into_iter()
+        .filter_map(|(path, incoming)| {
+            let local = get_path(root, &path).filter(|v| !v.is_null()).cloned();
+            (local.as_ref() != Some(&incoming)).then_some(Change {
+                path,
+                local,
+                incoming,
+            })
+        })
+        .collect())
+}
+
+fn get_path<'a>(root: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |node, key| node.get(key))
+}
+
+fn set_path(root: &mut Value, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut node = root;
+    for key in parents {
+        if !node.get(key).is_some_and(Value::is_object) {
+            node[key] = Value::Object(Default::default());
+        }
+        node = &mut node[key];
+    }
+    node[last] = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProfileConfig, RepoMapping, ThoughtsConfig, tests::git_backend};
+
+    fn local_config() -> HyprlayerConfig {
+        let mut thoughts = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: git_backend("/local/thoughts"),
+            ..Default::default()
+        };
+        thoughts
+            .repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+        HyprlayerConfig {
+            thoughts: Some(thoughts),
+            ..Default::default()
+        }
+    }
+
+    fn bundle() -> ConfigBundle {
+        let mut bundle = ConfigBundle {
+            hyprlayer_export: BUNDLE_FORMAT,
+            user: Some("alice".to_string()),
+            backend: Some(git_backend("~/thoughts")),
+            ..Default::default()
+        };
+        bundle.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_backend("~/work-thoughts"),
+                ai: None,
+            },
+        );
+        bundle
+    }
+
+    #[test]
+    fn merge_adds_missing_and_keeps_conflicts_when_declined() {
+        let mut asked = Vec::new();
+        let (merged, summary) = merge(local_config(), &bundle(), |c| {
+            asked.push(c.key());
+            Ok(false)
+        })
+        .unwrap();
+
+        assert_eq!(asked, vec!["thoughts.backend"]);
+        assert_eq!(
+            summary,
+            MergeSummary {
+                added: 1,
+                replaced: 0,
+                kept: 1
+            }
+        );
+        let thoughts = merged.thoughts.unwrap();
+        assert_eq!(
+            thoughts.backend.as_git().unwrap().thoughts_repo,
+            "/local/thoughts"
+        );
+        assert!(thoughts.profiles.contains_key("work"));
+        assert_eq!(thoughts.repo_mappings["/code/app"].repo(), "app");
+    }
+
+    #[test]
+    fn merge_overwrites_conflicts_when_accepted() {
+        let (merged, summary) = merge(local_config(), &bundle(), |_| Ok(true)).unwrap();
+        assert_eq!(summary.replaced, 1);
+        let thoughts = merged.thoughts.unwrap();
+        assert_eq!(
+            thoughts.backend.as_git().unwrap().thoughts_repo,
+            "~/thoughts"
+        );
+        assert_eq!(thoughts.repo_mappings.len(), 1);
+    }
+
+    #[test]
+    fn merge_into_empty_config_creates_sections() {
+        let (merged, summary) =
+            merge(HyprlayerConfig::default(), &bundle(), |_| unreachable!()).unwrap();
+        assert_eq!(summary.added, 3);
+        assert_eq!(merged.thoughts.unwrap().user, "alice");
+    }
+
+    #[test]
+    fn merge_rejects_result_that_fails_validation() {
+        let mut bad = bundle();
+        bad.profiles.insert(
+            "broken".to_string(),
+            ProfileConfig {
+                backend: git_backend(""),
+                ai: None,
+            },
+        );
+        let err = merge(local_config(), &bad, |_| Ok(true)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("profiles.broken.backend.thoughtsRepo is empty")
+        );
+    }
+
+    #[test]
+    fn merge_rejects_unknown_format() {
+        let mut future = bundle();
+        future.hyprlayer_export = BUNDLE_FORMAT + 1;
+        assert!(merge(local_config(), &future, |_| Ok(true)).is_err());
+    }
+}
@@ -3,32 +3,62 @@ use colored::Colorize;
 use std::fs;
 use std::process::Command;
 
-use crate::cli::ConfigArgsCmd;
+use crate::cli::{ConfigAction, ConfigArgsCmd};
 use crate::commands::thoughts::backend_display::print_backend_block;
-use crate::config::HyprlayerConfig;
+use crate::config::{HyprlayerConfig, redact_json};
+
+pub mod diff;
+pub mod effective;
+pub mod export;
+pub mod import;
+pub mod set;
+pub mod unset;
 
 pub fn config(args: ConfigArgsCmd) -> Result<()> {
-    let ConfigArgsCmd { edit, json, config } = args;
+    let ConfigArgsCmd {
+        action,
+        edit,
+        diff,
+        json,
+        show_sensitive,
+        config,
+    } = args;
+
+    if let Some(action) = action {
+        return match action {
+            ConfigAction::Effective(a) => effective::effective(a),
+            ConfigAction::Export(a) => export::export(a),
+            ConfigAction::Import(a) => import::import(a),
+            ConfigAction::Set(a) => set::set(a),
+            ConfigAction::Unset(a) => unset::unset(a),
+        };
+    }
+
+    config.reject_overrides("thoughts config")?;
     let config_path = config.path()?;
 
+    let editor = editor();
+
+    if diff {
+        return diff::diff(&config_path, &editor, edit, json, show_sensitive);
+    }
+
     if edit {
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| {
-            if cfg!(windows) {
-                "notepad".to_string()
-            } else {
-                "vi".to_string()
-            }
-        });
         Command::new(&editor).arg(&config_path).status()?;
         return Ok(());
     }
 
     if json {
         let content = fs::read_to_string(&config_path)?;
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::from_str::<serde_json::Value>(&content)?)?
-        );
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        if !show_sensitive {
+            redact_json(&mut value);
+            // The thoughts section as hyprlayer reads it, older shapes migrated.
+            if let Some(thoughts) = HyprlayerConfig::parse(&content)?.0.thoughts {
+                value["thoughts"] = thoughts.to_redacted();
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&value)?);
         return Ok(());
     }
 
@@ -69,7 +99,13 @@ pub fn config(args: ConfigArgsCmd) -> Result<()> {
     } else {
         for (repo, mapping) in &thoughts.repo_mappings {
             println!("  {}", repo.cyan());
-            println!("    → {}", mapping.repo().green());
+            println!("    → {}", mapping.to_string().green());
+        }
+        for group in thoughts.find_duplicate_mappings() {
+            println!(
+                "  {}",
+                format!("Warning: {} share one thoughts directory", group.join(", ")).yellow()
+            );
         }
     }
 
@@ -81,3 +117,14 @@ pub fn config(args: ConfigArgsCmd) -> Result<()> {
 
     Ok(())
 }
+
+/// `$EDITOR`, falling back to the platform's stock editor.
+pub fn editor() -> String {
+    std::env::var("EDITOR").unwrap_or_else(|_| {
+        if cfg!(windows) {
+            "notepad".to_string()
+        } else {
+            "vi".to_string()
+        }
+    })
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::ConfigSetArgs;
+use crate::config::HyprlayerConfig;
+
+const SETTABLE_KEYS: &str = "agent-pin-ref (ai.agentPinRef), disableUpdateCheck, update-check-interval (updateCheckIntervalHours), proxy (thoughts.proxy)";
+
+pub fn set(args: ConfigSetArgs) -> Result<()> {
+    let ConfigSetArgs { key, value, config } = args;
+    config.reject_overrides("thoughts config set")?;
+    let (message, saved) = config.update(|target| {
+        let message = set_key(target, &key, &value)?;
+        Ok((message, target.clone()))
+    })?;
+    println!("{}", message.green());
+
+    if let Some(warning) = saved
+        .agent_pin_ref()
+        .filter(|_| is_pin_key(&key))
+        .and_then(crate::version::pin_warning)
+    {
+        println!("{}", warning.yellow());
+    }
+    if let Some(warning) = saved
+        .update_check_interval_hours
+        .filter(|_| is_interval_key(&key))
+        .and_then(crate::version::interval_warning)
+    {
+        println!("{}", warning.yellow());
+    }
+    Ok(())
+}
+
+fn is_interval_key(key: &str) -> bool {
+    matches!(key, "update-check-interval" | "updateCheckIntervalHours")
+}
+
+fn is_pin_key(key: &str) -> bool {
+    matches!(key, "agent-pin-ref" | "ai.agentPinRef")
+}
+
+/// Apply `key = value` and describe the change. `null` clears optional keys.
+fn set_key(config: &mut HyprlayerConfig, key: &str, value: &str) -> Result<String> {
+    match key {
+        _ if is_pin_key(key) => {
+            if value == "null" {
+                config.ai_mut().agent_pin_ref = None;
+                Ok("Agent files follow the latest release again. \
+                    Run 'hyprlayer ai reinstall' to update them."
+                    .to_string())
+            } else {
+                config.ai_mut().agent_pin_ref = Some(value.to_string());
+                Ok(format!(
+                    "Pinned agent files to {value}. Run 'hyprlayer ai reinstall' to install it."
+                ))
+            }
+        }
+        "disableUpdateCheck" => {
+            config.disable_update_check = match value {
+                "true" => true,
+                "false" | "null" => false,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "disableUpdateCheck must be true or false, got \"{value}\""
+                    ));
+                }
+            };
+            Ok(format!(
+                "Set disableUpdateCheck to {}",
+                config.disable_update_check
+            ))
+        }
+        _ if is_interval_key(key) => {
+            if value == "null" {
+                config.update_check_interval_hours = None;
+                return Ok("Checking for updates once a day again".to_string());
+            }
+            let hours: u64 = value.parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "update-check-interval must be a whole number of hours, got \"{value}\""
+                )
+            })?;
+            config.update_check_interval_hours = Some(hours);
+            Ok(match hours {
+                0 => "Checking for updates on every run".to_string(),
+                _ => format!("Checking for updates every {hours} hours"),
+            })
+        }
+        "proxy" | "thoughts.proxy" => {
+            if value == "null" {
+                config.thoughts_mut().proxy = None;
+                return Ok("Using the proxy from the environment again".to_string());
+            }
+            ureq::Proxy::new(value)
+                .map_err(|e| anyhow::anyhow!("Invalid proxy URL \"{value}\": {e}"))?;
+            config.thoughts_mut().proxy = Some(value.to_string());
+            Ok(format!("Sending hyprlayer's HTTP requests through {value}"))
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unknown config key `{key}`. Keys that can be set: {SETTABLE_KEYS}"
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_clear_agent_pin_ref() {
+        let mut cfg = HyprlayerConfig::default();
+        set_key(&mut cfg, "agent-pin-ref", "v1.2.3").unwrap();
+        assert_eq!(cfg.agent_pin_ref(), Some("v1.2.3"));
+
+        let json = serde_json::to_value(&cfg).unwrap();
+        assert_eq!(json["ai"]["agentPinRef"], "v1.2.3");
+
+        set_key(&mut cfg, "ai.agentPinRef", "null").unwrap();
+        assert_eq!(cfg.agent_pin_ref(), None);
+        let json = serde_json::to_value(&cfg).unwrap();
+        assert!(json["ai"].get("agentPinRef").is_none());
+    }
+
+    #[test]
+    fn set_rejects_unknown_keys_and_bad_bools() {
+        let mut cfg = HyprlayerConfig::default();
+        assert!(set_key(&mut cfg, "nope", "x").is_err());
+        assert!(set_key(&mut cfg, "disableUpdateCheck", "yes").is_err());
+        set_key(&mut cfg, "disableUpdateCheck", "true").unwrap();
+        assert!(cfg.disable_update_check);
+    }
+
+    #[test]
+    fn set_and_clear_proxy() {
+        let mut cfg = HyprlayerConfig::default();
+        set_key(&mut cfg, "proxy", "http://proxy.corp:3128").unwrap();
+        let json = serde_json::to_value(&cfg).unwrap();
+        assert_eq!(json["thoughts"]["proxy"], "http://proxy.corp:3128");
+
+        assert!(set_key(&mut cfg, "thoughts.proxy", "::nope").is_err());
+        set_key(&mut cfg, "thoughts.proxy", "null").unwrap();
+        assert_eq!(cfg.thoughts.unwrap().proxy, None);
+    }
+
+    #[test]
+    fn set_and_clear_update_check_interval() {
+        let mut cfg = HyprlayerConfig::default();
+        set_key(&mut cfg, "update-check-interval", "0").unwrap();
+        assert_eq!(cfg.update_check_interval_hours, Some(0));
+
+        set_key(&mut cfg, "updateCheckIntervalHours", "48").unwrap();
+        let json = serde_json::to_value(&cfg).unwrap();
+        assert_eq!(json["updateCheckIntervalHours"], 48);
+
+        assert!(set_key(&mut cfg, "update-check-interval", "-1").is_err());
+        assert!(set_key(&mut cfg, "update-check-interval", "2.5").is_err());
+        assert_eq!(cfg.update_check_interval_hours, Some(48));
+
+        set_key(&mut cfg, "update-check-interval", "null").unwrap();
+        assert_eq!(cfg.update_check_interval_hours, None);
+    }
+}
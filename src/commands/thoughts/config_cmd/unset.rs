@@ -0,0 +1,263 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::cli::ConfigUnsetArgs;
+use crate::commands::thoughts::profile::delete::check_profile_not_in_use;
+use crate::config::{BackendConfig, HyprlayerConfig};
+
+const UNSETTABLE_KEYS: &str = "disableUpdateCheck, updateCheckIntervalHours, defaultProfile, proxy, ai.agentTools, ai.opencodeProvider, \
+     ai.opencodeSonnetModel, ai.opencodeOpusModel, ai.agentPinRef, backend.<optional field>, profiles.<name>, \
+     profiles.<name>.backend.<optional field>";
+
+pub fn unset(args: ConfigUnsetArgs) -> Result<()> {
+    let ConfigUnsetArgs { key, force, config } = args;
+    config.reject_overrides("thoughts config unset")?;
+    if !config.path()?.exists() {
+        return Err(anyhow::anyhow!(
+            "No configuration found. Run 'hyprlayer thoughts init' first."
+        ));
+    }
+
+    let previous = config.update(|target| unset_key(target, &key, force))?;
+    let Some(previous) = previous else {
+        println!("{}", format!("{key} is not set").bright_black());
+        return Ok(());
+    };
+    println!(
+        "{}",
+        format!("Unset {key} (was {})", display_value(&previous)).green()
+    );
+    Ok(())
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Clear `key` and return its previous value, or `None` if it was already
+/// unset. Keys may be given with or without a leading `thoughts.`.
+fn unset_key(config: &mut HyprlayerConfig, key: &str, force: bool) -> Result<Option<Value>> {
+    let parts: Vec<&str> = key.split('.').collect();
+    let parts = match parts.as_slice() {
+        ["thoughts", rest @ ..] if !rest.is_empty() => rest,
+        all => all,
+    };
+
+    match parts {
+        ["disableUpdateCheck"] => {
+            let was = std::mem::take(&mut config.disable_update_check);
+            Ok(was.then_some(Value::Bool(true)))
+        }
+        ["updateCheckIntervalHours"] => {
+            Ok(config.update_check_interval_hours.take().map(Value::from))
+        }
+        ["ai", field] => {
+            let Some(ai) = config.ai.as_mut() else {
+                return Ok(None);
+            };
+            let previous = match *field {
+                "agentTools" | "agentTool" => {
+                    let tools = std::mem::take(&mut ai.agent_tools);
+                    (!tools.is_empty())
+                        .then(|| serde_json::to_value(tools))
+                        .transpose()?
+                }
+                "opencodeProvider" => ai
+                    .opencode_provider
+                    .take()
+                    .map(serde_json::to_value)
+                    .transpose()?,
+                "opencodeSonnetModel" => ai.opencode_sonnet_model.take().map(Value::String),
+                "opencodeOpusModel" => ai.opencode_opus_model.take().map(Value::String),
+                "agentPinRef" => ai.agent_pin_ref.take().map(Value::String),
+                _ => return Err(unknown_key(key)),
+            };
+            Ok(previous)
+        }
+        ["defaultProfile"] => Ok(config
+            .thoughts
+            .as_mut()
+            .and_then(|t| t.default_profile.take())
+            .map(Value::String)),
+        ["proxy"] => Ok(config
+            .thoughts
+            .as_mut()
+            .and_then(|t| t.proxy.take())
+            .map(Value::String)),
+        ["user"] => Err(anyhow::anyhow!(
+            "`user` is required and can't be unset. Run 'hyprlayer thoughts init --force' to change it."
+        )),
+        ["repoMappings", ..] => Err(anyhow::anyhow!(
+            "Repository mappings can't be unset here. Run 'hyprlayer thoughts uninit' in the repository instead."
+        )),
+        ["backend", field] => {
+            let thoughts = config
+                .thoughts
+                .as_mut()
+                .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
+            unset_backend_field(&mut thoughts.backend, field, key)
+        }
+        ["profiles", name] => {
+            if !force {
+                check_profile_not_in_use(&serde_json::to_value(&*config)?, name)?;
+            }
+            let thoughts = config.thoughts.as_mut();
+            let removed = thoughts
+                .and_then(|t| {
+                    if t.default_profile.as_deref() == Some(*name) {
+                        t.default_profile = None;
+                    }
+                    t.profiles.remove(*name)
+                })
+                .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" does not exist", name))?;
+            Ok(Some(serde_json::to_value(removed)?))
+        }
+        ["profiles", name, "backend", field] => {
+            let profile = config
+                .thoughts
+                .as_mut()
+                .and_then(|t| t.profiles.get_mut(*name))
+                .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" does not exist", name))?;
+            unset_backend_field(&mut profile.backend, field, key)
+        }
+        _ => Err(unknown_key(key)),
+    }
+}
+
+fn unset_backend_field(
+    backend: &mut BackendConfig,
+    field: &str,
+    key: &str,
+) -> Result<Option<Value>> {
+    let kind = backend.kind();
+    let previous = match (backend, field) {
+        (BackendConfig::Obsidian(o), "vaultSubpath") => o.vault_subpath.take(),
+        (BackendConfig::Notion(n), "databaseId") => n.database_id.take(),
+        (BackendConfig::Anytype(a), "typeId") => a.type_id.take(),
+        (BackendConfig::Anytype(a), "apiTokenEnv") => a.api_token_env.take(),
+        (
+            _,
+            "kind" | "thoughtsRepo" | "reposDir" | "globalDir" | "vaultPath" | "parentPageId"
+            | "spaceId",
+        ) => {
+            return Err(anyhow::anyhow!(
+                "`{key}` is required by the {kind} backend and can't be unset. \
+                 Run 'hyprlayer thoughts init --force' to reconfigure it."
+            ));
+        }
+        _ => {
+            return Err(anyhow::anyhow!(
+                "`{key}` is not an optional field of the {kind} backend"
+            ));
+        }
+    };
+    Ok(previous.map(Value::String))
+}
+
+fn unknown_key(key: &str) -> anyhow::Error {
+    anyhow::anyhow!("Unknown config key `{key}`. Keys that can be unset: {UNSETTABLE_KEYS}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agents::AgentTool;
+    use crate::config::{
+        AiConfig, GitConfig, ObsidianConfig, ProfileConfig, RepoMapping, ThoughtsConfig,
+    };
+
+    fn config() -> HyprlayerConfig {
+        let mut thoughts = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: "/t".to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            ..Default::default()
+        };
+        thoughts.profiles.insert(
+            "vault".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Obsidian(ObsidianConfig {
+                    vault_path: "/v".to_string(),
+                    vault_subpath: Some("notes".to_string()),
+                    repos_dir: "repos".to_string(),
+                    global_dir: "global".to_string(),
+                }),
+                ai: None,
+            },
+        );
+        thoughts.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("vault".to_string())),
+        );
+        HyprlayerConfig {
+            thoughts: Some(thoughts),
+            ai: Some(AiConfig {
+                agent_tools: vec![AgentTool::Claude],
+                ..Default::default()
+            }),
+            disable_update_check: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unset_clears_optional_fields_and_returns_previous() {
+        let mut cfg = config();
+        assert_eq!(
+            unset_key(&mut cfg, "ai.agentTools", false).unwrap(),
+            Some(serde_json::json!(["claude"]))
+        );
+        assert!(cfg.ai.as_ref().unwrap().agent_tools.is_empty());
+        assert_eq!(unset_key(&mut cfg, "ai.agentTool", false).unwrap(), None);
+
+        assert_eq!(
+            unset_key(&mut cfg, "disableUpdateCheck", false).unwrap(),
+            Some(Value::Bool(true))
+        );
+        assert!(!cfg.disable_update_check);
+
+        assert_eq!(
+            unset_key(
+                &mut cfg,
+                "thoughts.profiles.vault.backend.vaultSubpath",
+                false
+            )
+            .unwrap(),
+            Some(Value::from("notes"))
+        );
+    }
+
+    #[test]
+    fn unset_refuses_required_fields() {
+        let mut cfg = config();
+        let err = unset_key(&mut cfg, "user", false).unwrap_err();
+        assert!(err.to_string().contains("required"));
+        let err = unset_key(&mut cfg, "backend.thoughtsRepo", false).unwrap_err();
+        assert!(err.to_string().contains("required by the git backend"));
+        let err = unset_key(&mut cfg, "backend.vaultSubpath", false).unwrap_err();
+        assert!(err.to_string().contains("not an optional field"));
+        assert!(unset_key(&mut cfg, "nope", false).is_err());
+    }
+
+    #[test]
+    fn unset_profile_checks_usage_unless_forced() {
+        let mut cfg = config();
+        let err = unset_key(&mut cfg, "profiles.vault", false).unwrap_err();
+        assert!(err.to_string().contains("in use by repository: /code/app"));
+
+        assert!(
+            unset_key(&mut cfg, "profiles.vault", true)
+                .unwrap()
+                .is_some()
+        );
+        assert!(cfg.thoughts.as_ref().unwrap().profiles.is_empty());
+        assert!(unset_key(&mut cfg, "profiles.vault", true).is_err());
+    }
+}
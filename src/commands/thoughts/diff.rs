@@ -0,0 +1,121 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+
+use crate::cli::{DiffArgs, DiffScope};
+use crate::config::expand_path;
+use crate::git_ops::GitRepo;
+use crate::search_index;
+
+pub fn diff(args: DiffArgs) -> Result<()> {
+    let DiffArgs {
+        ref1,
+        ref2,
+        scope,
+        config,
+    } = args;
+
+    let (_, effective) = config.load_with_profile()?;
+    let mapped_name = effective.mapped_name.as_deref().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Thoughts not initialized for this repository. Run 'hyprlayer thoughts init' first."
+        )
+    })?;
+    let git = effective.backend.as_git().ok_or_else(|| {
+        anyhow::anyhow!(
+            "diff needs a git thoughts backend, not {}",
+            effective.backend.kind()
+        )
+    })?;
+    let repo = GitRepo::open(&expand_path(&git.thoughts_repo))?;
+
+    let (dir, shown_as) = scope_dir(
+        scope,
+        &git.repos_dir,
+        &git.global_dir,
+        mapped_name,
+        &effective.user,
+    );
+    let from = resolve_ref(&repo, &ref1)?;
+    let to = resolve_ref(&repo, &ref2)?;
+    let patch = repo.diff_between(&from, &to, &dir, &shown_as)?;
+
+    if patch.is_empty() {
+        println!(
+            "{}",
+            format!("No changes between {ref1} and {ref2}").bright_black()
+        );
+        return Ok(());
+    }
+    for line in patch.lines() {
+        println!("{}", colorize(line));
+    }
+    Ok(())
+}
+
+/// The thoughts repo directory `scope` covers and how to show it, so paths
+/// read as they do under the code repository's `thoughts/`.
+fn scope_dir(
+    scope: Option<DiffScope>,
+    repos_dir: &str,
+    global_dir: &str,
+    mapped_name: &str,
+    user: &str,
+) -> (String, String) {
+    let repo_dir = format!("{repos_dir}/{mapped_name}/");
+    match scope {
+        None => (repo_dir, String::new()),
+        Some(DiffScope::User) => (format!("{repo_dir}{user}/"), format!("{user}/")),
+        Some(DiffScope::Shared) => (format!("{repo_dir}shared/"), "shared/".to_string()),
+        Some(DiffScope::Global) => (format!("{global_dir}/"), "global/".to_string()),
+    }
+}
+
+/// `spec` as a commit `git rev-parse` accepts. A date (`2024-01-01`,
+/// `1week`) becomes the last commit made at or before it.
+fn resolve_ref(repo: &GitRepo, spec: &str) -> Result<String> {
+    let Ok(time) = search_index::parse_date(spec, Local::now()) else {
+        return Ok(spec.to_string());
+    };
+    repo.log_matching(1, |c| c.time <= time)?
+        .pop()
+        .map(|c| c.id)
+        .ok_or_else(|| anyhow::anyhow!("No thoughts commit at or before {spec}"))
+}
+
+fn colorize(line: &str) -> String {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff ") {
+        line.bold().to_string()
+    } else if line.starts_with('+') {
+        line.green().to_string()
+    } else if line.starts_with('-') {
+        line.red().to_string()
+    } else if line.starts_with("@@") {
+        line.cyan().to_string()
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_dir_matches_thoughts_layout() {
+        let dir = |scope| scope_dir(scope, "repos", "global", "app", "alice");
+        assert_eq!(dir(None), ("repos/app/".to_string(), String::new()));
+        assert_eq!(
+            dir(Some(DiffScope::User)),
+            ("repos/app/alice/".to_string(), "alice/".to_string())
+        );
+        assert_eq!(
+            dir(Some(DiffScope::Shared)),
+            ("repos/app/shared/".to_string(), "shared/".to_string())
+        );
+        assert_eq!(
+            dir(Some(DiffScope::Global)),
+            ("global/".to_string(), "global/".to_string())
+        );
+    }
+}
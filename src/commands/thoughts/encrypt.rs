@@ -0,0 +1,234 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::Password;
+use dialoguer::theme::ColorfulTheme;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use age::secrecy::SecretString;
+
+use crate::cli::EncryptArgs;
+use crate::config::expand_path;
+use crate::git_ops::GitRepo;
+
+/// Environment variable holding the passphrase, so scripts don't get
+/// prompted.
+pub const PASSPHRASE_ENV: &str = "HYPRLAYER_AGE_PASSPHRASE";
+
+pub fn encrypt(args: EncryptArgs) -> Result<()> {
+    let EncryptArgs {
+        file,
+        decrypt,
+        force,
+        config,
+    } = args;
+
+    // Fails unless thoughts are configured.
+    config.load()?;
+    let path = std::path::absolute(expand_path(&file))?;
+    if !decrypt {
+        check_not_committed(&path, force)?;
+    }
+    let passphrase = passphrase(!decrypt)?;
+
+    if decrypt {
+        let encrypted = encrypted_path(&path);
+        let plain = decrypt_file(&encrypted, &passphrase)?;
+        let encrypted = encrypted.display().to_string();
+        config.update(|target| {
+            target
+                .thoughts_mut()
+                .encrypted_files
+                .retain(|f| *f != encrypted);
+            Ok(())
+        })?;
+        println!("{}", format!("Decrypted {}", plain.display()).green());
+    } else {
+        let encrypted = encrypt_file(&path, &passphrase)?;
+        let encrypted = encrypted.display().to_string();
+        config.update(|target| {
+            let files = &mut target.thoughts_mut().encrypted_files;
+            if !files.contains(&encrypted) {
+                files.push(encrypted.clone());
+            }
+            Ok(())
+        })?;
+        println!("{}", format!("Encrypted to {encrypted}").green());
+    }
+    Ok(())
+}
+
+/// Refuse to encrypt a file whose plaintext is already committed, since
+/// it stays in the git history and on the remote; with `force`, warn.
+fn check_not_committed(path: &Path, force: bool) -> Result<()> {
+    let Some(repo) = GitRepo::committed_in(path) else {
+        return Ok(());
+    };
+    let warning = format!(
+        "{} is already committed to {}. Encrypting it doesn't remove the plaintext \
+         from the git history or the remote: rotate whatever it holds, or rewrite the history.",
+        path.display(),
+        repo.display()
+    );
+    if !force {
+        return Err(anyhow::anyhow!(
+            "{warning} Pass --force to encrypt it anyway."
+        ));
+    }
+    eprintln!("{}", format!("Warning: {warning}").red().bold());
+    Ok(())
+}
+
+/// The passphrase from `PASSPHRASE_ENV`, or asked for on the terminal
+/// (twice when `confirm`, so a typo can't lock a file away).
+fn passphrase(confirm: bool) -> Result<SecretString> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV)
+        && !passphrase.is_empty()
+    {
+        return Ok(SecretString::from(passphrase));
+    }
+    let theme = ColorfulTheme::default();
+    let mut prompt = Password::with_theme(&theme).with_prompt("Passphrase");
+    if confirm {
+        prompt = prompt.with_confirmation("Confirm passphrase", "Passphrases don't match");
+    }
+    Ok(SecretString::from(prompt.interact()?))
+}
+
+/// `path` with `.age` appended, unless it already ends in `.age`.
+fn encrypted_path(path: &Path) -> PathBuf {
+    if path.extension().is_some_and(|ext| ext == "age") {
+        return path.to_path_buf();
+    }
+    PathBuf::from(format!("{}.age", path.display()))
+}
+
+/// Encrypt `path` to `<path>.age` with `passphrase` and delete the
+/// plaintext. Returns the encrypted file's path.
+fn encrypt_file(path: &Path, passphrase: &SecretString) -> Result<PathBuf> {
+    let target = encrypted_path(path);
+    if target == path {
+        return Err(anyhow::anyhow!("{} is already encrypted", path.display()));
+    }
+    if target.exists() {
+        return Err(anyhow::anyhow!("{} already exists", target.display()));
+    }
+    let plaintext = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let recipient = age::scrypt::Recipient::new(passphrase.clone());
+    let ciphertext = age::encrypt(&recipient, &plaintext)
+        .with_context(|| format!("Failed to encrypt {}", path.display()))?;
+    fs::write(&target, ciphertext)?;
+    fs::remove_file(path)?;
+    Ok(target)
+}
+
+/// Decrypt the `.age` file at `path` next to it, without the extension,
+/// and delete the encrypted copy. Returns the plaintext file's path.
+fn decrypt_file(path: &Path, passphrase: &SecretString) -> Result<PathBuf> {
+    let target = path.with_extension("");
+    if target.exists() {
+        return Err(anyhow::anyhow!("{} already exists", target.display()));
+    }
+    let ciphertext =
+        fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let identity = age::scrypt::Identity::new(passphrase.clone());
+    let plaintext = age::decrypt(&identity, &ciphertext).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to decrypt {} ({e}). Is the passphrase right?",
+            path.display()
+        )
+    })?;
+    fs::write(&target, plaintext)?;
+    fs::remove_file(path)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::tests::git_thoughts;
+    use crate::git_ops::tests::repo_with_identity;
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("secret.md");
+        fs::write(&note, "api key: hunter2\n").unwrap();
+        let passphrase = SecretString::from("correct horse".to_string());
+
+        let encrypted = encrypt_file(&note, &passphrase).unwrap();
+        assert_eq!(encrypted, dir.path().join("secret.md.age"));
+        assert!(!note.exists());
+        assert!(!fs::read(&encrypted).unwrap().ends_with(b"hunter2\n"));
+        assert!(encrypt_file(&encrypted, &passphrase).is_err());
+
+        let wrong = SecretString::from("wrong".to_string());
+        let err = decrypt_file(&encrypted, &wrong).unwrap_err();
+        assert!(err.to_string().contains("Is the passphrase right?"));
+        assert!(encrypted.exists());
+
+        assert_eq!(encrypted_path(&note), encrypted);
+        assert_eq!(decrypt_file(&encrypted, &passphrase).unwrap(), note);
+        assert_eq!(fs::read_to_string(&note).unwrap(), "api key: hunter2\n");
+        assert!(!encrypted.exists());
+    }
+
+    #[test]
+    fn committed_plaintext_needs_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_identity(dir.path(), Some("main"));
+        let note = dir.path().join("secret.md");
+        fs::write(&note, "api key: hunter2\n").unwrap();
+        check_not_committed(&note, false).unwrap();
+
+        repo.add_all().unwrap();
+        repo.commit("add secret").unwrap();
+        let err = check_not_committed(&note, false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        check_not_committed(&note, true).unwrap();
+    }
+
+    #[test]
+    fn encrypt_saves_the_file_list_but_not_set_overrides() {
+        use crate::cli::ConfigArgs;
+        use crate::config::HyprlayerConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        let thoughts_repo = dir.path().join("thoughts").display().to_string();
+        HyprlayerConfig {
+            thoughts: Some(git_thoughts(&thoughts_repo, "repos", "global")),
+            ..Default::default()
+        }
+        .save(&config_path)
+        .unwrap();
+        let note = dir.path().join("secret.md");
+        fs::write(&note, "api key: hunter2\n").unwrap();
+
+        // No other test reads the passphrase variable.
+        unsafe { std::env::set_var(PASSPHRASE_ENV, "correct horse") };
+        encrypt(EncryptArgs {
+            file: note.display().to_string(),
+            decrypt: false,
+            force: false,
+            config: ConfigArgs {
+                config_file: vec![config_path.display().to_string()],
+                overrides: vec!["thoughts.thoughtsRepo=/tmp/override".to_string()],
+            },
+        })
+        .unwrap();
+
+        let saved = HyprlayerConfig::load(&config_path).unwrap();
+        let thoughts = saved.thoughts.unwrap();
+        assert_eq!(
+            thoughts.encrypted_files,
+            [encrypted_path(&note).display().to_string()]
+        );
+        assert_ne!(
+            thoughts.backend.as_git().unwrap().thoughts_repo,
+            "/tmp/override"
+        );
+    }
+}
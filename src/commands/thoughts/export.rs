@@ -0,0 +1,465 @@
+use anyhow::{Context, Result};
+use chrono::{Local, TimeZone};
+use colored::Colorize;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde_json::json;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::backends::git::find_files_following_symlinks;
+use crate::cli::{ExportArgs, ExportFormat};
+use crate::config::{expand_path, get_current_repo_path};
+use crate::git_ops::GitRepo;
+
+const STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;line-height:1.5;color:#222}
+a{color:#0b5cad;text-decoration:none}a:hover{text-decoration:underline}
+nav.crumbs{font-size:.9rem;color:#666;margin-bottom:1rem}
+footer{margin-top:3rem;font-size:.8rem;color:#888}
+h2{font-size:1rem;color:#555;border-bottom:1px solid #eee;padding-bottom:.25rem;margin-top:2rem}
+ul.files{list-style:none;padding:0}ul.files li{padding:.15rem 0}
+input[type=search]{width:100%;padding:.5rem;font-size:1rem;box-sizing:border-box}
+pre{background:#f6f8fa;padding:.75rem;overflow:auto}code{background:#f6f8fa;padding:0 .2rem}
+table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:.25rem .5rem}";
+
+/// Filters the index as you type, against the `INDEX` of lowercased
+/// titles and contents written next to it.
+const SEARCH_SCRIPT: &str = "\
+const input=document.getElementById('search');
+input.addEventListener('input',()=>{
+  const q=input.value.trim().toLowerCase();
+  document.querySelectorAll('li[data-i]').forEach(li=>{
+    li.hidden=q!==''&&!INDEX[li.dataset.i].includes(q);
+  });
+  document.querySelectorAll('section.group').forEach(s=>{
+    s.hidden=!s.querySelector('li[data-i]:not([hidden])');
+  });
+});";
+
+/// One thought rendered for the site.
+struct Page {
+    /// The markdown file, relative to the thoughts root.
+    source: PathBuf,
+    title: String,
+    body: String,
+    text: String,
+}
+
+impl Page {
+    fn href(&self) -> String {
+        url_path(&self.source.with_extension("html"))
+    }
+
+    /// The directory the page is listed under on the index.
+    fn group(&self) -> String {
+        self.source.parent().map(url_path).unwrap_or_default()
+    }
+}
+
+pub fn export(args: ExportArgs) -> Result<()> {
+    let ExportArgs {
+        format: ExportFormat::Html,
+        output,
+        zip,
+        profile,
+        config,
+    } = args;
+
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+    let backend = match thoughts_config.resolve_profile(profile)? {
+        Some(name) => thoughts_config.profiles[&name].backend.clone(),
+        None => {
+            let current_repo = get_current_repo_path()?;
+            thoughts_config
+                .effective_config_for(&current_repo.display().to_string())
+                .backend
+        }
+    };
+    let root = backend.content_root().ok_or_else(|| {
+        anyhow::anyhow!(
+            "The {} backend stores thoughts remotely and can't be exported",
+            backend.kind().as_str()
+        )
+    })?;
+
+    let pages = collect_pages(&root)?;
+    if pages.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No thoughts to export under {}",
+            root.display()
+        ));
+    }
+    let site = build_site(&pages, last_synced(&root).as_deref());
+
+    let output = match output {
+        Some(path) => expand_path(&path),
+        None if zip => PathBuf::from("thoughts-site.zip"),
+        None => PathBuf::from("thoughts-site"),
+    };
+    if zip {
+        write_zip(&output, &site)?;
+    } else {
+        write_dir(&output, &site)?;
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Exported {} thought(s) to {}",
+            pages.len(),
+            output.display()
+        )
+        .green()
+    );
+    Ok(())
+}
+
+/// Every markdown file under `root`, rendered, sorted by path.
+fn collect_pages(root: &Path) -> Result<Vec<Page>> {
+    let mut files = find_files_following_symlinks(root, root, &mut HashSet::new())?;
+    files.retain(|f| f.extension().is_some_and(|ext| ext == "md"));
+    files.sort();
+
+    files
+        .into_iter()
+        .map(|source| {
+            let path = root.join(&source);
+            let markdown = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            Ok(render_page(source, &markdown))
+        })
+        .collect()
+}
+
+/// Render one thought. The title is its first `# heading`, else its file
+/// name; links to other `.md` files point at their `.html` pages.
+fn render_page(source: PathBuf, markdown: &str) -> Page {
+    let title = markdown
+        .lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|t| t.trim().to_string())
+        .unwrap_or_else(|| {
+            source
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+    // The site is meant to be shared, so raw HTML is shown as text and
+    // links other than http(s) or relative ones are left as plain text.
+    let mut unlinked: Option<TagEnd> = None;
+    let events = Parser::new_ext(markdown, Options::all()).filter_map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Some(Event::Text(html)),
+        Event::Start(Tag::Link { dest_url, .. }) if !is_safe_url(&dest_url) => {
+            unlinked = Some(TagEnd::Link);
+            None
+        }
+        Event::Start(Tag::Image { dest_url, .. }) if !is_safe_url(&dest_url) => {
+            unlinked = Some(TagEnd::Image);
+            None
+        }
+        Event::End(end) if unlinked == Some(end) => {
+            unlinked = None;
+            None
+        }
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Some(Event::Start(Tag::Link {
+            link_type,
+            dest_url: md_link_to_html(dest_url),
+            title,
+            id,
+        })),
+        other => Some(other),
+    });
+    let mut body = String::new();
+    pulldown_cmark::html::push_html(&mut body, events);
+
+    Page {
+        source,
+        title,
+        body,
+        text: markdown.to_lowercase(),
+    }
+}
+
+/// Whether `url` is relative or http(s). Browsers ignore ASCII tabs and
+/// newlines in a scheme, so `java\tscript:` counts as `javascript:`.
+fn is_safe_url(url: &str) -> bool {
+    let url: String = url
+        .trim_start_matches(|c: char| c.is_ascii_control() || c == ' ')
+        .chars()
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+    scheme.is_none_or(|scheme| {
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    })
+}
+
+/// `notes.md#intro` becomes `notes.html#intro`; absolute URLs and other
+/// targets are left alone.
+fn md_link_to_html(url: CowStr) -> CowStr {
+    if url.contains("://") || url.starts_with('/') || url.starts_with('#') {
+        return url;
+    }
+    let (path, fragment) = url
+        .split_once('#')
+        .map_or((&*url, None), |(p, f)| (p, Some(f)));
+    let Some(stem) = path.strip_suffix(".md") else {
+        return url;
+    };
+    match fragment {
+        Some(fragment) => format!("{stem}.html#{fragment}").into(),
+        None => format!("{stem}.html").into(),
+    }
+}
+
+/// When the thoughts were last committed, if `root` is a git repository.
+fn last_synced(root: &Path) -> Option<String> {
+    let commit = GitRepo::open(root).ok()?.log(1).ok()?.pop()?;
+    Local
+        .timestamp_opt(commit.time, 0)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// The site's files, as paths relative to the output root and contents:
+/// `index.html` and one page per thought.
+fn build_site(pages: &[Page], last_synced: Option<&str>) -> Vec<(PathBuf, String)> {
+    let footer = format!(
+        "<footer>Last synced: {} · Exported {}</footer>",
+        escape(last_synced.unwrap_or("never")),
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+
+    let mut groups: BTreeMap<String, Vec<(usize, &Page)>> = BTreeMap::new();
+    for (i, page) in pages.iter().enumerate() {
+        groups.entry(page.group()).or_default().push((i, page));
+    }
+    let mut list = String::new();
+    for (group, pages) in &groups {
+        let heading = if group.is_empty() { "/" } else { group };
+        list.push_str(&format!(
+            "<section class=\"group\" id=\"{}\"><h2>{}</h2><ul class=\"files\">\n",
+            escape(&group_anchor(group)),
+            escape(heading)
+        ));
+        for (i, page) in pages {
+            list.push_str(&format!(
+                "<li data-i=\"{i}\"><a href=\"{}\">{}</a></li>\n",
+                escape(&page.href()),
+                escape(&page.title)
+            ));
+        }
+        list.push_str("</ul></section>\n");
+    }
+
+    let index: Vec<String> = pages
+        .iter()
+        .map(|p| format!("{}\n{}", p.title.to_lowercase(), p.text))
+        .collect();
+    // `</` would end the inline script early.
+    let index = json!(index).to_string().replace("</", "<\\/");
+    let index_html = document(
+        "Thoughts",
+        &format!(
+            "<h1>Thoughts</h1>\n<input id=\"search\" type=\"search\" placeholder=\"Search thoughts\" autofocus>\n{list}{footer}\n<script>const INDEX={index};\n{SEARCH_SCRIPT}</script>"
+        ),
+    );
+
+    let mut site = vec![(PathBuf::from("index.html"), index_html)];
+    for page in pages {
+        let up = "../".repeat(page.source.components().count() - 1);
+        let group = page.group();
+        let crumbs = if group.is_empty() {
+            format!("<a href=\"{up}index.html\">Thoughts</a>")
+        } else {
+            format!(
+                "<a href=\"{up}index.html\">Thoughts</a> / <a href=\"{up}index.html#{}\">{}</a>",
+                escape(&group_anchor(&group)),
+                escape(&group)
+            )
+        };
+        let html = document(
+            &page.title,
+            &format!(
+                "<nav class=\"crumbs\">{crumbs} / {}</nav>\n<article>\n{}</article>\n{footer}",
+                escape(&page.title),
+                page.body
+            ),
+        );
+        site.push((page.source.with_extension("html"), html));
+    }
+    site
+}
+
+fn document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n{body}\n</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+fn group_anchor(group: &str) -> String {
+    format!("g-{}", group.replace('/', "-"))
+}
+
+/// `path` with `/` separators on every platform, for hrefs and zip entries.
+fn url_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_dir(output: &Path, site: &[(PathBuf, String)]) -> Result<()> {
+    for (path, content) in site {
+        let target = output.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+    }
+    Ok(())
+}
+
+fn write_zip(output: &Path, site: &[(PathBuf, String)]) -> Result<()> {
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::File::create(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default();
+    for (path, content) in site {
+        zip.start_file(url_path(path), options)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn index_lists_every_thought_with_valid_relative_links() {
+        let root = tempfile::tempdir().unwrap();
+        write(
+            root.path(),
+            "repos/app/alice/plan.md",
+            "# Auth plan\nSee [research](../shared/research.md#findings).",
+        );
+        write(
+            root.path(),
+            "repos/app/shared/research.md",
+            "No heading <here>",
+        );
+        write(root.path(), "global/shared/notes.md", "# Notes");
+        write(root.path(), "global/shared/diagram.png", "");
+        write(root.path(), ".git/HEAD", "");
+
+        let pages = collect_pages(root.path()).unwrap();
+        let site = build_site(&pages, Some("2026-01-02 03:04"));
+        let files: HashSet<&Path> = site.iter().map(|(p, _)| p.as_path()).collect();
+        assert_eq!(files.len(), 4);
+
+        let index = &site[0].1;
+        assert!(index.contains("Auth plan"));
+        assert!(index.contains(">research</a>"));
+        assert!(index.contains("<h2>repos/app/alice</h2>"));
+        assert!(index.contains("Last synced: 2026-01-02 03:04"));
+
+        // Every relative link in every page lands on a generated file.
+        let href = Regex::new(r##"href="([^"#]+)(#[^"]*)?""##).unwrap();
+        for (path, html) in &site {
+            let dir = path.parent().unwrap();
+            for link in href.captures_iter(html) {
+                let resolved = {
+                    let mut full = dir.to_path_buf();
+                    for part in Path::new(&link[1]).components() {
+                        match part {
+                            std::path::Component::ParentDir => {
+                                full.pop();
+                            }
+                            other => full.push(other),
+                        }
+                    }
+                    full
+                };
+                assert!(
+                    files.contains(resolved.as_path()),
+                    "{} links to missing {}",
+                    path.display(),
+                    &link[1]
+                );
+            }
+        }
+
+        let plan = &site
+            .iter()
+            .find(|(p, _)| p == Path::new("repos/app/alice/plan.html"))
+            .unwrap()
+            .1;
+        assert!(plan.contains("href=\"../shared/research.html#findings\""));
+        assert!(plan.contains("href=\"../../../index.html#g-repos-app-alice\""));
+    }
+
+    #[test]
+    fn zip_holds_the_same_files() {
+        let root = tempfile::tempdir().unwrap();
+        write(root.path(), "shared/a.md", "# A");
+        let site = build_site(&collect_pages(root.path()).unwrap(), None);
+        assert!(site[0].1.contains("Last synced: never"));
+
+        let out = root.path().join("out/site.zip");
+        write_zip(&out, &site).unwrap();
+        let archive = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort();
+        assert_eq!(names, ["index.html", "shared/a.html"]);
+    }
+
+    #[test]
+    fn pages_show_raw_html_as_text_and_drop_script_links() {
+        let page = render_page(
+            PathBuf::from("shared/a.md"),
+            "# A\n\nHi <script>alert(1)</script> there.\n\n<script>\nalert(2)\n</script>\n\n\
+             [bad](javascript:alert(3)) [tab](<java\tscript:alert(4)>) [ok](https://example.com) \
+             [rel](b.md) ![img](javascript:alert(5))",
+        );
+        assert!(!page.body.contains("<script"), "{}", page.body);
+        assert!(page.body.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!page.body.contains("javascript:"), "{}", page.body);
+        assert!(!page.body.contains("java\tscript:"), "{}", page.body);
+        assert!(page.body.contains("bad tab"));
+        assert!(page.body.contains("<a href=\"https://example.com\">ok</a>"));
+        assert!(page.body.contains("<a href=\"b.html\">rel</a>"));
+    }
+}
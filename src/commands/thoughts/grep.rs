@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use super::search;
+use crate::cli::{GrepArgs, GrepTool, SearchArgs};
+use crate::config::get_current_repo_path;
+
+const DETECTION_ORDER: [GrepTool; 3] = [GrepTool::Rg, GrepTool::Ag, GrepTool::Grep];
+
+pub fn grep(args: GrepArgs) -> Result<()> {
+    let GrepArgs {
+        pattern,
+        tool,
+        verbose,
+        config,
+        extra_args,
+    } = args;
+
+    let Some(tool) = tool.or_else(|| DETECTION_ORDER.into_iter().find(|t| is_available(*t))) else {
+        eprintln!(
+            "{}",
+            "Warning: none of rg, ag or grep found; falling back to 'thoughts search' (extra arguments ignored)"
+                .yellow()
+        );
+        return search::search(SearchArgs {
+            query: pattern,
+            index: false,
+            regex: false,
+            after: None,
+            before: None,
+            all_profiles: false,
+            json: false,
+            config,
+        });
+    };
+
+    let searchable = get_current_repo_path()?.join("thoughts").join("searchable");
+    if !searchable.is_dir() {
+        return Err(anyhow::anyhow!(
+            "No searchable directory at {}. Run 'hyprlayer thoughts sync' first.",
+            searchable.display()
+        ));
+    }
+
+    if verbose {
+        eprintln!(
+            "{}",
+            format!("Using {} in {}", program(tool), searchable.display()).bright_black()
+        );
+    }
+
+    let status = Command::new(program(tool))
+        .args(tool_args(tool, &pattern, &extra_args, &searchable))
+        .stdin(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run {}", program(tool)))?;
+
+    // Mirror the tool's exit code: 1 means "no matches" for all three.
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(2));
+    }
+    Ok(())
+}
+
+fn program(tool: GrepTool) -> &'static str {
+    match tool {
+        GrepTool::Rg => "rg",
+        GrepTool::Ag => "ag",
+        GrepTool::Grep => "grep",
+    }
+}
+
+fn is_available(tool: GrepTool) -> bool {
+    Command::new(program(tool))
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Extra arguments go first so they can't be mistaken for the path; the
+/// pattern is passed with `-e` (or after `--` for ag) so a leading dash
+/// isn't read as a flag.
+fn tool_args(tool: GrepTool, pattern: &str, extra: &[String], dir: &Path) -> Vec<String> {
+    let mut args: Vec<String> = match tool {
+        GrepTool::Grep => vec!["-r".to_string()],
+        GrepTool::Rg | GrepTool::Ag => Vec::new(),
+    };
+    args.extend(extra.iter().cloned());
+    match tool {
+        GrepTool::Rg | GrepTool::Grep => args.extend(["-e".to_string(), pattern.to_string()]),
+        GrepTool::Ag => args.extend(["--".to_string(), pattern.to_string()]),
+    }
+    args.push(dir.display().to_string());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_args_put_extra_args_before_pattern_and_dir() {
+        let dir = Path::new("/code/app/thoughts/searchable");
+        let extra = vec!["-i".to_string(), "--type".to_string(), "md".to_string()];
+        assert_eq!(
+            tool_args(GrepTool::Rg, "-todo", &extra, dir),
+            [
+                "-i",
+                "--type",
+                "md",
+                "-e",
+                "-todo",
+                "/code/app/thoughts/searchable"
+            ]
+        );
+        assert_eq!(
+            tool_args(GrepTool::Ag, "todo", &[], dir),
+            ["--", "todo", "/code/app/thoughts/searchable"]
+        );
+        assert_eq!(
+            tool_args(GrepTool::Grep, "todo", &extra[..1], dir),
+            ["-r", "-i", "-e", "todo", "/code/app/thoughts/searchable"]
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::{Select, theme::ColorfulTheme};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::sync::sync_repo;
+use crate::cli::{ImportArgs, ImportFormat, ImportScope};
+use crate::config::{expand_path, get_current_repo_path};
+
+pub fn import(args: ImportArgs) -> Result<()> {
+    let ImportArgs {
+        source,
+        format,
+        obsidian_vault,
+        scope,
+        config,
+    } = args;
+    let (source, ImportFormat::Obsidian) = match obsidian_vault {
+        Some(vault) => (vault, ImportFormat::Obsidian),
+        // clap requires both when --obsidian-vault is absent.
+        None => (source.unwrap(), format.unwrap()),
+    };
+
+    let config_path = config.path()?;
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let vault = expand_path(&source);
+    if !vault.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Obsidian vault not found at {}",
+            vault.display()
+        ));
+    }
+    let current_repo = get_current_repo_path()?;
+    let thoughts_dir = current_repo.join("thoughts");
+    if !thoughts_dir.is_dir() {
+        return Err(anyhow::anyhow!(
+            "Thoughts not initialized for this repository. Run 'hyprlayer thoughts init' first."
+        ));
+    }
+    let effective = thoughts_config.effective_config_for(&current_repo.display().to_string());
+
+    let notes = vault_notes(&vault)?;
+    if notes.is_empty() {
+        println!("{}", "No .md files found in the vault".yellow());
+        return Ok(());
+    }
+
+    let theme = ColorfulTheme::default();
+    let mut planned = Vec::new();
+    for (group, files) in group_by_top_level(&notes) {
+        let scope = match scope {
+            Some(scope) => Some(scope),
+            None => prompt_for_scope(&theme, group.as_deref(), files.len(), &effective.user)?,
+        };
+        if let Some(scope) = scope {
+            let dest = thoughts_dir.join(scope_dir(scope, &effective.user));
+            planned.extend(files.into_iter().map(|file| (file, dest.clone())));
+        }
+    }
+
+    let imported: Vec<PathBuf> = planned.iter().map(|(file, _)| file.clone()).collect();
+    for (note, target) in missing_links(&vault, &imported)? {
+        println!(
+            "{}",
+            format!(
+                "Warning: {} links to [[{}]], which is not being imported",
+                note.display(),
+                target
+            )
+            .yellow()
+        );
+    }
+
+    let (copied, skipped) = copy_notes(&vault, &planned)?;
+    for path in &skipped {
+        println!(
+            "{}",
+            format!("Skipped {}: already exists", path.display()).yellow()
+        );
+    }
+    println!(
+        "{}",
+        format!("Imported {} note(s) from {}", copied, vault.display()).green()
+    );
+    if copied == 0 {
+        return Ok(());
+    }
+
+    let vault_name = vault
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| vault.display().to_string());
+    sync_repo(
+        &config_path,
+        &hyprlayer_config,
+        &current_repo,
+        &effective,
+        Some(&format!("Import from Obsidian vault: {vault_name}")),
+        true,
+    )
+}
+
+/// Where a scope lives under the repository's `thoughts/` directory.
+fn scope_dir(scope: ImportScope, user: &str) -> PathBuf {
+    match scope {
+        ImportScope::User => PathBuf::from(user),
+        ImportScope::Shared => PathBuf::from("shared"),
+        ImportScope::Global => Path::new("global").join(user),
+    }
+}
+
+/// Ask where the notes under `group` (a top-level directory, or the vault
+/// root for `None`) go. `None` skips them.
+fn prompt_for_scope(
+    theme: &ColorfulTheme,
+    group: Option<&str>,
+    count: usize,
+    user: &str,
+) -> Result<Option<ImportScope>> {
+    let scopes = [ImportScope::User, ImportScope::Shared, ImportScope::Global];
+    let mut options: Vec<String> = scopes
+        .iter()
+        .map(|scope| format!("thoughts/{}", scope_dir(*scope, user).display()))
+        .collect();
+    options.push("Skip".to_string());
+
+    let what = match group {
+        Some(dir) => format!("{dir}/"),
+        None => "the vault root".to_string(),
+    };
+    let selection = Select::with_theme(theme)
+        .with_prompt(format!("Import {count} note(s) in {what} into"))
+        .items(&options)
+        .default(0)
+        .interact()?;
+    Ok(scopes.get(selection).copied())
+}
+
+/// Every `.md` file in the vault, relative to it and sorted. Dot
+/// directories such as `.obsidian` and `.trash` are skipped.
+fn vault_notes(vault: &Path) -> Result<Vec<PathBuf>> {
+    fn walk(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                walk(root, &path, out)?;
+            } else if path.extension().is_some_and(|ext| ext == "md")
+                && let Ok(rel) = path.strip_prefix(root)
+            {
+                out.push(rel.to_path_buf());
+            }
+        }
+        Ok(())
+    }
+
+    let mut notes = Vec::new();
+    walk(vault, vault, &mut notes)?;
+    notes.sort();
+    Ok(notes)
+}
+
+/// `notes` grouped by top-level directory, vault-root files under `None`
+/// first.
+fn group_by_top_level(notes: &[PathBuf]) -> BTreeMap<Option<String>, Vec<PathBuf>> {
+    let mut groups: BTreeMap<Option<String>, Vec<PathBuf>> = BTreeMap::new();
+    for note in notes {
+        let group = (note.components().count() > 1)
+            .then(|| note.components().next())
+            .flatten()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned());
+        groups.entry(group).or_default().push(note.clone());
+    }
+    groups
+}
+
+/// The targets of the `[[wiki links]]` (and `![[embeds]]`) in `content`,
+/// without any `#heading` or `|alias`.
+fn wiki_links(content: &str) -> Vec<&str> {
+    let mut links = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("[[") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        let link = &rest[..end];
+        let target = link.split(['|', '#']).next().unwrap_or_default().trim();
+        if !target.is_empty() {
+            links.push(target);
+        }
+        rest = &rest[end + 2..];
+    }
+    links
+}
+
+/// `(note, target)` for each wiki link in an imported note that doesn't
+/// resolve to another imported note. Like Obsidian, a target matches a
+/// note by file name or by path within the vault, with or without `.md`.
+fn missing_links(vault: &Path, imported: &[PathBuf]) -> Result<Vec<(PathBuf, String)>> {
+    let mut known = HashSet::new();
+    for note in imported {
+        let without_ext = note.with_extension("");
+        known.insert(without_ext.to_string_lossy().replace('\\', "/"));
+        if let Some(stem) = note.file_stem() {
+            known.insert(stem.to_string_lossy().into_owned());
+        }
+    }
+
+    let mut missing = Vec::new();
+    for note in imported {
+        let path = vault.join(note);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        for target in wiki_links(&content) {
+            let name = target.strip_suffix(".md").unwrap_or(target);
+            if !known.contains(name) {
+                missing.push((note.clone(), target.to_string()));
+            }
+        }
+    }
+    Ok(missing)
+}
+
+/// Copy each `(note, dest)` from `vault` to `dest/<note>`, keeping its
+/// directories. Existing files are left alone and returned as skipped.
+fn copy_notes(vault: &Path, planned: &[(PathBuf, PathBuf)]) -> Result<(usize, Vec<PathBuf>)> {
+    let mut copied = 0;
+    let mut skipped = Vec::new();
+    for (note, dest) in planned {
+        let target = dest.join(note);
+        if target.exists() {
+            skipped.push(target);
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(vault.join(note), &target)
+            .with_context(|| format!("Failed to copy {}", note.display()))?;
+        copied += 1;
+    }
+    Ok((copied, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn wiki_links_strip_headings_and_aliases() {
+        assert_eq!(
+            wiki_links(
+                "See [[Plan]], [[notes/Design#API|the API]] and ![[diagram.png]]. [[ ]] [[open"
+            ),
+            ["Plan", "notes/Design", "diagram.png"]
+        );
+    }
+
+    #[test]
+    fn imports_a_mock_vault_into_scopes() {
+        let vault = tempfile::tempdir().unwrap();
+        let vault = vault.path();
+        write(&vault.join("Inbox.md"), "Links to [[Plan]] and [[Secret]]");
+        write(
+            &vault.join("projects/Plan.md"),
+            "#plan see [[projects/Design.md]]",
+        );
+        write(&vault.join("projects/deep/Design.md"), "[[Inbox|home]]");
+        write(&vault.join("private/Secret.md"), "hidden");
+        write(&vault.join("projects/image.png"), "");
+        write(&vault.join(".obsidian/workspace.md"), "");
+
+        let notes = vault_notes(vault).unwrap();
+        assert_eq!(
+            notes,
+            [
+                PathBuf::from("Inbox.md"),
+                PathBuf::from("private/Secret.md"),
+                PathBuf::from("projects/Plan.md"),
+                PathBuf::from("projects/deep/Design.md"),
+            ]
+        );
+
+        let groups = group_by_top_level(&notes);
+        let keys: Vec<Option<&str>> = groups.keys().map(|k| k.as_deref()).collect();
+        assert_eq!(keys, [None, Some("private"), Some("projects")]);
+
+        // Import everything but `private/`.
+        let thoughts = tempfile::tempdir().unwrap();
+        let user_dir = thoughts.path().join(scope_dir(ImportScope::User, "alice"));
+        let shared_dir = thoughts
+            .path()
+            .join(scope_dir(ImportScope::Shared, "alice"));
+        let planned = vec![
+            (PathBuf::from("Inbox.md"), user_dir.clone()),
+            (PathBuf::from("projects/Plan.md"), shared_dir.clone()),
+            (PathBuf::from("projects/deep/Design.md"), shared_dir.clone()),
+        ];
+        let imported: Vec<PathBuf> = planned.iter().map(|(n, _)| n.clone()).collect();
+
+        // `projects/Design.md` names a path that doesn't exist; Obsidian
+        // would not resolve it either.
+        let missing = missing_links(vault, &imported).unwrap();
+        assert_eq!(
+            missing,
+            [
+                (PathBuf::from("Inbox.md"), "Secret".to_string()),
+                (
+                    PathBuf::from("projects/Plan.md"),
+                    "projects/Design.md".to_string()
+                ),
+            ]
+        );
+
+        write(&shared_dir.join("projects/Plan.md"), "already here");
+        let (copied, skipped) = copy_notes(vault, &planned).unwrap();
+        assert_eq!(copied, 2);
+        assert_eq!(skipped, [shared_dir.join("projects/Plan.md")]);
+        assert_eq!(
+            fs::read_to_string(user_dir.join("Inbox.md")).unwrap(),
+            "Links to [[Plan]] and [[Secret]]"
+        );
+        assert!(shared_dir.join("projects/deep/Design.md").is_file());
+        assert_eq!(
+            fs::read_to_string(shared_dir.join("projects/Plan.md")).unwrap(),
+            "already here"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use super::search::print_hits;
+use crate::cli::{IndexBuildArgs, IndexSearchArgs};
+use crate::search_index::{self, SearchIndex};
+
+pub fn build(args: IndexBuildArgs) -> Result<()> {
+    let IndexBuildArgs { profile, config } = args;
+
+    let config_path = config.path()?;
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let backend = match thoughts_config.resolve_profile(profile)? {
+        Some(name) => &thoughts_config.profiles[&name].backend,
+        None => &thoughts_config.backend,
+    };
+    let root = backend.content_root().ok_or_else(|| {
+        anyhow::anyhow!(
+            "The {} backend stores thoughts remotely and can't be indexed",
+            backend.kind().as_str()
+        )
+    })?;
+
+    let index_path = search_index::index_path(&config_path);
+    let mut index = SearchIndex::load(&index_path)?;
+    let count = index.rebuild_root(&root)?;
+    index.save(&index_path)?;
+
+    println!(
+        "{}",
+        format!("Indexed {count} file(s) under {}", root.display()).green()
+    );
+    println!("  Index: {}", index_path.display().to_string().cyan());
+    Ok(())
+}
+
+pub fn search(args: IndexSearchArgs) -> Result<()> {
+    let IndexSearchArgs {
+        query,
+        regex,
+        config,
+    } = args;
+
+    let index_path = search_index::index_path(&config.path()?);
+    let index = SearchIndex::load(&index_path)?;
+    if index.file_count() == 0 {
+        return Err(anyhow::anyhow!(
+            "Search index is empty. Run 'hyprlayer thoughts index build' first."
+        ));
+    }
+
+    print_hits(&index.search(&query, regex)?, None);
+    Ok(())
+}
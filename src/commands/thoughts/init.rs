@@ -1,15 +1,16 @@
 use anyhow::Result;
 use colored::Colorize;
 use dialoguer::{Confirm, Input, Select, theme::ColorfulTheme};
+use serde::Serialize;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::backends::{self, BackendContext};
+use crate::backends::{self, BackendContext, GitSetup};
 use crate::cli::InitArgs;
 use crate::config::{
     AnytypeConfig, BackendConfig, BackendKind, GitConfig, HyprlayerConfig, NotionConfig,
     ObsidianConfig, ProfileConfig, RepoMapping, ThoughtsConfig, expand_path, get_current_repo_path,
-    get_default_thoughts_repo, get_repo_name_from_path, sanitize_directory_name,
+    get_default_thoughts_repo, get_repo_name_from_path, validate_directory_name,
 };
 use crate::git_ops::GitRepo;
 
@@ -17,8 +18,12 @@ pub fn init(args: InitArgs) -> Result<()> {
     let InitArgs {
         force,
         directory,
+        user,
         profile,
         backend,
+        thoughts_repo,
+        repos_dir,
+        global_dir,
         vault_path,
         vault_subpath,
         parent_page_id,
@@ -26,11 +31,24 @@ pub fn init(args: InitArgs) -> Result<()> {
         space_id,
         type_id,
         api_token_env,
+        remote,
+        branch,
+        no_hooks,
+        hooks,
+        sync_jitter,
         yes,
+        list_existing,
+        json,
         config,
     } = args;
 
+    if list_existing {
+        return print_existing_directories(&config, profile, json);
+    }
+    config.reject_overrides("thoughts init")?;
+
     let current_repo = get_current_repo_path()?;
+    let user = explicit_username(user)?;
 
     if backend == Some(BackendKind::Notion) && api_token_env.is_some() {
         return Err(anyhow::anyhow!(
@@ -48,34 +66,42 @@ pub fn init(args: InitArgs) -> Result<()> {
         type_id,
         api_token_env: api_token_env.clone(),
     };
+    let git_setup = GitSetup { remote, branch };
+    let hook_flags = HookFlags {
+        enabled: (hooks || no_hooks).then_some(hooks),
+        sync_jitter,
+    };
+    let dir_flags = DirFlags {
+        thoughts_repo,
+        repos_dir,
+        global_dir,
+    };
+    // Only the git backend has a thoughts repository to point at.
+    let backend = backend.or(dir_flags.thoughts_repo.as_ref().map(|_| BackendKind::Git));
 
     if yes {
         return init_non_interactive(
             config,
             current_repo,
             directory,
+            user,
             profile,
             backend,
+            dir_flags,
             vault_path,
             vault_subpath,
             notion_flags,
             anytype_flags,
+            git_setup,
+            hook_flags,
             force,
         );
     }
 
-    let config_path = config.path()?;
-    let mut hyprlayer_config = config.load_if_exists()?.unwrap_or_default();
-
-    if hyprlayer_config
-        .ai
-        .as_ref()
-        .is_none_or(|ai| ai.agent_tool.is_none())
-    {
-        return Err(anyhow::anyhow!(
-            "AI tool not configured. Run 'hyprlayer ai configure' first."
-        ));
-    }
+    // `init` edits the file writes go to; the AI tool may come from any
+    // `--config-file` layer.
+    let mut hyprlayer_config = config.load_target()?.unwrap_or_default();
+    let agent_tool = configured_agent_tool(&config, &current_repo)?;
 
     let orphaned = hyprlayer_config.thoughts_mut().find_orphaned_mappings();
     if !orphaned.is_empty() {
@@ -92,11 +118,15 @@ pub fn init(args: InitArgs) -> Result<()> {
             .interact()?
         {
             hyprlayer_config.thoughts_mut().remove_mappings(&orphaned);
-            hyprlayer_config.save(&config_path)?;
+            config.update(|target| {
+                target.thoughts_mut().remove_mappings(&orphaned);
+                Ok(())
+            })?;
         }
     }
 
-    hyprlayer_config.thoughts_mut().validate_profile(&profile)?;
+    let profile = apply_default_profile(hyprlayer_config.thoughts_mut(), profile);
+    let profile = hyprlayer_config.thoughts_mut().resolve_profile(profile)?;
 
     if !check_existing_setup(&current_repo, force)? {
         return Ok(());
@@ -105,19 +135,22 @@ pub fn init(args: InitArgs) -> Result<()> {
     let existing_profile = hyprlayer_config.thoughts_mut().resolve_dirs(&profile);
     let backend_kind = resolve_backend_interactive(backend, existing_profile.backend.kind())?;
 
+    dir_flags.check(backend_kind)?;
+    git_setup.check(backend_kind)?;
     require_git_repo_for_filesystem_backend(&current_repo, backend_kind)?;
 
-    let agent_tool = hyprlayer_config.ai.as_ref().and_then(|a| a.agent_tool);
     let refreshed = prompt_for_thoughts_fields(
         hyprlayer_config.thoughts.clone().unwrap_or_default(),
         &existing_profile,
         backend_kind,
+        &dir_flags,
         vault_path,
         vault_subpath,
         &notion_flags,
         &anytype_flags,
         &profile,
-        agent_tool,
+        Some(agent_tool),
+        user,
     )?;
     hyprlayer_config.thoughts = Some(refreshed);
 
@@ -126,8 +159,7 @@ pub fn init(args: InitArgs) -> Result<()> {
         let content_root = resolve_content_root(&resolved.backend)?;
         ensure_content_root(&content_root)?;
 
-        let repos_dir = resolved.backend.filesystem_repos_dir().unwrap_or("repos");
-        let repos_path = content_root.join(repos_dir);
+        let repos_path = repos_dir_path(&resolved.backend)?;
         fs::create_dir_all(&repos_path)?;
 
         select_or_create_directory(&repos_path, &current_repo, directory)?
@@ -143,27 +175,154 @@ pub fn init(args: InitArgs) -> Result<()> {
                 .default(default_name)
                 .interact()?,
         };
-        sanitize_directory_name(&chosen)
+        validate_directory_name(&chosen)?
     };
 
     let mapping = RepoMapping::new(&mapped_name, &profile);
+    let repo_key = current_repo.display().to_string();
+    warn_if_directory_shared(hyprlayer_config.thoughts_mut(), &repo_key, &mapping);
     hyprlayer_config
         .thoughts_mut()
         .repo_mappings
-        .insert(current_repo.display().to_string(), mapping);
-    hyprlayer_config.save(&config_path)?;
+        .insert(repo_key, mapping);
+    hook_flags.apply(hyprlayer_config.thoughts_mut());
+    save_thoughts(&config, &hyprlayer_config)?;
 
-    dispatch_backend_init(&hyprlayer_config, &current_repo, backend_kind)?;
+    // Set up the backend from what was saved, merged with any base layers.
+    let saved = config.load_if_exists()?.unwrap_or_default();
+    dispatch_backend_init(&saved, &current_repo, backend_kind, git_setup)?;
 
     Ok(())
 }
 
+/// Warn when `mapping` would put `repo` in a thoughts directory another
+/// repository already syncs to (see `find_duplicate_mappings`).
+fn warn_if_directory_shared(thoughts: &ThoughtsConfig, repo: &str, mapping: &RepoMapping) {
+    let mut candidate = thoughts.clone();
+    candidate
+        .repo_mappings
+        .insert(repo.to_string(), mapping.clone());
+    let Some(group) = candidate
+        .find_duplicate_mappings()
+        .into_iter()
+        .find(|paths| paths.iter().any(|p| p == repo))
+    else {
+        return;
+    };
+    let others: Vec<&str> = group
+        .iter()
+        .filter(|p| *p != repo)
+        .map(String::as_str)
+        .collect();
+    println!(
+        "{}",
+        format!(
+            "Warning: thoughts directory '{}' is already used by {}; both repositories will sync into it",
+            mapping.repo(),
+            others.join(", ")
+        )
+        .yellow()
+    );
+}
+
+/// Fall back to `defaultProfile` when no `--profile` flag was given.
+fn apply_default_profile(thoughts: &ThoughtsConfig, profile: Option<String>) -> Option<String> {
+    let explicit = profile.is_some();
+    let profile = thoughts.profile_for_new_mapping(profile);
+    if !explicit && let Some(name) = &profile {
+        println!(
+            "{}",
+            format!("Using default profile: {name}").bright_black()
+        );
+    }
+    profile
+}
+
+/// `--thoughts-repo`, `--repos-dir` and `--global-dir`. Each one given
+/// replaces its prompt; the rest keep their prior value or default.
+#[derive(Debug, Default, Clone)]
+struct DirFlags {
+    thoughts_repo: Option<String>,
+    repos_dir: Option<String>,
+    global_dir: Option<String>,
+}
+
+impl DirFlags {
+    fn is_empty(&self) -> bool {
+        self.thoughts_repo.is_none() && self.repos_dir.is_none() && self.global_dir.is_none()
+    }
+
+    /// Reject flags the chosen backend has no field for.
+    fn check(&self, backend_kind: BackendKind) -> Result<()> {
+        if self.thoughts_repo.is_some() && backend_kind != BackendKind::Git {
+            return Err(anyhow::anyhow!(
+                "--thoughts-repo only applies to the git backend, not {}",
+                backend_kind
+            ));
+        }
+        if (self.repos_dir.is_some() || self.global_dir.is_some())
+            && !backend_kind.uses_filesystem()
+        {
+            return Err(anyhow::anyhow!(
+                "--repos-dir and --global-dir only apply to the git and obsidian backends, not {}",
+                backend_kind
+            ));
+        }
+        for (flag, value) in [
+            ("--thoughts-repo", &self.thoughts_repo),
+            ("--repos-dir", &self.repos_dir),
+            ("--global-dir", &self.global_dir),
+        ] {
+            if value.as_ref().is_some_and(|v| v.trim().is_empty()) {
+                return Err(anyhow::anyhow!("{} cannot be empty", flag));
+            }
+        }
+        if self.repos_dir.is_some() && self.repos_dir == self.global_dir {
+            return Err(anyhow::anyhow!("--repos-dir and --global-dir must differ"));
+        }
+        Ok(())
+    }
+
+    /// The directory names for a filesystem backend: flag, then `prior`,
+    /// then `repos` / `global`.
+    fn dir_names(&self, prior: Option<(&str, &str)>) -> (String, String) {
+        let pick = |flag: &Option<String>, prior: Option<&str>, default: &str| {
+            flag.clone()
+                .or_else(|| prior.filter(|s| !s.is_empty()).map(str::to_string))
+                .unwrap_or_else(|| default.to_string())
+        };
+        (
+            pick(&self.repos_dir, prior.map(|p| p.0), "repos"),
+            pick(&self.global_dir, prior.map(|p| p.1), "global"),
+        )
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct NotionFlags {
     parent_page_id: Option<String>,
     database_id: Option<String>,
 }
 
+/// `--hooks` / `--no-hooks` (as `enabled`) and `--sync-jitter`, which are
+/// remembered in the config for later inits.
+#[derive(Debug, Default, Clone)]
+struct HookFlags {
+    enabled: Option<bool>,
+    sync_jitter: Option<u64>,
+}
+
+impl HookFlags {
+    fn apply(&self, thoughts: &mut ThoughtsConfig) {
+        if let Some(enabled) = self.enabled {
+            thoughts.hooks_enabled = enabled;
+        }
+        if let Some(seconds) = self.sync_jitter {
+            thoughts.sync_jitter_max_seconds = (seconds > 0).then_some(seconds);
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct AnytypeFlags {
     space_id: Option<String>,
@@ -176,50 +335,51 @@ fn init_non_interactive(
     config: crate::cli::ConfigArgs,
     current_repo: PathBuf,
     directory: Option<String>,
-    profile: Option<String>,
+    user: Option<String>,
+    mut profile: Option<String>,
     backend_flag: Option<BackendKind>,
+    dir_flags: DirFlags,
     vault_path_flag: Option<String>,
     vault_subpath_flag: Option<String>,
     notion_flags: NotionFlags,
     anytype_flags: AnytypeFlags,
+    git_setup: GitSetup,
+    hook_flags: HookFlags,
     force: bool,
 ) -> Result<()> {
     let directory =
         directory.ok_or_else(|| anyhow::anyhow!("--directory is required when using --yes"))?;
 
-    let config_path = config.path()?;
-    let mut hyprlayer_config = config.load_if_exists()?.ok_or_else(|| {
-        anyhow::anyhow!(
-            "No existing config found. Run 'hyprlayer thoughts init' interactively first."
-        )
-    })?;
-
-    {
-        let thoughts = hyprlayer_config.thoughts.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Config is incomplete. Run 'hyprlayer thoughts init' interactively to complete setup."
-            )
-        })?;
-
-        if !thoughts.is_thoughts_configured() {
-            return Err(anyhow::anyhow!(
-                "Config is incomplete. Run 'hyprlayer thoughts init' interactively to complete setup."
-            ));
-        }
-
-        if hyprlayer_config
-            .ai
-            .as_ref()
-            .is_none_or(|ai| ai.agent_tool.is_none())
-        {
+    let mut hyprlayer_config = match config.load_target()? {
+        Some(existing) => existing,
+        None if user.is_some() => HyprlayerConfig::default(),
+        None => {
             return Err(anyhow::anyhow!(
-                "AI tool not configured. Run 'hyprlayer ai configure' first."
+                "No existing config found. Pass --user to create one, or run 'hyprlayer thoughts init' interactively first."
             ));
         }
+    };
 
-        thoughts.validate_profile(&profile)?;
+    // First setup: with a username, the backend is built from flags and
+    // defaults below instead of requiring an interactive run.
+    let first_setup = !hyprlayer_config
+        .thoughts
+        .as_ref()
+        .is_some_and(ThoughtsConfig::is_thoughts_configured);
+    if first_setup && user.is_none() {
+        return Err(anyhow::anyhow!(
+            "Config is incomplete. Pass --user to finish setup without prompts, or run 'hyprlayer thoughts init' interactively."
+        ));
+    }
+    if let Some(user) = user {
+        hyprlayer_config.thoughts_mut().user = user;
     }
 
+    configured_agent_tool(&config, &current_repo)?;
+
+    let thoughts = hyprlayer_config.thoughts_mut();
+    profile = thoughts.resolve_profile(apply_default_profile(thoughts, profile))?;
+
     let thoughts_dir = current_repo.join("thoughts");
     if thoughts_dir.exists() && !force {
         println!(
@@ -233,6 +393,8 @@ fn init_non_interactive(
     let prior_kind = existing_profile.backend.kind();
     let backend_kind = backend_flag.unwrap_or(prior_kind);
 
+    dir_flags.check(backend_kind)?;
+    git_setup.check(backend_kind)?;
     require_git_repo_for_filesystem_backend(&current_repo, backend_kind)?;
 
     // When the kind is unchanged, preserve existing variant fields so flags
@@ -241,20 +403,12 @@ fn init_non_interactive(
     let new_backend = if backend_kind == prior_kind {
         match backend_kind {
             BackendKind::Git => {
-                let prior = existing_profile.backend.as_git();
-                BackendConfig::Git(GitConfig {
-                    thoughts_repo: prior.map(|g| g.thoughts_repo.clone()).unwrap_or_default(),
-                    repos_dir: prior
-                        .map(|g| g.repos_dir.clone())
-                        .unwrap_or_else(|| "repos".to_string()),
-                    global_dir: prior
-                        .map(|g| g.global_dir.clone())
-                        .unwrap_or_else(|| "global".to_string()),
-                })
+                git_variant_non_interactive(&dir_flags, existing_profile.backend.as_git())?
             }
             BackendKind::Obsidian => obsidian_variant_non_interactive(
                 vault_path_flag,
                 vault_subpath_flag,
+                &dir_flags,
                 existing_profile.backend.as_obsidian(),
             )?,
             BackendKind::Notion => {
@@ -267,14 +421,13 @@ fn init_non_interactive(
         }
     } else {
         match backend_kind {
-            BackendKind::Git => BackendConfig::Git(GitConfig {
-                thoughts_repo: get_default_thoughts_repo()?.display().to_string(),
-                repos_dir: "repos".to_string(),
-                global_dir: "global".to_string(),
-            }),
-            BackendKind::Obsidian => {
-                obsidian_variant_non_interactive(vault_path_flag, vault_subpath_flag, None)?
-            }
+            BackendKind::Git => git_variant_non_interactive(&dir_flags, None)?,
+            BackendKind::Obsidian => obsidian_variant_non_interactive(
+                vault_path_flag,
+                vault_subpath_flag,
+                &dir_flags,
+                None,
+            )?,
             BackendKind::Notion => notion_variant_non_interactive(notion_flags, None)?,
             BackendKind::Anytype => anytype_variant_non_interactive(anytype_flags, None)?,
         }
@@ -282,20 +435,23 @@ fn init_non_interactive(
 
     // A bare `--yes` with no `--backend` defaulting to Git has nothing to
     // write; every other branch either set fields or explicitly re-selected
-    // Git, and needs to persist.
-    if backend_kind != BackendKind::Git || backend_flag.is_some() {
+    // Git, and needs to persist. A first setup always writes the defaults.
+    if backend_kind != BackendKind::Git
+        || backend_flag.is_some()
+        || !dir_flags.is_empty()
+        || first_setup
+    {
         apply_backend(hyprlayer_config.thoughts_mut(), &profile, new_backend);
     }
 
     let resolved = hyprlayer_config.thoughts_mut().resolve_dirs(&profile);
-    let mapped_name = sanitize_directory_name(&directory);
+    let mapped_name = validate_directory_name(&directory)?;
 
     if backend_kind.uses_filesystem() {
         let content_root = resolve_content_root(&resolved.backend)?;
         ensure_content_root(&content_root)?;
 
-        let repos_dir = resolved.backend.filesystem_repos_dir().unwrap_or("repos");
-        let repos_path = content_root.join(repos_dir);
+        let repos_path = repos_dir_path(&resolved.backend)?;
         fs::create_dir_all(&repos_path)?;
 
         let target_dir = repos_path.join(&mapped_name);
@@ -305,17 +461,50 @@ fn init_non_interactive(
     }
 
     let mapping = RepoMapping::new(&mapped_name, &profile);
+    let repo_key = current_repo.display().to_string();
+    warn_if_directory_shared(hyprlayer_config.thoughts_mut(), &repo_key, &mapping);
     hyprlayer_config
         .thoughts_mut()
         .repo_mappings
-        .insert(current_repo.display().to_string(), mapping);
-    hyprlayer_config.save(&config_path)?;
+        .insert(repo_key, mapping);
+    hook_flags.apply(hyprlayer_config.thoughts_mut());
+    save_thoughts(&config, &hyprlayer_config)?;
 
-    dispatch_backend_init(&hyprlayer_config, &current_repo, backend_kind)?;
+    // Set up the backend from what was saved, merged with any base layers.
+    let saved = config.load_if_exists()?.unwrap_or_default();
+    dispatch_backend_init(&saved, &current_repo, backend_kind, git_setup)?;
 
     Ok(())
 }
 
+/// The AI tool for `repo`, from every `--config-file` layer; `init` needs
+/// one to know where to install its commands.
+fn configured_agent_tool(
+    config: &crate::cli::ConfigArgs,
+    repo: &Path,
+) -> Result<crate::agents::AgentTool> {
+    config
+        .load_if_exists()?
+        .unwrap_or_default()
+        .effective_agent_tool_for_repo(Some(&repo.display().to_string()))
+        .copied()
+        .ok_or_else(|| {
+            anyhow::anyhow!("AI tool not configured. Run 'hyprlayer ai configure' first.")
+        })
+}
+
+/// Write `init`'s result back. Only the `thoughts` section is replaced, so
+/// settings another process saved while `init` was prompting survive.
+fn save_thoughts(
+    config: &crate::cli::ConfigArgs,
+    hyprlayer_config: &HyprlayerConfig,
+) -> Result<()> {
+    config.update(|target| {
+        target.thoughts = hyprlayer_config.thoughts.clone();
+        Ok(())
+    })
+}
+
 /// Filesystem backends (git, obsidian) install commit hooks into the working
 /// repo, so they need a real git tree. Notion and Anytype store everything
 /// externally and have no such requirement.
@@ -373,12 +562,14 @@ fn prompt_for_thoughts_fields(
     existing: ThoughtsConfig,
     existing_profile: &ProfileConfig,
     backend_kind: BackendKind,
+    dir_flags: &DirFlags,
     vault_path_flag: Option<String>,
     vault_subpath_flag: Option<String>,
     notion_flags: &NotionFlags,
     anytype_flags: &AnytypeFlags,
     profile: &Option<String>,
     agent_tool: Option<crate::agents::AgentTool>,
+    user_flag: Option<String>,
 ) -> Result<ThoughtsConfig> {
     let theme = ColorfulTheme::default();
 
@@ -390,31 +581,21 @@ fn prompt_for_thoughts_fields(
                 .map(|g| g.thoughts_repo.clone())
                 .filter(|s| !s.is_empty())
                 .unwrap_or(fallback);
-            let repo: String = Input::with_theme(&theme)
-                .with_prompt("Thoughts repository location")
-                .default(default_repo.clone())
-                .allow_empty(true)
-                .interact()
-                .map(|s: String| if s.is_empty() { default_repo } else { s })?;
-
-            println!();
-            let default_repos_dir = prior
-                .map(|g| g.repos_dir.clone())
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| "repos".to_string());
-            let repos_dir: String = Input::with_theme(&theme)
-                .with_prompt("Directory name for repository-specific thoughts")
-                .default(default_repos_dir)
-                .interact()?;
-
-            let default_global_dir = prior
-                .map(|g| g.global_dir.clone())
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| "global".to_string());
-            let global_dir: String = Input::with_theme(&theme)
-                .with_prompt("Directory name for global thoughts")
-                .default(default_global_dir)
-                .interact()?;
+            let repo = match dir_flags.thoughts_repo.clone() {
+                Some(repo) => repo,
+                None => Input::with_theme(&theme)
+                    .with_prompt("Thoughts repository location")
+                    .default(default_repo.clone())
+                    .allow_empty(true)
+                    .interact()
+                    .map(|s: String| if s.is_empty() { default_repo } else { s })?,
+            };
+
+            let (repos_dir, global_dir) = prompt_dir_names(
+                &theme,
+                dir_flags,
+                prior.map(|g| (g.repos_dir.as_str(), g.global_dir.as_str())),
+            )?;
 
             BackendConfig::Git(GitConfig {
                 thoughts_repo: repo,
@@ -441,23 +622,11 @@ fn prompt_for_thoughts_fields(
                     .interact()?,
             };
 
-            println!();
-            let default_repos_dir = prior
-                .map(|o| o.repos_dir.clone())
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| "repos".to_string());
-            let repos_dir: String = Input::with_theme(&theme)
-                .with_prompt("Directory name for repository-specific thoughts")
-                .default(default_repos_dir)
-                .interact()?;
-            let default_global_dir = prior
-                .map(|o| o.global_dir.clone())
-                .filter(|s| !s.is_empty())
-                .unwrap_or_else(|| "global".to_string());
-            let global_dir: String = Input::with_theme(&theme)
-                .with_prompt("Directory name for global thoughts")
-                .default(default_global_dir)
-                .interact()?;
+            let (repos_dir, global_dir) = prompt_dir_names(
+                &theme,
+                dir_flags,
+                prior.map(|o| (o.repos_dir.as_str(), o.global_dir.as_str())),
+            )?;
 
             BackendConfig::Obsidian(ObsidianConfig {
                 vault_path,
@@ -479,20 +648,32 @@ fn prompt_for_thoughts_fields(
         )?),
     };
 
-    let user = prompt_for_username(&theme, &existing.user)?;
+    let user = match user_flag {
+        Some(user) => user,
+        None => prompt_for_username(&theme, &existing.user)?,
+    };
 
     let mut out = ThoughtsConfig {
         user,
         repo_mappings: existing.repo_mappings,
         profiles: existing.profiles,
         backend: existing.backend,
+        default_profile: existing.default_profile,
+        hooks_enabled: existing.hooks_enabled,
+        sync_jitter_max_seconds: existing.sync_jitter_max_seconds,
+        encrypted_files: existing.encrypted_files,
+        skip_secrets_scan: existing.skip_secrets_scan,
+        proxy: existing.proxy,
+        push_with_git_cli: existing.push_with_git_cli,
     };
     match profile.as_ref() {
         Some(name) => {
+            let ai = out.profiles.get(name).and_then(|p| p.ai.clone());
             out.profiles.insert(
                 name.clone(),
                 ProfileConfig {
                     backend: new_backend,
+                    ai,
                 },
             );
         }
@@ -504,6 +685,34 @@ fn prompt_for_thoughts_fields(
     Ok(out)
 }
 
+/// Prompt for the repos and global directory names, skipping the prompt
+/// for any name given by flag. `prior` seeds the defaults.
+fn prompt_dir_names(
+    theme: &ColorfulTheme,
+    flags: &DirFlags,
+    prior: Option<(&str, &str)>,
+) -> Result<(String, String)> {
+    let (default_repos_dir, default_global_dir) = DirFlags::default().dir_names(prior);
+    if flags.repos_dir.is_none() || flags.global_dir.is_none() {
+        println!();
+    }
+    let repos_dir = match flags.repos_dir.clone() {
+        Some(dir) => dir,
+        None => Input::with_theme(theme)
+            .with_prompt("Directory name for repository-specific thoughts")
+            .default(default_repos_dir)
+            .interact()?,
+    };
+    let global_dir = match flags.global_dir.clone() {
+        Some(dir) => dir,
+        None => Input::with_theme(theme)
+            .with_prompt("Directory name for global thoughts")
+            .default(default_global_dir)
+            .interact()?,
+    };
+    Ok((repos_dir, global_dir))
+}
+
 fn prompt_notion_config(
     theme: &ColorfulTheme,
     existing: Option<&NotionConfig>,
@@ -640,6 +849,33 @@ fn prompt_vault_path(theme: &ColorfulTheme, existing: &str) -> Result<String> {
     }
 }
 
+/// Reject usernames that can't name a directory under the repo's thoughts:
+/// empty ones and `global`, which holds cross-project thoughts.
+fn validate_username(user: &str) -> Result<String> {
+    let user = user.trim();
+    if user.is_empty() {
+        return Err(anyhow::anyhow!("Username cannot be empty"));
+    }
+    if user.eq_ignore_ascii_case("global") {
+        return Err(anyhow::anyhow!(
+            "Username cannot be \"global\" as it's reserved for cross-project thoughts."
+        ));
+    }
+    Ok(user.to_string())
+}
+
+/// `--user`, else a non-empty `$HYPRLAYER_USER`, validated. Either one
+/// skips the username prompt.
+fn explicit_username(flag: Option<String>) -> Result<Option<String>> {
+    flag.or_else(|| {
+        std::env::var("HYPRLAYER_USER")
+            .ok()
+            .filter(|u| !u.trim().is_empty())
+    })
+    .map(|user| validate_username(&user))
+    .transpose()
+}
+
 fn prompt_for_username(theme: &ColorfulTheme, existing_user: &str) -> Result<String> {
     let default_user = if existing_user.is_empty() {
         std::env::var("USER")
@@ -655,13 +891,10 @@ fn prompt_for_username(theme: &ColorfulTheme, existing_user: &str) -> Result<Str
             .default(default_user.clone())
             .interact()?;
 
-        if input.to_lowercase() != "global" {
-            return Ok(input);
+        match validate_username(&input) {
+            Ok(user) => return Ok(user),
+            Err(e) => println!("{}", e.to_string().red()),
         }
-        println!(
-            "{}",
-            "Username cannot be \"global\" as it's reserved for cross-project thoughts.".red()
-        );
     }
 }
 
@@ -678,6 +911,17 @@ fn check_existing_setup(current_repo: &Path, force: bool) -> Result<bool> {
     Ok(reconfigure)
 }
 
+/// `backend`'s repos directory, once `resolve_content_root` has accepted
+/// its content root.
+fn repos_dir_path(backend: &BackendConfig) -> Result<PathBuf> {
+    backend.repos_dir_path().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Backend '{}' has no repos directory",
+            backend.kind().as_str()
+        )
+    })
+}
+
 fn resolve_content_root(backend: &BackendConfig) -> Result<PathBuf> {
     match backend {
         BackendConfig::Git(g) => Ok(expand_path(&g.thoughts_repo)),
@@ -714,11 +958,35 @@ fn ensure_content_root(content_root: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Non-interactive git variant: each field comes from its flag, then the
+/// prior value, then the default thoughts repository and `repos` / `global`.
+fn git_variant_non_interactive(
+    flags: &DirFlags,
+    prior: Option<&GitConfig>,
+) -> Result<BackendConfig> {
+    let thoughts_repo = match flags.thoughts_repo.clone().or_else(|| {
+        prior
+            .map(|g| g.thoughts_repo.clone())
+            .filter(|s| !s.is_empty())
+    }) {
+        Some(repo) => repo,
+        None => get_default_thoughts_repo()?.display().to_string(),
+    };
+    let (repos_dir, global_dir) =
+        flags.dir_names(prior.map(|g| (g.repos_dir.as_str(), g.global_dir.as_str())));
+    Ok(BackendConfig::Git(GitConfig {
+        thoughts_repo,
+        repos_dir,
+        global_dir,
+    }))
+}
+
 /// Non-interactive Obsidian variant: `--vault-path` is required (no safe default),
 /// `--vault-subpath` falls back to the prior value then `hyprlayer`.
 fn obsidian_variant_non_interactive(
     vault_path_flag: Option<String>,
     vault_subpath_flag: Option<String>,
+    dir_flags: &DirFlags,
     prior: Option<&ObsidianConfig>,
 ) -> Result<BackendConfig> {
     let vault_path = vault_path_flag
@@ -733,14 +1001,8 @@ fn obsidian_variant_non_interactive(
     let vault_subpath = vault_subpath_flag
         .or_else(|| prior.and_then(|o| o.vault_subpath.clone()))
         .unwrap_or_else(|| "hyprlayer".to_string());
-    let repos_dir = prior
-        .map(|o| o.repos_dir.clone())
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "repos".to_string());
-    let global_dir = prior
-        .map(|o| o.global_dir.clone())
-        .filter(|s| !s.is_empty())
-        .unwrap_or_else(|| "global".to_string());
+    let (repos_dir, global_dir) =
+        dir_flags.dir_names(prior.map(|o| (o.repos_dir.as_str(), o.global_dir.as_str())));
     Ok(BackendConfig::Obsidian(ObsidianConfig {
         vault_path,
         vault_subpath: Some(vault_subpath),
@@ -807,7 +1069,7 @@ fn apply_backend(thoughts: &mut ThoughtsConfig, profile: &Option<String>, backen
             } else {
                 thoughts
                     .profiles
-                    .insert(name.clone(), ProfileConfig { backend });
+                    .insert(name.clone(), ProfileConfig { backend, ai: None });
             }
         }
         None => {
@@ -835,7 +1097,7 @@ fn select_or_create_directory(
 }
 
 fn use_existing_directory(repos_path: &Path, dir: &str) -> Result<String> {
-    let sanitized = sanitize_directory_name(dir);
+    let sanitized = validate_directory_name(dir)?;
     if !repos_path.join(&sanitized).exists() {
         return Err(anyhow::anyhow!(
             "Directory \"{}\" not found in thoughts repository",
@@ -845,6 +1107,84 @@ fn use_existing_directory(repos_path: &Path, dir: &str) -> Result<String> {
     Ok(sanitized)
 }
 
+/// A directory in the thoughts repository's repos dir, for `--list-existing`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExistingDirectory {
+    name: String,
+    /// RFC 3339; `None` where the filesystem doesn't record it.
+    created: Option<String>,
+    modified: Option<String>,
+}
+
+/// `init --list-existing`: the directories a `--directory` could name for
+/// `profile` (or the default profile), without changing anything.
+fn print_existing_directories(
+    config: &crate::cli::ConfigArgs,
+    profile: Option<String>,
+    json: bool,
+) -> Result<()> {
+    let hyprlayer_config = config.load_if_exists()?.unwrap_or_default();
+    let thoughts = hyprlayer_config.thoughts.unwrap_or_default();
+    let profile = thoughts.resolve_profile(thoughts.profile_for_new_mapping(profile))?;
+    let backend = thoughts.resolve_dirs(&profile).backend;
+    let Some(repos_path) = backend.repos_dir_path() else {
+        return Err(anyhow::anyhow!(
+            "The {} backend has no thoughts directories to list",
+            backend.kind().as_str()
+        ));
+    };
+    let directories = existing_directories(&repos_path)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&directories)?);
+        return Ok(());
+    }
+    if directories.is_empty() {
+        println!("No directories in {}", repos_path.display());
+        return Ok(());
+    }
+    let width = directories.iter().map(|d| d.name.len()).max().unwrap_or(0);
+    let time = |t: &Option<String>| {
+        t.as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map_or_else(
+                || "-".to_string(),
+                |t| t.format("%Y-%m-%d %H:%M").to_string(),
+            )
+    };
+    for dir in &directories {
+        println!(
+            "{:<width$}  created {}  modified {}",
+            dir.name,
+            time(&dir.created),
+            time(&dir.modified)
+        );
+    }
+    Ok(())
+}
+
+/// Every non-hidden directory in `repos_path`, sorted by name.
+fn existing_directories(repos_path: &Path) -> Result<Vec<ExistingDirectory>> {
+    let rfc3339 = |t: std::io::Result<std::time::SystemTime>| {
+        t.ok()
+            .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+    };
+    let mut directories: Vec<ExistingDirectory> = list_existing_repos(repos_path)?
+        .into_iter()
+        .map(|name| {
+            let metadata = fs::metadata(repos_path.join(&name)).ok();
+            ExistingDirectory {
+                created: metadata.as_ref().and_then(|m| rfc3339(m.created())),
+                modified: metadata.as_ref().and_then(|m| rfc3339(m.modified())),
+                name,
+            }
+        })
+        .collect();
+    directories.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(directories)
+}
+
 fn list_existing_repos(repos_path: &Path) -> Result<Vec<String>> {
     if !repos_path.exists() {
         return Ok(Vec::new());
@@ -867,7 +1207,7 @@ fn prompt_for_new_directory(current_repo: &Path) -> Result<String> {
         .default(default_name)
         .interact()?;
 
-    Ok(sanitize_directory_name(&input))
+    validate_directory_name(&input)
 }
 
 fn select_or_create_from_existing(
@@ -893,31 +1233,81 @@ fn select_or_create_from_existing(
     }
 }
 
-fn dispatch_backend_init(
+pub(crate) fn dispatch_backend_init(
     config: &HyprlayerConfig,
     current_repo: &Path,
     backend_kind: BackendKind,
+    git_setup: GitSetup,
 ) -> Result<()> {
+    git_setup.check(backend_kind)?;
+
     let current_repo_str = current_repo.display().to_string();
-    let effective = config
+    let thoughts = config
         .thoughts
         .as_ref()
-        .expect("thoughts config must exist here")
-        .effective_config_for(&current_repo_str);
-
-    let agent_tool = config.ai.as_ref().and_then(|a| a.agent_tool);
-    let ctx = BackendContext::new(current_repo, &effective).with_agent_tool(agent_tool);
+        .expect("thoughts config must exist here");
+    let effective = thoughts.effective_config_for(&current_repo_str);
+
+    let agent_tool = config
+        .effective_agent_tool_for_repo(Some(&current_repo_str))
+        .copied();
+    let ctx = BackendContext::new(current_repo, &effective)
+        .with_agent_tool(agent_tool)
+        .with_git_setup(git_setup)
+        .with_hooks(thoughts.hooks_enabled)
+        .with_sync_jitter(thoughts.sync_jitter_max_seconds.unwrap_or(0));
     let backend_impl = backends::for_kind(backend_kind);
     backend_impl.init(&ctx)?;
 
+    if !thoughts.hooks_enabled {
+        println!(
+            "{}",
+            "Git hooks not installed. Run 'hyprlayer thoughts init --force --hooks' to install them later."
+                .bright_black()
+        );
+    } else if backend_kind == BackendKind::Git {
+        println!(
+            "{}",
+            format!(
+                "Thoughts sync after every commit. Set {}=1 to skip that, e.g. during a rebase.",
+                crate::hooks::NO_SYNC_ENV
+            )
+            .bright_black()
+        );
+    }
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git_ops::tests::repo_with_identity;
     use tempfile::tempdir;
 
+    #[test]
+    fn existing_directories_lists_visible_dirs_by_name() {
+        let tmp = tempdir().unwrap();
+        for dir in ["zeta", "alpha", ".hidden"] {
+            fs::create_dir(tmp.path().join(dir)).unwrap();
+        }
+        fs::write(tmp.path().join("notes.md"), "x").unwrap();
+
+        let dirs = existing_directories(tmp.path()).unwrap();
+        let names: Vec<&str> = dirs.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["alpha", "zeta"]);
+        assert!(dirs[0].modified.is_some());
+
+        let json = serde_json::to_value(&dirs).unwrap();
+        assert_eq!(json[0]["name"], "alpha");
+        assert!(json[0].get("created").is_some());
+        assert!(
+            existing_directories(&tmp.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
     #[test]
     fn require_git_repo_passes_for_notion_outside_git() {
         let tmp = tempdir().unwrap();
@@ -939,10 +1329,229 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_username_rejects_empty_and_global() {
+        assert_eq!(validate_username(" alice ").unwrap(), "alice");
+        assert!(validate_username("  ").is_err());
+        assert!(validate_username("Global").is_err());
+        assert_eq!(
+            explicit_username(Some("bob".to_string())).unwrap(),
+            Some("bob".to_string())
+        );
+        assert!(explicit_username(Some("global".to_string())).is_err());
+    }
+
+    #[test]
+    fn yes_with_user_completes_first_setup_without_prompts() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        fs::create_dir_all(&vault).unwrap();
+        let code = tmp.path().join("app");
+        GitRepo::init(&code, None).unwrap();
+
+        // Only `hyprlayer ai configure` has run so far.
+        let config_path = tmp.path().join("config.json");
+        let mut seeded = HyprlayerConfig::default();
+        seeded.ai_mut().agent_tools = vec![crate::agents::AgentTool::Claude];
+        seeded.save(&config_path).unwrap();
+        let config = crate::cli::ConfigArgs {
+            config_file: vec![config_path.display().to_string()],
+            overrides: Vec::new(),
+        };
+
+        let run = |user: Option<&str>| {
+            init_non_interactive(
+                config.clone(),
+                code.clone(),
+                Some("app".to_string()),
+                user.map(str::to_string),
+                None,
+                Some(BackendKind::Obsidian),
+                DirFlags::default(),
+                Some(vault.display().to_string()),
+                None,
+                NotionFlags::default(),
+                AnytypeFlags::default(),
+                GitSetup::default(),
+                HookFlags {
+                    enabled: Some(false),
+                    ..Default::default()
+                },
+                false,
+            )
+        };
+        let err = run(None).unwrap_err();
+        assert!(err.to_string().contains("--user"));
+
+        run(Some("alice")).unwrap();
+        let saved = HyprlayerConfig::load(&config_path).unwrap();
+        let thoughts = saved.thoughts.unwrap();
+        assert_eq!(thoughts.user, "alice");
+        assert!(thoughts.is_thoughts_configured());
+        assert!(code.join("thoughts").join("alice").exists());
+    }
+
+    #[test]
+    fn yes_with_dir_flags_sets_up_git_backend_without_prompts() {
+        let tmp = tempdir().unwrap();
+        let code = tmp.path().join("app");
+        GitRepo::init(&code, None).unwrap();
+        // Pre-create the repository with a local identity for the initial
+        // commit.
+        let thoughts_repo = tmp.path().join("notes");
+        repo_with_identity(&thoughts_repo, None);
+
+        let config_path = tmp.path().join("config.json");
+        let mut seeded = HyprlayerConfig::default();
+        seeded.ai_mut().agent_tools = vec![crate::agents::AgentTool::Claude];
+        seeded.save(&config_path).unwrap();
+
+        let args = InitArgs {
+            force: false,
+            directory: Some("app".to_string()),
+            user: Some("alice".to_string()),
+            profile: None,
+            backend: None,
+            thoughts_repo: Some(thoughts_repo.display().to_string()),
+            repos_dir: Some("projects".to_string()),
+            global_dir: Some("shared".to_string()),
+            vault_path: None,
+            vault_subpath: None,
+            parent_page_id: None,
+            database_id: None,
+            space_id: None,
+            type_id: None,
+            api_token_env: None,
+            remote: None,
+            branch: None,
+            no_hooks: true,
+            hooks: false,
+            sync_jitter: None,
+            yes: true,
+            list_existing: false,
+            json: false,
+            config: crate::cli::ConfigArgs {
+                config_file: vec![config_path.display().to_string()],
+                overrides: Vec::new(),
+            },
+        };
+        crate::commands::storage::test_util::with_cwd(&code, || init(args).unwrap());
+
+        let saved = HyprlayerConfig::load(&config_path).unwrap();
+        let git = saved.thoughts.unwrap().backend.as_git().cloned().unwrap();
+        assert_eq!(git.thoughts_repo, thoughts_repo.display().to_string());
+        assert_eq!(git.repos_dir, "projects");
+        assert_eq!(git.global_dir, "shared");
+        assert!(thoughts_repo.join("projects").join("app").is_dir());
+        assert!(thoughts_repo.join("shared").is_dir());
+        assert!(code.join("thoughts").join("alice").exists());
+        assert!(code.join("thoughts").join("shared").exists());
+        assert!(code.join("thoughts").join("global").exists());
+    }
+
+    #[test]
+    fn git_setup_is_checked_against_the_backend() {
+        let setup = GitSetup {
+            remote: Some("git@example.com:me/thoughts.git".to_string()),
+            branch: None,
+        };
+        setup.check(BackendKind::Git).unwrap();
+        let err = setup.check(BackendKind::Obsidian).unwrap_err();
+        assert!(err.to_string().contains("only apply to the git backend"));
+        GitSetup::default().check(BackendKind::Notion).unwrap();
+    }
+
+    #[test]
+    fn dir_flags_are_checked_against_the_backend() {
+        let flags = DirFlags {
+            thoughts_repo: Some("~/notes".to_string()),
+            ..Default::default()
+        };
+        flags.check(BackendKind::Git).unwrap();
+        assert!(flags.check(BackendKind::Obsidian).is_err());
+
+        let flags = DirFlags {
+            repos_dir: Some("projects".to_string()),
+            ..Default::default()
+        };
+        flags.check(BackendKind::Obsidian).unwrap();
+        assert!(flags.check(BackendKind::Notion).is_err());
+        assert_eq!(
+            flags.dir_names(Some(("old", "everyone"))),
+            ("projects".to_string(), "everyone".to_string())
+        );
+        assert_eq!(
+            DirFlags::default().dir_names(None),
+            ("repos".to_string(), "global".to_string())
+        );
+    }
+
+    #[test]
+    fn hook_flags_store_sync_jitter() {
+        let mut thoughts = ThoughtsConfig::default();
+        let flags = |sync_jitter| HookFlags {
+            enabled: None,
+            sync_jitter,
+        };
+
+        flags(Some(30)).apply(&mut thoughts);
+        assert_eq!(thoughts.sync_jitter_max_seconds, Some(30));
+        flags(None).apply(&mut thoughts);
+        assert_eq!(thoughts.sync_jitter_max_seconds, Some(30));
+        assert!(thoughts.hooks_enabled);
+        flags(Some(0)).apply(&mut thoughts);
+        assert_eq!(thoughts.sync_jitter_max_seconds, None);
+    }
+
+    #[test]
+    fn no_hooks_leaves_git_hooks_dir_untouched() {
+        let tmp = tempdir().unwrap();
+        let vault = tmp.path().join("vault");
+        fs::create_dir_all(&vault).unwrap();
+        let code = tmp.path().join("app");
+        GitRepo::init(&code, None).unwrap();
+
+        let mut config = HyprlayerConfig::default();
+        let thoughts = config.thoughts_mut();
+        thoughts.user = "alice".to_string();
+        thoughts.backend = BackendConfig::Obsidian(ObsidianConfig {
+            vault_path: vault.display().to_string(),
+            vault_subpath: None,
+            repos_dir: "repos".to_string(),
+            global_dir: "global".to_string(),
+        });
+        thoughts
+            .repo_mappings
+            .insert(code.display().to_string(), RepoMapping::new("app", &None));
+        thoughts.hooks_enabled = false;
+
+        let pre_commit = code.join(".git").join("hooks").join("pre-commit");
+        dispatch_backend_init(&config, &code, BackendKind::Obsidian, GitSetup::default()).unwrap();
+        assert!(code.join("thoughts").exists());
+        assert!(!pre_commit.exists());
+
+        config.thoughts_mut().hooks_enabled = true;
+        dispatch_backend_init(&config, &code, BackendKind::Obsidian, GitSetup::default()).unwrap();
+        assert!(pre_commit.exists());
+    }
+
+    #[test]
+    fn use_existing_directory_rejects_a_name_that_slugifies_to_nothing() {
+        let tmp = tempdir().unwrap();
+        fs::create_dir(tmp.path().join("notes")).unwrap();
+        assert_eq!(
+            use_existing_directory(tmp.path(), "notes").unwrap(),
+            "notes"
+        );
+        // The empty slug would otherwise name the repos dir itself.
+        let err = use_existing_directory(tmp.path(), "日本").unwrap_err();
+        assert!(err.to_string().contains("no usable characters"));
+    }
+
     #[test]
     fn require_git_repo_passes_for_filesystem_backend_inside_git() {
         let tmp = tempdir().unwrap();
-        GitRepo::init(tmp.path()).unwrap();
+        GitRepo::init(tmp.path(), None).unwrap();
         require_git_repo_for_filesystem_backend(tmp.path(), BackendKind::Git).unwrap();
         require_git_repo_for_filesystem_backend(tmp.path(), BackendKind::Obsidian).unwrap();
     }
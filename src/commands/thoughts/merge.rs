@@ -0,0 +1,153 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::MergeArgs;
+
+pub fn merge(args: MergeArgs) -> Result<()> {
+    let MergeArgs {
+        branch,
+        squash,
+        message,
+        config,
+    } = args;
+
+    let (thoughts_repo, repo) = config.load_git_thoughts_repo("thoughts merge")?;
+
+    let result = if squash {
+        repo.merge_squash(&branch, message.as_deref())?
+    } else {
+        repo.merge(&branch, message.as_deref())?
+    };
+
+    if !result.conflicts.is_empty() {
+        println!("{}", "Conflicting files:".red());
+        for file in &result.conflicts {
+            println!("  {}", file.yellow());
+        }
+        println!();
+        println!("To resolve manually:");
+        println!("  cd {}", thoughts_repo.display());
+        println!("  # fix the files, then stage them with: git add <file>");
+        println!("  git commit");
+        println!("{}", "Or give up with: git merge --abort".bright_black());
+        return Err(anyhow::anyhow!("Merge of {} has conflicts", branch));
+    }
+
+    match result.new_commit {
+        None => println!("{}", format!("Already up to date with {}", branch).green()),
+        Some(_) if result.fast_forwarded => {
+            println!("{}", format!("Fast-forwarded to {}", branch).green())
+        }
+        Some(_) => println!("{}", format!("Merged {}", branch).green()),
+    }
+    if result.new_commit.is_some() {
+        println!("  Commit: {}", repo.get_last_commit()?.cyan());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git_ops::GitRepo;
+    use crate::git_ops::tests::repo_with_identity;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    /// A repository on `main` with `note.md` committed, plus a `topic`
+    /// branch that changes it to `topic_content`.
+    fn repo_with_topic(dir: &Path, topic_content: &str) -> GitRepo {
+        let repo = repo_with_identity(dir, Some("main"));
+        fs::write(dir.join("note.md"), "one\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "-qm", "base"]);
+        git(dir, &["checkout", "-qb", "topic"]);
+        fs::write(dir.join("note.md"), topic_content).unwrap();
+        git(dir, &["commit", "-qam", "topic change"]);
+        git(dir, &["checkout", "-q", "main"]);
+        repo
+    }
+
+    #[test]
+    fn merge_fast_forwards_then_reports_up_to_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_topic(dir.path(), "one\ntwo\n");
+        let topic = git(dir.path(), &["rev-parse", "topic"]);
+
+        let result = repo.merge("topic", None).unwrap();
+        assert!(result.fast_forwarded);
+        assert_eq!(result.new_commit.as_deref(), Some(topic.as_str()));
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), topic);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("note.md")).unwrap(),
+            "one\ntwo\n"
+        );
+
+        assert_eq!(repo.merge("topic", None).unwrap(), Default::default());
+    }
+
+    #[test]
+    fn merge_commits_diverged_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_topic(dir.path(), "one\ntwo\n");
+        fs::write(dir.path().join("other.md"), "other\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-qm", "other"]);
+
+        let result = repo.merge("topic", None).unwrap();
+        assert!(!result.fast_forwarded);
+        assert!(result.conflicts.is_empty());
+        let parents = git(dir.path(), &["log", "-1", "--format=%P"]);
+        assert_eq!(parents.split(' ').count(), 2);
+        assert_eq!(
+            git(dir.path(), &["log", "-1", "--format=%s"]),
+            "Merge branch 'topic'"
+        );
+        assert_eq!(git(dir.path(), &["status", "--porcelain"]), "");
+    }
+
+    #[test]
+    fn merge_squash_records_single_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_topic(dir.path(), "one\ntwo\n");
+
+        let result = repo.merge_squash("topic", Some("Squash topic")).unwrap();
+        assert!(!result.fast_forwarded);
+        assert!(result.new_commit.is_some());
+        let parents = git(dir.path(), &["log", "-1", "--format=%P"]);
+        assert_eq!(parents.split(' ').count(), 1);
+        assert_eq!(
+            git(dir.path(), &["log", "-1", "--format=%s"]),
+            "Squash topic"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join("note.md")).unwrap(),
+            "one\ntwo\n"
+        );
+    }
+
+    #[test]
+    fn merge_reports_conflicts_without_committing() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_topic(dir.path(), "uno\n");
+        fs::write(dir.path().join("note.md"), "eins\n").unwrap();
+        git(dir.path(), &["commit", "-qam", "German"]);
+        let head = git(dir.path(), &["rev-parse", "HEAD"]);
+
+        let result = repo.merge("topic", None).unwrap();
+        assert_eq!(result.conflicts, vec!["note.md".to_string()]);
+        assert_eq!(result.new_commit, None);
+        assert_eq!(git(dir.path(), &["rev-parse", "HEAD"]), head);
+        assert!(dir.path().join(".git/MERGE_HEAD").exists());
+    }
+}
@@ -1,7 +1,22 @@
+pub mod apply_patch;
+pub mod audit;
 pub mod backend_display;
 pub mod config_cmd;
+pub mod diff;
+pub mod encrypt;
+pub mod export;
+pub mod grep;
+pub mod import;
+pub mod index;
 pub mod init;
+pub mod merge;
+pub mod open;
 pub mod profile;
+pub mod rebase;
+pub mod search;
 pub mod status;
 pub mod sync;
+pub mod tui;
 pub mod uninit;
+pub mod use_profile;
+pub mod worktree;
@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+use std::process::Command;
+
+use super::config_cmd::editor;
+use crate::cli::{OpenArgs, OpenScope};
+use crate::config::{BackendConfig, get_current_repo_path};
+
+pub fn open(args: OpenArgs) -> Result<()> {
+    let OpenArgs {
+        scope,
+        profile,
+        file_manager,
+        config,
+    } = args;
+
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let current_repo = get_current_repo_path()?;
+    let effective = thoughts_config.effective_config_for(&current_repo.display().to_string());
+    let backend = match thoughts_config.resolve_profile(profile)? {
+        Some(name) => &thoughts_config.profiles[&name].backend,
+        None => &effective.backend,
+    };
+
+    let path = scope_path(
+        backend,
+        scope,
+        &effective.user,
+        effective.mapped_name.as_deref(),
+    )?;
+    if !path.is_dir() {
+        return Err(anyhow::anyhow!(
+            "No thoughts directory at {}. Run 'hyprlayer thoughts init' first.",
+            path.display()
+        ));
+    }
+
+    println!("Opening {}", path.display().to_string().cyan());
+    if file_manager {
+        // File managers return immediately (explorer.exe even exits
+        // non-zero on success), so only a failure to start is an error.
+        let program = file_manager_program();
+        Command::new(program)
+            .arg(&path)
+            .spawn()
+            .with_context(|| format!("Failed to run {program}"))?;
+        return Ok(());
+    }
+
+    let editor = editor();
+    let status = Command::new(&editor)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run {editor}"))?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("{} exited with {}", editor, status));
+    }
+    Ok(())
+}
+
+/// The real directory behind a `thoughts/` scope: `user` and `shared` are
+/// the repo's folders under the repos dir, `global` the global dir and
+/// `all` the thoughts root itself.
+fn scope_path(
+    backend: &BackendConfig,
+    scope: OpenScope,
+    user: &str,
+    mapped_name: Option<&str>,
+) -> Result<PathBuf> {
+    let (Some(root), Some(repos_dir), Some(global_dir)) = (
+        backend.content_root(),
+        backend.filesystem_repos_dir(),
+        backend.filesystem_global_dir(),
+    ) else {
+        return Err(anyhow::anyhow!(
+            "The {} backend stores thoughts remotely; there is no directory to open",
+            backend.kind().as_str()
+        ));
+    };
+
+    let repo_dir = || {
+        mapped_name
+            .map(|mapped| root.join(repos_dir).join(mapped))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "This repository has no thoughts. Run 'hyprlayer thoughts init' first, or use --scope global or --scope all."
+                )
+            })
+    };
+    Ok(match scope {
+        OpenScope::User => repo_dir()?.join(user),
+        OpenScope::Shared => repo_dir()?.join("shared"),
+        OpenScope::Global => root.join(global_dir),
+        OpenScope::All => root,
+    })
+}
+
+fn file_manager_program() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer.exe"
+    } else {
+        "xdg-open"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{GitConfig, NotionConfig};
+    use std::path::Path;
+
+    #[test]
+    fn scope_path_resolves_each_scope_under_the_thoughts_repo() {
+        let backend = BackendConfig::Git(GitConfig {
+            thoughts_repo: "/thoughts".to_string(),
+            repos_dir: "repos".to_string(),
+            global_dir: "global".to_string(),
+        });
+        let path = |scope| scope_path(&backend, scope, "alice", Some("app")).unwrap();
+
+        assert_eq!(
+            path(OpenScope::User),
+            Path::new("/thoughts/repos/app/alice")
+        );
+        assert_eq!(
+            path(OpenScope::Shared),
+            Path::new("/thoughts/repos/app/shared")
+        );
+        assert_eq!(path(OpenScope::Global), Path::new("/thoughts/global"));
+        assert_eq!(path(OpenScope::All), Path::new("/thoughts"));
+
+        assert!(scope_path(&backend, OpenScope::User, "alice", None).is_err());
+        assert_eq!(
+            scope_path(&backend, OpenScope::Global, "alice", None).unwrap(),
+            Path::new("/thoughts/global")
+        );
+    }
+
+    #[test]
+    fn scope_path_rejects_remote_backends() {
+        let backend = BackendConfig::Notion(NotionConfig::default());
+        let err = scope_path(&backend, OpenScope::All, "alice", Some("app")).unwrap_err();
+        assert!(err.to_string().contains("remotely"));
+    }
+}
@@ -1,54 +1,325 @@
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::Input;
 use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input};
 use std::fs;
-use std::path::MAIN_SEPARATOR_STR as SEP;
+use std::io::IsTerminal;
+use std::path::{MAIN_SEPARATOR_STR as SEP, Path};
 
 use crate::cli::ProfileCreateArgs;
+use crate::commands::thoughts::profile::list::profile_entry;
 use crate::config::{
-    BackendConfig, GitConfig, HyprlayerConfig, ProfileConfig, expand_path,
-    get_default_thoughts_repo, sanitize_directory_name,
+    BackendConfig, GitConfig, HyprlayerConfig, ProfileConfig, RepoOverlap, ThoughtsConfig,
+    expand_path, get_default_thoughts_repo,
 };
 use crate::git_ops::GitRepo;
 
-fn prompt_for_profile_config(profile_name: &str) -> Result<(String, String, String)> {
+/// Prefilled answers for the create prompts, copied from `--from` /
+/// `--from-default` or the built-in defaults.
+#[derive(Debug, PartialEq)]
+struct ProfileDefaults {
+    thoughts_repo: Option<String>,
+    repos_dir: String,
+    global_dir: String,
+}
+
+impl ProfileDefaults {
+    fn builtin(profile_name: &str) -> Result<Self> {
+        Ok(Self {
+            thoughts_repo: Some(format!(
+                "{}{SEP}{}",
+                get_default_thoughts_repo()?.display(),
+                profile_name
+            )),
+            repos_dir: "repos".to_string(),
+            global_dir: "global".to_string(),
+        })
+    }
+
+    /// Copy `reposDir`/`globalDir` from `source`. For git sources the repo
+    /// path becomes `<source repo's parent>/<profile_name>`.
+    fn from_backend(source: &BackendConfig, label: &str, profile_name: &str) -> Result<Self> {
+        match source {
+            BackendConfig::Git(g) => Ok(Self {
+                thoughts_repo: Path::new(&g.thoughts_repo)
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .map(|p| format!("{}{SEP}{}", p.display(), profile_name)),
+                repos_dir: g.repos_dir.clone(),
+                global_dir: g.global_dir.clone(),
+            }),
+            BackendConfig::Obsidian(o) => Ok(Self {
+                thoughts_repo: None,
+                repos_dir: o.repos_dir.clone(),
+                global_dir: o.global_dir.clone(),
+            }),
+            BackendConfig::Notion(_) | BackendConfig::Anytype(_) => Err(anyhow::anyhow!(
+                "Cannot copy settings from {}: the {} backend has no directories",
+                label,
+                source.kind()
+            )),
+        }
+    }
+}
+
+/// Resolve the `--from` / `--from-default` source, if any.
+fn source_defaults(
+    thoughts: &ThoughtsConfig,
+    from: Option<&str>,
+    from_default: bool,
+    profile_name: &str,
+) -> Result<Option<ProfileDefaults>> {
+    if from_default {
+        return ProfileDefaults::from_backend(&thoughts.backend, "the default", profile_name)
+            .map(Some);
+    }
+    let Some(from) = from else {
+        return Ok(None);
+    };
+    let from = thoughts.resolve_profile_name(from)?;
+    let source = &thoughts.profiles[&from];
+    ProfileDefaults::from_backend(
+        &source.backend,
+        &format!("profile \"{from}\""),
+        profile_name,
+    )
+    .map(Some)
+}
+
+/// Fill the settings that weren't given as flags from `source`, without
+/// prompting. Errors naming every flag that is still missing.
+fn resolve_without_prompts(
+    repo: Option<String>,
+    repos_dir: Option<String>,
+    global_dir: Option<String>,
+    source: Option<ProfileDefaults>,
+) -> Result<(String, String, String)> {
+    let (source_repo, source_repos_dir, source_global_dir) = match source {
+        Some(s) => (s.thoughts_repo, Some(s.repos_dir), Some(s.global_dir)),
+        None => (None, None, None),
+    };
+    let repo = repo.or(source_repo);
+    let repos_dir = repos_dir.or(source_repos_dir);
+    let global_dir = global_dir.or(source_global_dir);
+    match (repo, repos_dir, global_dir) {
+        (Some(repo), Some(repos_dir), Some(global_dir)) => Ok((repo, repos_dir, global_dir)),
+        (repo, repos_dir, global_dir) => {
+            let missing: Vec<&str> = [
+                ("--repo", repo.is_none()),
+                ("--repos-dir", repos_dir.is_none()),
+                ("--global-dir", global_dir.is_none()),
+            ]
+            .into_iter()
+            .filter_map(|(flag, missing)| missing.then_some(flag))
+            .collect();
+            Err(anyhow::anyhow!(
+                "Missing required values: {}",
+                missing.join(", ")
+            ))
+        }
+    }
+}
+
+/// Echo a setting that was given as a flag, in place of its prompt.
+fn echo_provided(label: &str, value: &str) {
+    println!("{} {}", format!("{label}:").bright_black(), value.cyan());
+}
+
+/// Prompt for whichever of the three settings weren't given as flags,
+/// offering `defaults` as the prefilled answers.
+fn prompt_for_profile_config(
+    repo: Option<String>,
+    repos_dir: Option<String>,
+    global_dir: Option<String>,
+    defaults: ProfileDefaults,
+) -> Result<(String, String, String)> {
     let theme = ColorfulTheme::default();
 
-    let default_repo = format!(
-        "{}{SEP}{}",
-        get_default_thoughts_repo()?.display(),
-        profile_name
-    );
-    let thoughts_repo: String = Input::with_theme(&theme)
-        .with_prompt("Thoughts repository")
-        .default(default_repo.clone())
-        .allow_empty(true)
-        .interact()
-        .map(|s: String| if s.is_empty() { default_repo } else { s })?;
+    let thoughts_repo = match repo {
+        Some(r) => {
+            echo_provided("Thoughts repository", &r);
+            r
+        }
+        None => {
+            let mut input = Input::with_theme(&theme).with_prompt("Thoughts repository");
+            if let Some(default_repo) = defaults.thoughts_repo {
+                input = input.default(default_repo);
+            }
+            input.interact()?
+        }
+    };
 
     println!();
-    let repos_dir: String = Input::with_theme(&theme)
-        .with_prompt("Repository-specific thoughts directory")
-        .default("repos".to_string())
-        .interact()?;
+    let repos_dir = match repos_dir {
+        Some(d) => {
+            echo_provided("Repository-specific thoughts directory", &d);
+            d
+        }
+        None => Input::with_theme(&theme)
+            .with_prompt("Repository-specific thoughts directory")
+            .default(defaults.repos_dir)
+            .interact()?,
+    };
 
-    let global_dir: String = Input::with_theme(&theme)
-        .with_prompt("Global thoughts directory")
-        .default("global".to_string())
-        .interact()?;
+    let global_dir = match global_dir {
+        Some(d) => {
+            echo_provided("Global thoughts directory", &d);
+            d
+        }
+        None => Input::with_theme(&theme)
+            .with_prompt("Global thoughts directory")
+            .default(defaults.global_dir)
+            .interact()?,
+    };
 
     Ok((thoughts_repo, repos_dir, global_dir))
 }
 
+/// Refuse a thoughts repository that is exactly the default config's or
+/// another profile's (unless `allow_shared`), and warn when it is nested
+/// inside one or contains one. `profile_name` is excluded from the
+/// comparison so editing a profile doesn't collide with itself.
+pub fn check_repo_collisions(
+    thoughts: &ThoughtsConfig,
+    thoughts_repo: &str,
+    profile_name: &str,
+    allow_shared: bool,
+) -> Result<()> {
+    let overlaps = thoughts.repo_overlaps(&expand_path(thoughts_repo), Some(profile_name));
+    for (owner, overlap) in &overlaps {
+        match overlap {
+            RepoOverlap::Same if !allow_shared => {
+                return Err(anyhow::anyhow!(
+                    "Thoughts repository {} is already used by {}. \
+                     Pass --allow-shared-repo to share it (with a different reposDir).",
+                    thoughts_repo,
+                    owner
+                ));
+            }
+            RepoOverlap::Same => println!(
+                "{}",
+                format!("Sharing thoughts repository with {owner}").yellow()
+            ),
+            RepoOverlap::Nested => println!(
+                "{}",
+                format!(
+                    "Warning: {thoughts_repo} is nested with the thoughts repository of {owner}"
+                )
+                .yellow()
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Remote URLs compare equal regardless of a trailing `/` or `.git`.
+fn same_remote(a: &str, b: &str) -> bool {
+    fn normalize(url: &str) -> &str {
+        let url = url.trim_end_matches('/');
+        url.strip_suffix(".git").unwrap_or(url)
+    }
+    normalize(a) == normalize(b)
+}
+
+/// `--remote`: clone `url` into `repo` when it is missing or empty, reuse
+/// it when it is already a clone of `url`, and refuse anything else.
+fn clone_or_reuse(repo: &Path, url: &str) -> Result<()> {
+    let is_empty = fs::read_dir(repo).map_or(true, |mut entries| entries.next().is_none());
+    if is_empty {
+        println!("Cloning {} into {}...", url.cyan(), repo.display());
+        if let Some(parent) = repo.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        GitRepo::clone_with_progress(url, repo)?;
+        return Ok(());
+    }
+
+    let existing = GitRepo::open(repo).ok().and_then(|r| r.remote_url());
+    match existing {
+        Some(existing) if same_remote(&existing, url) => {
+            println!(
+                "{}",
+                format!("Using existing clone of {} at {}", url, repo.display()).green()
+            );
+            Ok(())
+        }
+        Some(existing) => Err(anyhow::anyhow!(
+            "{} is a clone of {}, not {}",
+            repo.display(),
+            existing,
+            url
+        )),
+        None => Err(anyhow::anyhow!(
+            "{} already exists and is not a clone of {}; choose another --repo",
+            repo.display(),
+            url
+        )),
+    }
+}
+
+/// Check that a cloned repository has the profile's directories. Missing
+/// ones are created, committed and pushed when `interactive` and the user
+/// agrees; otherwise they're only reported.
+fn ensure_profile_dirs(repo: &Path, dirs: &[&str], interactive: bool) -> Result<()> {
+    let missing: Vec<&str> = dirs
+        .iter()
+        .copied()
+        .filter(|dir| !repo.join(dir).is_dir())
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let list = missing.join(", ");
+    println!(
+        "{}",
+        format!("The cloned repository has no {list} directory").yellow()
+    );
+    if !interactive
+        || !Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Create {list} and push them?"))
+            .default(true)
+            .interact()?
+    {
+        println!(
+            "{}",
+            "They will be created locally the first time the profile is used.".bright_black()
+        );
+        return Ok(());
+    }
+
+    for dir in &missing {
+        fs::create_dir_all(repo.join(dir))?;
+        fs::write(repo.join(dir).join(".gitkeep"), "")?;
+    }
+    let git = GitRepo::open(repo)?;
+    git.add_all()?;
+    git.commit(&format!("Add {list} directories"))?;
+    match git.push() {
+        Ok(()) => println!("{}", format!("Pushed {list} to the remote").green()),
+        Err(e) => println!(
+            "{}",
+            format!("Committed {list} locally but could not push: {e}").yellow()
+        ),
+    }
+    Ok(())
+}
+
 pub fn create(args: ProfileCreateArgs) -> Result<()> {
     let ProfileCreateArgs {
         name: profile_name,
         repo,
         repos_dir,
         global_dir,
+        from,
+        from_default,
+        remote,
+        allow_shared_repo,
+        non_interactive,
+        json,
         config,
     } = args;
+    config.reject_overrides("thoughts profile create")?;
     let config_path = config.path()?;
 
     if !config_path.exists() {
@@ -57,13 +328,13 @@ pub fn create(args: ProfileCreateArgs) -> Result<()> {
         ));
     }
 
-    let mut hyprlayer_config = HyprlayerConfig::load(&config_path)?;
+    let hyprlayer_config = HyprlayerConfig::load(&config_path)?;
     let thoughts = hyprlayer_config
         .thoughts
-        .as_mut()
+        .as_ref()
         .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
 
-    let sanitized_name = sanitize_directory_name(&profile_name);
+    let sanitized_name = thoughts.check_new_profile_name(&profile_name, None)?;
     if sanitized_name != profile_name {
         println!(
             "{}",
@@ -75,34 +346,232 @@ pub fn create(args: ProfileCreateArgs) -> Result<()> {
         );
     }
 
-    if thoughts.profiles.contains_key(&sanitized_name) {
-        return Err(anyhow::anyhow!(
-            "Profile \"{}\" already exists",
-            sanitized_name
-        ));
-    }
-
-    let (thoughts_repo, repos_dir, global_dir) = match (repo, repos_dir, global_dir) {
-        (Some(r), Some(rd), Some(gd)) => (r, rd, gd),
-        _ => prompt_for_profile_config(&sanitized_name)?,
+    let source = source_defaults(thoughts, from.as_deref(), from_default, &sanitized_name)?;
+    // Explicit flags always win over copied settings, and only the missing
+    // settings are prompted for. With --non-interactive, or without a
+    // terminal when copying, the copied settings are used as-is instead.
+    let all_given = repo.is_some() && repos_dir.is_some() && global_dir.is_some();
+    let skip_prompts =
+        all_given || non_interactive || (source.is_some() && !std::io::stdin().is_terminal());
+    let (thoughts_repo, repos_dir, global_dir) = if skip_prompts {
+        resolve_without_prompts(repo, repos_dir, global_dir, source)?
+    } else {
+        let defaults = match source {
+            Some(source) => source,
+            None => ProfileDefaults::builtin(&sanitized_name)?,
+        };
+        prompt_for_profile_config(repo, repos_dir, global_dir, defaults)?
     };
 
     let profile = ProfileConfig {
         backend: BackendConfig::Git(GitConfig {
             thoughts_repo: thoughts_repo.clone(),
-            repos_dir,
-            global_dir,
+            repos_dir: repos_dir.clone(),
+            global_dir: global_dir.clone(),
         }),
+        ai: None,
     };
-    thoughts.profiles.insert(sanitized_name.clone(), profile);
-
-    hyprlayer_config.save(&config_path)?;
+    profile.check(&sanitized_name)?;
+    check_repo_collisions(thoughts, &thoughts_repo, &sanitized_name, allow_shared_repo)?;
 
+    // Set up the repository first so a failed clone doesn't leave a
+    // profile pointing at nothing.
     let expanded_repo = expand_path(&thoughts_repo);
-    fs::create_dir_all(&expanded_repo)?;
-    if !GitRepo::is_repo(&expanded_repo) {
-        let _ = GitRepo::init(&expanded_repo);
+    match &remote {
+        Some(url) => {
+            clone_or_reuse(&expanded_repo, url)?;
+            ensure_profile_dirs(
+                &expanded_repo,
+                &[&repos_dir, &global_dir],
+                !non_interactive && std::io::stdin().is_terminal(),
+            )?;
+        }
+        None => {
+            fs::create_dir_all(&expanded_repo)?;
+            if !GitRepo::is_repo(&expanded_repo) {
+                let _ = GitRepo::init(&expanded_repo, None);
+            }
+        }
+    }
+
+    // Prompting and cloning happened without the config lock, so the name
+    // is checked again against the config as it is now.
+    let entry = config.update(|target| {
+        let thoughts = target
+            .thoughts
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
+        thoughts.check_new_profile_name(&sanitized_name, None)?;
+        thoughts.profiles.insert(sanitized_name.clone(), profile);
+        profile_entry(thoughts, &sanitized_name)
+    })?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entry)?);
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{NotionConfig, ObsidianConfig};
+
+    fn thoughts() -> ThoughtsConfig {
+        let mut thoughts = ThoughtsConfig {
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: "~/thoughts/personal".to_string(),
+                repos_dir: "projects".to_string(),
+                global_dir: "shared".to_string(),
+            }),
+            ..Default::default()
+        };
+        thoughts.profiles.insert(
+            "vault".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Obsidian(ObsidianConfig {
+                    vault_path: "~/vault".to_string(),
+                    vault_subpath: None,
+                    repos_dir: "code".to_string(),
+                    global_dir: "all".to_string(),
+                }),
+                ai: None,
+            },
+        );
+        thoughts.profiles.insert(
+            "notes".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Notion(NotionConfig::default()),
+                ai: None,
+            },
+        );
+        thoughts
+    }
+
+    #[test]
+    fn check_repo_collisions_rejects_same_repo_unless_allowed() {
+        let root = tempfile::tempdir().unwrap();
+        let mut thoughts = thoughts();
+        thoughts.backend = BackendConfig::Git(GitConfig {
+            thoughts_repo: root.path().join("main").display().to_string(),
+            repos_dir: "repos".to_string(),
+            global_dir: "global".to_string(),
+        });
+        let same = root.path().join("main").display().to_string();
+
+        let err = check_repo_collisions(&thoughts, &same, "work", false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("already used by the default config")
+        );
+        assert!(check_repo_collisions(&thoughts, &same, "work", true).is_ok());
+
+        let nested = root.path().join("main/sub").display().to_string();
+        assert!(check_repo_collisions(&thoughts, &nested, "work", false).is_ok());
+        let other = root.path().join("other").display().to_string();
+        assert!(check_repo_collisions(&thoughts, &other, "work", false).is_ok());
+    }
+
+    #[test]
+    fn from_default_copies_dirs_and_derives_sibling_repo() {
+        let defaults = source_defaults(&thoughts(), None, true, "work")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            defaults,
+            ProfileDefaults {
+                thoughts_repo: Some(format!("~/thoughts{SEP}work")),
+                repos_dir: "projects".to_string(),
+                global_dir: "shared".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn from_profile_copies_dirs_without_repo_for_obsidian() {
+        let defaults = source_defaults(&thoughts(), Some("vault"), false, "work")
+            .unwrap()
+            .unwrap();
+        assert_eq!(defaults.thoughts_repo, None);
+        assert_eq!(defaults.repos_dir, "code");
+        assert_eq!(defaults.global_dir, "all");
+
+        assert!(
+            source_defaults(&thoughts(), None, false, "work")
+                .unwrap()
+                .is_none()
+        );
+        assert!(source_defaults(&thoughts(), Some("missing"), false, "work").is_err());
+        let err = source_defaults(&thoughts(), Some("notes"), false, "work").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("notion backend has no directories")
+        );
+    }
+
+    #[test]
+    fn resolve_without_prompts_lists_missing_flags() {
+        let err = resolve_without_prompts(Some("~/t".to_string()), None, None, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Missing required values: --repos-dir, --global-dir"
+        );
+
+        let vault = source_defaults(&thoughts(), Some("vault"), false, "work")
+            .unwrap()
+            .unwrap();
+        let err = resolve_without_prompts(None, None, None, Some(vault)).unwrap_err();
+        assert_eq!(err.to_string(), "Missing required values: --repo");
+
+        let default = source_defaults(&thoughts(), None, true, "work")
+            .unwrap()
+            .unwrap();
+        let resolved =
+            resolve_without_prompts(None, Some("mine".to_string()), None, Some(default)).unwrap();
+        assert_eq!(
+            resolved,
+            (
+                format!("~/thoughts{SEP}work"),
+                "mine".to_string(),
+                "shared".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn clone_or_reuse_clones_empty_paths_and_reuses_matching_clones() {
+        let root = tempfile::tempdir().unwrap();
+        let upstream = root.path().join("team.git");
+        git2::Repository::init_bare(&upstream).unwrap();
+        let url = upstream.display().to_string();
+
+        let target = root.path().join("team");
+        clone_or_reuse(&target, &url).unwrap();
+        assert_eq!(
+            GitRepo::open(&target).unwrap().remote_url().as_deref(),
+            Some(url.as_str())
+        );
+
+        clone_or_reuse(&target, &format!("{url}/")).unwrap();
+        let err = clone_or_reuse(&target, "git@example.com:other.git").unwrap_err();
+        assert!(err.to_string().contains("not git@example.com:other.git"));
+
+        let unrelated = root.path().join("notes");
+        fs::create_dir(&unrelated).unwrap();
+        fs::write(unrelated.join("a.md"), "x").unwrap();
+        assert!(clone_or_reuse(&unrelated, &url).is_err());
+    }
+
+    #[test]
+    fn same_remote_ignores_trailing_git_and_slash() {
+        assert!(same_remote(
+            "https://example.com/team.git",
+            "https://example.com/team/"
+        ));
+        assert!(!same_remote(
+            "https://example.com/team",
+            "https://example.com/other"
+        ));
+    }
+}
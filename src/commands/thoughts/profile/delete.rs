@@ -1,9 +1,19 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use dialoguer::{Confirm, theme::ColorfulTheme};
 use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
-use crate::cli::ProfileDeleteArgs;
+use crate::cli::{ConfigArgs, ProfileDeleteArgs};
+use crate::config::{BackendConfig, RepoOverlap, ThoughtsConfig, expand_path, update_raw_config};
+use crate::git_ops::GitRepo;
+use crate::platform;
 
-fn check_profile_not_in_use(config: &serde_json::Value, profile_name: &str) -> Result<()> {
+pub(crate) fn check_profile_not_in_use(
+    config: &serde_json::Value,
+    profile_name: &str,
+) -> Result<()> {
     let repo_mappings = config
         .get("thoughts")
         .and_then(|t| t.get("repoMappings"))
@@ -34,13 +44,66 @@ pub fn delete(args: ProfileDeleteArgs) -> Result<()> {
     let ProfileDeleteArgs {
         name: profile_name,
         force,
+        migrate_to,
+        move_content,
+        remove_repo,
+        yes,
         config,
     } = args;
-    let (config_path, mut config_json) = config.load_raw()?;
 
+    if remove_repo && !yes && !std::io::stdin().is_terminal() {
+        return Err(anyhow::anyhow!(
+            "--remove-repo needs confirmation; pass --yes when not running interactively"
+        ));
+    }
+
+    let hyprlayer_config = config.load()?;
+    let thoughts = hyprlayer_config
+        .thoughts
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No thoughts configuration"))?;
+    let profile_name = thoughts.resolve_profile_name(&profile_name)?;
+    let migrate_to = migrate_to
+        .map(|target| match target.as_str() {
+            "default" => Ok(target),
+            _ => thoughts.resolve_profile_name(&target),
+        })
+        .transpose()?;
+    let repo_to_remove = if remove_repo {
+        removable_repo(thoughts, &profile_name, dirs::home_dir().as_deref())?
+    } else {
+        None
+    };
+
+    match migrate_to {
+        Some(target) => delete_with_migration(&profile_name, &target, move_content, &config)?,
+        None => delete_profile(&profile_name, force, &config)?,
+    }
+
+    match repo_to_remove {
+        Some(repo) => remove_repo_dir(&repo, yes),
+        None => Ok(()),
+    }
+}
+
+fn delete_profile(profile_name: &str, force: bool, config: &ConfigArgs) -> Result<()> {
+    let config_path = config.path()?;
+    update_raw_config(&config_path, |config_json| {
+        remove_profile(config_json, profile_name, force)
+    })
+}
+
+/// Remove `profile_name` from the raw JSON config, and from
+/// `defaultProfile` if it's the default. Unless `force`, refuses a
+/// profile that repositories still use.
+fn remove_profile(
+    config_json: &mut serde_json::Value,
+    profile_name: &str,
+    force: bool,
+) -> Result<()> {
     // Check if profile is in use (unless force)
     if !force {
-        check_profile_not_in_use(&config_json, &profile_name)?;
+        check_profile_not_in_use(config_json, profile_name)?;
     }
     let thoughts_obj = config_json
         .get_mut("thoughts")
@@ -52,20 +115,327 @@ pub fn delete(args: ProfileDeleteArgs) -> Result<()> {
         .and_then(|p| p.as_object_mut())
         .ok_or_else(|| anyhow::anyhow!("No profiles configured"))?;
 
-    if !profiles.contains_key(&profile_name) {
+    if !profiles.contains_key(profile_name) {
         return Err(anyhow::anyhow!(
             "Profile \"{}\" does not exist",
             profile_name
         ));
     }
 
-    profiles.remove(&profile_name);
+    profiles.remove(profile_name);
 
     if profiles.is_empty() {
         thoughts_obj.remove("profiles");
     }
+    if thoughts_obj.get("defaultProfile").and_then(|p| p.as_str()) == Some(profile_name) {
+        thoughts_obj.remove("defaultProfile");
+        println!(
+            "{}",
+            format!("\"{profile_name}\" was the default profile; new repositories will use the default backend.")
+                .yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// `--remove-repo`: the profile's canonical thoughts repository, or `None`
+/// if it's already gone. Refuses non-git profiles, `$HOME` and `/`, and
+/// repositories that the default config or another profile uses or nests
+/// with.
+fn removable_repo(
+    thoughts: &ThoughtsConfig,
+    profile_name: &str,
+    home: Option<&Path>,
+) -> Result<Option<PathBuf>> {
+    let profile = thoughts
+        .profiles
+        .get(profile_name)
+        .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" does not exist", profile_name))?;
+    let git = profile.backend.as_git().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Profile \"{}\" uses the {} backend; --remove-repo only applies to git profiles",
+            profile_name,
+            profile.backend.kind()
+        )
+    })?;
+
+    let repo = expand_path(&git.thoughts_repo);
+    let Ok(repo) = repo.canonicalize() else {
+        println!(
+            "{}",
+            format!("{} does not exist; nothing to remove.", repo.display()).bright_black()
+        );
+        return Ok(None);
+    };
+    if repo.parent().is_none() || home.is_some_and(|h| h == repo) {
+        return Err(anyhow::anyhow!("Refusing to remove {}", repo.display()));
+    }
+    if let Some((owner, overlap)) = thoughts
+        .repo_overlaps(&repo, Some(profile_name))
+        .into_iter()
+        .next()
+    {
+        let relation = match overlap {
+            RepoOverlap::Same => "is also used by",
+            RepoOverlap::Nested => "is nested with the thoughts repository of",
+        };
+        return Err(anyhow::anyhow!(
+            "Not removing {}: it {} {}",
+            repo.display(),
+            relation,
+            owner
+        ));
+    }
+    Ok(Some(repo))
+}
+
+/// Show `repo`, warn about work that only exists there, and delete it once
+/// confirmed (or straight away with `yes`).
+fn remove_repo_dir(repo: &Path, yes: bool) -> Result<()> {
+    println!("Thoughts repository: {}", repo.display().to_string().cyan());
+    if let Ok(git) = GitRepo::open(repo) {
+        let unpushed = git.unpushed_commit_count()?;
+        if unpushed > 0 {
+            eprintln!(
+                "{}",
+                format!(
+                    "WARNING: {} commit(s) have not been pushed to any remote and will be lost!",
+                    unpushed
+                )
+                .red()
+                .bold()
+            );
+        }
+        if git.has_changes()? {
+            eprintln!(
+                "{}",
+                "WARNING: the repository has uncommitted changes that will be lost!"
+                    .red()
+                    .bold()
+            );
+        }
+    }
+
+    if !yes
+        && !Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!("Permanently delete {}?", repo.display()))
+            .default(false)
+            .interact()?
+    {
+        println!("{}", format!("Kept {}", repo.display()).bright_black());
+        return Ok(());
+    }
+
+    fs::remove_dir_all(repo).with_context(|| format!("Failed to remove {}", repo.display()))?;
+    println!("{}", format!("Removed {}", repo.display()).green());
+    Ok(())
+}
+
+/// `--migrate-to`: repoint every repository using `profile_name` at
+/// `target` ("default" for the default backend), optionally move their
+/// notes, then delete the profile.
+fn delete_with_migration(
+    profile_name: &str,
+    target: &str,
+    move_content: bool,
+    config: &ConfigArgs,
+) -> Result<()> {
+    let target = (target != "default").then_some(target);
+    let target_label = target.unwrap_or("the default backend");
+    let (migrated, moves) = config.update(|config| {
+        let thoughts = config.thoughts_mut();
+        let old_backend = thoughts
+            .profiles
+            .get(profile_name)
+            .map(|p| p.backend.clone())
+            .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" does not exist", profile_name))?;
+
+        let migrated = thoughts.migrate_profile_mappings(profile_name, target)?;
+        let moves = note_moves(thoughts, &old_backend, target, &migrated)?;
+
+        thoughts.profiles.remove(profile_name);
+        if thoughts.default_profile.as_deref() == Some(profile_name) {
+            thoughts.default_profile = target.map(str::to_string);
+        }
+        Ok((migrated, moves))
+    })?;
+
+    for repo in &migrated {
+        println!(
+            "  {} {} -> {}",
+            "Migrated".green(),
+            repo.cyan(),
+            target_label
+        );
+    }
+    println!("{}", format!("Deleted profile \"{profile_name}\"").green());
 
-    fs::write(&config_path, serde_json::to_string_pretty(&config_json)?)?;
+    if move_content {
+        if moves.is_empty() {
+            println!("{}", "No note directories to move.".bright_black());
+        }
+        move_notes(&moves, profile_name, target_label)?;
+    }
 
+    if !migrated.is_empty() {
+        if !move_content {
+            for m in &moves {
+                println!(
+                    "{}",
+                    format!("Notes left in place: {}", m.from.display()).bright_black()
+                );
+            }
+        }
+        println!(
+            "{}",
+            "Run 'hyprlayer thoughts init --force' in each migrated repository to relink its thoughts directory."
+                .bright_black()
+        );
+    }
     Ok(())
 }
+
+/// A migrated repository's notes directory and where it should go.
+struct NoteMove {
+    from: PathBuf,
+    to: PathBuf,
+    from_repo: PathBuf,
+    to_repo: PathBuf,
+}
+
+/// Plan moving `repos_dir/<mapped>` for each migrated repository from the
+/// old profile's thoughts repo to the target's. Only git backends can be
+/// moved; directories that don't exist are skipped, and an existing
+/// destination is an error so nothing is overwritten.
+fn note_moves(
+    thoughts: &ThoughtsConfig,
+    old_backend: &BackendConfig,
+    target: Option<&str>,
+    migrated: &[String],
+) -> Result<Vec<NoteMove>> {
+    let new_backend = match target {
+        Some(name) => &thoughts.profiles[name].backend,
+        None => &thoughts.backend,
+    };
+    let (BackendConfig::Git(old), BackendConfig::Git(new)) = (old_backend, new_backend) else {
+        return Ok(Vec::new());
+    };
+    let from_repo = expand_path(&old.thoughts_repo);
+    let to_repo = expand_path(&new.thoughts_repo);
+    if from_repo == to_repo {
+        return Ok(Vec::new());
+    }
+
+    let mut moves = Vec::new();
+    for repo in migrated {
+        let mapped = thoughts.repo_mappings[repo].repo();
+        let from = from_repo.join(&old.repos_dir).join(mapped);
+        if !from.exists() {
+            continue;
+        }
+        let to = to_repo.join(&new.repos_dir).join(mapped);
+        if to.exists() {
+            return Err(anyhow::anyhow!(
+                "Cannot move notes for {}: {} already exists",
+                repo,
+                to.display()
+            ));
+        }
+        moves.push(NoteMove {
+            from,
+            to,
+            from_repo: from_repo.clone(),
+            to_repo: to_repo.clone(),
+        });
+    }
+    Ok(moves)
+}
+
+/// Move each planned directory, then commit the removal in the old thoughts
+/// repo and the addition in the new one.
+fn move_notes(moves: &[NoteMove], profile_name: &str, target_label: &str) -> Result<()> {
+    let Some(first) = moves.first() else {
+        return Ok(());
+    };
+    let from_git = GitRepo::open(&first.from_repo)?;
+    let to_git = GitRepo::open(&first.to_repo)?;
+
+    for m in moves {
+        platform::move_dir(&m.from, &m.to)?;
+        println!(
+            "  {} {} -> {}",
+            "Moved".green(),
+            m.from.display().to_string().cyan(),
+            m.to.display().to_string().cyan()
+        );
+    }
+
+    let message = format!("Move notes from profile {profile_name} to {target_label}");
+    for git in [&from_git, &to_git] {
+        git.add_all()?;
+        if git.has_changes()? {
+            git.commit(&message)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProfileConfig, tests::git_backend};
+
+    fn with_profiles(default: &Path, profiles: &[(&str, &Path)]) -> ThoughtsConfig {
+        let mut thoughts = ThoughtsConfig {
+            backend: git_backend(default),
+            ..Default::default()
+        };
+        for (name, path) in profiles {
+            thoughts.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: git_backend(path),
+                    ai: None,
+                },
+            );
+        }
+        thoughts
+    }
+
+    #[test]
+    fn removable_repo_refuses_shared_and_nested_paths() {
+        let root = tempfile::tempdir().unwrap();
+        let (main, work, inner) = (
+            root.path().join("main"),
+            root.path().join("work"),
+            root.path().join("main/inner"),
+        );
+        for dir in [&main, &work, &inner] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let thoughts = with_profiles(
+            &main,
+            &[
+                ("work", &work),
+                ("shared", &main),
+                ("inner", &inner),
+                ("gone", &root.path().join("missing")),
+            ],
+        );
+
+        let repo = removable_repo(&thoughts, "work", None).unwrap();
+        assert_eq!(repo, Some(work.canonicalize().unwrap()));
+
+        let err = removable_repo(&thoughts, "shared", None).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("is also used by the default config")
+        );
+        let err = removable_repo(&thoughts, "inner", None).unwrap_err();
+        assert!(err.to_string().contains("is nested with"));
+
+        assert_eq!(removable_repo(&thoughts, "gone", None).unwrap(), None);
+        assert!(removable_repo(&thoughts, "work", Some(&work.canonicalize().unwrap())).is_err());
+    }
+}
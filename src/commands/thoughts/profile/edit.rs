@@ -0,0 +1,154 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Input, Select};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::ProfileEditArgs;
+use crate::commands::thoughts::profile::create::check_repo_collisions;
+use crate::config::{BackendConfig, GitConfig, expand_path};
+use crate::git_ops::GitRepo;
+
+pub fn edit(args: ProfileEditArgs) -> Result<()> {
+    let ProfileEditArgs {
+        name: profile_name,
+        repo,
+        repos_dir,
+        global_dir,
+        allow_shared_repo,
+        config,
+    } = args;
+    config.reject_overrides("thoughts profile edit")?;
+    let hyprlayer_config = config
+        .load_if_exists()?
+        .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+    let thoughts = hyprlayer_config
+        .thoughts
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+
+    let profile_name = thoughts.resolve_profile_name(&profile_name)?;
+    let profile = &thoughts.profiles[&profile_name];
+    let Some(current) = profile.backend.as_git().cloned() else {
+        return Err(anyhow::anyhow!(
+            "Profile \"{}\" uses the {} backend; only git profiles can be edited",
+            profile_name,
+            profile.backend.kind()
+        ));
+    };
+
+    let theme = ColorfulTheme::default();
+    let prompt = |value: Option<String>, label: &str, default: &str| -> Result<String> {
+        match value {
+            Some(v) => Ok(v),
+            None => Ok(Input::with_theme(&theme)
+                .with_prompt(label)
+                .default(default.to_string())
+                .interact()?),
+        }
+    };
+    let updated = GitConfig {
+        thoughts_repo: prompt(repo, "Thoughts repository", &current.thoughts_repo)?,
+        repos_dir: prompt(
+            repos_dir,
+            "Repository-specific thoughts directory",
+            &current.repos_dir,
+        )?,
+        global_dir: prompt(global_dir, "Global thoughts directory", &current.global_dir)?,
+    };
+
+    if updated == current {
+        println!("{}", "No changes to profile.".bright_black());
+        return Ok(());
+    }
+
+    if updated.thoughts_repo != current.thoughts_repo {
+        check_repo_collisions(
+            thoughts,
+            &updated.thoughts_repo,
+            &profile_name,
+            allow_shared_repo,
+        )?;
+    }
+    ensure_thoughts_repo(&expand_path(&updated.thoughts_repo))?;
+
+    let affected = thoughts.repos_using_profile(&profile_name);
+    // Prompting happened without the config lock, so only the edited
+    // profile is written back.
+    config.update(|target| {
+        let profile = target
+            .thoughts
+            .as_mut()
+            .and_then(|t| t.profiles.get_mut(&profile_name))
+            .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" no longer exists", profile_name))?;
+        profile.backend = BackendConfig::Git(updated);
+        Ok(())
+    })?;
+
+    println!(
+        "{}",
+        format!("Updated profile \"{}\"", profile_name).green()
+    );
+
+    if !affected.is_empty() {
+        println!();
+        println!(
+            "{}",
+            "Existing thoughts/ symlinks in these repositories still point at the old location:"
+                .yellow()
+        );
+        for repo in &affected {
+            println!("  {}", repo.cyan());
+        }
+        println!(
+            "{}",
+            format!(
+                "Run 'hyprlayer thoughts init --force --profile {}' in each to relink.",
+                profile_name
+            )
+            .bright_black()
+        );
+    }
+
+    Ok(())
+}
+
+/// Make sure the (new) thoughts repository exists, offering to create an
+/// empty one or clone a remote when it doesn't.
+fn ensure_thoughts_repo(path: &Path) -> Result<()> {
+    if path.exists() {
+        if !GitRepo::is_repo(path) {
+            let _ = GitRepo::init(path, None);
+        }
+        return Ok(());
+    }
+
+    let theme = ColorfulTheme::default();
+    let selection = Select::with_theme(&theme)
+        .with_prompt(format!(
+            "Thoughts repository {} does not exist",
+            path.display()
+        ))
+        .items(&["Create it", "Clone it from a remote URL", "Cancel"])
+        .default(0)
+        .interact()?;
+
+    match selection {
+        0 => {
+            fs::create_dir_all(path)?;
+            GitRepo::init(path, None)?;
+        }
+        1 => {
+            let url: String = Input::with_theme(&theme)
+                .with_prompt("Remote URL")
+                .interact()?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            GitRepo::clone_from(&url, path)?;
+        }
+        _ => return Err(anyhow::anyhow!("Profile not updated")),
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::{ProfileExportArgs, ProfileFormat};
+use crate::commands::thoughts::config_cmd::export::portable_backend;
+use crate::config::{AiConfig, BackendConfig, ThoughtsConfig, expand_path};
+use crate::git_ops::GitRepo;
+
+/// Bumped whenever the snippet shape changes incompatibly.
+pub const PROFILE_FORMAT: u32 = 1;
+
+/// Shareable definition of a single profile. Paths under `$HOME` are
+/// written as `~/…`; `remote` lets `profile import` clone the thoughts
+/// repository on a machine that doesn't have it yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSnippet {
+    pub hyprlayer_profile: u32,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    pub backend: BackendConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai: Option<AiConfig>,
+}
+
+impl ProfileSnippet {
+    pub fn from_profile(
+        thoughts: &ThoughtsConfig,
+        name: &str,
+        remote: Option<String>,
+        home: Option<&Path>,
+    ) -> Result<Self> {
+        let profile = thoughts
+            .profiles
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" not found", name))?;
+        Ok(Self {
+            hyprlayer_profile: PROFILE_FORMAT,
+            name: name.to_string(),
+            remote,
+            backend: portable_backend(&profile.backend, home),
+            ai: profile.ai.clone(),
+        })
+    }
+
+    pub fn to_string(&self, format: ProfileFormat) -> Result<String> {
+        Ok(match format {
+            ProfileFormat::Json => serde_json::to_string_pretty(self)?,
+            ProfileFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    /// Parse a snippet in either format. JSON is recognised by its leading
+    /// `{`; anything else is read as TOML.
+    pub fn parse(content: &str) -> Result<Self> {
+        let snippet: Self = if content.trim_start().starts_with('{') {
+            serde_json::from_str(content).context("Invalid JSON profile snippet")?
+        } else {
+            toml::from_str(content).context("Invalid TOML profile snippet")?
+        };
+        if snippet.hyprlayer_profile > PROFILE_FORMAT {
+            return Err(anyhow::anyhow!(
+                "Profile snippet format {} is newer than this hyprlayer supports ({}); upgrade hyprlayer first",
+                snippet.hyprlayer_profile,
+                PROFILE_FORMAT
+            ));
+        }
+        Ok(snippet)
+    }
+}
+
+/// The thoughts repository's `origin` URL, for git profiles whose
+/// repository exists locally.
+fn detect_remote(backend: &BackendConfig) -> Option<String> {
+    let git = backend.as_git()?;
+    GitRepo::open(&expand_path(&git.thoughts_repo))
+        .ok()?
+        .remote_url()
+}
+
+pub fn export(args: ProfileExportArgs) -> Result<()> {
+    let ProfileExportArgs {
+        name,
+        output,
+        format,
+        remote,
+        config,
+    } = args;
+
+    let hyprlayer_config = config
+        .load_if_exists()?
+        .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+    let thoughts = hyprlayer_config
+        .thoughts
+        .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+    let name = thoughts.resolve_profile_name(&name)?;
+
+    let remote = remote.or_else(|| {
+        thoughts
+            .profiles
+            .get(&name)
+            .and_then(|p| detect_remote(&p.backend))
+    });
+    let snippet =
+        ProfileSnippet::from_profile(&thoughts, &name, remote, dirs::home_dir().as_deref())?;
+
+    // Without --format, a `.toml` output file selects TOML.
+    let format = format.unwrap_or_else(|| match &output {
+        Some(path) if path.ends_with(".toml") => ProfileFormat::Toml,
+        _ => ProfileFormat::Json,
+    });
+    let text = snippet.to_string(format)?;
+
+    match output {
+        Some(path) => {
+            let path = expand_path(&path);
+            fs::write(&path, format!("{}\n", text.trim_end()))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "{}",
+                format!("Exported profile \"{}\" to {}", name, path.display()).green()
+            );
+            if snippet.remote.is_none() && snippet.backend.as_git().is_some() {
+                eprintln!(
+                    "{}",
+                    "No remote URL found; importers will get an empty local repository. Pass --remote to include one."
+                        .bright_black()
+                );
+            }
+        }
+        None => println!("{}", text.trim_end()),
+    }
+    Ok(())
+}
@@ -0,0 +1,290 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use super::create::check_repo_collisions;
+use super::export::ProfileSnippet;
+use crate::cli::ProfileImportArgs;
+use crate::config::{
+    BackendConfig, HyprlayerConfig, ProfileConfig, ThoughtsConfig, expand_path,
+    validate_profile_name,
+};
+use crate::git_ops::GitRepo;
+use crate::http;
+
+/// Read a snippet from a local file or an `http(s)://` URL.
+fn read_source(source: &str) -> Result<String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let body = http::get_bytes(source, Duration::from_secs(30))?;
+        return String::from_utf8(body).with_context(|| format!("{} is not UTF-8 text", source));
+    }
+    let path = expand_path(source);
+    fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Add `snippet` to `thoughts` as `name`, refusing to replace an existing
+/// profile unless `force`. A profile whose name differs only by case is
+/// never replaced.
+fn add_profile(
+    thoughts: &mut ThoughtsConfig,
+    snippet: &ProfileSnippet,
+    name: &str,
+    force: bool,
+) -> Result<()> {
+    match thoughts.profile_named_like(name) {
+        Some(existing) if existing != name => {
+            return Err(anyhow::anyhow!(
+                "Profile \"{}\" already exists; names may not differ only by case",
+                existing
+            ));
+        }
+        Some(_) if !force => {
+            return Err(anyhow::anyhow!(
+                "Profile \"{}\" already exists. Pass --force to replace it.",
+                name
+            ));
+        }
+        _ => {}
+    }
+    thoughts.profiles.insert(
+        name.to_string(),
+        ProfileConfig {
+            backend: snippet.backend.clone(),
+            ai: snippet.ai.clone(),
+        },
+    );
+    Ok(())
+}
+
+/// Make sure a git profile's thoughts repository exists: clone `remote`
+/// into it when the directory is missing or empty, otherwise initialise
+/// a local repository as `profile create` does.
+fn prepare_repo(repo: &Path, remote: Option<&str>) -> Result<()> {
+    let is_empty = fs::read_dir(repo).map_or(true, |mut entries| entries.next().is_none());
+    if let Some(url) = remote
+        && is_empty
+    {
+        println!(
+            "{}",
+            format!("Cloning {} into {}...", url, repo.display()).yellow()
+        );
+        if repo.exists() {
+            fs::remove_dir(repo)?;
+        }
+        GitRepo::clone_from(url, repo)?;
+        return Ok(());
+    }
+
+    fs::create_dir_all(repo)?;
+    if !GitRepo::is_repo(repo) {
+        if remote.is_some() {
+            println!(
+                "{}",
+                format!(
+                    "{} is not empty; initialising it without cloning the remote",
+                    repo.display()
+                )
+                .yellow()
+            );
+        }
+        GitRepo::init(repo, None)?;
+    }
+    Ok(())
+}
+
+pub fn import(args: ProfileImportArgs) -> Result<()> {
+    let ProfileImportArgs {
+        source,
+        name,
+        force,
+        allow_shared_repo,
+        config,
+    } = args;
+    config.reject_overrides("thoughts profile import")?;
+    let config_path = config.path()?;
+
+    if !config_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Thoughts not configured. Run 'hyprlayer thoughts init' first."
+        ));
+    }
+
+    let snippet = ProfileSnippet::parse(&read_source(&source)?)
+        .with_context(|| format!("{} is not a hyprlayer profile export", source))?;
+
+    let mut hyprlayer_config = HyprlayerConfig::load(&config_path)?;
+    // Checked here first so a conflicting import fails before any clone.
+    let thoughts = hyprlayer_config
+        .thoughts
+        .as_mut()
+        .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
+
+    let requested = name.unwrap_or_else(|| snippet.name.clone());
+    let profile_name = validate_profile_name(&requested)?;
+    if profile_name != requested {
+        println!(
+            "{}",
+            format!(
+                "Profile name sanitized: \"{}\" → \"{}\"",
+                requested, profile_name
+            )
+            .yellow()
+        );
+    }
+
+    ProfileConfig {
+        backend: snippet.backend.clone(),
+        ai: None,
+    }
+    .check(&profile_name)?;
+    if let BackendConfig::Git(git) = &snippet.backend {
+        check_repo_collisions(
+            thoughts,
+            &git.thoughts_repo,
+            &profile_name,
+            allow_shared_repo,
+        )?;
+    }
+    add_profile(thoughts, &snippet, &profile_name, force)?;
+
+    // Set up the repository before saving so a failed clone leaves the
+    // config untouched.
+    if let BackendConfig::Git(git) = &snippet.backend {
+        prepare_repo(&expand_path(&git.thoughts_repo), snippet.remote.as_deref())?;
+    }
+
+    config.update(|target| add_profile(target.thoughts_mut(), &snippet, &profile_name, force))?;
+    println!(
+        "{}",
+        format!("Imported profile \"{}\"", profile_name).green()
+    );
+    println!(
+        "{}",
+        format!(
+            "Use it in a repository with: hyprlayer thoughts init --profile {}",
+            profile_name
+        )
+        .bright_black()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::ProfileFormat;
+    use crate::commands::thoughts::config_cmd::export::portable_backend;
+    use crate::config::{AiConfig, GitConfig, ObsidianConfig};
+    use std::path::PathBuf;
+
+    fn team_config() -> ThoughtsConfig {
+        let mut thoughts = ThoughtsConfig::default();
+        thoughts.profiles.insert(
+            "team".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Git(GitConfig {
+                    thoughts_repo: "/home/alice/thoughts/team".to_string(),
+                    repos_dir: "projects".to_string(),
+                    global_dir: "shared".to_string(),
+                }),
+                ai: Some(AiConfig {
+                    opencode_sonnet_model: Some("custom/sonnet".to_string()),
+                    ..Default::default()
+                }),
+            },
+        );
+        thoughts.profiles.insert(
+            "vault".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Obsidian(ObsidianConfig {
+                    vault_path: "/home/alice/vault".to_string(),
+                    vault_subpath: Some("work".to_string()),
+                    repos_dir: "repos".to_string(),
+                    global_dir: "global".to_string(),
+                }),
+                ai: None,
+            },
+        );
+        thoughts
+    }
+
+    #[test]
+    fn export_then_import_round_trips_in_both_formats() {
+        let home = PathBuf::from("/home/alice");
+        let source = team_config();
+        for format in [ProfileFormat::Json, ProfileFormat::Toml] {
+            for name in ["team", "vault"] {
+                let remote = (name == "team").then(|| "git@example.com:team.git".to_string());
+                let text = ProfileSnippet::from_profile(&source, name, remote, Some(&home))
+                    .unwrap()
+                    .to_string(format)
+                    .unwrap();
+                let snippet = ProfileSnippet::parse(&text).unwrap();
+                assert_eq!(snippet.name, name);
+
+                let mut clean = ThoughtsConfig::default();
+                add_profile(&mut clean, &snippet, &snippet.name, false).unwrap();
+                let imported = &clean.profiles[name];
+                let original = &source.profiles[name];
+                assert_eq!(
+                    imported.backend,
+                    portable_backend(&original.backend, Some(&home))
+                );
+                assert_eq!(
+                    serde_json::to_value(&imported.ai).unwrap(),
+                    serde_json::to_value(&original.ai).unwrap()
+                );
+            }
+        }
+
+        let text = ProfileSnippet::from_profile(&source, "team", None, Some(&home))
+            .unwrap()
+            .to_string(ProfileFormat::Json)
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["backend"]["thoughtsRepo"], "~/thoughts/team");
+        assert!(json.get("remote").is_none());
+    }
+
+    #[test]
+    fn add_profile_requires_force_to_replace() {
+        let mut thoughts = team_config();
+        let snippet = ProfileSnippet::from_profile(&thoughts, "vault", None, None).unwrap();
+
+        let err = add_profile(&mut thoughts, &snippet, "team", false).unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert!(thoughts.profiles["team"].backend.as_git().is_some());
+
+        add_profile(&mut thoughts, &snippet, "team", true).unwrap();
+        assert!(thoughts.profiles["team"].backend.as_git().is_none());
+
+        let err = add_profile(&mut thoughts, &snippet, "Team", true).unwrap_err();
+        assert!(err.to_string().contains("only by case"));
+    }
+
+    #[test]
+    fn parse_rejects_newer_formats() {
+        let text = r#"{"hyprlayerProfile": 99, "name": "x", "backend": {"kind": "notion", "parentPageId": "p"}}"#;
+        let err = ProfileSnippet::parse(text).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+    }
+
+    #[test]
+    fn prepare_repo_clones_remote_into_empty_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let upstream = root.path().join("upstream");
+        GitRepo::init(&upstream, None).unwrap();
+
+        let target = root.path().join("team");
+        fs::create_dir(&target).unwrap();
+        prepare_repo(&target, Some(upstream.to_str().unwrap())).unwrap();
+        let cloned = GitRepo::open(&target).unwrap();
+        assert_eq!(cloned.remote_url().as_deref(), upstream.to_str());
+
+        let local = root.path().join("local");
+        prepare_repo(&local, None).unwrap();
+        assert!(GitRepo::is_repo(&local));
+    }
+}
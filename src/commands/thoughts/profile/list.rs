@@ -1,24 +1,27 @@
 use anyhow::Result;
 use colored::Colorize;
+use serde_json::{Map, Value, json};
 
 use crate::cli::ProfileListArgs;
 use crate::commands::thoughts::backend_display::print_backend_block;
+use crate::config::{BackendConfig, ThoughtsConfig, redact_json};
 
 pub fn list(args: ProfileListArgs) -> Result<()> {
     let ProfileListArgs { json, config } = args;
-    let (_, config_json) = config.load_raw()?;
+
+    let hyprlayer_config = config.load_if_exists()?;
+    let thoughts = hyprlayer_config.as_ref().and_then(|c| c.thoughts.as_ref());
 
     if json {
-        let profiles = config_json
-            .get("thoughts")
-            .and_then(|t| t.get("profiles"))
-            .unwrap_or(&serde_json::Value::Null);
-        println!("{}", serde_json::to_string_pretty(profiles)?);
+        let document = match thoughts {
+            Some(t) => list_json(t)?,
+            None => json!({ "default": null, "profiles": [] }),
+        };
+        println!("{}", serde_json::to_string_pretty(&document)?);
         return Ok(());
     }
 
-    let hyprlayer_config = config.load_if_exists()?;
-    let Some(thoughts) = hyprlayer_config.as_ref().and_then(|c| c.thoughts.as_ref()) else {
+    let Some(thoughts) = thoughts else {
         return Ok(());
     };
 
@@ -34,21 +37,176 @@ pub fn list(args: ProfileListArgs) -> Result<()> {
             "{}",
             "Create a profile with: hyprlayer thoughts profile create <name>".bright_black()
         );
-        return Ok(());
-    }
+    } else {
+        println!(
+            "{}",
+            format!("Profiles ({}):", thoughts.profiles.len()).yellow()
+        );
+        println!();
 
-    println!(
-        "{}",
-        format!("Profiles ({}):", thoughts.profiles.len()).yellow()
-    );
-    println!();
+        for (name, profile) in &thoughts.profiles {
+            let used_by = thoughts.repos_using_profile(name);
+            let mut markers = Vec::new();
+            if thoughts.default_profile.as_deref() == Some(name) {
+                markers.push("(default)");
+            }
+            if used_by.is_empty() {
+                markers.push("(unused)");
+            }
+            if markers.is_empty() {
+                println!("  {}:", name.cyan());
+            } else {
+                println!("  {}: {}", name.cyan(), markers.join(" ").bright_black());
+            }
+            println!("    Backend: {}", profile.backend.kind().as_str().cyan());
+            print_backend_block(&profile.backend, "    ", false);
+            if !used_by.is_empty() {
+                println!("    Used by ({}):", used_by.len());
+                for repo in &used_by {
+                    println!("      {}", repo.cyan());
+                }
+            }
+            println!();
+        }
+    }
 
-    for (name, profile) in &thoughts.profiles {
-        println!("  {}:", name.cyan());
-        println!("    Backend: {}", profile.backend.kind().as_str().cyan());
-        print_backend_block(&profile.backend, "    ", false);
+    let orphans = thoughts.orphaned_mappings();
+    if !orphans.is_empty() {
+        println!(
+            "{}",
+            format!("Mappings to missing profiles ({}):", orphans.len()).yellow()
+        );
+        for (repo, profile) in &orphans {
+            println!("  {} -> {}", repo.cyan(), profile.red());
+        }
         println!();
     }
 
     Ok(())
 }
+
+/// `{ "default": {...}, "profiles": [...] }` with profiles sorted by name.
+fn list_json(thoughts: &ThoughtsConfig) -> Result<Value> {
+    let mut names: Vec<&String> = thoughts.profiles.keys().collect();
+    names.sort();
+    let profiles = names
+        .into_iter()
+        .map(|name| profile_entry(thoughts, name))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(json!({
+        "default": default_entry(thoughts)?,
+        "profiles": profiles,
+    }))
+}
+
+/// The default (non-profile) configuration: its backend fields, whether
+/// its content directory exists, and the repositories mapped without a
+/// profile.
+pub(crate) fn default_entry(thoughts: &ThoughtsConfig) -> Result<Value> {
+    let mut used_by: Vec<String> = thoughts
+        .repo_mappings
+        .iter()
+        .filter(|(_, m)| m.profile().is_none())
+        .map(|(path, _)| path.clone())
+        .collect();
+    used_by.sort();
+    let mut entry = backend_entry(&thoughts.backend)?;
+    entry.insert("usedBy".to_string(), json!(used_by));
+    Ok(Value::Object(entry))
+}
+
+/// One profile as emitted by `profile list --json`, `profile show --json`
+/// and `profile create --json`.
+pub(crate) fn profile_entry(thoughts: &ThoughtsConfig, name: &str) -> Result<Value> {
+    let profile = thoughts
+        .profiles
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" not found", name))?;
+    let mut entry = Map::new();
+    entry.insert("name".to_string(), json!(name));
+    entry.extend(backend_entry(&profile.backend)?);
+    entry.insert(
+        "isDefault".to_string(),
+        json!(thoughts.default_profile.as_deref() == Some(name)),
+    );
+    entry.insert(
+        "usedBy".to_string(),
+        json!(thoughts.repos_using_profile(name)),
+    );
+    if let Some(ai) = &profile.ai {
+        entry.insert("ai".to_string(), serde_json::to_value(ai)?);
+    }
+    Ok(Value::Object(entry))
+}
+
+/// Backend fields flattened next to `kind`, redacted, plus `exists` for the
+/// content directory (`null` for backends that store content remotely).
+fn backend_entry(backend: &BackendConfig) -> Result<Map<String, Value>> {
+    let mut value = serde_json::to_value(backend)?;
+    redact_json(&mut value);
+    let Value::Object(mut entry) = value else {
+        return Ok(Map::new());
+    };
+    entry.insert(
+        "exists".to_string(),
+        json!(backend.content_root().map(|root| root.is_dir())),
+    );
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AnytypeConfig, GitConfig, ProfileConfig, RepoMapping};
+
+    #[test]
+    fn list_json_describes_default_and_each_profile() {
+        let repo = tempfile::tempdir().unwrap();
+        let mut thoughts = ThoughtsConfig {
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: repo.path().display().to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            default_profile: Some("team".to_string()),
+            ..Default::default()
+        };
+        for name in ["idle", "team"] {
+            thoughts.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: BackendConfig::Anytype(AnytypeConfig {
+                        space_id: "s1".to_string(),
+                        type_id: None,
+                        api_token_env: Some("ANYTYPE_TOKEN".to_string()),
+                    }),
+                    ai: None,
+                },
+            );
+        }
+        thoughts.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("team".to_string())),
+        );
+        thoughts
+            .repo_mappings
+            .insert("/code/lib".to_string(), RepoMapping::new("lib", &None));
+
+        let json = list_json(&thoughts).unwrap();
+        let default = &json["default"];
+        assert_eq!(default["kind"], "git");
+        assert_eq!(default["reposDir"], "repos");
+        assert_eq!(default["exists"], true);
+        assert_eq!(default["usedBy"], serde_json::json!(["/code/lib"]));
+
+        let profiles = json["profiles"].as_array().unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0]["name"], "idle");
+        assert_eq!(profiles[0]["usedBy"], serde_json::json!([]));
+        assert_eq!(profiles[1]["name"], "team");
+        assert_eq!(profiles[1]["isDefault"], true);
+        assert_eq!(profiles[1]["spaceId"], "s1");
+        assert_eq!(profiles[1]["exists"], Value::Null);
+        assert_eq!(profiles[1]["usedBy"], serde_json::json!(["/code/app"]));
+    }
+}
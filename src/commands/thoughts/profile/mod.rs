@@ -1,4 +1,10 @@
 pub mod create;
 pub mod delete;
+pub mod edit;
+pub mod export;
+pub mod import;
 pub mod list;
+pub mod rename;
+pub mod set_default;
 pub mod show;
+pub mod unset_default;
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::json;
+
+use crate::cli::ProfileRenameArgs;
+use crate::config::RepoMapping;
+
+pub fn rename(args: ProfileRenameArgs) -> Result<()> {
+    let ProfileRenameArgs {
+        old,
+        new,
+        json,
+        config,
+    } = args;
+    config.reject_overrides("thoughts profile rename")?;
+    let (old, sanitized_name, updated, changes) = config.update(|target| {
+        let thoughts = target
+            .thoughts
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+        let old = thoughts.resolve_profile_name(&old)?;
+        let sanitized_name = thoughts.check_new_profile_name(&new, Some(&old))?;
+        let before = thoughts.repo_mappings.clone();
+        let updated = thoughts.rename_profile(&old, &sanitized_name)?;
+        let changes: Vec<(String, Vec<String>)> = updated
+            .iter()
+            .map(|repo| {
+                let changes = RepoMapping::diff(&before[repo], &thoughts.repo_mappings[repo]);
+                (repo.clone(), changes)
+            })
+            .collect();
+        Ok((old, sanitized_name, updated, changes))
+    })?;
+    if sanitized_name != new && !json {
+        println!(
+            "{}",
+            format!(
+                "Profile name sanitized: \"{}\" → \"{}\"",
+                new, sanitized_name
+            )
+            .yellow()
+        );
+    }
+
+    if json {
+        let payload = json!({
+            "oldName": old,
+            "newName": sanitized_name,
+            "updatedMappings": updated.len(),
+            "repositories": updated,
+        });
+        println!("{}", serde_json::to_string_pretty(&payload)?);
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        format!("Renamed profile \"{}\" → \"{}\"", old, sanitized_name).green()
+    );
+    println!("  Updated {} repository mapping(s)", updated.len());
+    for (repo, changes) in &changes {
+        println!("    {}: {}", repo.cyan(), changes.join(", "));
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::ProfileSetDefaultArgs;
+
+pub fn set_default(args: ProfileSetDefaultArgs) -> Result<()> {
+    let ProfileSetDefaultArgs { name, config } = args;
+    config.reject_overrides("thoughts profile set-default")?;
+    let name = config.update(|target| {
+        let thoughts = target
+            .thoughts
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+        let name = thoughts.resolve_profile_name(&name)?;
+        thoughts.default_profile = Some(name.clone());
+        Ok(name)
+    })?;
+
+    println!("{}", format!("Default profile set to \"{}\"", name).green());
+    println!(
+        "{}",
+        "New repositories initialized without --profile will use it; existing mappings are unchanged."
+            .bright_black()
+    );
+    Ok(())
+}
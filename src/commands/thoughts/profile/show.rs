@@ -1,34 +1,66 @@
 use anyhow::Result;
 use colored::Colorize;
+use serde_json::{Map, Value, json};
 
 use crate::cli::ProfileShowArgs;
 use crate::commands::thoughts::backend_display::print_backend_block;
+use crate::commands::thoughts::profile::list::profile_entry;
+use crate::config::{RepoOverlap, ThoughtsConfig, expand_path};
+use crate::git_ops::GitRepo;
+
+/// Outcome of one `--validate` check. `key` names the entry in the JSON
+/// `checks` object.
+#[derive(Debug)]
+struct Check {
+    key: &'static str,
+    label: &'static str,
+    passed: bool,
+    detail: String,
+}
+
+impl Check {
+    fn new(key: &'static str, label: &'static str, passed: bool, detail: String) -> Self {
+        Self {
+            key,
+            label,
+            passed,
+            detail,
+        }
+    }
+}
 
 pub fn show(args: ProfileShowArgs) -> Result<()> {
     let ProfileShowArgs {
         name: profile_name,
         json,
+        validate,
         config,
     } = args;
 
-    if json {
-        let (_, config_json) = config.load_raw()?;
-        let profile = config_json
-            .get("thoughts")
-            .and_then(|t| t.get("profiles"))
-            .and_then(|p| p.get(&profile_name))
-            .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" not found", profile_name))?;
-
-        println!("{}", serde_json::to_string_pretty(profile)?);
-        return Ok(());
-    }
-
     let hyprlayer_config = config
         .load_if_exists()?
         .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
     let thoughts = hyprlayer_config
         .thoughts
         .ok_or_else(|| anyhow::anyhow!("No thoughts configuration found"))?;
+    let profile_name = thoughts.resolve_profile_name(&profile_name)?;
+
+    let checks = if validate {
+        Some(validate_profile(&thoughts, &profile_name)?)
+    } else {
+        None
+    };
+
+    if json {
+        let mut entry = profile_entry(&thoughts, &profile_name)?;
+        if let (Some(checks), Some(map)) = (&checks, entry.as_object_mut()) {
+            map.insert("checks".to_string(), checks_json(checks));
+        }
+
+        println!("{}", serde_json::to_string_pretty(&entry)?);
+        return finish(&profile_name, checks.as_deref());
+    }
+
     let profile = thoughts
         .profiles
         .get(&profile_name)
@@ -36,5 +68,181 @@ pub fn show(args: ProfileShowArgs) -> Result<()> {
 
     println!("  Backend: {}", profile.backend.kind().as_str().cyan());
     print_backend_block(&profile.backend, "  ", true);
+
+    if let Some(checks) = &checks {
+        println!();
+        println!("{}", "Checks:".yellow());
+        for check in checks {
+            let status = if check.passed {
+                "PASS".green()
+            } else {
+                "FAIL".red()
+            };
+            println!(
+                "  {} {}: {}",
+                status,
+                check.label,
+                check.detail.bright_black()
+            );
+        }
+    }
+    finish(&profile_name, checks.as_deref())
+}
+
+/// Turn failed checks into an error so the command exits non-zero.
+fn finish(profile_name: &str, checks: Option<&[Check]>) -> Result<()> {
+    let failed = checks.map_or(0, |c| c.iter().filter(|c| !c.passed).count());
+    if failed > 0 {
+        return Err(anyhow::anyhow!(
+            "Profile \"{}\" failed {} check(s)",
+            profile_name,
+            failed
+        ));
+    }
     Ok(())
 }
+
+fn checks_json(checks: &[Check]) -> Value {
+    let map: Map<String, Value> = checks
+        .iter()
+        .map(|c| {
+            (
+                c.key.to_string(),
+                json!({ "passed": c.passed, "detail": c.detail }),
+            )
+        })
+        .collect();
+    Value::Object(map)
+}
+
+/// Health checks for a git profile's thoughts repository: it exists, is a
+/// git repo, has its `reposDir` and `globalDir`, and doesn't share its path
+/// with another profile or the default backend. The remote is reported but
+/// never fails, since local-only repositories are supported.
+fn validate_profile(thoughts: &ThoughtsConfig, name: &str) -> Result<Vec<Check>> {
+    let profile = thoughts
+        .profiles
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" not found", name))?;
+    let git = profile.backend.as_git().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Profile \"{}\" uses the {} backend; --validate only checks git profiles",
+            name,
+            profile.backend.kind()
+        )
+    })?;
+
+    let repo = expand_path(&git.thoughts_repo);
+    let exists = repo.is_dir();
+    let is_repo = exists && GitRepo::is_repo(&repo);
+    let mut checks = vec![Check::new(
+        "thoughtsRepoExists",
+        "thoughtsRepo exists",
+        exists,
+        repo.display().to_string(),
+    )];
+    checks.push(Check::new(
+        "isGitRepo",
+        "is a git repository",
+        is_repo,
+        if is_repo {
+            "ok".to_string()
+        } else {
+            "not a git repository".to_string()
+        },
+    ));
+    for (key, label, dir) in [
+        ("reposDirExists", "reposDir exists", &git.repos_dir),
+        ("globalDirExists", "globalDir exists", &git.global_dir),
+    ] {
+        let path = repo.join(dir);
+        checks.push(Check::new(
+            key,
+            label,
+            path.is_dir(),
+            path.display().to_string(),
+        ));
+    }
+
+    let remote = is_repo
+        .then(|| GitRepo::open(&repo).ok()?.remote_url())
+        .flatten();
+    checks.push(Check::new(
+        "remote",
+        "remote",
+        true,
+        remote.unwrap_or_else(|| "none (sync stays local)".to_string()),
+    ));
+
+    let duplicates: Vec<String> = thoughts
+        .repo_overlaps(&repo, Some(name))
+        .into_iter()
+        .filter(|(_, overlap)| *overlap == RepoOverlap::Same)
+        .map(|(owner, _)| owner)
+        .collect();
+    checks.push(Check::new(
+        "uniqueRepo",
+        "not shared with another profile",
+        duplicates.is_empty(),
+        if duplicates.is_empty() {
+            "ok".to_string()
+        } else {
+            format!("same repository as {}", duplicates.join(", "))
+        },
+    ));
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProfileConfig, tests::git_backend};
+
+    fn status(checks: &[Check], key: &str) -> bool {
+        checks.iter().find(|c| c.key == key).unwrap().passed
+    }
+
+    #[test]
+    fn validate_reports_each_check() {
+        let default_dir = tempfile::tempdir().unwrap();
+        let work_dir = tempfile::tempdir().unwrap();
+        GitRepo::init(work_dir.path(), None).unwrap();
+        std::fs::create_dir(work_dir.path().join("repos")).unwrap();
+
+        let mut thoughts = ThoughtsConfig {
+            backend: git_backend(default_dir.path()),
+            ..Default::default()
+        };
+        for (name, dir) in [
+            ("work", work_dir.path()),
+            ("same-as-default", default_dir.path()),
+        ] {
+            thoughts.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: git_backend(dir),
+                    ai: None,
+                },
+            );
+        }
+
+        let checks = validate_profile(&thoughts, "work").unwrap();
+        assert!(status(&checks, "thoughtsRepoExists"));
+        assert!(status(&checks, "isGitRepo"));
+        assert!(status(&checks, "reposDirExists"));
+        assert!(!status(&checks, "globalDirExists"));
+        assert!(status(&checks, "remote"));
+        assert!(status(&checks, "uniqueRepo"));
+        assert!(finish("work", Some(&checks)).is_err());
+
+        let checks = validate_profile(&thoughts, "same-as-default").unwrap();
+        assert!(!status(&checks, "isGitRepo"));
+        let unique = checks.iter().find(|c| c.key == "uniqueRepo").unwrap();
+        assert!(!unique.passed);
+        assert_eq!(unique.detail, "same repository as the default config");
+
+        let json = checks_json(&checks);
+        assert_eq!(json["uniqueRepo"]["passed"], false);
+        assert_eq!(json["remote"]["detail"], "none (sync stays local)");
+    }
+}
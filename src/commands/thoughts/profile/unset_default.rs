@@ -0,0 +1,29 @@
+use anyhow::Result;
+use colored::Colorize;
+
+use crate::cli::ProfileUnsetDefaultArgs;
+
+pub fn unset_default(args: ProfileUnsetDefaultArgs) -> Result<()> {
+    args.config
+        .reject_overrides("thoughts profile unset-default")?;
+    if args.config.load_if_exists()?.is_none() {
+        return Err(anyhow::anyhow!("No thoughts configuration found"));
+    }
+
+    let previous = args.config.update(|target| {
+        Ok(target
+            .thoughts
+            .as_mut()
+            .and_then(|t| t.default_profile.take()))
+    })?;
+    let Some(previous) = previous else {
+        println!("{}", "No default profile is set".bright_black());
+        return Ok(());
+    };
+
+    println!(
+        "{}",
+        format!("Cleared default profile (was \"{}\")", previous).green()
+    );
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
+
+use crate::cli::{RebaseAction, RebaseArgs};
+use crate::git_ops::GitRepo;
+
+pub fn rebase(args: RebaseArgs) -> Result<()> {
+    let RebaseArgs {
+        action,
+        interactive,
+        config,
+    } = args;
+
+    let (thoughts_repo, repo) = config.load_git_thoughts_repo("thoughts rebase")?;
+
+    let succeeded = match (action, interactive) {
+        (Some(_), Some(_)) => {
+            return Err(anyhow::anyhow!(
+                "--interactive starts a rebase; it can't be combined with continue, abort or skip"
+            ));
+        }
+        (Some(action), None) => {
+            if !repo.rebase_in_progress() {
+                println!("{}", "No rebase in progress".bright_black());
+                return Ok(());
+            }
+            let flag = match action {
+                RebaseAction::Continue => "--continue",
+                RebaseAction::Abort => "--abort",
+                RebaseAction::Skip => "--skip",
+            };
+            repo.rebase(&[flag])?
+        }
+        (None, Some(base)) => {
+            if repo.rebase_in_progress() {
+                print_stopped(&repo, &thoughts_repo)?;
+                return Err(anyhow::anyhow!("A rebase is already in progress"));
+            }
+            repo.rebase(&["--interactive", &base])?
+        }
+        (None, None) => {
+            if repo.rebase_in_progress() {
+                print_stopped(&repo, &thoughts_repo)?;
+            } else {
+                println!("{}", "No rebase in progress".bright_black());
+            }
+            return Ok(());
+        }
+    };
+
+    if repo.rebase_in_progress() {
+        print_stopped(&repo, &thoughts_repo)?;
+        return Err(anyhow::anyhow!("Rebase stopped before finishing"));
+    }
+    if !succeeded {
+        return Err(anyhow::anyhow!("git rebase failed"));
+    }
+    if action == Some(RebaseAction::Abort) {
+        println!(
+            "{}",
+            "Rebase aborted; the thoughts repo is back where it started".green()
+        );
+    } else {
+        println!("{}", "Rebase finished".green());
+        println!("Run 'hyprlayer thoughts sync' to push the result.");
+    }
+    Ok(())
+}
+
+/// What to do about a rebase that stopped, listing any conflicting files.
+fn print_stopped(repo: &GitRepo, thoughts_repo: &Path) -> Result<()> {
+    let conflicts = repo.conflicted_files()?;
+    if !conflicts.is_empty() {
+        println!("{}", "Conflicting files:".red());
+        for file in &conflicts {
+            println!("  {}", file.yellow());
+        }
+        println!();
+    }
+    println!(
+        "A rebase of {} is in progress. To finish it:",
+        thoughts_repo.display()
+    );
+    println!("  # fix the files, then stage them with: git add <file>");
+    println!("  hyprlayer thoughts rebase continue");
+    println!(
+        "{}",
+        "Or drop the stopped commit with 'hyprlayer thoughts rebase skip', or give up with 'hyprlayer thoughts rebase abort'"
+            .bright_black()
+    );
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::Colorize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::SearchArgs;
+use crate::config::{ThoughtsConfig, get_current_repo_path};
+use crate::search_index::{self, DateRange, Matcher, SearchHit, SearchIndex};
+
+/// A thoughts content root and the profile it belongs to (`default` for the
+/// top-level backend).
+#[derive(Debug, Clone)]
+struct SearchRoot {
+    profile: String,
+    root: PathBuf,
+}
+
+pub fn search(args: SearchArgs) -> Result<()> {
+    let SearchArgs {
+        query,
+        index,
+        regex,
+        after,
+        before,
+        all_profiles,
+        json,
+        config,
+    } = args;
+
+    let now = Local::now();
+    let dates = DateRange {
+        after: after
+            .map(|d| search_index::parse_date(&d, now))
+            .transpose()?,
+        before: before
+            .map(|d| search_index::parse_date(&d, now))
+            .transpose()?,
+    };
+
+    let config_path = config.path()?;
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let mut roots = if all_profiles {
+        all_roots(thoughts_config)
+    } else {
+        vec![current_root(thoughts_config)?]
+    };
+
+    let index = index
+        .then(|| SearchIndex::load(&search_index::index_path(&config_path)))
+        .transpose()?;
+    if let Some(index) = &index {
+        if !all_profiles && !index.covers(&roots[0].root) {
+            let build = match roots[0].profile.as_str() {
+                "default" => "hyprlayer thoughts index build".to_string(),
+                profile => format!("hyprlayer thoughts index build --profile {profile}"),
+            };
+            return Err(anyhow::anyhow!(
+                "No search index for {}. Run '{build}' first.",
+                roots[0].root.display()
+            ));
+        }
+        roots.retain(|r| {
+            let covered = index.covers(&r.root);
+            if !covered {
+                eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: profile {} is not indexed; skipping {}",
+                        r.profile,
+                        r.root.display()
+                    )
+                    .yellow()
+                );
+            }
+            covered
+        });
+    }
+
+    let results = search_roots(&roots, &query, regex, dates, index.as_ref())?;
+
+    if json {
+        let items: Vec<_> = results
+            .iter()
+            .map(|(root, hit)| {
+                json!({
+                    "profile": root.profile,
+                    "path": relative(hit, &root.root).display().to_string(),
+                    "line": hit.line,
+                    "text": search_index::hit_text(hit).unwrap_or_default(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&items)?);
+    } else if all_profiles {
+        print_sections(&results);
+    } else {
+        let hits: Vec<SearchHit> = results.into_iter().map(|(_, hit)| hit).collect();
+        print_hits(&hits, Some(&roots[0].root));
+    }
+    Ok(())
+}
+
+/// The content root of the current repository's effective backend.
+fn current_root(thoughts: &ThoughtsConfig) -> Result<SearchRoot> {
+    let current_repo = get_current_repo_path()?;
+    let effective = thoughts.effective_config_for(&current_repo.display().to_string());
+    let root = effective.backend.content_root().ok_or_else(|| {
+        anyhow::anyhow!(
+            "The {} backend stores thoughts remotely; search is only available for git and obsidian",
+            effective.backend.kind().as_str()
+        )
+    })?;
+    Ok(SearchRoot {
+        profile: effective
+            .profile_name
+            .unwrap_or_else(|| "default".to_string()),
+        root,
+    })
+}
+
+/// The default backend's root followed by each profile's, by name. Remote
+/// backends have nothing on disk to search and are left out.
+fn all_roots(thoughts: &ThoughtsConfig) -> Vec<SearchRoot> {
+    let mut profiles: Vec<_> = thoughts.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| *name);
+
+    std::iter::once(("default", &thoughts.backend))
+        .chain(profiles.into_iter().map(|(n, p)| (n.as_str(), &p.backend)))
+        .filter_map(|(profile, backend)| {
+            backend.content_root().map(|root| SearchRoot {
+                profile: profile.to_string(),
+                root,
+            })
+        })
+        .collect()
+}
+
+/// Search each root in order. A file reachable from several roots (profiles
+/// sharing a thoughts repo) is reported only under the first one.
+fn search_roots(
+    roots: &[SearchRoot],
+    query: &str,
+    regex: bool,
+    dates: DateRange,
+    index: Option<&SearchIndex>,
+) -> Result<Vec<(SearchRoot, SearchHit)>> {
+    let matcher = Matcher::new(query, regex)?;
+    let indexed = index.map(|i| i.search(query, regex)).transpose()?;
+
+    let mut owner: HashMap<PathBuf, usize> = HashMap::new();
+    let mut results = Vec::new();
+    for (idx, root) in roots.iter().enumerate() {
+        // Narrow by date before matching so files outside the range are never read.
+        let files = dates.filter_files(&root.root, search_index::list_files(&root.root)?)?;
+        let hits = match &indexed {
+            Some(hits) => hits
+                .iter()
+                .filter(|hit| files.binary_search(&hit.path).is_ok())
+                .cloned()
+                .collect(),
+            None => search_index::scan(&files, &matcher)?,
+        };
+        for hit in hits {
+            let canonical = fs::canonicalize(&hit.path).unwrap_or_else(|_| hit.path.clone());
+            if *owner.entry(canonical).or_insert(idx) == idx {
+                results.push((root.clone(), hit));
+            }
+        }
+    }
+    Ok(results)
+}
+
+fn relative<'a>(hit: &'a SearchHit, root: &Path) -> &'a Path {
+    hit.path.strip_prefix(root).unwrap_or(&hit.path)
+}
+
+/// `--all-profiles` output: one section per profile with matches, each line
+/// prefixed with the profile name.
+fn print_sections(results: &[(SearchRoot, SearchHit)]) {
+    if results.is_empty() {
+        println!("{}", "No matches found".bright_black());
+        return;
+    }
+    let mut current: Option<&str> = None;
+    for (root, hit) in results {
+        if current != Some(root.profile.as_str()) {
+            if current.is_some() {
+                println!();
+            }
+            println!(
+                "{}",
+                format!("── {} ({}) ──", root.profile, root.root.display()).magenta()
+            );
+            current = Some(&root.profile);
+        }
+        println!(
+            "{}:{}:{}: {}",
+            root.profile.magenta(),
+            relative(hit, &root.root).display().to_string().cyan(),
+            hit.line.to_string().yellow(),
+            search_index::hit_text(hit).unwrap_or_default()
+        );
+    }
+}
+
+/// Print hits as `path:line: text`, with paths relative to `root` when given.
+pub(crate) fn print_hits(hits: &[SearchHit], root: Option<&Path>) {
+    if hits.is_empty() {
+        println!("{}", "No matches found".bright_black());
+        return;
+    }
+    for hit in hits {
+        let path = root.map_or(hit.path.as_path(), |r| relative(hit, r));
+        let text = search_index::hit_text(hit).unwrap_or_default();
+        println!(
+            "{}:{}: {}",
+            path.display().to_string().cyan(),
+            hit.line.to_string().yellow(),
+            text
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ProfileConfig, tests::git_backend};
+    use tempfile::TempDir;
+
+    #[test]
+    fn all_profiles_search_labels_hits_and_dedupes_shared_repos() {
+        let default_dir = TempDir::new().unwrap();
+        let work_dir = TempDir::new().unwrap();
+        fs::write(default_dir.path().join("a.md"), "rollout plan\n").unwrap();
+        fs::write(
+            work_dir.path().join("b.md"),
+            "unrelated\nrollout checklist\n",
+        )
+        .unwrap();
+
+        let mut thoughts = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: git_backend(default_dir.path()),
+            ..Default::default()
+        };
+        for (name, dir) in [("work", work_dir.path()), ("zz-shared", default_dir.path())] {
+            thoughts.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: git_backend(dir),
+                    ai: None,
+                },
+            );
+        }
+
+        let roots = all_roots(&thoughts);
+        let names: Vec<_> = roots.iter().map(|r| r.profile.as_str()).collect();
+        assert_eq!(names, ["default", "work", "zz-shared"]);
+
+        let results = search_roots(&roots, "rollout", false, DateRange::default(), None).unwrap();
+        let found: Vec<_> = results
+            .iter()
+            .map(|(root, hit)| {
+                (
+                    root.profile.as_str(),
+                    relative(hit, &root.root).display().to_string(),
+                    hit.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("default", "a.md".to_string(), 1),
+                ("work", "b.md".to_string(), 2)
+            ]
+        );
+    }
+}
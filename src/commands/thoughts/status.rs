@@ -1,18 +1,247 @@
 use anyhow::Result;
 use colored::Colorize;
-use std::path::MAIN_SEPARATOR_STR as SEP;
+use serde::Serialize;
+use std::path::{MAIN_SEPARATOR_STR as SEP, Path};
 
 use crate::backends::{self, BackendContext};
 use crate::cli::StatusArgs;
-use crate::config::{BackendConfig, get_current_repo_path};
+use crate::config::{
+    AiConfig, BackendConfig, EffectiveConfig, ThoughtsConfig, get_current_repo_path,
+};
+use crate::git_ops::{CommitInfo, FileStatus, GitRepo, WorktreeInfo};
+
+/// `status --json` output. The directory fields are empty for backends
+/// that don't store thoughts on disk; `git` is only set for git backends
+/// whose repository exists.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatusOutput {
+    config_path: String,
+    backend: &'static str,
+    profile: Option<String>,
+    thoughts_repo: String,
+    repos_dir: String,
+    global_dir: String,
+    user: String,
+    current_repo: Option<CurrentRepoStatus>,
+    git: Option<GitStatus>,
+    /// Only set with `--worktrees`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    worktrees: Option<Vec<WorktreeInfo>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CurrentRepoStatus {
+    path: String,
+    mapped_name: String,
+    initialized: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GitStatus {
+    last_commit: Option<CommitInfo>,
+    remote_configured: bool,
+    uncommitted_files: Vec<FileStatus>,
+}
+
+/// One row of `status --all-repos`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RepoStatusSummary {
+    repo_path: String,
+    mapped_name: String,
+    profile: Option<String>,
+    initialized: bool,
+    /// Whether every `thoughts/` link resolves. Always true for backends
+    /// that don't link into a filesystem tree.
+    links_valid: bool,
+    /// hyprlayer git hooks installed in the repository.
+    hooks: Vec<String>,
+}
+
+impl RepoStatusSummary {
+    /// Initialized with working links, and with hooks unless `init
+    /// --no-hooks` turned them off.
+    fn is_set_up(&self, hooks_enabled: bool) -> bool {
+        self.initialized && self.links_valid && (!hooks_enabled || !self.hooks.is_empty())
+    }
+}
+
+/// A summary of every mapped repository, sorted by path.
+fn repo_summaries(thoughts_config: &ThoughtsConfig) -> Result<Vec<RepoStatusSummary>> {
+    let mut paths: Vec<&String> = thoughts_config.repo_mappings.keys().collect();
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let effective = thoughts_config.effective_config_for(path);
+            let repo = Path::new(path);
+            let thoughts_dir = repo.join("thoughts");
+            let initialized = thoughts_dir.is_dir();
+            let links_valid = !initialized
+                || effective.backend.filesystem_repos_dir().is_none()
+                || [effective.user.as_str(), "shared", "global"]
+                    .iter()
+                    .all(|link| thoughts_dir.join(link).exists());
+            let hooks = if repo.is_dir() {
+                crate::hooks::installed_hooks(repo)?
+            } else {
+                Vec::new()
+            };
+            Ok(RepoStatusSummary {
+                repo_path: path.clone(),
+                mapped_name: effective.mapped_name.unwrap_or_default(),
+                profile: effective.profile_name,
+                initialized,
+                links_valid,
+                hooks,
+            })
+        })
+        .collect()
+}
+
+/// Print `summaries` as a table: green rows are fully set up, red ones
+/// not initialized, yellow ones initialized with broken links or no hooks.
+fn print_repo_summaries(summaries: &[RepoStatusSummary], hooks_enabled: bool) {
+    if summaries.is_empty() {
+        println!("{}", "No repositories mapped to thoughts".yellow());
+        return;
+    }
+    let width = |header: &str, cell: fn(&RepoStatusSummary) -> usize| {
+        summaries
+            .iter()
+            .map(cell)
+            .max()
+            .unwrap_or(0)
+            .max(header.len())
+    };
+    let path_width = width("Repository", |s| s.repo_path.len());
+    let name_width = width("Mapped name", |s| s.mapped_name.len());
+    let profile_width = width("Profile", |s| s.profile.as_deref().unwrap_or("-").len());
+    let init_width = "Initialized".len().max("broken links".len());
+
+    println!(
+        "{:<path_width$}  {:<name_width$}  {:<profile_width$}  {:<init_width$}  Hooks",
+        "Repository", "Mapped name", "Profile", "Initialized"
+    );
+    for summary in summaries {
+        let initialized = match (summary.initialized, summary.links_valid) {
+            (false, _) => "no",
+            (true, false) => "broken links",
+            (true, true) => "yes",
+        };
+        let hooks = if summary.hooks.is_empty() {
+            "none".to_string()
+        } else {
+            summary.hooks.join(", ")
+        };
+        let row = format!(
+            "{:<path_width$}  {:<name_width$}  {:<profile_width$}  {:<init_width$}  {}",
+            summary.repo_path,
+            summary.mapped_name,
+            summary.profile.as_deref().unwrap_or("-"),
+            initialized,
+            hooks
+        );
+        let row = if !summary.initialized {
+            row.red()
+        } else if summary.is_set_up(hooks_enabled) {
+            row.green()
+        } else {
+            row.yellow()
+        };
+        println!("{row}");
+    }
+}
+
+fn git_status(repo: &Path) -> Result<Option<GitStatus>> {
+    if !repo.exists() {
+        return Ok(None);
+    }
+    let git = GitRepo::open(repo)?;
+    Ok(Some(GitStatus {
+        last_commit: git.log(1)?.pop(),
+        remote_configured: git.remote_url().is_some(),
+        uncommitted_files: git.status()?,
+    }))
+}
+
+fn status_output(
+    config_path: &Path,
+    current_repo: &Path,
+    effective: &EffectiveConfig,
+) -> Result<StatusOutput> {
+    let (thoughts_repo, repos_dir, global_dir) = match &effective.backend {
+        BackendConfig::Git(g) => (
+            g.thoughts_repo.clone(),
+            g.repos_dir.clone(),
+            g.global_dir.clone(),
+        ),
+        BackendConfig::Obsidian(o) => (
+            o.vault_path.clone(),
+            o.repos_dir.clone(),
+            o.global_dir.clone(),
+        ),
+        BackendConfig::Notion(_) | BackendConfig::Anytype(_) => Default::default(),
+    };
+    let git = if effective.backend.as_git().is_some() {
+        git_status(&effective.thoughts_repo_path()?)?
+    } else {
+        None
+    };
+    Ok(StatusOutput {
+        config_path: config_path.display().to_string(),
+        backend: effective.backend.kind().as_str(),
+        profile: effective.profile_name.clone(),
+        thoughts_repo,
+        repos_dir,
+        global_dir,
+        user: effective.user.clone(),
+        current_repo: effective
+            .mapped_name
+            .as_ref()
+            .map(|mapped_name| CurrentRepoStatus {
+                path: current_repo.display().to_string(),
+                mapped_name: mapped_name.clone(),
+                initialized: current_repo.join("thoughts").exists(),
+            }),
+        git,
+        worktrees: None,
+    })
+}
 
 pub fn status(args: StatusArgs) -> Result<()> {
-    let hyprlayer_config = args.config.load()?;
-    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+    if args.all_repos {
+        let hyprlayer_config = args.config.load()?;
+        let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+        let summaries = repo_summaries(thoughts_config)?;
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&summaries)?);
+        } else {
+            print_repo_summaries(&summaries, thoughts_config.hooks_enabled);
+        }
+        return Ok(());
+    }
 
     let current_repo = get_current_repo_path()?;
     let current_repo_str = current_repo.display().to_string();
-    let effective = thoughts_config.effective_config_for(&current_repo_str);
+    let (hyprlayer_config, effective) = args.config.load_with_profile_for(&current_repo_str)?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let worktrees = if args.worktrees {
+        Some(GitRepo::open(&current_repo)?.list_worktrees()?)
+    } else {
+        None
+    };
+
+    if args.json {
+        let mut output = status_output(&args.config.path()?, &current_repo, &effective)?;
+        output.worktrees = worktrees;
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
 
     println!("{}", "Configuration:".yellow());
     println!("  Backend: {}", effective.backend.kind().as_str().cyan());
@@ -45,6 +274,12 @@ pub fn status(args: StatusArgs) -> Result<()> {
     if let Some(ref mapped_name) = effective.mapped_name {
         println!("{}", "Current Repository:".yellow());
         println!("  Path: {}", current_repo_str.cyan());
+        if GitRepo::is_worktree(&current_repo) {
+            println!(
+                "  Auto-sync: {}",
+                "skipped (linked worktree or submodule)".yellow()
+            );
+        }
 
         if let Some(repos_dir) = effective.backend.filesystem_repos_dir() {
             println!(
@@ -59,13 +294,33 @@ pub fn status(args: StatusArgs) -> Result<()> {
             } else {
                 println!("  Status: {}", "Not initialized".red());
             }
+            if let Some(group) = thoughts_config
+                .find_duplicate_mappings()
+                .into_iter()
+                .find(|paths| paths.contains(&current_repo_str))
+            {
+                let others: Vec<&str> = group
+                    .iter()
+                    .filter(|p| **p != current_repo_str)
+                    .map(String::as_str)
+                    .collect();
+                println!("  Shared with: {}", others.join(", ").yellow());
+            }
         }
     } else {
         println!("{}", "Current repository not mapped to thoughts".yellow());
     }
     println!();
 
-    let agent_tool = hyprlayer_config.ai.as_ref().and_then(|a| a.agent_tool);
+    if let Some(worktrees) = worktrees {
+        print_worktrees(&worktrees);
+        println!();
+    }
+
+    let agent_tool = hyprlayer_config
+        .ai
+        .as_ref()
+        .and_then(AiConfig::primary_tool);
     let ctx = BackendContext::new(&current_repo, &effective).with_agent_tool(agent_tool);
     let backend = backends::for_kind(effective.backend.kind());
     let report = backend.status(&ctx)?;
@@ -75,3 +330,129 @@ pub fn status(args: StatusArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// The post-commit hook only auto-syncs from the main worktree, so linked
+/// ones are flagged.
+fn print_worktrees(worktrees: &[WorktreeInfo]) {
+    println!("{}", "Worktrees:".yellow());
+    for worktree in worktrees {
+        let note = if worktree.is_main {
+            "main".green()
+        } else {
+            "auto-sync skipped".bright_black()
+        };
+        println!(
+            "  {} ({}) {}",
+            worktree.path.display().to_string().cyan(),
+            worktree.branch,
+            note
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::GitConfig;
+    use crate::git_ops::tests::repo_with_identity;
+    use std::fs;
+
+    #[test]
+    fn json_output_reports_repo_and_git_state() {
+        let root = tempfile::tempdir().unwrap();
+        let thoughts = root.path().join("thoughts");
+        let git = repo_with_identity(&thoughts, None);
+        fs::write(thoughts.join("a.md"), "a").unwrap();
+        git.add_all().unwrap();
+        git.commit("First note").unwrap();
+        fs::write(thoughts.join("b.md"), "b").unwrap();
+
+        let code = root.path().join("app");
+        fs::create_dir_all(code.join("thoughts")).unwrap();
+        let effective = EffectiveConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: thoughts.display().to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            profile_name: None,
+            mapped_name: Some("app".to_string()),
+        };
+
+        let output = status_output(Path::new("/cfg.json"), &code, &effective).unwrap();
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["configPath"], "/cfg.json");
+        assert_eq!(json["reposDir"], "repos");
+        assert_eq!(json["user"], "alice");
+        assert_eq!(json["currentRepo"]["mappedName"], "app");
+        assert_eq!(json["currentRepo"]["initialized"], true);
+        assert_eq!(json["git"]["lastCommit"]["summary"], "First note");
+        assert_eq!(json["git"]["lastCommit"]["author"], "Test");
+        assert_eq!(json["git"]["remoteConfigured"], false);
+        assert_eq!(
+            json["git"]["uncommittedFiles"],
+            serde_json::json!([{ "path": "b.md", "status": "untracked" }])
+        );
+    }
+
+    #[test]
+    fn repo_summaries_report_each_mapping_sorted() {
+        let root = tempfile::tempdir().unwrap();
+        let thoughts = root.path().join("thoughts-repo");
+        let ready = root.path().join("b-ready");
+        let fresh = root.path().join("a-fresh");
+        fs::create_dir_all(&fresh).unwrap();
+        GitRepo::init(&ready, None).unwrap();
+        for target in ["repos/ready/alice", "repos/ready/shared", "global"] {
+            fs::create_dir_all(thoughts.join(target)).unwrap();
+        }
+        fs::create_dir(ready.join("thoughts")).unwrap();
+        for (target, link) in [
+            ("repos/ready/alice", "alice"),
+            ("repos/ready/shared", "shared"),
+            ("global", "global"),
+        ] {
+            crate::platform::create_dir_link(
+                &thoughts.join(target),
+                &ready.join("thoughts").join(link),
+            )
+            .unwrap();
+        }
+        crate::hooks::setup_git_hooks(&ready, false, 0).unwrap();
+
+        let mut config = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: thoughts.display().to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            ..ThoughtsConfig::default()
+        };
+        for (path, name) in [(&ready, "ready"), (&fresh, "fresh")] {
+            config.repo_mappings.insert(
+                path.display().to_string(),
+                crate::config::RepoMapping::new(name, &None),
+            );
+        }
+
+        let summaries = repo_summaries(&config).unwrap();
+        assert_eq!(summaries[0].mapped_name, "fresh");
+        assert!(!summaries[0].initialized);
+        assert!(summaries[0].hooks.is_empty());
+        assert!(!summaries[0].is_set_up(true));
+        assert_eq!(summaries[1].mapped_name, "ready");
+        assert!(summaries[1].initialized && summaries[1].links_valid);
+        assert_eq!(summaries[1].hooks, ["pre-commit"]);
+        assert!(summaries[1].is_set_up(true));
+
+        fs::remove_dir_all(thoughts.join("global")).unwrap();
+        let summaries = repo_summaries(&config).unwrap();
+        assert!(!summaries[1].links_valid);
+        let json = serde_json::to_value(&summaries).unwrap();
+        assert_eq!(json[1]["repoPath"], ready.display().to_string());
+        assert_eq!(json[1]["linksValid"], false);
+        assert_eq!(json[0]["profile"], serde_json::Value::Null);
+    }
+}
@@ -1,23 +1,127 @@
 use anyhow::Result;
+use colored::Colorize;
+use std::path::Path;
 
 use crate::backends::{self, BackendContext};
 use crate::cli::SyncArgs;
-use crate::config::get_current_repo_path;
+use crate::config::{EffectiveConfig, HyprlayerConfig, expand_path, get_current_repo_path};
+use crate::git_ops::GitRepo;
+use crate::search_index::{self, SearchIndex};
+use crate::secrets::SecretsPolicy;
 
 pub fn sync(args: SyncArgs) -> Result<()> {
-    let SyncArgs { message, config } = args;
+    let SyncArgs {
+        message,
+        no_interactive,
+        thoughts_repo,
+        config,
+    } = args;
+    if crate::hooks::sync_disabled() {
+        return Ok(());
+    }
 
-    let hyprlayer_config = config.load()?;
-    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+    let config_path = config.path()?;
+    if let Some(thoughts_repo) = thoughts_repo {
+        let hyprlayer_config = config.load_if_exists()?.unwrap_or_default();
+        return sync_thoughts_repo(
+            &config_path,
+            &hyprlayer_config,
+            &expand_path(&thoughts_repo),
+            message.as_deref(),
+            !no_interactive,
+        );
+    }
 
     let current_repo = get_current_repo_path()?;
-    let current_repo_str = current_repo.display().to_string();
-    let effective = thoughts_config.effective_config_for(&current_repo_str);
+    let (hyprlayer_config, effective) =
+        config.load_with_profile_for(&current_repo.display().to_string())?;
+    sync_repo(
+        &config_path,
+        &hyprlayer_config,
+        &current_repo,
+        &effective,
+        message.as_deref(),
+        !no_interactive,
+    )
+}
 
-    let agent_tool = hyprlayer_config.ai.as_ref().and_then(|a| a.agent_tool);
-    let ctx = BackendContext::new(&current_repo, &effective).with_agent_tool(agent_tool);
+/// Sync `repo` through `effective`'s backend, then refresh the search index
+/// if one exists. Unless `interactive`, likely secrets in the files to
+/// commit fail the sync instead of prompting.
+pub fn sync_repo(
+    config_path: &Path,
+    hyprlayer_config: &HyprlayerConfig,
+    repo: &Path,
+    effective: &EffectiveConfig,
+    message: Option<&str>,
+    interactive: bool,
+) -> Result<()> {
+    let agent_tool = hyprlayer_config
+        .effective_agent_tool_for_repo(Some(&repo.display().to_string()))
+        .copied();
+    let ctx = BackendContext::new(repo, effective)
+        .with_agent_tool(agent_tool)
+        .with_secrets_policy(secrets_policy(hyprlayer_config, interactive));
     let backend = backends::for_kind(effective.backend.kind());
-    backend.sync(&ctx, message.as_deref())?;
+    backend.sync(&ctx, message)?;
+
+    if let Ok(root) = effective.thoughts_repo_path() {
+        refresh_index(config_path, &root);
+    }
+    Ok(())
+}
+
+/// Commit, pull and push the thoughts repository at `thoughts_repo` on its
+/// own, skipping the code repository and its searchable directory.
+fn sync_thoughts_repo(
+    config_path: &Path,
+    hyprlayer_config: &HyprlayerConfig,
+    thoughts_repo: &Path,
+    message: Option<&str>,
+    interactive: bool,
+) -> Result<()> {
+    if !GitRepo::is_repo(thoughts_repo) {
+        anyhow::bail!("{} is not a git repository", thoughts_repo.display());
+    }
+    backends::git::sync_thoughts_repo(
+        thoughts_repo,
+        message,
+        secrets_policy(hyprlayer_config, interactive),
+    )?;
+    refresh_index(config_path, thoughts_repo);
+    Ok(())
+}
+
+fn secrets_policy(hyprlayer_config: &HyprlayerConfig, interactive: bool) -> SecretsPolicy {
+    let skip_scan = hyprlayer_config
+        .thoughts
+        .as_ref()
+        .is_some_and(|t| t.skip_secrets_scan);
+    match (skip_scan, interactive) {
+        (true, _) => SecretsPolicy::Skip,
+        (false, true) => SecretsPolicy::Confirm,
+        (false, false) => SecretsPolicy::Refuse,
+    }
+}
+
+/// Keep an existing search index current; never create one implicitly.
+fn refresh_index(config_path: &Path, root: &Path) {
+    let index_path = search_index::index_path(config_path);
+    if search_index::index_exists(&index_path)
+        && let Err(e) = update_index(&index_path, root)
+    {
+        println!(
+            "{}",
+            format!("Warning: could not update search index: {e}").yellow()
+        );
+    }
+}
 
+fn update_index(index_path: &Path, root: &Path) -> Result<()> {
+    let mut index = SearchIndex::load(index_path)?;
+    let update = index.update_root(root)?;
+    if update.added + update.updated + update.removed > 0 {
+        index.save(index_path)?;
+    }
     Ok(())
 }
@@ -0,0 +1,508 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::{Confirm, theme::ColorfulTheme};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use super::config_cmd::editor;
+use super::sync::sync_repo;
+use crate::backends::git::find_files_following_symlinks;
+use crate::cli::TuiArgs;
+use crate::config::{EffectiveConfig, HyprlayerConfig, get_current_repo_path};
+
+/// One line of the file tree: a directory or a file, relative to the root.
+#[derive(Debug, Clone, PartialEq)]
+struct Row {
+    path: PathBuf,
+    depth: usize,
+    is_dir: bool,
+}
+
+/// What the tree pane is reading keys for.
+#[derive(Debug, PartialEq)]
+enum Mode {
+    Browse,
+    /// Typing the name of a new file to create under `dir`.
+    NewFile {
+        dir: PathBuf,
+        name: String,
+    },
+}
+
+/// Work that has to leave the terminal UI to run.
+#[derive(Debug, PartialEq)]
+enum Action {
+    Edit(PathBuf),
+    Create(PathBuf),
+    Sync,
+}
+
+struct App {
+    root: PathBuf,
+    title: String,
+    files: Vec<PathBuf>,
+    collapsed: HashSet<PathBuf>,
+    rows: Vec<Row>,
+    list: ListState,
+    mode: Mode,
+    message: String,
+    modified: bool,
+    quit: bool,
+}
+
+pub fn tui(args: TuiArgs) -> Result<()> {
+    let TuiArgs { profile, config } = args;
+
+    let config_path = config.path()?;
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config.thoughts.as_ref().unwrap();
+
+    let current_repo = get_current_repo_path()?;
+    let mut effective = thoughts_config.effective_config_for(&current_repo.display().to_string());
+    let root = match thoughts_config.resolve_profile(profile)? {
+        Some(name) => {
+            effective.backend = thoughts_config.profiles[&name].backend.clone();
+            effective.profile_name = Some(name);
+            effective.backend.content_root().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "The {} backend stores thoughts remotely; there is nothing to browse",
+                    effective.backend.kind().as_str()
+                )
+            })?
+        }
+        None => current_repo.join("thoughts"),
+    };
+    if !root.is_dir() {
+        return Err(anyhow::anyhow!(
+            "No thoughts found at {}. Run 'hyprlayer thoughts init' first.",
+            root.display()
+        ));
+    }
+
+    let title = format!(
+        "{} ({})",
+        root.display(),
+        effective.profile_name.as_deref().unwrap_or("default")
+    );
+    let mut app = App::new(root, title)?;
+
+    let mut terminal = ratatui::try_init()?;
+    let result = run(
+        &mut terminal,
+        &mut app,
+        &config_path,
+        &hyprlayer_config,
+        &current_repo,
+        &effective,
+    );
+    ratatui::try_restore()?;
+    result?;
+
+    if app.modified
+        && Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Thoughts were modified. Sync now?")
+            .default(true)
+            .interact()?
+    {
+        sync_repo(
+            &config_path,
+            &hyprlayer_config,
+            &current_repo,
+            &effective,
+            None,
+            true,
+        )?;
+        println!("{}", "Thoughts synced".green());
+    }
+    Ok(())
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    config_path: &Path,
+    hyprlayer_config: &HyprlayerConfig,
+    repo: &Path,
+    effective: &EffectiveConfig,
+) -> Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| app.draw(frame))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let Some(action) = app.handle_key(key) else {
+            continue;
+        };
+
+        // Everything below prints or runs an editor, so hand the terminal
+        // back for the duration.
+        ratatui::try_restore()?;
+        let mut select = None;
+        match action {
+            Action::Edit(path) => app.edit(&path, false),
+            Action::Create(path) => {
+                app.edit(&path, true);
+                select = Some(path);
+            }
+            Action::Sync => {
+                match sync_repo(config_path, hyprlayer_config, repo, effective, None, true) {
+                    Ok(()) => {
+                        app.modified = false;
+                        app.message = "Thoughts synced".to_string();
+                    }
+                    Err(e) => app.message = format!("Sync failed: {e:#}"),
+                }
+            }
+        }
+        *terminal = ratatui::try_init()?;
+        app.refresh(select.as_deref())?;
+    }
+    Ok(())
+}
+
+impl App {
+    fn new(root: PathBuf, title: String) -> Result<Self> {
+        let mut app = Self {
+            root,
+            title,
+            files: Vec::new(),
+            collapsed: HashSet::new(),
+            rows: Vec::new(),
+            list: ListState::default(),
+            mode: Mode::Browse,
+            message: String::new(),
+            modified: false,
+            quit: false,
+        };
+        app.refresh(None)?;
+        Ok(app)
+    }
+
+    /// Re-read the tree from disk and select `select`, or else keep the
+    /// selection on the same path where it still exists.
+    fn refresh(&mut self, select: Option<&Path>) -> Result<()> {
+        self.files = find_files_following_symlinks(&self.root, &self.root, &mut HashSet::new())?;
+        let selected = select
+            .map(Path::to_path_buf)
+            .or_else(|| self.selected().map(|row| row.path.clone()));
+        self.rebuild(selected.as_deref());
+        Ok(())
+    }
+
+    fn rebuild(&mut self, select: Option<&Path>) {
+        self.rows = build_rows(&self.files, &self.collapsed);
+        let index = select
+            .and_then(|path| self.rows.iter().position(|row| row.path == path))
+            .or_else(|| self.list.selected())
+            .map(|i| i.min(self.rows.len().saturating_sub(1)));
+        self.list.select(if self.rows.is_empty() {
+            None
+        } else {
+            index.or(Some(0))
+        });
+    }
+
+    fn selected(&self) -> Option<&Row> {
+        self.list.selected().and_then(|i| self.rows.get(i))
+    }
+
+    /// The directory a new file goes in: the selected directory, or the
+    /// selected file's.
+    fn selected_dir(&self) -> PathBuf {
+        match self.selected() {
+            Some(row) if row.is_dir => row.path.clone(),
+            Some(row) => row.path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            None => PathBuf::new(),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if let Mode::NewFile { dir, name } = &mut self.mode {
+            match key.code {
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Esc => self.mode = Mode::Browse,
+                KeyCode::Enter => {
+                    let path = new_file_path(dir, name);
+                    self.mode = Mode::Browse;
+                    match path {
+                        Ok(path) if self.root.join(&path).exists() => {
+                            self.message = format!("{} already exists", path.display());
+                        }
+                        Ok(path) => return Some(Action::Create(path)),
+                        Err(e) => self.message = e.to_string(),
+                    }
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        self.message.clear();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char('s') => return Some(Action::Sync),
+            KeyCode::Char('n') => {
+                self.mode = Mode::NewFile {
+                    dir: self.selected_dir(),
+                    name: String::new(),
+                };
+            }
+            KeyCode::Up => self.list.select_previous(),
+            KeyCode::Down if self.list.selected() < Some(self.rows.len().saturating_sub(1)) => {
+                self.list.select_next()
+            }
+            KeyCode::Enter => {
+                let row = self.selected()?.clone();
+                if !row.is_dir {
+                    return Some(Action::Edit(row.path));
+                }
+                if !self.collapsed.remove(&row.path) {
+                    self.collapsed.insert(row.path.clone());
+                }
+                self.rebuild(Some(&row.path));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Open `path` in the editor, creating it first when `create`. Runs
+    /// with the terminal UI suspended; failures land in the status bar.
+    fn edit(&mut self, path: &Path, create: bool) {
+        let full = self.root.join(path);
+        let modified_at = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+
+        if create {
+            let written = full
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(&full, ""));
+            if let Err(e) = written {
+                self.message = format!("Could not create {}: {e}", path.display());
+                return;
+            }
+            self.modified = true;
+            self.collapsed.retain(|dir| !path.starts_with(dir));
+        }
+
+        let before: Option<SystemTime> = modified_at(&full);
+        let editor = editor();
+        match Command::new(&editor).arg(&full).status() {
+            Ok(status) if status.success() => {}
+            Ok(status) => self.message = format!("{editor} exited with {status}"),
+            Err(e) => self.message = format!("Could not run {editor}: {e}"),
+        }
+        if modified_at(&full) != before {
+            self.modified = true;
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [tree, preview] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let name = row.path.file_name().unwrap_or_default().to_string_lossy();
+                let indent = "  ".repeat(row.depth);
+                if row.is_dir {
+                    let marker = if self.collapsed.contains(&row.path) {
+                        '▸'
+                    } else {
+                        '▾'
+                    };
+                    ListItem::new(format!("{indent}{marker} {name}/"))
+                        .style(Style::default().fg(Color::Blue))
+                } else {
+                    ListItem::new(format!("{indent}  {name}"))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(self.title.as_str()),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree, &mut self.list);
+
+        let (title, body) = match self.selected() {
+            Some(row) if !row.is_dir => (
+                row.path.display().to_string(),
+                fs::read(self.root.join(&row.path))
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .unwrap_or_else(|e| format!("Could not read file: {e}")),
+            ),
+            Some(row) => (row.path.display().to_string(), String::new()),
+            None => (
+                String::new(),
+                "No thoughts yet. Press n to create one.".to_string(),
+            ),
+        };
+        let preview_widget = Paragraph::new(body)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(preview_widget, preview);
+
+        let status_line = match &self.mode {
+            Mode::NewFile { dir, name } => {
+                Line::from(format!("New file in {}/: {name}", dir.display()))
+            }
+            Mode::Browse if !self.message.is_empty() => Line::from(self.message.as_str()),
+            Mode::Browse => Line::from(format!(
+                "↑/↓ move  Enter edit  n new  s sync  q quit{}",
+                if self.modified { "  [modified]" } else { "" }
+            )),
+        };
+        frame.render_widget(
+            Paragraph::new(status_line).style(Style::default().add_modifier(Modifier::REVERSED)),
+            status,
+        );
+    }
+}
+
+/// Tree rows for `files` (paths relative to the root), sorted, with each
+/// directory listed before its contents. Nothing below a `collapsed`
+/// directory is listed.
+fn build_rows(files: &[PathBuf], collapsed: &HashSet<PathBuf>) -> Vec<Row> {
+    let mut files: Vec<&PathBuf> = files.iter().collect();
+    files.sort();
+    files.dedup();
+
+    let mut seen = BTreeSet::new();
+    let mut rows = Vec::new();
+    'files: for file in files {
+        let mut dir = PathBuf::new();
+        let components: Vec<_> = file.components().collect();
+        for (depth, component) in components[..components.len() - 1].iter().enumerate() {
+            dir.push(component);
+            if seen.insert(dir.clone()) {
+                rows.push(Row {
+                    path: dir.clone(),
+                    depth,
+                    is_dir: true,
+                });
+            }
+            if collapsed.contains(&dir) {
+                continue 'files;
+            }
+        }
+        rows.push(Row {
+            path: file.clone(),
+            depth: components.len() - 1,
+            is_dir: false,
+        });
+    }
+    rows
+}
+
+/// The path, relative to the root, for a new file typed as `name` in
+/// `dir`. Names without an extension get `.md`.
+fn new_file_path(dir: &Path, name: &str) -> Result<PathBuf> {
+    let name = Path::new(name.trim());
+    if name.as_os_str().is_empty() {
+        return Err(anyhow::anyhow!("File name cannot be empty"));
+    }
+    if !name.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(anyhow::anyhow!(
+            "File name must stay inside {}/",
+            dir.display()
+        ));
+    }
+    let mut path = dir.join(name);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn press(app: &mut App, code: KeyCode) -> Option<Action> {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn build_rows_lists_directories_before_their_contents() {
+        let files = [
+            PathBuf::from("shared/plans/b.md"),
+            PathBuf::from("alice/notes.md"),
+            PathBuf::from("shared/a.md"),
+        ];
+        let rows = build_rows(&files, &HashSet::new());
+        let listed: Vec<(String, usize)> = rows
+            .iter()
+            .map(|r| (r.path.display().to_string(), r.depth))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("alice".to_string(), 0),
+                ("alice/notes.md".to_string(), 1),
+                ("shared".to_string(), 0),
+                ("shared/a.md".to_string(), 1),
+                ("shared/plans".to_string(), 1),
+                ("shared/plans/b.md".to_string(), 2),
+            ]
+        );
+
+        let collapsed = HashSet::from([PathBuf::from("shared")]);
+        let rows = build_rows(&files, &collapsed);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[2].path, Path::new("shared"));
+    }
+
+    #[test]
+    fn new_file_is_typed_into_the_selected_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("shared")).unwrap();
+        fs::write(dir.path().join("shared/a.md"), "a").unwrap();
+        let mut app = App::new(dir.path().to_path_buf(), String::new()).unwrap();
+
+        assert_eq!(press(&mut app, KeyCode::Down), None);
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            Some(Action::Edit("shared/a.md".into()))
+        );
+        assert_eq!(press(&mut app, KeyCode::Char('n')), None);
+        for c in "plan".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(
+            press(&mut app, KeyCode::Enter),
+            Some(Action::Create("shared/plan.md".into()))
+        );
+        assert_eq!(app.mode, Mode::Browse);
+
+        press(&mut app, KeyCode::Char('n'));
+        for c in "../x".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        assert_eq!(press(&mut app, KeyCode::Enter), None);
+        assert!(app.message.contains("must stay inside"));
+    }
+}
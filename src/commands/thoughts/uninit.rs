@@ -1,19 +1,26 @@
 use anyhow::Result;
+use colored::Colorize;
 use std::fs;
 use std::path::Path;
 
 use crate::cli::UninitArgs;
 use crate::config::{HyprlayerConfig, get_current_repo_path};
+use crate::hooks;
 
 fn remove_from_config(config_path: &Path, repo_key: &str) -> Result<()> {
-    let mut config = HyprlayerConfig::load(config_path)?;
-    config.thoughts_mut().repo_mappings.remove(repo_key);
-    config.save(config_path)?;
-    Ok(())
+    HyprlayerConfig::update(config_path, |config| {
+        config.thoughts_mut().repo_mappings.remove(repo_key);
+        Ok(())
+    })
 }
 
 pub fn uninit(args: UninitArgs) -> Result<()> {
-    let UninitArgs { force, config } = args;
+    let UninitArgs {
+        force,
+        remove_hooks,
+        keep_hooks,
+        config,
+    } = args;
     let current_repo = get_current_repo_path()?;
     let thoughts_dir = current_repo.join("thoughts");
 
@@ -35,7 +42,8 @@ pub fn uninit(args: UninitArgs) -> Result<()> {
         ));
     }
 
-    if thoughts_dir.exists() {
+    let removed_dir = thoughts_dir.exists();
+    if removed_dir {
         let searchable_dir = thoughts_dir.join("searchable");
         if searchable_dir.exists() {
             #[cfg(unix)]
@@ -54,5 +62,41 @@ pub fn uninit(args: UninitArgs) -> Result<()> {
         remove_from_config(&config_path, &current_repo_str)?;
     }
 
+    println!(
+        "{}",
+        format!("Removed thoughts setup from {}", current_repo.display()).green()
+    );
+    if removed_dir {
+        println!("  thoughts/ directory: {}", "removed".green());
+    }
+    if is_mapped {
+        println!("  Repository mapping: {}", "removed".green());
+    }
+
+    if remove_hooks {
+        let removed = hooks::remove_git_hooks(&current_repo, None, true)?;
+        let status = if removed.is_empty() {
+            "none installed".bright_black()
+        } else {
+            format!("removed {}", removed.join(", ")).green()
+        };
+        println!("  Git hooks: {}", status);
+    } else {
+        let installed = hooks::installed_hooks(&current_repo)?;
+        if !installed.is_empty() {
+            println!(
+                "  Git hooks: {}",
+                format!("kept {}", installed.join(", ")).cyan()
+            );
+            if !keep_hooks {
+                println!(
+                    "{}",
+                    "Git hooks are still installed. Use --remove-hooks to also remove them, or --keep-hooks to suppress this message."
+                        .yellow()
+                );
+            }
+        }
+    }
+
     Ok(())
 }
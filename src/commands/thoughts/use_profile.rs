@@ -0,0 +1,189 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::{Select, theme::ColorfulTheme};
+
+use super::init::dispatch_backend_init;
+use crate::backends::GitSetup;
+use crate::cli::UseProfileArgs;
+use crate::config::{BackendConfig, BackendKind, RepoMapping, get_current_repo_path};
+use crate::git_ops::GitRepo;
+use crate::platform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentAction {
+    Move,
+    Copy,
+    Leave,
+}
+
+impl ContentAction {
+    fn verb(self) -> &'static str {
+        match self {
+            ContentAction::Move => "Move",
+            ContentAction::Copy => "Copy",
+            ContentAction::Leave => "Leave",
+        }
+    }
+
+    fn past(self) -> &'static str {
+        match self {
+            ContentAction::Move => "Moved",
+            ContentAction::Copy => "Copied",
+            ContentAction::Leave => "Left",
+        }
+    }
+}
+
+pub fn use_profile(args: UseProfileArgs) -> Result<()> {
+    let UseProfileArgs {
+        name,
+        move_content,
+        copy,
+        leave,
+        config,
+    } = args;
+
+    let mut hyprlayer_config = config.load()?;
+    let thoughts = hyprlayer_config.thoughts.as_mut().unwrap();
+
+    let current_repo = get_current_repo_path()?;
+    let current_repo_str = current_repo.display().to_string();
+    let current = thoughts.effective_config_for(&current_repo_str);
+    let mapped_name = current.mapped_name.clone().ok_or_else(|| {
+        anyhow::anyhow!(
+            "This repository is not initialized. Run 'hyprlayer thoughts init --profile {name}' instead."
+        )
+    })?;
+
+    let target = (name != "default")
+        .then(|| thoughts.resolve_profile_name(&name))
+        .transpose()?;
+    let target = target.as_deref();
+    let target_label = target.unwrap_or("default");
+
+    if current.profile_name.as_deref() == target {
+        println!(
+            "{}",
+            format!("Repository already uses profile \"{target_label}\"").bright_black()
+        );
+        return Ok(());
+    }
+
+    let new_backend = match target {
+        Some(profile) => thoughts.profiles[profile].backend.clone(),
+        None => thoughts.backend.clone(),
+    };
+    let old_dir = current.mapped_thoughts_path().ok();
+    let new_dir = new_backend
+        .repos_dir_path()
+        .map(|repos| repos.join(&mapped_name));
+
+    let action = match (&old_dir, &new_dir) {
+        (Some(old), Some(new)) if old.exists() && old != new => {
+            let action = choose_action(move_content, copy, leave)?;
+            if action != ContentAction::Leave && new.exists() {
+                return Err(anyhow::anyhow!(
+                    "{} already exists in the target thoughts repository. Use --leave to keep both copies separate.",
+                    new.display()
+                ));
+            }
+            action
+        }
+        _ => ContentAction::Leave,
+    };
+
+    if let (Some(old), Some(new)) = (&old_dir, &new_dir) {
+        match action {
+            ContentAction::Move => platform::move_dir(old, new)?,
+            ContentAction::Copy => platform::copy_dir(old, new)?,
+            ContentAction::Leave => {}
+        }
+        if action != ContentAction::Leave {
+            println!(
+                "  {} {} -> {}",
+                action.past().green(),
+                old.display().to_string().cyan(),
+                new.display().to_string().cyan()
+            );
+        }
+    }
+
+    let mapping = RepoMapping::new(&mapped_name, &target.map(str::to_string));
+    thoughts
+        .repo_mappings
+        .insert(current_repo_str.clone(), mapping.clone());
+    config.update(|config| {
+        config
+            .thoughts_mut()
+            .repo_mappings
+            .insert(current_repo_str, mapping);
+        Ok(())
+    })?;
+
+    // Lays out the new tree and re-points the `thoughts/` links.
+    dispatch_backend_init(
+        &hyprlayer_config,
+        &current_repo,
+        new_backend.kind(),
+        GitSetup::default(),
+    )?;
+
+    if action != ContentAction::Leave {
+        let message = format!(
+            "{} {mapped_name} thoughts to profile {target_label}",
+            action.verb()
+        );
+        if action == ContentAction::Move {
+            commit_if_git(&current.backend, &message)?;
+        }
+        commit_if_git(&new_backend, &message)?;
+    }
+
+    println!(
+        "{}",
+        format!("Repository now uses profile \"{target_label}\"").green()
+    );
+    Ok(())
+}
+
+fn choose_action(move_content: bool, copy: bool, leave: bool) -> Result<ContentAction> {
+    if move_content {
+        return Ok(ContentAction::Move);
+    }
+    if copy {
+        return Ok(ContentAction::Copy);
+    }
+    if leave {
+        return Ok(ContentAction::Leave);
+    }
+    let actions = [
+        ContentAction::Move,
+        ContentAction::Copy,
+        ContentAction::Leave,
+    ];
+    let selection = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt("What should happen to this repository's existing notes?")
+        .items(&[
+            "Move them to the new thoughts repository",
+            "Copy them, keeping the originals",
+            "Leave them where they are",
+        ])
+        .default(0)
+        .interact()?;
+    Ok(actions[selection])
+}
+
+fn commit_if_git(backend: &BackendConfig, message: &str) -> Result<()> {
+    if backend.kind() != BackendKind::Git {
+        return Ok(());
+    }
+    let Some(root) = backend.content_root() else {
+        return Ok(());
+    };
+    let git = GitRepo::open(&root)?;
+    git.add_all()?;
+    if git.has_changes()? {
+        git.commit(message)?;
+    }
+    Ok(())
+}
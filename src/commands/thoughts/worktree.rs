@@ -0,0 +1,190 @@
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::cli::{ConfigArgs, WorktreeAddArgs, WorktreeListArgs};
+use crate::config::{ThoughtsConfig, expand_path, get_current_repo_path};
+use crate::git_ops::{GitRepo, WorktreeInfo};
+
+/// A worktree as reported by `thoughts worktree list`. `thoughts` is the
+/// mapped thoughts directory, when the worktree has thoughts linked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeThoughts {
+    pub path: PathBuf,
+    pub branch: String,
+    pub is_main: bool,
+    pub thoughts: Option<String>,
+}
+
+pub fn add(args: WorktreeAddArgs) -> Result<()> {
+    let WorktreeAddArgs {
+        path,
+        branch,
+        config,
+    } = args;
+
+    // The child `thoughts init` saves the config, which `--set` can't apply to;
+    // refuse before the worktree exists rather than leave it half linked.
+    config.reject_overrides("thoughts worktree add")?;
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config
+        .thoughts
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
+    let current_repo = get_current_repo_path()?;
+    let effective = thoughts_config.effective_config_for(&current_repo.display().to_string());
+    let mapped_name = effective.mapped_name.ok_or_else(|| {
+        anyhow::anyhow!(
+            "Thoughts not initialized for this repository. Run 'hyprlayer thoughts init' first."
+        )
+    })?;
+
+    let worktree = std::path::absolute(expand_path(&path))?;
+    GitRepo::open(&current_repo)?.add_worktree(&worktree, branch.as_deref())?;
+    println!(
+        "{}",
+        format!("Created worktree at {}", worktree.display()).green()
+    );
+
+    // `init` in the new worktree, mapped to the same thoughts directory.
+    let mut init = Command::new(std::env::current_exe()?);
+    init.args(["thoughts", "init", "--yes", "--directory", &mapped_name])
+        .args(config_flags(&config))
+        .current_dir(&worktree);
+    if let Some(profile) = &effective.profile_name {
+        init.args(["--profile", profile]);
+    }
+    let status = init.status().context("Failed to run thoughts init")?;
+    if !status.success() {
+        return Err(anyhow::anyhow!(
+            "The worktree was created, but linking its thoughts failed. Run 'hyprlayer thoughts init --directory {}' in {}.",
+            mapped_name,
+            worktree.display()
+        ));
+    }
+    Ok(())
+}
+
+pub fn list(args: WorktreeListArgs) -> Result<()> {
+    let WorktreeListArgs { json, config } = args;
+
+    let hyprlayer_config = config.load()?;
+    let thoughts_config = hyprlayer_config
+        .thoughts
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Thoughts not configured"))?;
+    let worktrees = GitRepo::open(&get_current_repo_path()?)?.list_worktrees()?;
+    let rows = with_thoughts(worktrees, thoughts_config);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+    for row in &rows {
+        let thoughts = match &row.thoughts {
+            Some(name) => format!("thoughts: {name}").green(),
+            None => "no thoughts".bright_black(),
+        };
+        let main = if row.is_main { " (main)" } else { "" };
+        println!(
+            "  {} ({}){} {}",
+            row.path.display().to_string().cyan(),
+            row.branch,
+            main,
+            thoughts
+        );
+    }
+    Ok(())
+}
+
+/// `worktrees` with the thoughts directory each is mapped to, if it's
+/// mapped and its `thoughts/` link exists.
+fn with_thoughts(
+    worktrees: Vec<WorktreeInfo>,
+    thoughts_config: &ThoughtsConfig,
+) -> Vec<WorktreeThoughts> {
+    worktrees
+        .into_iter()
+        .map(|worktree| {
+            let thoughts = thoughts_config
+                .effective_config_for(&worktree.path.display().to_string())
+                .mapped_name
+                .filter(|_| worktree.path.join("thoughts").is_dir());
+            WorktreeThoughts {
+                path: worktree.path,
+                branch: worktree.branch,
+                is_main: worktree.is_main,
+                thoughts,
+            }
+        })
+        .collect()
+}
+
+/// `config`'s files as command-line flags, to hand on to a child
+/// `hyprlayer thoughts init`, which rejects `--set`.
+fn config_flags(config: &ConfigArgs) -> Vec<String> {
+    config
+        .config_file
+        .iter()
+        .flat_map(|file| ["--config-file".to_string(), file.clone()])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RepoMapping;
+    use std::fs;
+
+    #[test]
+    fn with_thoughts_needs_mapping_and_link() {
+        let root = tempfile::tempdir().unwrap();
+        let worktree = |name: &str, is_main| {
+            let path = root.path().join(name);
+            fs::create_dir_all(&path).unwrap();
+            WorktreeInfo {
+                path,
+                branch: name.to_string(),
+                is_main,
+            }
+        };
+        let main = worktree("main", true);
+        let linked = worktree("linked", false);
+        let unmapped = worktree("unmapped", false);
+        fs::create_dir(main.path.join("thoughts")).unwrap();
+        fs::create_dir(unmapped.path.join("thoughts")).unwrap();
+
+        let mut thoughts_config = ThoughtsConfig::default();
+        for worktree in [&main, &linked] {
+            thoughts_config.repo_mappings.insert(
+                worktree.path.display().to_string(),
+                RepoMapping::new("app", &None),
+            );
+        }
+
+        let thoughts: Vec<Option<String>> = with_thoughts(
+            vec![main.clone(), linked.clone(), unmapped.clone()],
+            &thoughts_config,
+        )
+        .into_iter()
+        .map(|row| row.thoughts)
+        .collect();
+        assert_eq!(thoughts, [Some("app".to_string()), None, None]);
+    }
+
+    #[test]
+    fn config_flags_pass_config_files_but_not_overrides() {
+        let config = ConfigArgs {
+            config_file: vec!["base.json".to_string(), "local.json".to_string()],
+            overrides: vec!["thoughts.user=someone".to_string()],
+        };
+        assert_eq!(
+            config_flags(&config),
+            ["--config-file", "base.json", "--config-file", "local.json"]
+        );
+        assert!(config.reject_overrides("thoughts worktree add").is_err());
+    }
+}
@@ -0,0 +1,17 @@
+use anyhow::Result;
+
+use crate::cli::{VERSION, VersionArgs};
+
+pub fn version(args: VersionArgs) -> Result<()> {
+    println!("hyprlayer {VERSION}");
+    if args.changelog {
+        let tag = format!("v{}", env!("CARGO_PKG_VERSION"));
+        let changelog = crate::version::fetch_changelog(&tag)?;
+        if changelog.trim().is_empty() {
+            println!("\nNo release notes for {tag}");
+        } else {
+            println!("\n{}", changelog.trim());
+        }
+    }
+    Ok(())
+}
@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::ffi::OsString;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use crate::agents::{AgentTool, OpenCodeProvider};
 
@@ -116,6 +120,18 @@ impl BackendConfig {
         }
     }
 
+    /// Local directory holding thoughts files, for backends that keep them
+    /// on disk. Notion and Anytype store content remotely.
+    pub fn content_root(&self) -> Option<PathBuf> {
+        match self {
+            BackendConfig::Git(g) if !g.thoughts_repo.is_empty() => {
+                Some(expand_path(&g.thoughts_repo))
+            }
+            BackendConfig::Obsidian(o) => o.obsidian_root(),
+            _ => None,
+        }
+    }
+
     pub fn as_git(&self) -> Option<&GitConfig> {
         if let Self::Git(c) = self {
             Some(c)
@@ -207,19 +223,216 @@ impl BackendConfig {
             BackendConfig::Notion(_) | BackendConfig::Anytype(_) => None,
         }
     }
+
+    /// `<content root>/<reposDir>`, for filesystem-backed backends with a
+    /// content root set.
+    pub fn repos_dir_path(&self) -> Option<PathBuf> {
+        Some(self.content_root()?.join(self.filesystem_repos_dir()?))
+    }
+
+    /// The `global_dir` counterpart of `filesystem_repos_dir`.
+    pub fn filesystem_global_dir(&self) -> Option<&str> {
+        match self {
+            BackendConfig::Git(g) => Some(&g.global_dir),
+            BackendConfig::Obsidian(o) => Some(&o.global_dir),
+            BackendConfig::Notion(_) | BackendConfig::Anytype(_) => None,
+        }
+    }
+}
+
+const SENSITIVE_KEY_PARTS: [&str; 4] = ["token", "key", "secret", "password"];
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Whether a JSON field name looks like it holds a credential. Fields
+/// ending in `Env` (e.g. `apiTokenEnv`) name an environment variable rather
+/// than holding the secret itself, so they stay visible.
+fn is_sensitive_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    !lower.ends_with("env") && SENSITIVE_KEY_PARTS.iter().any(|part| lower.contains(part))
+}
+
+/// Replace every non-null value whose field name contains `token`, `key`,
+/// `secret` or `password` (case-insensitive) with `"[REDACTED]"`, at any
+/// depth. Keys of `repoMappings` and `profiles` are paths and user-chosen
+/// names, not field names, so they are never matched.
+pub fn redact_json(value: &mut serde_json::Value) {
+    redact_fields(value, true);
+}
+
+fn redact_fields(value: &mut serde_json::Value, keys_are_fields: bool) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if keys_are_fields && !child.is_null() && is_sensitive_key(key) {
+                    *child = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    let named_entries =
+                        keys_are_fields && (key == "repoMappings" || key == "profiles");
+                    redact_fields(child, !named_entries);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_fields(item, true);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn overlay(dst: &mut String, src: String) {
+    if !src.is_empty() {
+        *dst = src;
+    }
+}
+
+fn merge_backend(base: BackendConfig, over: BackendConfig) -> BackendConfig {
+    match (base, over) {
+        (BackendConfig::Git(mut b), BackendConfig::Git(o)) => {
+            overlay(&mut b.thoughts_repo, o.thoughts_repo);
+            overlay(&mut b.repos_dir, o.repos_dir);
+            overlay(&mut b.global_dir, o.global_dir);
+            BackendConfig::Git(b)
+        }
+        (BackendConfig::Obsidian(mut b), BackendConfig::Obsidian(o)) => {
+            overlay(&mut b.vault_path, o.vault_path);
+            b.vault_subpath = o.vault_subpath.or(b.vault_subpath);
+            overlay(&mut b.repos_dir, o.repos_dir);
+            overlay(&mut b.global_dir, o.global_dir);
+            BackendConfig::Obsidian(b)
+        }
+        (BackendConfig::Notion(mut b), BackendConfig::Notion(o)) => {
+            overlay(&mut b.parent_page_id, o.parent_page_id);
+            b.database_id = o.database_id.or(b.database_id);
+            BackendConfig::Notion(b)
+        }
+        (BackendConfig::Anytype(mut b), BackendConfig::Anytype(o)) => {
+            overlay(&mut b.space_id, o.space_id);
+            b.type_id = o.type_id.or(b.type_id);
+            b.api_token_env = o.api_token_env.or(b.api_token_env);
+            BackendConfig::Anytype(b)
+        }
+        (_, over) if backend_problems("", &over).is_empty() => over,
+        (base, _) => base,
+    }
+}
+
+/// Required-field checks for one backend, prefixed with its config key path.
+fn backend_problems(prefix: &str, backend: &BackendConfig) -> Vec<String> {
+    let required: Vec<(&str, &str)> = match backend {
+        BackendConfig::Git(g) => vec![
+            ("thoughtsRepo", &g.thoughts_repo),
+            ("reposDir", &g.repos_dir),
+            ("globalDir", &g.global_dir),
+        ],
+        BackendConfig::Obsidian(o) => vec![
+            ("vaultPath", &o.vault_path),
+            ("reposDir", &o.repos_dir),
+            ("globalDir", &o.global_dir),
+        ],
+        BackendConfig::Notion(n) => vec![("parentPageId", &n.parent_page_id)],
+        BackendConfig::Anytype(a) => vec![("spaceId", &a.space_id)],
+    };
+
+    let mut problems: Vec<String> = required
+        .into_iter()
+        .filter(|(_, value)| value.is_empty())
+        .map(|(key, _)| format!("{prefix}.{key} is empty"))
+        .collect();
+    if let Some(repos_dir) = backend.filesystem_repos_dir()
+        && !repos_dir.is_empty()
+        && Some(repos_dir) == backend.filesystem_global_dir()
+    {
+        problems.push(format!(
+            "{prefix}.reposDir and {prefix}.globalDir must differ (both \"{repos_dir}\")"
+        ));
+    }
+    for (key, dir) in [
+        ("reposDir", backend.filesystem_repos_dir()),
+        ("globalDir", backend.filesystem_global_dir()),
+    ] {
+        if let Some(dir) = dir
+            && dir.contains(['/', '\\'])
+        {
+            problems.push(format!(
+                "{prefix}.{key} must be a single directory name, not a path (\"{dir}\")"
+            ));
+        }
+    }
+    problems
+}
+
+/// Problems with `path` as a directory to create: it must already be a
+/// directory, or its nearest existing ancestor must be one.
+fn directory_problems(key: &str, path: &Path) -> Vec<String> {
+    if path.is_dir() {
+        return Vec::new();
+    }
+    if path.exists() {
+        return vec![format!("{key} {} is not a directory", path.display())];
+    }
+    match path.ancestors().skip(1).find(|a| a.exists()) {
+        Some(ancestor) if !ancestor.is_dir() => vec![format!(
+            "{key} {} can't be created: {} is not a directory",
+            path.display(),
+            ancestor.display()
+        )],
+        _ => Vec::new(),
+    }
 }
 
 fn dispatch_mismatch(expected: BackendKind, actual: BackendKind) -> anyhow::Error {
     anyhow::anyhow!("{expected} backend dispatched on {actual} config")
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ProfileConfig {
     pub backend: BackendConfig,
+    /// AI settings for repositories using this profile. Unset fields fall
+    /// back to the top-level `ai` block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai: Option<AiConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ProfileConfig {
+    /// Problems that would stop profile `name` from working: missing or
+    /// path-like directory names, and a thoughts repository or vault that
+    /// can't be created. Empty when the profile is usable.
+    pub fn validate(&self, name: &str) -> Vec<String> {
+        let prefix = format!("profiles.{name}.backend");
+        let mut problems = backend_problems(&prefix, &self.backend);
+        let root = match &self.backend {
+            BackendConfig::Git(g) => Some(("thoughtsRepo", &g.thoughts_repo)),
+            BackendConfig::Obsidian(o) => Some(("vaultPath", &o.vault_path)),
+            BackendConfig::Notion(_) | BackendConfig::Anytype(_) => None,
+        };
+        if let Some((key, path)) = root
+            && !path.is_empty()
+        {
+            problems.extend(directory_problems(
+                &format!("{prefix}.{key}"),
+                &expand_path(path),
+            ));
+        }
+        problems
+    }
+
+    /// `validate` as an error listing every problem, if there are any.
+    pub fn check(&self, name: &str) -> Result<()> {
+        let problems = self.validate(name);
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(anyhow::anyhow!(
+            "Profile \"{name}\" is invalid:\n  - {}",
+            problems.join("\n  - ")
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum RepoMapping {
     String(String),
@@ -254,9 +467,37 @@ impl RepoMapping {
             None => RepoMapping::String(mapped_name.to_string()),
         }
     }
+
+    /// What changed from `old` to `new`, one description per change, e.g.
+    /// `repo changed: app → web` or `profile added: work`. Empty when they
+    /// map to the same directory and profile, whichever variant holds them.
+    pub fn diff(old: &RepoMapping, new: &RepoMapping) -> Vec<String> {
+        let mut changes = Vec::new();
+        if old.repo() != new.repo() {
+            changes.push(format!("repo changed: {} → {}", old.repo(), new.repo()));
+        }
+        match (old.profile(), new.profile()) {
+            (None, Some(profile)) => changes.push(format!("profile added: {profile}")),
+            (Some(profile), None) => changes.push(format!("profile removed: {profile}")),
+            (Some(old), Some(new)) if old != new => {
+                changes.push(format!("profile changed: {old} → {new}"));
+            }
+            _ => {}
+        }
+        changes
+    }
+}
+
+impl std::fmt::Display for RepoMapping {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.profile() {
+            Some(profile) => write!(f, "{} (profile: {profile})", self.repo()),
+            None => f.write_str(self.repo()),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ThoughtsConfig {
     pub user: String,
@@ -266,19 +507,120 @@ pub struct ThoughtsConfig {
     pub repo_mappings: HashMap<String, RepoMapping>,
     #[serde(default)]
     pub profiles: HashMap<String, ProfileConfig>,
+    /// Profile that `init` maps new repositories to when no `--profile` is
+    /// given. Existing mappings are unaffected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_profile: Option<String>,
+    /// Whether `init` installs git hooks into code repositories. Cleared by
+    /// `init --no-hooks`, set again by `init --hooks`.
+    #[serde(default = "default_hooks_enabled")]
+    pub hooks_enabled: bool,
+    /// Upper bound in seconds of the random delay the post-commit hook
+    /// waits before auto-syncing. Set by `init --sync-jitter`; unset means
+    /// no delay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_jitter_max_seconds: Option<u64>,
+    /// Absolute paths of the `.age` files `thoughts encrypt` wrote.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub encrypted_files: Vec<String>,
+    /// Commit without scanning for likely secrets (AWS keys, GitHub
+    /// tokens, private keys) on sync.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_secrets_scan: bool,
+    /// Proxy URL for hyprlayer's HTTP requests (agent downloads, update
+    /// checks), e.g. `http://proxy.corp:3128`. Overrides `HTTPS_PROXY` and
+    /// friends, including `NO_PROXY`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Pull and push with the `git` binary instead of the built-in client,
+    /// for credential setups libgit2 can't use (e.g. an SSH `ProxyCommand`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub push_with_git_cli: bool,
+}
+
+fn default_hooks_enabled() -> bool {
+    true
+}
+
+impl Default for ThoughtsConfig {
+    fn default() -> Self {
+        Self {
+            user: String::new(),
+            backend: BackendConfig::default(),
+            repo_mappings: HashMap::new(),
+            profiles: HashMap::new(),
+            default_profile: None,
+            hooks_enabled: true,
+            sync_jitter_max_seconds: None,
+            encrypted_files: Vec::new(),
+            skip_secrets_scan: false,
+            proxy: None,
+            push_with_git_cli: false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AiConfig {
-    #[serde(default)]
-    pub agent_tool: Option<AgentTool>,
+    /// Every configured AI tool. The first is the primary one, used where
+    /// only one tool applies (e.g. Anytype MCP registration). Older configs
+    /// stored a single `agentTool`, which still loads and is written back
+    /// as a list on the next save.
+    #[serde(
+        default,
+        alias = "agentTool",
+        deserialize_with = "one_or_many_tools",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub agent_tools: Vec<AgentTool>,
     #[serde(default)]
     pub opencode_provider: Option<OpenCodeProvider>,
     #[serde(default)]
     pub opencode_sonnet_model: Option<String>,
     #[serde(default)]
     pub opencode_opus_model: Option<String>,
+    /// Git ref (tag, branch or commit) agent files are installed from
+    /// instead of the latest `master`. Only the top-level `ai` block's
+    /// value is used, since every repository shares one install.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_pin_ref: Option<String>,
+    /// Where Copilot's files go instead of VS Code's User directory, set by
+    /// `ai configure --dest`. Only the top-level `ai` block's value is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copilot_dest: Option<String>,
+}
+
+impl AiConfig {
+    /// The first configured tool.
+    pub fn primary_tool(&self) -> Option<AgentTool> {
+        self.agent_tools.first().copied()
+    }
+}
+
+/// `agentTools` as a list, or the single (possibly null) `agentTool` that
+/// older configs wrote.
+fn one_or_many_tools<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<AgentTool>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(AgentTool),
+        Many(Vec<AgentTool>),
+    }
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(tool)) => vec![tool],
+        Some(OneOrMany::Many(tools)) => tools,
+    })
+}
+
+/// How a thoughts repository path relates to one already in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoOverlap {
+    Same,
+    Nested,
 }
 
 /// Effective configuration for a specific repository
@@ -290,6 +632,41 @@ pub struct EffectiveConfig {
     pub mapped_name: Option<String>,
 }
 
+impl EffectiveConfig {
+    /// Expanded thoughts repository (or Obsidian vault root). Errors for
+    /// backends that keep nothing on disk.
+    pub fn thoughts_repo_path(&self) -> Result<PathBuf> {
+        self.backend
+            .content_root()
+            .ok_or_else(|| self.no_local_directory())
+    }
+
+    /// `<thoughts repo>/<reposDir>`.
+    pub fn repos_dir_path(&self) -> Result<PathBuf> {
+        self.backend
+            .repos_dir_path()
+            .ok_or_else(|| self.no_local_directory())
+    }
+
+    fn no_local_directory(&self) -> anyhow::Error {
+        anyhow::anyhow!(
+            "The {} backend has no local thoughts directory",
+            self.backend.kind()
+        )
+    }
+
+    /// `<thoughts repo>/<reposDir>/<mapped name>`, this repository's
+    /// thoughts. Errors when the repository isn't mapped.
+    pub fn mapped_thoughts_path(&self) -> Result<PathBuf> {
+        let mapped_name = self.mapped_name.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Thoughts not initialized for this repository. Run 'hyprlayer thoughts init' first."
+            )
+        })?;
+        Ok(self.repos_dir_path()?.join(mapped_name))
+    }
+}
+
 impl ThoughtsConfig {
     /// Check whether the essential thoughts fields are populated.
     /// Returns false when only AI-related fields were configured
@@ -310,14 +687,253 @@ impl ThoughtsConfig {
         }
     }
 
-    /// Validate that a profile exists in the config (if specified)
-    pub fn validate_profile(&self, profile: &Option<String>) -> Result<()> {
-        if let Some(name) = profile
-            && !self.profiles.contains_key(name)
+    /// Resolve an optional `--profile` value to its stored name (see
+    /// `resolve_profile_name`).
+    pub fn resolve_profile(&self, profile: Option<String>) -> Result<Option<String>> {
+        profile
+            .map(|name| self.resolve_profile_name(&name))
+            .transpose()
+    }
+
+    /// The stored profile a user-typed name refers to: an exact match, else
+    /// the name as `profile create` would have sanitized it, else a unique
+    /// case-insensitive match of that.
+    pub fn resolve_profile_name(&self, name: &str) -> Result<String> {
+        if self.profiles.contains_key(name) {
+            return Ok(name.to_string());
+        }
+        let sanitized = sanitize_profile_name(name);
+        if self.profiles.contains_key(&sanitized) {
+            return Ok(sanitized);
+        }
+        let matches: Vec<&String> = self
+            .profiles
+            .keys()
+            .filter(|key| key.eq_ignore_ascii_case(&sanitized))
+            .collect();
+        match matches.as_slice() {
+            [only] => Ok((*only).clone()),
+            [] => Err(anyhow::anyhow!("Profile \"{}\" does not exist", name)),
+            _ => Err(anyhow::anyhow!(
+                "Profile \"{}\" is ambiguous: matches {}",
+                name,
+                matches
+                    .iter()
+                    .map(|m| format!("\"{m}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    /// An existing profile whose name equals `name` ignoring case.
+    pub fn profile_named_like(&self, name: &str) -> Option<&str> {
+        self.profiles
+            .keys()
+            .find(|key| key.eq_ignore_ascii_case(name))
+            .map(String::as_str)
+    }
+
+    /// Sanitized, validated name for a new profile (see
+    /// `validate_profile_name`) that doesn't collide, ignoring case, with an
+    /// existing profile other than `current` (the profile being renamed).
+    pub fn check_new_profile_name(&self, name: &str, current: Option<&str>) -> Result<String> {
+        let sanitized = validate_profile_name(name)?;
+        if let Some(existing) = self.profile_named_like(&sanitized)
+            && Some(existing) != current
         {
-            return Err(anyhow::anyhow!("Profile \"{}\" does not exist", name));
+            return Err(if existing == sanitized {
+                anyhow::anyhow!("Profile \"{}\" already exists", sanitized)
+            } else {
+                anyhow::anyhow!(
+                    "Profile \"{}\" already exists; names may not differ only by case",
+                    existing
+                )
+            });
         }
-        Ok(())
+        Ok(sanitized)
+    }
+
+    /// Profile a new mapping should use: the `--profile` flag if given,
+    /// otherwise `defaultProfile`.
+    pub fn profile_for_new_mapping(&self, flag: Option<String>) -> Option<String> {
+        flag.or_else(|| self.default_profile.clone())
+    }
+
+    /// Structural problems that would make commands misbehave: missing
+    /// required fields, unusable profile names, and mappings that point at
+    /// profiles which don't exist. Empty when the config is valid.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.user.is_empty() {
+            problems.push("user is empty".to_string());
+        }
+        problems.extend(backend_problems("backend", &self.backend));
+
+        let mut profiles: Vec<_> = self.profiles.iter().collect();
+        profiles.sort_by_key(|(name, _)| *name);
+        for (name, profile) in profiles {
+            if sanitize_profile_name(name) != *name {
+                problems.push(format!("profile name \"{name}\" is not a valid slug"));
+            } else if validate_profile_name(name).is_err() {
+                problems.push(format!("profile name \"{name}\" is reserved"));
+            }
+            problems.extend(backend_problems(
+                &format!("profiles.{name}.backend"),
+                &profile.backend,
+            ));
+        }
+
+        let mut mappings: Vec<_> = self.repo_mappings.iter().collect();
+        mappings.sort_by_key(|(path, _)| *path);
+        for (path, mapping) in mappings {
+            if mapping.repo().is_empty() {
+                problems.push(format!("repoMappings.{path} has an empty directory name"));
+            }
+            if let Some(profile) = mapping.profile()
+                && !self.profiles.contains_key(profile)
+            {
+                problems.push(format!(
+                    "repoMappings.{path} references missing profile \"{profile}\""
+                ));
+            }
+        }
+        if let Some(profile) = &self.default_profile
+            && !self.profiles.contains_key(profile)
+        {
+            problems.push(format!(
+                "defaultProfile references missing profile \"{profile}\""
+            ));
+        }
+        problems
+    }
+
+    /// Layer `override_` on top of `base`. Non-empty scalars in the override
+    /// win; `repo_mappings` and `profiles` are merged by key, with the
+    /// override winning on collision. A backend of the same kind is merged
+    /// field by field; a different kind replaces the base only when it is
+    /// fully specified, so a partial override file can't blank it out.
+    pub fn merge(base: ThoughtsConfig, override_: ThoughtsConfig) -> ThoughtsConfig {
+        let mut merged = base;
+        overlay(&mut merged.user, override_.user);
+        merged.backend = merge_backend(merged.backend, override_.backend);
+        merged.repo_mappings.extend(override_.repo_mappings);
+        merged.profiles.extend(override_.profiles);
+        if override_.default_profile.is_some() {
+            merged.default_profile = override_.default_profile;
+        }
+        // An omitted field reads as enabled, so only disabling overrides.
+        merged.hooks_enabled &= override_.hooks_enabled;
+        if override_.sync_jitter_max_seconds.is_some() {
+            merged.sync_jitter_max_seconds = override_.sync_jitter_max_seconds;
+        }
+        merged.encrypted_files.extend(override_.encrypted_files);
+        merged.skip_secrets_scan |= override_.skip_secrets_scan;
+        if override_.proxy.is_some() {
+            merged.proxy = override_.proxy;
+        }
+        merged.push_with_git_cli |= override_.push_with_git_cli;
+        merged
+    }
+
+    /// Repo paths whose mapping names `profile`, sorted.
+    pub fn repos_using_profile(&self, profile: &str) -> Vec<String> {
+        let mut repos: Vec<String> = self
+            .repo_mappings
+            .iter()
+            .filter(|(_, m)| m.profile() == Some(profile))
+            .map(|(path, _)| path.clone())
+            .collect();
+        repos.sort();
+        repos
+    }
+
+    /// `(repo path, profile)` for every mapping that names a profile which
+    /// doesn't exist, sorted by repo path.
+    pub fn orphaned_mappings(&self) -> Vec<(String, String)> {
+        let mut orphans: Vec<(String, String)> = self
+            .repo_mappings
+            .iter()
+            .filter_map(|(path, m)| {
+                m.profile()
+                    .filter(|p| !self.profiles.contains_key(*p))
+                    .map(|p| (path.clone(), p.to_string()))
+            })
+            .collect();
+        orphans.sort();
+        orphans
+    }
+
+    /// Move profile `old` to `new` and repoint every repo mapping that
+    /// referenced it. Returns the repo paths whose mappings changed, sorted.
+    pub fn rename_profile(&mut self, old: &str, new: &str) -> Result<Vec<String>> {
+        if old == new {
+            return Err(anyhow::anyhow!("Profile is already named \"{}\"", new));
+        }
+        if self.profiles.contains_key(new) {
+            return Err(anyhow::anyhow!("Profile \"{}\" already exists", new));
+        }
+        let profile = self
+            .profiles
+            .remove(old)
+            .ok_or_else(|| anyhow::anyhow!("Profile \"{}\" does not exist", old))?;
+        self.profiles.insert(new.to_string(), profile);
+        if self.default_profile.as_deref() == Some(old) {
+            self.default_profile = Some(new.to_string());
+        }
+
+        let mut updated = Vec::new();
+        for (path, mapping) in self.repo_mappings.iter_mut() {
+            if mapping.profile() == Some(old) {
+                *mapping = RepoMapping::new(mapping.repo(), &Some(new.to_string()));
+                updated.push(path.clone());
+            }
+        }
+        updated.sort();
+        Ok(updated)
+    }
+
+    /// Repoint every mapping that uses profile `from` to profile `to`, or
+    /// to the default backend when `to` is `None`. Returns the repo paths
+    /// whose mappings changed, sorted. `from` itself is left in place.
+    pub fn migrate_profile_mappings(
+        &mut self,
+        from: &str,
+        to: Option<&str>,
+    ) -> Result<Vec<String>> {
+        if !self.profiles.contains_key(from) {
+            return Err(anyhow::anyhow!("Profile \"{}\" does not exist", from));
+        }
+        if let Some(to) = to {
+            if to == from {
+                return Err(anyhow::anyhow!(
+                    "Cannot migrate profile \"{}\" to itself",
+                    from
+                ));
+            }
+            if !self.profiles.contains_key(to) {
+                return Err(anyhow::anyhow!("Profile \"{}\" does not exist", to));
+            }
+        }
+
+        let target = to.map(str::to_string);
+        let mut migrated = Vec::new();
+        for (path, mapping) in self.repo_mappings.iter_mut() {
+            if mapping.profile() == Some(from) {
+                *mapping = RepoMapping::new(mapping.repo(), &target);
+                migrated.push(path.clone());
+            }
+        }
+        migrated.sort();
+        Ok(migrated)
+    }
+
+    /// Serialize for display with sensitive values replaced by
+    /// `"[REDACTED]"` (see `redact_json`).
+    pub fn to_redacted(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        redact_json(&mut value);
+        value
     }
 
     /// Resolve the effective profile entry — the named profile if mapped, or
@@ -329,7 +945,73 @@ impl ThoughtsConfig {
             .cloned()
             .unwrap_or(ProfileConfig {
                 backend: self.backend.clone(),
+                ai: None,
+            })
+    }
+
+    /// Thoughts repositories of the default backend and of every profile
+    /// other than `exclude` that `repo` equals or is nested with, labelled
+    /// "the default config" or `profile "<name>"`. Paths are compared after
+    /// expansion and, where they exist, canonicalization.
+    pub fn repo_overlaps(&self, repo: &Path, exclude: Option<&str>) -> Vec<(String, RepoOverlap)> {
+        fn canonical(path: &Path) -> PathBuf {
+            path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+        }
+
+        let target = canonical(repo);
+        let mut owners: Vec<(String, &BackendConfig)> = self
+            .profiles
+            .iter()
+            .filter(|(name, _)| Some(name.as_str()) != exclude)
+            .map(|(name, p)| (format!("profile \"{name}\""), &p.backend))
+            .collect();
+        owners.sort_by(|a, b| a.0.cmp(&b.0));
+        owners.insert(0, ("the default config".to_string(), &self.backend));
+
+        owners
+            .into_iter()
+            .filter_map(|(owner, backend)| {
+                let other = canonical(&backend.content_root()?);
+                let overlap = if other == target {
+                    RepoOverlap::Same
+                } else if other.starts_with(&target) || target.starts_with(&other) {
+                    RepoOverlap::Nested
+                } else {
+                    return None;
+                };
+                Some((owner, overlap))
+            })
+            .collect()
+    }
+
+    /// Groups of repo paths whose mappings share a directory name in the
+    /// same thoughts repository, so they'd sync into one `repos/<name>`
+    /// directory. Notion and Anytype mappings only name a project and are
+    /// never duplicates. Each group and the list are sorted.
+    pub fn find_duplicate_mappings(&self) -> Vec<Vec<String>> {
+        let mut groups: BTreeMap<PathBuf, Vec<String>> = BTreeMap::new();
+        for (path, mapping) in &self.repo_mappings {
+            let backend = self
+                .resolve_dirs(&mapping.profile().map(str::to_string))
+                .backend;
+            let (Some(root), Some(repos_dir)) =
+                (backend.content_root(), backend.filesystem_repos_dir())
+            else {
+                continue;
+            };
+            groups
+                .entry(root.join(repos_dir).join(mapping.repo()))
+                .or_default()
+                .push(path.clone());
+        }
+        groups
+            .into_values()
+            .filter(|paths| paths.len() > 1)
+            .map(|mut paths| {
+                paths.sort();
+                paths
             })
+            .collect()
     }
 
     /// Find repo mappings whose paths no longer exist on disk.
@@ -395,6 +1077,16 @@ impl ThoughtsConfig {
             mapped_name: mapping.map(|m| m.repo().to_string()),
         }
     }
+
+    /// AI settings of the profile `repo_path` is mapped to, if that profile
+    /// exists and carries its own `ai` block.
+    pub fn profile_ai_for_repo(&self, repo_path: &str) -> Option<&AiConfig> {
+        self.repo_mappings
+            .get(repo_path)
+            .and_then(|m| m.profile())
+            .and_then(|name| self.profiles.get(name))
+            .and_then(|p| p.ai.as_ref())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -408,8 +1100,15 @@ pub struct HyprlayerConfig {
     pub last_agent_check: Option<i64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agents_installed_sha: Option<String>,
+    /// The release whose changelog the update notification last showed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_changelog_shown: Option<String>,
     #[serde(default)]
     pub disable_update_check: bool,
+    /// Hours between update checks; `0` checks on every run. Unset means
+    /// once a day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_check_interval_hours: Option<u64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub thoughts: Option<ThoughtsConfig>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -423,7 +1122,9 @@ impl Default for HyprlayerConfig {
             last_version_check: None,
             last_agent_check: None,
             agents_installed_sha: None,
+            last_changelog_shown: None,
             disable_update_check: false,
+            update_check_interval_hours: None,
             thoughts: None,
             ai: None,
         }
@@ -541,6 +1242,76 @@ struct V2HyprlayerConfig {
     ai: Option<AiConfig>,
 }
 
+/// Where the values of a loaded config came from when not from the first
+/// config file, keyed by JSON pointer into the config (`/thoughts/user`),
+/// e.g. "config file local.json" or "--set".
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigSources(BTreeMap<String, String>);
+
+impl ConfigSources {
+    /// Attribute to `label` every value that differs between `before` and
+    /// `after`, the config before and after one layer or override.
+    pub fn record(
+        &mut self,
+        before: &HyprlayerConfig,
+        after: &HyprlayerConfig,
+        label: &str,
+    ) -> Result<()> {
+        let mut changed = Vec::new();
+        changed_pointers(
+            &serde_json::to_value(before)?,
+            &serde_json::to_value(after)?,
+            String::new(),
+            &mut changed,
+        );
+        for pointer in changed {
+            self.0.insert(pointer, label.to_string());
+        }
+        Ok(())
+    }
+
+    /// Where the value at `pointer`, or any value beneath it, came from.
+    /// `None` when it's the first config file's own.
+    pub fn get(&self, pointer: &str) -> Option<&str> {
+        let nested = format!("{pointer}/");
+        self.0
+            .iter()
+            .find(|(key, _)| *key == pointer || key.starts_with(&nested))
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+/// The JSON pointer to `key` inside the object at `parent`.
+pub fn json_pointer(parent: &str, key: &str) -> String {
+    format!("{parent}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Push the pointers under `path` of the leaves that differ between
+/// `before` and `after`. A key missing on one side counts as `null`.
+fn changed_pointers(
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+    path: String,
+    out: &mut Vec<String>,
+) {
+    use serde_json::Value;
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            let keys: std::collections::BTreeSet<&String> = b.keys().chain(a.keys()).collect();
+            for key in keys {
+                changed_pointers(
+                    b.get(key).unwrap_or(&Value::Null),
+                    a.get(key).unwrap_or(&Value::Null),
+                    json_pointer(&path, key),
+                    out,
+                );
+            }
+        }
+        (b, a) if b != a => out.push(path),
+        _ => {}
+    }
+}
+
 #[derive(Deserialize)]
 struct VersionPeek {
     #[serde(default)]
@@ -552,75 +1323,160 @@ impl HyprlayerConfig {
     pub fn load(config_path: &Path) -> Result<Self> {
         let content = fs::read_to_string(config_path)
             .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+        let (cfg, migrated) = Self::parse(&content)?;
+        if migrated {
+            cfg.save(config_path)?;
+        }
+        Ok(cfg)
+    }
+
+    /// Parse config JSON of any version without touching disk. The flag is
+    /// true when the content was migrated from an older shape.
+    pub fn parse(content: &str) -> Result<(Self, bool)> {
         let peek: VersionPeek =
-            serde_json::from_str(&content).with_context(|| "Failed to parse config file")?;
+            serde_json::from_str(content).with_context(|| "Failed to parse config file")?;
         let version = peek.version.unwrap_or(0);
 
         let cfg = match version {
             0 | 1 => {
-                let v2 = Self::migrate_v1(&content)?;
+                let v2 = Self::migrate_v1(content)?;
                 Self::migrate_v2(&serde_json::to_string(&v2)?)?
             }
-            2 => Self::migrate_v2(&content)?,
-            3 => {
-                serde_json::from_str(&content).with_context(|| "Failed to parse v3 config file")?
-            }
+            2 => Self::migrate_v2(content)?,
+            3 => serde_json::from_str(content).with_context(|| "Failed to parse v3 config file")?,
             v => return Err(anyhow::anyhow!("Unknown config version: {v}")),
         };
+        Ok((cfg, version != 3))
+    }
 
-        if version != 3 {
-            cfg.save(config_path)?;
+    /// Load several config files and fold them left to right, so later
+    /// files take precedence (see `ThoughtsConfig::merge`). AI fields set in
+    /// a later file win; bookkeeping fields come from the first file. The
+    /// sources record which later file each changed value came from.
+    pub fn load_merged(paths: &[&Path]) -> Result<(Self, ConfigSources)> {
+        let (first, rest) = paths
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("No config files given"))?;
+        let mut merged = Self::load(first)?;
+        let mut sources = ConfigSources::default();
+        for path in rest {
+            let layered = merged.clone().layered_with(Self::load(path)?);
+            sources.record(
+                &merged,
+                &layered,
+                &format!("config file {}", path.display()),
+            )?;
+            merged = layered;
         }
-        Ok(cfg)
+        Ok((merged, sources))
     }
 
-    /// Save config to a file path.
+    /// Apply a `--set KEY=VALUE` override. `key` is a dot path into the
+    /// JSON config, where a leading `thoughts.` may be left off, as may
+    /// `backend.` for the active backend's fields (so `thoughtsRepo`,
+    /// `thoughts.thoughtsRepo` and `thoughts.backend.thoughtsRepo` are the
+    /// same key). `value` is read as JSON, else as a plain string.
     ///
-    /// Atomic write: serialize to a sibling `<name>.tmp.<pid>` first, then
-    /// `rename` over the destination. Two concurrent `hyprlayer`
-    /// invocations writing the same config in the same window can
-    /// otherwise interleave bytes and produce invalid JSON; one of them
-    /// would then fail to load on the next startup. POSIX `rename` is
-    /// atomic; Windows `MoveFileEx` (which `fs::rename` calls into) is
-    /// effectively atomic for same-volume moves.
-    pub fn save(&self, config_path: &Path) -> Result<()> {
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent).with_context(|| {
-                format!("Failed to create config directory: {}", parent.display())
-            })?;
+    /// Returns `false` when no such key exists, and errors when the key
+    /// exists but `value` doesn't fit it.
+    pub fn apply_override(&mut self, key: &str, value: &str) -> Result<bool> {
+        let current = serde_json::to_value(&*self)?;
+        let values: Vec<serde_json::Value> = serde_json::from_str(value)
+            .ok()
+            .into_iter()
+            .chain([serde_json::Value::String(value.to_string())])
+            .collect();
+
+        let mut rejected = None;
+        for path in override_paths(key) {
+            let pointer = format!("/{}", path.join("/"));
+            for value in &values {
+                let mut json = current.clone();
+                if !set_json_path(&mut json, &path, value.clone()) {
+                    break;
+                }
+                match serde_json::from_value::<Self>(json) {
+                    // Unknown keys deserialize fine but don't survive the
+                    // round trip.
+                    Ok(updated)
+                        if serde_json::to_value(&updated)?.pointer(&pointer) == Some(value) =>
+                    {
+                        *self = updated;
+                        return Ok(true);
+                    }
+                    Ok(_) => {}
+                    Err(e) if current.pointer(&pointer).is_some() => rejected = Some(e),
+                    Err(_) => {}
+                }
+            }
         }
-        let json = serde_json::to_string_pretty(&self)?;
-
-        let pid = std::process::id();
-        let mut tmp_name = config_path
-            .file_name()
-            .map(|n| n.to_os_string())
-            .unwrap_or_else(|| "config.json".into());
-        tmp_name.push(format!(".tmp.{pid}"));
-        let tmp_path = match config_path.parent() {
-            Some(parent) => parent.join(&tmp_name),
-            None => PathBuf::from(&tmp_name),
-        };
+        match rejected {
+            Some(e) => Err(anyhow::anyhow!("Invalid value for {key}: {e}")),
+            None => Ok(false),
+        }
+    }
 
-        if let Err(e) = fs::write(&tmp_path, json) {
-            let _ = fs::remove_file(&tmp_path);
-            return Err(anyhow::Error::new(e).context(format!(
-                "Failed to write config tempfile: {}",
-                tmp_path.display()
-            )));
-        }
-        if let Err(e) = fs::rename(&tmp_path, config_path) {
-            // A failed rename leaves the tempfile behind; clear it so
-            // repeated startup checks don't accumulate stale `<name>.tmp.<pid>`
-            // siblings in the config directory.
-            let _ = fs::remove_file(&tmp_path);
-            return Err(anyhow::Error::new(e).context(format!(
-                "Failed to atomically rename {} -> {}",
-                tmp_path.display(),
-                config_path.display()
-            )));
+    fn layered_with(self, over: Self) -> Self {
+        let thoughts = match (self.thoughts, over.thoughts) {
+            (Some(base), Some(over)) => Some(ThoughtsConfig::merge(base, over)),
+            (base, over) => over.or(base),
+        };
+        let ai = match (self.ai, over.ai) {
+            (Some(base), Some(over)) => Some(AiConfig {
+                agent_tools: if over.agent_tools.is_empty() {
+                    base.agent_tools
+                } else {
+                    over.agent_tools
+                },
+                opencode_provider: over.opencode_provider.or(base.opencode_provider),
+                opencode_sonnet_model: over.opencode_sonnet_model.or(base.opencode_sonnet_model),
+                opencode_opus_model: over.opencode_opus_model.or(base.opencode_opus_model),
+                agent_pin_ref: over.agent_pin_ref.or(base.agent_pin_ref),
+                copilot_dest: over.copilot_dest.or(base.copilot_dest),
+            }),
+            (base, over) => over.or(base),
+        };
+        Self {
+            disable_update_check: self.disable_update_check || over.disable_update_check,
+            thoughts,
+            ai,
+            ..self
         }
-        Ok(())
+    }
+
+    /// Save config to a file path, replacing whatever is there.
+    ///
+    /// Written with `write_atomic` under `acquire_config_lock`, so two
+    /// concurrent `hyprlayer` invocations can't interleave bytes and leave
+    /// invalid JSON behind. Commands that change part of an existing config
+    /// use `update` instead, which also keeps the read under the lock.
+    pub fn save(&self, config_path: &Path) -> Result<()> {
+        create_config_dir(config_path)?;
+        let _lock = acquire_config_lock(config_path)?;
+        write_atomic(config_path, &serde_json::to_string_pretty(&self)?)
+    }
+
+    /// Load the config at `config_path` (default when it doesn't exist),
+    /// apply `change` and save the result, all under one
+    /// `acquire_config_lock`. A concurrent `hyprlayer` then can't save in
+    /// between and have its changes overwritten. Nothing is saved when
+    /// `change` fails.
+    pub fn update<T>(config_path: &Path, change: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        create_config_dir(config_path)?;
+        let _lock = acquire_config_lock(config_path)?;
+        // `parse`, not `load`: a migrating `load` would `save`, which waits on
+        // the lock held here. The write below stores the migrated shape.
+        let mut config = if config_path.exists() {
+            let content = fs::read_to_string(config_path).with_context(|| {
+                format!("Failed to read config file: {}", config_path.display())
+            })?;
+            Self::parse(&content)?.0
+        } else {
+            Self::default()
+        };
+        let result = change(&mut config)?;
+        write_atomic(config_path, &serde_json::to_string_pretty(&config)?)?;
+        Ok(result)
     }
 
     /// Get or create the thoughts section
@@ -633,6 +1489,81 @@ impl HyprlayerConfig {
         self.ai.get_or_insert_with(AiConfig::default)
     }
 
+    /// Profile-level `ai` block for `repo_path`, if any. Callers fall back
+    /// to the top-level `ai` block field by field.
+    fn profile_ai_for_repo(&self, repo_path: Option<&str>) -> Option<&AiConfig> {
+        let thoughts = self.thoughts.as_ref()?;
+        thoughts.profile_ai_for_repo(repo_path?)
+    }
+
+    /// Agent tools for `repo_path`: the mapped profile's `agentTools` if
+    /// set, otherwise the top-level ones. `None` resolves the top-level
+    /// value only.
+    pub fn effective_agent_tools_for_repo(&self, repo_path: Option<&str>) -> &[AgentTool] {
+        self.profile_ai_for_repo(repo_path)
+            .map(|ai| ai.agent_tools.as_slice())
+            .filter(|tools| !tools.is_empty())
+            .or_else(|| Some(self.ai.as_ref()?.agent_tools.as_slice()))
+            .unwrap_or_default()
+    }
+
+    /// The primary agent tool for `repo_path` (see
+    /// `effective_agent_tools_for_repo`).
+    pub fn effective_agent_tool_for_repo(&self, repo_path: Option<&str>) -> Option<&AgentTool> {
+        self.effective_agent_tools_for_repo(repo_path).first()
+    }
+
+    /// OpenCode provider for `repo_path`, with the same profile fallback as
+    /// `effective_agent_tool_for_repo`.
+    pub fn effective_opencode_provider_for_repo(
+        &self,
+        repo_path: Option<&str>,
+    ) -> Option<&OpenCodeProvider> {
+        self.profile_ai_for_repo(repo_path)
+            .and_then(|ai| ai.opencode_provider.as_ref())
+            .or_else(|| self.ai.as_ref()?.opencode_provider.as_ref())
+    }
+
+    /// OpenCode `(sonnet, opus)` model overrides for `repo_path`, each
+    /// resolved independently with profile fallback.
+    pub fn effective_models_for_repo(
+        &self,
+        repo_path: Option<&str>,
+    ) -> (Option<&str>, Option<&str>) {
+        let profile = self.profile_ai_for_repo(repo_path);
+        let sonnet = profile
+            .and_then(|ai| ai.opencode_sonnet_model.as_deref())
+            .or_else(|| self.ai.as_ref()?.opencode_sonnet_model.as_deref());
+        let opus = profile
+            .and_then(|ai| ai.opencode_opus_model.as_deref())
+            .or_else(|| self.ai.as_ref()?.opencode_opus_model.as_deref());
+        (sonnet, opus)
+    }
+
+    /// The full AI configuration in effect for `repo_path`, or `None` when
+    /// neither the profile nor the top level has any AI settings.
+    pub fn effective_ai_for_repo(&self, repo_path: Option<&str>) -> Option<AiConfig> {
+        if self.ai.is_none() && self.profile_ai_for_repo(repo_path).is_none() {
+            return None;
+        }
+        let (sonnet, opus) = self.effective_models_for_repo(repo_path);
+        Some(AiConfig {
+            agent_tools: self.effective_agent_tools_for_repo(repo_path).to_vec(),
+            opencode_provider: self
+                .effective_opencode_provider_for_repo(repo_path)
+                .cloned(),
+            opencode_sonnet_model: sonnet.map(str::to_string),
+            opencode_opus_model: opus.map(str::to_string),
+            agent_pin_ref: self.agent_pin_ref().map(str::to_string),
+            copilot_dest: self.ai.as_ref().and_then(|ai| ai.copilot_dest.clone()),
+        })
+    }
+
+    /// The pinned agent-file ref from the top-level `ai` block, if any.
+    pub fn agent_pin_ref(&self) -> Option<&str> {
+        self.ai.as_ref()?.agent_pin_ref.as_deref()
+    }
+
     /// Migrate a v1 config (no version field) to a v2-shaped intermediate
     /// representation. The result is fed straight into `migrate_v2` to land
     /// on the live v3 shape — v1 is never deserialized into the live types.
@@ -659,10 +1590,12 @@ impl HyprlayerConfig {
         };
 
         let ai = AiConfig {
-            agent_tool: old.agent_tool,
+            agent_tools: old.agent_tool.into_iter().collect(),
             opencode_provider: old.opencode_provider,
             opencode_sonnet_model: old.opencode_sonnet_model,
             opencode_opus_model: old.opencode_opus_model,
+            agent_pin_ref: None,
+            copilot_dest: None,
         };
 
         Ok(V2HyprlayerConfig {
@@ -708,10 +1641,18 @@ impl HyprlayerConfig {
                                 &p.repos_dir,
                                 &p.global_dir,
                             ),
+                            ai: None,
                         },
                     )
                 })
                 .collect(),
+            default_profile: None,
+            hooks_enabled: true,
+            sync_jitter_max_seconds: None,
+            encrypted_files: Vec::new(),
+            skip_secrets_scan: false,
+            proxy: None,
+            push_with_git_cli: false,
         });
 
         Ok(HyprlayerConfig {
@@ -719,7 +1660,9 @@ impl HyprlayerConfig {
             last_version_check: v2.last_version_check,
             last_agent_check: v2.last_agent_check,
             agents_installed_sha: v2.agents_installed_sha,
+            last_changelog_shown: None,
             disable_update_check: v2.disable_update_check,
+            update_check_interval_hours: None,
             thoughts,
             ai: v2.ai,
         })
@@ -757,24 +1700,187 @@ fn build_v3_backend(
     }
 }
 
-pub fn get_default_config_path() -> anyhow::Result<PathBuf> {
-    let config_dir = dirs::config_dir()
-        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
-    Ok(config_dir.join("hyprlayer").join("config.json"))
-}
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
 
-pub fn get_default_thoughts_repo() -> anyhow::Result<PathBuf> {
-    let home_dir =
-        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
-    Ok(home_dir.join("thoughts"))
+/// Guard for the exclusive lock on `<config_path>.lock`, released on drop
+/// (or by the OS if the process dies holding it).
+#[derive(Debug)]
+pub struct LockFile {
+    _file: fs::File,
 }
 
-pub fn expand_path(path: &str) -> PathBuf {
-    let expanded = shellexpand::tilde(path);
-    PathBuf::from(expanded.as_ref())
+/// Take the exclusive lock guarding changes to `config_path`.
+///
+/// Two git hooks firing back to back can run `hyprlayer` concurrently, and
+/// both may change the config. The lock is an OS file lock on a sibling
+/// `<name>.lock`, so a crashed holder can't leave it stuck; the file itself
+/// stays behind and records the last holder's PID for diagnosis. Retries
+/// every 100ms for up to 5 seconds.
+pub fn acquire_config_lock(config_path: &Path) -> Result<LockFile> {
+    acquire_lock_with_timeout(config_path, LOCK_TIMEOUT)
 }
 
-pub fn get_current_repo_path() -> anyhow::Result<PathBuf> {
+fn acquire_lock_with_timeout(config_path: &Path, timeout: Duration) -> Result<LockFile> {
+    let mut lock_name = config_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "config.json".into());
+    lock_name.push(".lock");
+    let lock_path = config_path.with_file_name(lock_name);
+
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&lock_path)
+        .with_context(|| format!("Failed to create config lock: {}", lock_path.display()))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match file.try_lock() {
+            Ok(()) => {
+                let _ = file.set_len(0);
+                let _ = write!(file, "{}", std::process::id());
+                return Ok(LockFile { _file: file });
+            }
+            Err(fs::TryLockError::WouldBlock) => {}
+            Err(fs::TryLockError::Error(e)) => {
+                return Err(anyhow::Error::new(e)
+                    .context(format!("Failed to lock config: {}", lock_path.display())));
+            }
+        }
+
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "Another hyprlayer process is modifying {} (lock: {})",
+                config_path.display(),
+                lock_path.display()
+            ));
+        }
+        std::thread::sleep(LOCK_RETRY_INTERVAL);
+    }
+}
+
+/// Write `contents` to `path` through a sibling `<name>.tmp.<pid>` renamed
+/// into place, so readers never see a half-written file. POSIX `rename` is
+/// atomic; Windows `MoveFileEx` (which `fs::rename` calls into) is
+/// effectively atomic for same-volume moves.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp_name = path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_else(|| "config.json".into());
+    tmp_name.push(format!(".tmp.{}", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    if let Err(e) = fs::write(&tmp_path, contents) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(anyhow::Error::new(e).context(format!(
+            "Failed to write config tempfile: {}",
+            tmp_path.display()
+        )));
+    }
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        // A failed rename leaves the tempfile behind; clear it so
+        // repeated startup checks don't accumulate stale `<name>.tmp.<pid>`
+        // siblings in the config directory.
+        let _ = fs::remove_file(&tmp_path);
+        return Err(anyhow::Error::new(e).context(format!(
+            "Failed to atomically rename {} -> {}",
+            tmp_path.display(),
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// `HyprlayerConfig::update` for the config file as raw JSON, for edits
+/// that must leave keys this version doesn't know about untouched. Errors
+/// when there is no config at `config_path`.
+pub fn update_raw_config(
+    config_path: &Path,
+    change: impl FnOnce(&mut serde_json::Value) -> Result<()>,
+) -> Result<()> {
+    if !config_path.exists() {
+        return Err(anyhow::anyhow!("No thoughts configuration found"));
+    }
+    let _lock = acquire_config_lock(config_path)?;
+    let mut json: serde_json::Value = serde_json::from_str(&fs::read_to_string(config_path)?)?;
+    change(&mut json)?;
+    write_atomic(config_path, &serde_json::to_string_pretty(&json)?)
+}
+
+fn create_config_dir(config_path: &Path) -> Result<()> {
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create config directory: {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+/// `<base>/hyprlayer` for an XDG base directory: `value` of its variable
+/// when that is an absolute path, otherwise `<home>/<fallback>`. Relative
+/// and empty values are ignored, as the XDG Base Directory spec requires.
+fn xdg_dir(value: Option<OsString>, home: Option<PathBuf>, fallback: &str) -> Option<PathBuf> {
+    let base = value
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| home.map(|home| home.join(fallback)))?;
+    Some(base.join("hyprlayer"))
+}
+
+#[cfg(target_os = "linux")]
+fn config_dir() -> Option<PathBuf> {
+    xdg_dir(env::var_os("XDG_CONFIG_HOME"), dirs::home_dir(), ".config")
+}
+
+#[cfg(not(target_os = "linux"))]
+fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("hyprlayer"))
+}
+
+#[cfg(target_os = "linux")]
+fn data_dir() -> Option<PathBuf> {
+    xdg_dir(
+        env::var_os("XDG_DATA_HOME"),
+        dirs::home_dir(),
+        ".local/share",
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("hyprlayer"))
+}
+
+/// `$XDG_CONFIG_HOME/hyprlayer/config.json` on Linux (`~/.config` when
+/// unset), the platform's config directory elsewhere.
+pub fn get_default_config_path() -> anyhow::Result<PathBuf> {
+    let config_dir =
+        config_dir().ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
+    Ok(config_dir.join("config.json"))
+}
+
+/// Where hyprlayer keeps data it can rebuild, such as the search index:
+/// `$XDG_DATA_HOME/hyprlayer` on Linux (`~/.local/share` when unset), the
+/// platform's data directory elsewhere.
+pub fn get_data_dir() -> anyhow::Result<PathBuf> {
+    data_dir().ok_or_else(|| anyhow::anyhow!("Could not determine data directory"))
+}
+
+pub fn get_default_thoughts_repo() -> anyhow::Result<PathBuf> {
+    let home_dir =
+        dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    Ok(home_dir.join("thoughts"))
+}
+
+pub fn expand_path(path: &str) -> PathBuf {
+    let expanded = shellexpand::tilde(path);
+    PathBuf::from(expanded.as_ref())
+}
+
+pub fn get_current_repo_path() -> anyhow::Result<PathBuf> {
     std::env::current_dir().map_err(|e| anyhow::anyhow!("Could not get current directory: {}", e))
 }
 
@@ -785,15 +1891,154 @@ pub fn get_repo_name_from_path(path: &Path) -> String {
         .to_string()
 }
 
+/// Turn an arbitrary name into a filesystem-safe slug.
+///
+/// Input is NFC-normalized so decomposed accents compose first, then accented
+/// Latin letters are transliterated (`café` → `cafe`, `straße` → `strasse`).
+/// Anything else outside `[A-Za-z0-9_-]` — CJK, punctuation, whitespace —
+/// becomes `_`. Runs of `_` collapse to one and leading/trailing `_`/`-` are
+/// trimmed, so the result may be empty for all-special input (see
+/// [`validate_directory_name`]).
 pub fn sanitize_directory_name(name: &str) -> String {
-    name.replace(|c: char| !c.is_alphanumeric() && c != '_' && c != '-', "_")
+    slugify(name, false)
+}
+
+/// Sanitize a thoughts directory name, rejecting names with no usable
+/// characters: an empty slug would map the repository onto the repos dir
+/// itself.
+pub fn validate_directory_name(name: &str) -> Result<String> {
+    let sanitized = sanitize_directory_name(name);
+    if sanitized.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Directory name \"{}\" has no usable characters; pick one with ASCII letters or digits",
+            name
+        ));
+    }
+    Ok(sanitized)
+}
+
+/// Like [`sanitize_directory_name`], but whitespace becomes `-` so
+/// `"Work Laptop"` yields `Work-Laptop`.
+pub fn sanitize_profile_name(name: &str) -> String {
+    slugify(name, true)
+}
+
+/// Profile names with a meaning of their own: `default` stands for the
+/// default backend in `use-profile` and `delete --migrate-to`, and `global`
+/// is the shared thoughts directory.
+pub const RESERVED_PROFILE_NAMES: [&str; 2] = ["default", "global"];
+
+/// Sanitize a new profile name, rejecting names with no usable characters
+/// and reserved names (in any case).
+pub fn validate_profile_name(name: &str) -> Result<String> {
+    let sanitized = sanitize_profile_name(name);
+    if sanitized.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Profile name \"{}\" has no usable characters",
+            name
+        ));
+    }
+    if RESERVED_PROFILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&sanitized))
+    {
+        return Err(anyhow::anyhow!(
+            "\"{}\" is reserved and can't be used as a profile name",
+            sanitized
+        ));
+    }
+    Ok(sanitized)
+}
+
+fn slugify(name: &str, spaces_to_hyphens: bool) -> String {
+    use unicode_normalization::UnicodeNormalization;
+
+    let mut out = String::with_capacity(name.len());
+    let mut push = |c: char| {
+        // Collapse separator runs we introduced (and any `_` runs in the input).
+        let is_sep = c == '_' || (spaces_to_hyphens && c == '-');
+        if !(is_sep && out.ends_with(c)) {
+            out.push(c);
+        }
+    };
+
+    for c in name.nfc() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+            push(c);
+        } else if spaces_to_hyphens && c.is_whitespace() {
+            push('-');
+        } else if c.is_alphabetic() && is_latin_extended(c) {
+            for t in unidecode::unidecode_char(c).chars() {
+                push(if t.is_ascii_alphanumeric() { t } else { '_' });
+            }
+        } else {
+            push('_');
+        }
+    }
+
+    out.trim_matches(|c| c == '_' || c == '-').to_string()
+}
+
+/// Latin-1 Supplement, Latin Extended-A/B and Latin Extended Additional —
+/// the blocks where transliteration to ASCII is unambiguous.
+fn is_latin_extended(c: char) -> bool {
+    matches!(c, '\u{00C0}'..='\u{024F}' | '\u{1E00}'..='\u{1EFF}')
+}
+
+/// The dot paths `apply_override` tries for `key`, most literal first.
+fn override_paths(key: &str) -> Vec<Vec<&str>> {
+    let parts: Vec<&str> = key.split('.').collect();
+    let rest = match parts.as_slice() {
+        ["thoughts", rest @ ..] if !rest.is_empty() => rest,
+        all => all,
+    };
+    let mut paths = vec![parts.clone()];
+    for prefix in [&["thoughts"][..], &["thoughts", "backend"]] {
+        let path: Vec<&str> = prefix.iter().copied().chain(rest.iter().copied()).collect();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Set `path` in `json` to `value`, creating missing objects along the
+/// way. `false` if something other than an object is in the way.
+fn set_json_path(json: &mut serde_json::Value, path: &[&str], value: serde_json::Value) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut node = json;
+    for part in parents {
+        if node.is_null() {
+            *node = serde_json::json!({});
+        }
+        let Some(object) = node.as_object_mut() else {
+            return false;
+        };
+        node = object.entry(*part).or_insert(serde_json::Value::Null);
+    }
+    if node.is_null() {
+        *node = serde_json::json!({});
+    }
+    match node.as_object_mut() {
+        Some(object) => {
+            object.insert(last.to_string(), value);
+            true
+        }
+        None => false,
+    }
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
-    fn git_thoughts(thoughts_repo: &str, repos_dir: &str, global_dir: &str) -> ThoughtsConfig {
+    pub(crate) fn git_thoughts(
+        thoughts_repo: &str,
+        repos_dir: &str,
+        global_dir: &str,
+    ) -> ThoughtsConfig {
         ThoughtsConfig {
             user: "testuser".to_string(),
             backend: BackendConfig::Git(GitConfig {
@@ -805,6 +2050,16 @@ mod tests {
         }
     }
 
+    /// A git backend at `thoughts_repo` with the default `repos` and
+    /// `global` directories.
+    pub(crate) fn git_backend(thoughts_repo: impl AsRef<Path>) -> BackendConfig {
+        BackendConfig::Git(GitConfig {
+            thoughts_repo: thoughts_repo.as_ref().display().to_string(),
+            repos_dir: "repos".to_string(),
+            global_dir: "global".to_string(),
+        })
+    }
+
     #[test]
     fn thoughts_config_default_values() {
         let config = ThoughtsConfig::default();
@@ -817,12 +2072,35 @@ mod tests {
     #[test]
     fn ai_config_default_values() {
         let config = AiConfig::default();
-        assert!(config.agent_tool.is_none());
+        assert!(config.agent_tools.is_empty());
         assert!(config.opencode_provider.is_none());
         assert!(config.opencode_sonnet_model.is_none());
         assert!(config.opencode_opus_model.is_none());
     }
 
+    #[test]
+    fn ai_config_accepts_single_agent_tool_and_saves_a_list() {
+        let old: AiConfig = serde_json::from_str(r#"{"agentTool": "opencode"}"#).unwrap();
+        assert_eq!(old.agent_tools, [AgentTool::OpenCode]);
+        let json = serde_json::to_value(&old).unwrap();
+        assert_eq!(json["agentTools"], serde_json::json!(["opencode"]));
+        assert!(json.get("agentTool").is_none());
+
+        let unset: AiConfig = serde_json::from_str(r#"{"agentTool": null}"#).unwrap();
+        assert!(unset.agent_tools.is_empty());
+        assert!(
+            serde_json::to_value(&unset)
+                .unwrap()
+                .get("agentTools")
+                .is_none()
+        );
+
+        let many: AiConfig =
+            serde_json::from_str(r#"{"agentTools": ["claude", "opencode"]}"#).unwrap();
+        assert_eq!(many.agent_tools, [AgentTool::Claude, AgentTool::OpenCode]);
+        assert_eq!(many.primary_tool(), Some(AgentTool::Claude));
+    }
+
     #[test]
     fn hyprlayer_config_save_load_round_trip() {
         let temp_dir = std::env::temp_dir().join("hyprlayer_test_config_round_trip");
@@ -833,10 +2111,12 @@ mod tests {
             last_version_check: Some(1700000000),
             last_agent_check: Some(1700000000),
             agents_installed_sha: Some("abc123def456".to_string()),
+            last_changelog_shown: Some("v1.6.0".to_string()),
             disable_update_check: true,
+            update_check_interval_hours: Some(12),
             thoughts: Some(git_thoughts("~/thoughts", "repos", "global")),
             ai: Some(AiConfig {
-                agent_tool: Some(AgentTool::Claude),
+                agent_tools: vec![AgentTool::Claude],
                 ..Default::default()
             }),
         };
@@ -848,6 +2128,8 @@ mod tests {
         assert_eq!(loaded.last_version_check, Some(1700000000));
         assert_eq!(loaded.last_agent_check, Some(1700000000));
         assert_eq!(loaded.agents_installed_sha.as_deref(), Some("abc123def456"));
+        assert_eq!(loaded.last_changelog_shown.as_deref(), Some("v1.6.0"));
+        assert_eq!(loaded.update_check_interval_hours, Some(12));
         assert!(loaded.disable_update_check);
 
         let thoughts = loaded.thoughts.unwrap();
@@ -859,12 +2141,179 @@ mod tests {
         assert!(thoughts.repo_mappings.is_empty());
 
         let ai = loaded.ai.unwrap();
-        assert!(matches!(ai.agent_tool, Some(AgentTool::Claude)));
+        assert_eq!(ai.agent_tools, [AgentTool::Claude]);
         assert!(ai.opencode_provider.is_none());
 
         fs::remove_dir_all(&temp_dir).ok();
     }
 
+    /// Every object key in `value`, except the user-chosen ones of
+    /// `repoMappings` and `profiles` (paths and profile names).
+    fn config_keys(value: &serde_json::Value, keys: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    keys.push(key.clone());
+                    if key == "repoMappings" || key == "profiles" {
+                        for entry in child.as_object().into_iter().flat_map(|m| m.values()) {
+                            config_keys(entry, keys);
+                        }
+                    } else {
+                        config_keys(child, keys);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    config_keys(item, keys);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Lists every field without `..Default::default()`, so adding a field
+    /// to `ThoughtsConfig` doesn't compile until it's covered here.
+    #[test]
+    fn thoughts_config_round_trips_with_camel_case_keys() {
+        let config = ThoughtsConfig {
+            user: "alice".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: "~/thoughts".to_string(),
+                repos_dir: "repos".to_string(),
+                global_dir: "global".to_string(),
+            }),
+            repo_mappings: HashMap::from([
+                (
+                    "/src/my_app".to_string(),
+                    RepoMapping::String("my_app".to_string()),
+                ),
+                (
+                    "/src/work".to_string(),
+                    RepoMapping::new("work", &Some("client_a".to_string())),
+                ),
+            ]),
+            profiles: HashMap::from([(
+                "client_a".to_string(),
+                ProfileConfig {
+                    backend: BackendConfig::Obsidian(ObsidianConfig {
+                        vault_path: "~/vault".to_string(),
+                        vault_subpath: Some("notes".to_string()),
+                        repos_dir: "repos".to_string(),
+                        global_dir: "global".to_string(),
+                    }),
+                    ai: Some(AiConfig {
+                        agent_tools: vec![AgentTool::OpenCode],
+                        opencode_provider: Some(OpenCodeProvider::GithubCopilot),
+                        opencode_sonnet_model: Some("sonnet".to_string()),
+                        opencode_opus_model: Some("opus".to_string()),
+                        agent_pin_ref: Some("v1.2.3".to_string()),
+                        copilot_dest: Some("~/vscode".to_string()),
+                    }),
+                },
+            )]),
+            default_profile: Some("client_a".to_string()),
+            hooks_enabled: false,
+            sync_jitter_max_seconds: Some(30),
+            encrypted_files: vec!["/thoughts/secret.md.age".to_string()],
+            skip_secrets_scan: true,
+            proxy: Some("http://proxy.corp:3128".to_string()),
+            push_with_git_cli: true,
+        };
+
+        let json = serde_json::to_value(&config).unwrap();
+        let mut keys = Vec::new();
+        config_keys(&json, &mut keys);
+        for key in [
+            "user",
+            "backend",
+            "kind",
+            "thoughtsRepo",
+            "reposDir",
+            "globalDir",
+            "repoMappings",
+            "repo",
+            "profile",
+            "profiles",
+            "vaultPath",
+            "vaultSubpath",
+            "ai",
+            "agentTools",
+            "opencodeProvider",
+            "opencodeSonnetModel",
+            "opencodeOpusModel",
+            "agentPinRef",
+            "copilotDest",
+            "defaultProfile",
+            "hooksEnabled",
+            "syncJitterMaxSeconds",
+            "encryptedFiles",
+            "skipSecretsScan",
+            "proxy",
+            "pushWithGitCli",
+        ] {
+            assert!(keys.iter().any(|k| k == key), "missing key {key}");
+        }
+        let snake: Vec<&String> = keys.iter().filter(|k| k.contains('_')).collect();
+        assert!(snake.is_empty(), "snake_case keys: {snake:?}");
+
+        let back: ThoughtsConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(back, config);
+    }
+
+    /// A v1 config on disk (no `version`, everything flat under
+    /// `thoughts`) loads as the current shape and is rewritten in it.
+    #[test]
+    fn load_migrates_v1_file_to_current_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        fs::write(
+            &config_path,
+            r#"{
+                "thoughts": {
+                    "thoughtsRepo": "~/thoughts",
+                    "reposDir": "repos",
+                    "globalDir": "global",
+                    "user": "alice",
+                    "agentTool": "opencode",
+                    "opencodeProvider": "github-copilot",
+                    "repoMappings": {"/src/app": "app"},
+                    "profiles": {},
+                    "lastVersionCheck": 1700000000,
+                    "disableUpdateCheck": true
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let config = HyprlayerConfig::load(&config_path).unwrap();
+        assert_eq!(config.version, Some(3));
+        assert_eq!(config.last_version_check, Some(1_700_000_000));
+        assert!(config.disable_update_check);
+        let thoughts = config.thoughts.as_ref().unwrap();
+        assert_eq!(
+            *thoughts,
+            ThoughtsConfig {
+                user: "alice".to_string(),
+                repo_mappings: HashMap::from([(
+                    "/src/app".to_string(),
+                    RepoMapping::String("app".to_string())
+                )]),
+                ..git_thoughts("~/thoughts", "repos", "global")
+            }
+        );
+        let ai = config.ai.as_ref().unwrap();
+        assert_eq!(ai.agent_tools, [AgentTool::OpenCode]);
+        assert_eq!(ai.opencode_provider, Some(OpenCodeProvider::GithubCopilot));
+
+        let on_disk: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        let mut keys = Vec::new();
+        config_keys(&on_disk, &mut keys);
+        assert!(keys.iter().all(|k| !k.contains('_')), "{keys:?}");
+        assert!(on_disk["thoughts"].get("agentTool").is_none());
+    }
+
     #[test]
     fn migrate_v1_full_config() {
         let json = r#"{
@@ -897,7 +2346,7 @@ mod tests {
         assert_eq!(thoughts.user, "testuser");
 
         let ai = config.ai.unwrap();
-        assert!(matches!(ai.agent_tool, Some(AgentTool::Claude)));
+        assert_eq!(ai.agent_tools, [AgentTool::Claude]);
     }
 
     #[test]
@@ -914,7 +2363,7 @@ mod tests {
         let v2 = HyprlayerConfig::migrate_v1(json).unwrap();
         let config = HyprlayerConfig::migrate_v2(&serde_json::to_string(&v2).unwrap()).unwrap();
         let ai = config.ai.unwrap();
-        assert!(matches!(ai.agent_tool, Some(AgentTool::Copilot)));
+        assert_eq!(ai.agent_tools, [AgentTool::Copilot]);
 
         let thoughts = config.thoughts.unwrap();
         assert!(!thoughts.is_thoughts_configured());
@@ -945,7 +2394,7 @@ mod tests {
         assert!(thoughts.is_thoughts_configured());
 
         let ai = config.ai.unwrap();
-        assert!(ai.agent_tool.is_none());
+        assert!(ai.agent_tools.is_empty());
     }
 
     #[test]
@@ -1425,6 +2874,859 @@ mod tests {
         assert_eq!(BackendConfig::default().kind(), BackendKind::Git);
     }
 
+    #[test]
+    fn merge_combines_mappings_and_prefers_override_scalars() {
+        let mut base = git_thoughts("/base", "repos", "global");
+        base.repo_mappings
+            .insert("/code/a".to_string(), RepoMapping::new("a", &None));
+        base.repo_mappings
+            .insert("/code/shared".to_string(), RepoMapping::new("old", &None));
+        base.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/base-work", "repos", "global").backend,
+                ai: None,
+            },
+        );
+
+        let mut over = ThoughtsConfig {
+            user: "override".to_string(),
+            backend: BackendConfig::Git(GitConfig {
+                thoughts_repo: "/over".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        over.repo_mappings
+            .insert("/code/b".to_string(), RepoMapping::new("b", &None));
+        over.repo_mappings
+            .insert("/code/shared".to_string(), RepoMapping::new("new", &None));
+        over.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/over-work", "repos", "global").backend,
+                ai: None,
+            },
+        );
+
+        let merged = ThoughtsConfig::merge(base, over);
+        assert_eq!(merged.user, "override");
+        let git = merged.backend.as_git().unwrap();
+        assert_eq!(git.thoughts_repo, "/over");
+        // Empty override fields don't clobber the base.
+        assert_eq!(git.repos_dir, "repos");
+        assert_eq!(git.global_dir, "global");
+
+        assert_eq!(merged.repo_mappings.len(), 3);
+        assert_eq!(merged.repo_mappings["/code/a"].repo(), "a");
+        assert_eq!(merged.repo_mappings["/code/b"].repo(), "b");
+        assert_eq!(merged.repo_mappings["/code/shared"].repo(), "new");
+        assert_eq!(
+            merged.profiles["work"]
+                .backend
+                .as_git()
+                .unwrap()
+                .thoughts_repo,
+            "/over-work"
+        );
+    }
+
+    #[test]
+    fn merge_keeps_base_user_and_backend_when_override_is_empty() {
+        let base = git_thoughts("/base", "repos", "global");
+        let over = ThoughtsConfig {
+            backend: BackendConfig::Notion(NotionConfig::default()),
+            ..Default::default()
+        };
+        let merged = ThoughtsConfig::merge(base, over);
+        assert_eq!(merged.user, "testuser");
+        assert_eq!(merged.backend.as_git().unwrap().thoughts_repo, "/base");
+
+        let over = ThoughtsConfig {
+            backend: BackendConfig::Notion(NotionConfig {
+                parent_page_id: "page".to_string(),
+                database_id: None,
+            }),
+            ..Default::default()
+        };
+        let merged = ThoughtsConfig::merge(git_thoughts("/base", "repos", "global"), over);
+        assert_eq!(merged.backend.kind(), BackendKind::Notion);
+    }
+
+    fn profile_ai_config() -> HyprlayerConfig {
+        let mut thoughts = git_thoughts("/t", "repos", "global");
+        thoughts.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: Some(AiConfig {
+                    agent_tools: vec![AgentTool::OpenCode],
+                    opencode_sonnet_model: Some("work-sonnet".to_string()),
+                    ..Default::default()
+                }),
+            },
+        );
+        thoughts.repo_mappings.insert(
+            "/code/work".to_string(),
+            RepoMapping::new("work", &Some("work".to_string())),
+        );
+        thoughts
+            .repo_mappings
+            .insert("/code/home".to_string(), RepoMapping::new("home", &None));
+        HyprlayerConfig {
+            thoughts: Some(thoughts),
+            ai: Some(AiConfig {
+                agent_tools: vec![AgentTool::Claude],
+                opencode_sonnet_model: Some("top-sonnet".to_string()),
+                opencode_opus_model: Some("top-opus".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn effective_ai_prefers_profile_and_falls_back_per_field() {
+        let cfg = profile_ai_config();
+        assert_eq!(
+            cfg.effective_agent_tool_for_repo(Some("/code/work")),
+            Some(&AgentTool::OpenCode)
+        );
+        assert_eq!(
+            cfg.effective_models_for_repo(Some("/code/work")),
+            (Some("work-sonnet"), Some("top-opus"))
+        );
+
+        for repo in [Some("/code/home"), Some("/code/unmapped"), None] {
+            assert_eq!(
+                cfg.effective_agent_tool_for_repo(repo),
+                Some(&AgentTool::Claude)
+            );
+            assert_eq!(
+                cfg.effective_models_for_repo(repo),
+                (Some("top-sonnet"), Some("top-opus"))
+            );
+        }
+    }
+
+    #[test]
+    fn effective_ai_uses_profile_without_top_level_block() {
+        let mut cfg = profile_ai_config();
+        cfg.ai = None;
+        let ai = cfg.effective_ai_for_repo(Some("/code/work")).unwrap();
+        assert_eq!(ai.agent_tools, [AgentTool::OpenCode]);
+        assert!(ai.opencode_provider.is_none());
+        assert_eq!(ai.opencode_opus_model, None);
+        assert!(cfg.effective_ai_for_repo(Some("/code/home")).is_none());
+        assert!(
+            cfg.effective_opencode_provider_for_repo(Some("/code/work"))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn profile_ai_round_trips_and_is_omitted_when_unset() {
+        let cfg = profile_ai_config();
+        let json = serde_json::to_value(&cfg).unwrap();
+        let profile = &json["thoughts"]["profiles"]["work"];
+        assert_eq!(profile["ai"]["agentTools"], serde_json::json!(["opencode"]));
+        let loaded: HyprlayerConfig = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            loaded.effective_agent_tool_for_repo(Some("/code/work")),
+            Some(&AgentTool::OpenCode)
+        );
+
+        let mut thoughts = cfg.thoughts.unwrap();
+        thoughts.profiles.get_mut("work").unwrap().ai = None;
+        let json = serde_json::to_value(&thoughts).unwrap();
+        assert!(json["profiles"]["work"].get("ai").is_none());
+    }
+
+    #[test]
+    fn load_merged_folds_files_left_to_right() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_load_merged");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let base_path = temp_dir.join("base.json");
+        let over_path = temp_dir.join("override.json");
+
+        let mut base = git_thoughts("/team/thoughts", "repos", "global");
+        base.repo_mappings
+            .insert("/code/a".to_string(), RepoMapping::new("a", &None));
+        HyprlayerConfig {
+            thoughts: Some(base),
+            ai: Some(AiConfig {
+                agent_tools: vec![AgentTool::Claude],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .save(&base_path)
+        .unwrap();
+        fs::write(
+            &over_path,
+            r#"{"version":3,"thoughts":{"user":"me","repoMappings":{"/code/b":"b"}}}"#,
+        )
+        .unwrap();
+
+        let (merged, sources) = HyprlayerConfig::load_merged(&[&base_path, &over_path]).unwrap();
+        let layer = format!("config file {}", over_path.display());
+        assert_eq!(sources.get("/thoughts/user"), Some(layer.as_str()));
+        assert_eq!(
+            sources.get(&json_pointer("/thoughts/repoMappings", "/code/b")),
+            Some(layer.as_str())
+        );
+        assert_eq!(sources.get("/thoughts/backend"), None);
+        assert_eq!(
+            sources.get(&json_pointer("/thoughts/repoMappings", "/code/a")),
+            None
+        );
+        let thoughts = merged.thoughts.unwrap();
+        assert_eq!(thoughts.user, "me");
+        assert_eq!(
+            thoughts.backend.as_git().unwrap().thoughts_repo,
+            "/team/thoughts"
+        );
+        assert!(thoughts.repo_mappings.contains_key("/code/a"));
+        assert!(thoughts.repo_mappings.contains_key("/code/b"));
+        assert_eq!(merged.ai.unwrap().agent_tools, [AgentTool::Claude]);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn apply_override_follows_dot_paths_and_shorthands() {
+        let mut cfg = HyprlayerConfig {
+            thoughts: Some(git_thoughts("/team/thoughts", "repos", "global")),
+            ..Default::default()
+        };
+        assert!(
+            cfg.apply_override("thoughts.thoughtsRepo", "/tmp/test-thoughts")
+                .unwrap()
+        );
+        let git = |cfg: &HyprlayerConfig| cfg.thoughts.as_ref().unwrap().backend.as_git().cloned();
+        assert_eq!(git(&cfg).unwrap().thoughts_repo, "/tmp/test-thoughts");
+        assert!(
+            cfg.apply_override("thoughts.backend.reposDir", "r2")
+                .unwrap()
+        );
+        assert_eq!(git(&cfg).unwrap().repos_dir, "r2");
+        assert!(cfg.apply_override("user", "someone").unwrap());
+        assert_eq!(cfg.thoughts.as_ref().unwrap().user, "someone");
+
+        // JSON values where they fit, plain strings otherwise.
+        assert!(cfg.apply_override("disableUpdateCheck", "true").unwrap());
+        assert!(cfg.disable_update_check);
+        assert!(cfg.apply_override("user", "123").unwrap());
+        assert_eq!(cfg.thoughts.as_ref().unwrap().user, "123");
+        assert!(cfg.apply_override("ai.agentPinRef", "v1.0.0").unwrap());
+        assert_eq!(cfg.agent_pin_ref(), Some("v1.0.0"));
+
+        assert!(!cfg.apply_override("thoughts.nope", "x").unwrap());
+        assert!(!cfg.apply_override("user.name", "x").unwrap());
+        assert!(cfg.apply_override("disableUpdateCheck", "maybe").is_err());
+        assert!(cfg.disable_update_check);
+    }
+
+    #[test]
+    fn set_overrides_apply_without_touching_the_file() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_set_overrides");
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("config.json");
+        HyprlayerConfig {
+            thoughts: Some(git_thoughts("/team/thoughts", "repos", "global")),
+            ..Default::default()
+        }
+        .save(&config_path)
+        .unwrap();
+        let before = fs::read_to_string(&config_path).unwrap();
+
+        let args = crate::cli::ConfigArgs {
+            config_file: vec![config_path.display().to_string()],
+            overrides: vec!["thoughts.thoughtsRepo=/tmp/test-thoughts".to_string()],
+        };
+        let cfg = args.load().unwrap();
+        assert_eq!(
+            cfg.thoughts
+                .unwrap()
+                .backend
+                .as_git()
+                .unwrap()
+                .thoughts_repo,
+            "/tmp/test-thoughts"
+        );
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), before);
+
+        let view = args.load_if_exists().unwrap().unwrap();
+        assert_eq!(
+            view.thoughts
+                .unwrap()
+                .backend
+                .as_git()
+                .unwrap()
+                .thoughts_repo,
+            "/tmp/test-thoughts"
+        );
+        assert!(args.reject_overrides("thoughts config set").is_err());
+
+        let bad = crate::cli::ConfigArgs {
+            overrides: vec!["thoughtsRepo".to_string()],
+            ..args
+        };
+        assert!(bad.load().is_err());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn config_file_layers_merge_on_load_and_writes_stay_in_the_target() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_config_layers");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let base = temp_dir.join("base.json");
+        let target = temp_dir.join("local.json");
+        HyprlayerConfig {
+            thoughts: Some(git_thoughts("/team/thoughts", "repos", "global")),
+            ..Default::default()
+        }
+        .save(&base)
+        .unwrap();
+        fs::write(&target, "{}").unwrap();
+
+        let args = crate::cli::ConfigArgs {
+            config_file: vec![base.display().to_string(), target.display().to_string()],
+            overrides: Vec::new(),
+        };
+        let merged = args.load_if_exists().unwrap().unwrap();
+        assert!(merged.thoughts.is_some());
+        assert!(args.load_target().unwrap().unwrap().thoughts.is_none());
+
+        args.update(|target| {
+            target.last_version_check = Some(1);
+            Ok(())
+        })
+        .unwrap();
+        let written = HyprlayerConfig::load(&target).unwrap();
+        assert!(written.thoughts.is_none());
+        assert!(written.last_version_check.is_some());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn config_lock_is_exclusive_and_released_on_drop() {
+        let temp_dir = std::env::temp_dir().join("hyprlayer_test_config_lock");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let config_path = temp_dir.join("config.json");
+        let lock_path = temp_dir.join("config.json.lock");
+
+        let lock = acquire_config_lock(&config_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&lock_path).unwrap(),
+            std::process::id().to_string()
+        );
+        let err = acquire_lock_with_timeout(&config_path, Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().contains("Another hyprlayer process"));
+
+        drop(lock);
+        assert!(acquire_config_lock(&config_path).is_ok());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn config_lock_ignores_lock_file_left_by_dead_process() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        // Only a held lock counts; the file outlives its holder.
+        fs::write(temp_dir.path().join("config.json.lock"), "999999").unwrap();
+        assert!(acquire_lock_with_timeout(&config_path, Duration::ZERO).is_ok());
+    }
+
+    #[test]
+    fn concurrent_updates_keep_every_change() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        HyprlayerConfig {
+            thoughts: Some(git_thoughts("/t", "repos", "global")),
+            ..Default::default()
+        }
+        .save(&config_path)
+        .unwrap();
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let config_path = &config_path;
+                scope.spawn(move || {
+                    HyprlayerConfig::update(config_path, |config| {
+                        config.thoughts_mut().repo_mappings.insert(
+                            format!("/code/{i}"),
+                            RepoMapping::new(&format!("repo{i}"), &None),
+                        );
+                        Ok(())
+                    })
+                    .unwrap();
+                });
+            }
+        });
+
+        let saved = HyprlayerConfig::load(&config_path).unwrap();
+        assert_eq!(saved.thoughts.unwrap().repo_mappings.len(), 8);
+    }
+
+    #[test]
+    fn update_migrates_an_older_config_without_waiting_on_its_own_lock() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+        fs::write(
+            &config_path,
+            r#"{
+                "version": 2,
+                "thoughts": {
+                    "thoughtsRepo": "~/thoughts",
+                    "reposDir": "repos",
+                    "globalDir": "global",
+                    "user": "alice",
+                    "backend": "git"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        HyprlayerConfig::update(&config_path, |config| {
+            config.disable_update_check = true;
+            Ok(())
+        })
+        .unwrap();
+        assert!(started.elapsed() < LOCK_TIMEOUT);
+
+        let (saved, migrated) =
+            HyprlayerConfig::parse(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert!(!migrated);
+        assert!(saved.disable_update_check);
+        assert_eq!(saved.thoughts.unwrap().user, "alice");
+    }
+
+    #[test]
+    fn rename_profile_moves_profile_and_repoints_mappings() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        cfg.profiles.insert(
+            "wrk".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: None,
+            },
+        );
+        cfg.repo_mappings.insert(
+            "/code/b".to_string(),
+            RepoMapping::new("b", &Some("wrk".to_string())),
+        );
+        cfg.repo_mappings.insert(
+            "/code/a".to_string(),
+            RepoMapping::new("a", &Some("wrk".to_string())),
+        );
+        cfg.repo_mappings
+            .insert("/code/c".to_string(), RepoMapping::new("c", &None));
+
+        assert_eq!(cfg.repos_using_profile("wrk"), vec!["/code/a", "/code/b"]);
+        let updated = cfg.rename_profile("wrk", "work").unwrap();
+        assert_eq!(updated, vec!["/code/a", "/code/b"]);
+        assert!(!cfg.profiles.contains_key("wrk"));
+        assert!(cfg.profiles.contains_key("work"));
+        assert_eq!(cfg.repo_mappings["/code/a"].profile(), Some("work"));
+        assert_eq!(cfg.repo_mappings["/code/a"].repo(), "a");
+        assert_eq!(cfg.repo_mappings["/code/c"].profile(), None);
+    }
+
+    #[test]
+    fn migrate_profile_mappings_repoints_to_profile_or_default() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        for name in ["old", "new"] {
+            cfg.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: git_thoughts("/w", "repos", "global").backend,
+                    ai: None,
+                },
+            );
+        }
+        cfg.repo_mappings.insert(
+            "/code/a".to_string(),
+            RepoMapping::new("a", &Some("old".to_string())),
+        );
+        cfg.repo_mappings
+            .insert("/code/b".to_string(), RepoMapping::new("b", &None));
+
+        assert!(cfg.migrate_profile_mappings("old", Some("old")).is_err());
+        assert!(cfg.migrate_profile_mappings("old", Some("gone")).is_err());
+        assert!(cfg.migrate_profile_mappings("gone", None).is_err());
+
+        let mut to_new = cfg.clone();
+        assert_eq!(
+            to_new.migrate_profile_mappings("old", Some("new")).unwrap(),
+            vec!["/code/a"]
+        );
+        assert_eq!(to_new.repo_mappings["/code/a"].profile(), Some("new"));
+
+        assert_eq!(
+            cfg.migrate_profile_mappings("old", None).unwrap(),
+            vec!["/code/a"]
+        );
+        assert!(matches!(
+            &cfg.repo_mappings["/code/a"],
+            RepoMapping::String(name) if name == "a"
+        ));
+    }
+
+    #[test]
+    fn default_profile_applies_to_new_mappings_and_follows_renames() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        cfg.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: None,
+            },
+        );
+        assert_eq!(cfg.profile_for_new_mapping(None), None);
+
+        cfg.default_profile = Some("work".to_string());
+        assert_eq!(cfg.profile_for_new_mapping(None).as_deref(), Some("work"));
+        assert_eq!(
+            cfg.profile_for_new_mapping(Some("other".to_string()))
+                .as_deref(),
+            Some("other")
+        );
+        assert!(cfg.validate().is_empty());
+
+        cfg.rename_profile("work", "job").unwrap();
+        assert_eq!(cfg.default_profile.as_deref(), Some("job"));
+
+        cfg.default_profile = Some("gone".to_string());
+        assert_eq!(
+            cfg.validate(),
+            vec!["defaultProfile references missing profile \"gone\""]
+        );
+    }
+
+    #[test]
+    fn find_duplicate_mappings_groups_repos_sharing_a_directory() {
+        let mut cfg = git_thoughts("/team/thoughts", "repos", "global");
+        cfg.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Git(GitConfig {
+                    thoughts_repo: "/work/thoughts".to_string(),
+                    repos_dir: "repos".to_string(),
+                    global_dir: "global".to_string(),
+                }),
+                ai: None,
+            },
+        );
+        for (path, name, profile) in [
+            ("/code/b", "my-project", None),
+            ("/code/a", "my-project", None),
+            ("/code/c", "other", None),
+            // Same name, but in the work profile's thoughts repository.
+            ("/code/d", "my-project", Some("work".to_string())),
+        ] {
+            cfg.repo_mappings
+                .insert(path.to_string(), RepoMapping::new(name, &profile));
+        }
+        assert_eq!(
+            cfg.find_duplicate_mappings(),
+            [vec!["/code/a".to_string(), "/code/b".to_string()]]
+        );
+
+        cfg.repo_mappings.remove("/code/b");
+        assert!(cfg.find_duplicate_mappings().is_empty());
+    }
+
+    #[test]
+    fn orphaned_mappings_lists_mappings_to_missing_profiles() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        cfg.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: None,
+            },
+        );
+        cfg.repo_mappings.insert(
+            "/code/a".to_string(),
+            RepoMapping::new("a", &Some("work".to_string())),
+        );
+        cfg.repo_mappings.insert(
+            "/code/b".to_string(),
+            RepoMapping::new("b", &Some("gone".to_string())),
+        );
+        cfg.repo_mappings
+            .insert("/code/c".to_string(), RepoMapping::new("c", &None));
+
+        assert_eq!(
+            cfg.orphaned_mappings(),
+            vec![("/code/b".to_string(), "gone".to_string())]
+        );
+    }
+
+    #[test]
+    fn repo_mapping_displays_repo_and_profile() {
+        assert_eq!(RepoMapping::new("app", &None).to_string(), "app");
+        assert_eq!(
+            RepoMapping::new("app", &Some("work".to_string())).to_string(),
+            "app (profile: work)"
+        );
+        let no_profile = RepoMapping::Object {
+            repo: "app".to_string(),
+            profile: None,
+        };
+        assert_eq!(no_profile.to_string(), "app");
+    }
+
+    #[test]
+    fn repo_mapping_diff_describes_each_change() {
+        let plain = |repo: &str| RepoMapping::new(repo, &None);
+        let with = |repo: &str, profile: &str| RepoMapping::new(repo, &Some(profile.to_string()));
+
+        assert!(RepoMapping::diff(&plain("app"), &plain("app")).is_empty());
+        assert!(
+            RepoMapping::diff(
+                &plain("app"),
+                &RepoMapping::Object {
+                    repo: "app".to_string(),
+                    profile: None,
+                }
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            RepoMapping::diff(&plain("app"), &plain("web")),
+            ["repo changed: app → web"]
+        );
+        assert_eq!(
+            RepoMapping::diff(&plain("app"), &with("app", "work")),
+            ["profile added: work"]
+        );
+        assert_eq!(
+            RepoMapping::diff(&with("app", "work"), &plain("app")),
+            ["profile removed: work"]
+        );
+        assert_eq!(
+            RepoMapping::diff(&with("app", "wrk"), &with("web", "work")),
+            ["repo changed: app → web", "profile changed: wrk → work"]
+        );
+    }
+
+    #[test]
+    fn repo_mapping_equality_compares_variant_and_fields() {
+        let with = |profile: &str| RepoMapping::new("app", &Some(profile.to_string()));
+        assert_eq!(with("work"), with("work"));
+        assert_ne!(with("work"), with("home"));
+        assert_ne!(
+            RepoMapping::new("app", &None),
+            RepoMapping::Object {
+                repo: "app".to_string(),
+                profile: None,
+            }
+        );
+    }
+
+    #[test]
+    fn rename_profile_rejects_missing_taken_and_same_names() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        for name in ["a", "b"] {
+            cfg.profiles.insert(
+                name.to_string(),
+                ProfileConfig {
+                    backend: git_thoughts("/w", "repos", "global").backend,
+                    ai: None,
+                },
+            );
+        }
+        assert!(cfg.rename_profile("missing", "c").is_err());
+        assert!(cfg.rename_profile("a", "b").is_err());
+        assert!(cfg.rename_profile("a", "a").is_err());
+        assert!(cfg.profiles.contains_key("a"));
+    }
+
+    #[test]
+    fn redact_json_hides_sensitive_fields_only() {
+        let mut value = serde_json::json!({
+            "githubToken": "ghp_secret",
+            "github_token": "ghp_secret",
+            "ai": { "openaiApiKey": "sk-1", "opencodeOpusModel": "opus" },
+            "password": null,
+            "thoughts": {
+                "backend": { "kind": "anytype", "spaceId": "s", "apiTokenEnv": "ANYTYPE_KEY" },
+                "thoughtsRepo": "/home/me/thoughts",
+                "repoMappings": { "/code/keys-service": "keys-service" },
+                "profiles": { "keyring": { "backend": { "kind": "git", "clientSecret": "x" } } }
+            }
+        });
+        redact_json(&mut value);
+
+        assert_eq!(value["githubToken"], REDACTED);
+        assert_eq!(value["github_token"], REDACTED);
+        assert_eq!(value["ai"]["openaiApiKey"], REDACTED);
+        assert_eq!(value["ai"]["opencodeOpusModel"], "opus");
+        assert!(value["password"].is_null());
+        assert_eq!(value["thoughts"]["thoughtsRepo"], "/home/me/thoughts");
+        assert_eq!(value["thoughts"]["backend"]["apiTokenEnv"], "ANYTYPE_KEY");
+        assert_eq!(
+            value["thoughts"]["repoMappings"]["/code/keys-service"],
+            "keys-service"
+        );
+        assert_eq!(
+            value["thoughts"]["profiles"]["keyring"]["backend"]["clientSecret"],
+            REDACTED
+        );
+    }
+
+    #[test]
+    fn to_redacted_keeps_non_sensitive_thoughts_fields() {
+        let cfg = git_thoughts("/t", "repos", "global");
+        let value = cfg.to_redacted();
+        assert_eq!(value["user"], "testuser");
+        assert_eq!(value["backend"]["thoughtsRepo"], "/t");
+    }
+
+    #[test]
+    fn validate_accepts_complete_config() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        cfg.profiles.insert(
+            "work".to_string(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: None,
+            },
+        );
+        cfg.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("work".to_string())),
+        );
+        assert!(cfg.validate().is_empty());
+    }
+
+    #[test]
+    fn validate_reports_each_problem() {
+        let mut cfg = git_thoughts("", "same", "same");
+        cfg.user.clear();
+        cfg.profiles.insert(
+            "bad name".to_string(),
+            ProfileConfig {
+                backend: BackendConfig::Notion(NotionConfig::default()),
+                ai: None,
+            },
+        );
+        cfg.repo_mappings.insert(
+            "/code/app".to_string(),
+            RepoMapping::new("app", &Some("gone".to_string())),
+        );
+
+        assert_eq!(
+            cfg.validate(),
+            vec![
+                "user is empty",
+                "backend.thoughtsRepo is empty",
+                "backend.reposDir and backend.globalDir must differ (both \"same\")",
+                "profile name \"bad name\" is not a valid slug",
+                "profiles.bad name.backend.parentPageId is empty",
+                "repoMappings./code/app references missing profile \"gone\"",
+            ]
+        );
+    }
+
+    fn git_profile(thoughts_repo: &str, repos_dir: &str, global_dir: &str) -> ProfileConfig {
+        ProfileConfig {
+            backend: git_thoughts(thoughts_repo, repos_dir, global_dir).backend,
+            ai: None,
+        }
+    }
+
+    #[test]
+    fn profile_validate_accepts_a_repo_under_an_existing_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("new/thoughts").display().to_string();
+        assert!(
+            git_profile(&repo, "repos", "global")
+                .validate("work")
+                .is_empty()
+        );
+        let repo = dir.path().display().to_string();
+        assert!(
+            git_profile(&repo, "repos", "global")
+                .validate("work")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn profile_validate_reports_empty_fields_and_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().display().to_string();
+        assert_eq!(
+            git_profile("", "", "global").validate("work"),
+            vec![
+                "profiles.work.backend.thoughtsRepo is empty",
+                "profiles.work.backend.reposDir is empty",
+            ]
+        );
+        assert_eq!(
+            git_profile(&repo, "a/repos", "b\\global").validate("work"),
+            vec![
+                "profiles.work.backend.reposDir must be a single directory name, not a path (\"a/repos\")",
+                "profiles.work.backend.globalDir must be a single directory name, not a path (\"b\\global\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn profile_validate_reports_a_repo_that_cant_be_created() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("file");
+        fs::write(&file, "").unwrap();
+
+        let problems = git_profile(&file.display().to_string(), "repos", "global").validate("work");
+        assert_eq!(
+            problems,
+            vec![format!(
+                "profiles.work.backend.thoughtsRepo {} is not a directory",
+                file.display()
+            )]
+        );
+
+        let nested = file.join("thoughts");
+        let profile = git_profile(&nested.display().to_string(), "repos", "global");
+        assert_eq!(
+            profile.validate("work"),
+            vec![format!(
+                "profiles.work.backend.thoughtsRepo {} can't be created: {} is not a directory",
+                nested.display(),
+                file.display()
+            )]
+        );
+        let err = profile.check("work").unwrap_err().to_string();
+        assert!(err.starts_with("Profile \"work\" is invalid:\n  - "));
+    }
+
+    #[test]
+    fn xdg_dir_prefers_an_absolute_variable() {
+        let home = Some(PathBuf::from("/home/alice"));
+        assert_eq!(
+            xdg_dir(Some("/xdg/config".into()), home.clone(), ".config"),
+            Some(PathBuf::from("/xdg/config/hyprlayer"))
+        );
+        for ignored in [None, Some(""), Some("relative/config")] {
+            assert_eq!(
+                xdg_dir(ignored.map(OsString::from), home.clone(), ".local/share"),
+                Some(PathBuf::from("/home/alice/.local/share/hyprlayer")),
+                "{ignored:?}"
+            );
+        }
+        assert_eq!(xdg_dir(None, None, ".config"), None);
+    }
+
     #[test]
     fn sanitize_directory_name_replaces_special_chars() {
         assert_eq!(sanitize_directory_name("my-project"), "my-project");
@@ -1432,6 +3734,109 @@ mod tests {
         assert_eq!(sanitize_directory_name("my project"), "my_project");
         assert_eq!(sanitize_directory_name("my/project"), "my_project");
         assert_eq!(sanitize_directory_name("my.project.rs"), "my_project_rs");
+        assert_eq!(
+            sanitize_directory_name("my-project 2025"),
+            "my-project_2025"
+        );
+    }
+
+    #[test]
+    fn sanitize_directory_name_transliterates_accented_latin() {
+        assert_eq!(sanitize_directory_name("café-notes"), "cafe-notes");
+        // Decomposed `e` + U+0301 composes under NFC before transliteration.
+        assert_eq!(sanitize_directory_name("cafe\u{0301}-notes"), "cafe-notes");
+        assert_eq!(sanitize_directory_name("Straße"), "Strasse");
+        assert_eq!(sanitize_directory_name("Ærøskøbing"), "AEroskobing");
+        assert_eq!(sanitize_directory_name("Nguyễn"), "Nguyen");
+    }
+
+    #[test]
+    fn sanitize_directory_name_replaces_cjk() {
+        assert_eq!(sanitize_directory_name("项目-notes"), "notes");
+        assert_eq!(
+            sanitize_directory_name("notes-日本語-draft"),
+            "notes-_-draft"
+        );
+    }
+
+    #[test]
+    fn sanitize_directory_name_collapses_and_trims() {
+        assert_eq!(sanitize_directory_name("a   b"), "a_b");
+        assert_eq!(sanitize_directory_name("a__b"), "a_b");
+        assert_eq!(sanitize_directory_name("__a.b--"), "a_b");
+        assert_eq!(sanitize_directory_name("-x-"), "x");
+    }
+
+    #[test]
+    fn sanitize_directory_name_handles_empty_and_all_special() {
+        assert_eq!(sanitize_directory_name(""), "");
+        assert_eq!(sanitize_directory_name("!!!"), "");
+        assert_eq!(sanitize_directory_name("日本"), "");
+        assert_eq!(sanitize_directory_name("_-_"), "");
+    }
+
+    #[test]
+    fn validate_directory_name_rejects_names_with_no_usable_characters() {
+        assert_eq!(validate_directory_name("my project").unwrap(), "my_project");
+        for name in ["", "!!!", "日本", "_-_"] {
+            let err = validate_directory_name(name).unwrap_err();
+            assert!(err.to_string().contains("no usable characters"), "{name}");
+        }
+    }
+
+    #[test]
+    fn sanitize_profile_name_hyphenates_whitespace() {
+        assert_eq!(sanitize_profile_name("Work Laptop"), "Work-Laptop");
+        assert_eq!(sanitize_profile_name("  work \t laptop  "), "work-laptop");
+        assert_eq!(sanitize_profile_name("café notes"), "cafe-notes");
+        assert_eq!(sanitize_profile_name("client/acme"), "client_acme");
+        assert_eq!(sanitize_profile_name("   "), "");
+    }
+
+    #[test]
+    fn validate_profile_name_rejects_empty_and_reserved_names() {
+        assert_eq!(validate_profile_name("My Work").unwrap(), "My-Work");
+        assert!(validate_profile_name("   ").is_err());
+        for reserved in ["default", "Global", " DEFAULT "] {
+            let err = validate_profile_name(reserved).unwrap_err();
+            assert!(err.to_string().contains("reserved"), "{reserved}");
+        }
+    }
+
+    #[test]
+    fn profile_names_resolve_as_created() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        let stored = cfg.check_new_profile_name("My Work", None).unwrap();
+        cfg.profiles.insert(
+            stored.clone(),
+            ProfileConfig {
+                backend: git_thoughts("/w", "repos", "global").backend,
+                ai: None,
+            },
+        );
+
+        // `profile show "My Work"` used to miss the `My-Work` that create stored.
+        for typed in ["My-Work", "My Work", "my work", "MY-WORK"] {
+            assert_eq!(cfg.resolve_profile_name(typed).unwrap(), "My-Work");
+        }
+        assert_eq!(
+            cfg.resolve_profile(Some("my work".to_string())).unwrap(),
+            Some("My-Work".to_string())
+        );
+        assert!(cfg.resolve_profile(None).unwrap().is_none());
+        let err = cfg.resolve_profile_name("other").unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+
+        let err = cfg.check_new_profile_name("my work", None).unwrap_err();
+        assert!(err.to_string().contains("differ only by case"));
+        let err = cfg.check_new_profile_name("My-Work", None).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        // A rename may change only the case of the profile's own name.
+        assert_eq!(
+            cfg.check_new_profile_name("my work", Some("My-Work"))
+                .unwrap(),
+            "my-work"
+        );
     }
 
     #[test]
@@ -1656,6 +4061,7 @@ mod tests {
                     repos_dir: "repos".to_string(),
                     global_dir: "global".to_string(),
                 }),
+                ai: None,
             },
         );
         cfg.repo_mappings.insert(
@@ -1686,4 +4092,55 @@ mod tests {
         assert_eq!(eff.backend.as_obsidian().unwrap().vault_path, "/vault");
         assert!(eff.mapped_name.is_none());
     }
+
+    #[test]
+    fn effective_config_builds_thoughts_paths() {
+        let mut cfg = git_thoughts("/t", "repos", "global");
+        cfg.repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+
+        let eff = cfg.effective_config_for("/code/app");
+        assert_eq!(eff.thoughts_repo_path().unwrap(), Path::new("/t"));
+        assert_eq!(eff.repos_dir_path().unwrap(), Path::new("/t/repos"));
+        assert_eq!(
+            eff.mapped_thoughts_path().unwrap(),
+            Path::new("/t/repos/app")
+        );
+
+        let unmapped = cfg.effective_config_for("/code/other");
+        assert_eq!(unmapped.repos_dir_path().unwrap(), Path::new("/t/repos"));
+        assert!(unmapped.mapped_thoughts_path().is_err());
+    }
+
+    #[test]
+    fn effective_config_paths_follow_obsidian_subpath_and_need_a_local_backend() {
+        let mut cfg = ThoughtsConfig {
+            user: "u".to_string(),
+            backend: BackendConfig::Obsidian(ObsidianConfig {
+                vault_path: "/vault".to_string(),
+                vault_subpath: Some("notes".to_string()),
+                repos_dir: "code".to_string(),
+                global_dir: "all".to_string(),
+            }),
+            ..Default::default()
+        };
+        cfg.repo_mappings
+            .insert("/code/app".to_string(), RepoMapping::new("app", &None));
+        assert_eq!(
+            cfg.effective_config_for("/code/app")
+                .mapped_thoughts_path()
+                .unwrap(),
+            Path::new("/vault/notes/code/app")
+        );
+
+        cfg.backend = BackendConfig::Notion(NotionConfig::default());
+        let err = cfg
+            .effective_config_for("/code/app")
+            .mapped_thoughts_path()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The notion backend has no local thoughts directory"
+        );
+    }
 }
@@ -1,8 +1,240 @@
 use anyhow::{Context, Result};
-use git2::{Repository, Status, StatusOptions};
-use std::process::Command;
+use git2::build::CheckoutBuilder;
+use git2::{
+    BranchType, Cred, CredentialType, ErrorClass, ErrorCode, FetchOptions, MergeOptions,
+    PushOptions, RemoteCallbacks, Repository, RepositoryInitOptions, Status, StatusOptions,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::UNIX_EPOCH;
 
+/// `thoughts.pushWithGitCli` from the config, set once at startup by
+/// `load_push_with_git_cli`. Pulls follow it too, since they authenticate
+/// the same way.
+static PUSH_WITH_GIT_CLI: AtomicBool = AtomicBool::new(false);
+
+/// Push with the `git` binary instead of libgit2 from now on.
+pub fn set_push_with_git_cli(enabled: bool) {
+    PUSH_WITH_GIT_CLI.store(enabled, Ordering::Relaxed);
+}
+
+/// Apply the `thoughts.pushWithGitCli` of the config at `config_path`, or
+/// of the default config.
+pub fn load_push_with_git_cli(config_path: Option<&Path>) {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => match crate::config::get_default_config_path() {
+            Ok(path) => path,
+            Err(_) => return,
+        },
+    };
+    let enabled = crate::config::HyprlayerConfig::load(&path)
+        .ok()
+        .and_then(|config| config.thoughts)
+        .is_some_and(|thoughts| thoughts.push_with_git_cli);
+    set_push_with_git_cli(enabled);
+}
+
+/// Private keys tried, in order, after the SSH agent.
+const SSH_KEY_NAMES: [&str; 3] = ["id_ed25519", "id_ecdsa", "id_rsa"];
+
+/// Answers libgit2's credential requests during a push: the SSH agent,
+/// then the usual keys in `~/.ssh` for SSH remotes, and git's credential
+/// helpers for HTTPS ones. libgit2 asks again after each rejected
+/// credential, so every source is offered once.
+struct CredentialChain {
+    config: git2::Config,
+    ssh_attempts: usize,
+    helper_tried: bool,
+}
+
+impl CredentialChain {
+    fn new(config: git2::Config) -> Self {
+        Self {
+            config,
+            ssh_attempts: 0,
+            helper_tried: false,
+        }
+    }
+
+    fn next(
+        &mut self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        let user = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(user);
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            let attempt = self.ssh_attempts;
+            self.ssh_attempts += 1;
+            if attempt == 0 {
+                return Cred::ssh_key_from_agent(user);
+            }
+            if let Some(key) = ssh_keys().get(attempt - 1) {
+                return Cred::ssh_key(user, None, key, None);
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.helper_tried {
+            self.helper_tried = true;
+            return Cred::credential_helper(&self.config, url, username);
+        }
+        Err(git2::Error::new(
+            ErrorCode::Auth,
+            ErrorClass::Callback,
+            "no credentials left to try",
+        ))
+    }
+}
+
+/// The keys in `~/.ssh` that `CredentialChain` offers.
+fn ssh_keys() -> Vec<PathBuf> {
+    let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) else {
+        return Vec::new();
+    };
+    SSH_KEY_NAMES
+        .iter()
+        .map(|name| ssh_dir.join(name))
+        .filter(|key| key.is_file())
+        .collect()
+}
+
+/// What went wrong talking to the remote at `url`, when `error` is an
+/// authentication or connection failure rather than the remote's answer.
+fn remote_problem(url: &str, error: &git2::Error) -> Option<String> {
+    if error.code() == ErrorCode::Auth {
+        return Some(format!(
+            "Authentication to {url} failed after trying the SSH agent, ~/.ssh keys and git credential helpers. \
+             Set thoughts.pushWithGitCli to use the git binary instead."
+        ));
+    }
+    let unreachable = error.code() == ErrorCode::NotFound
+        || matches!(
+            error.class(),
+            ErrorClass::Net | ErrorClass::Os | ErrorClass::Ssl | ErrorClass::Ssh | ErrorClass::Http
+        );
+    unreachable.then(|| format!("Could not reach {url}: {}", error.message()))
+}
+
+/// The outcome of a libgit2 push that failed with `error`.
+fn push_failure(url: &str, error: &git2::Error) -> PushOutcome {
+    match remote_problem(url, error) {
+        Some(problem) => PushOutcome::Unreachable(problem),
+        None => PushOutcome::Rejected(error.message().to_string()),
+    }
+}
+
+/// Result of a push. The failure variants carry git's stderr, or
+/// libgit2's error.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushOutcome {
+    Pushed,
+    /// The remote couldn't be reached (DNS, connection, auth transport).
+    Unreachable(String),
+    /// The remote answered but refused the push.
+    Rejected(String),
+}
+
+fn is_network_error(stderr: &str) -> bool {
+    [
+        "Could not resolve host",
+        "Could not read from remote repository",
+        "Connection refused",
+        "Connection timed out",
+        "Network is unreachable",
+        "unable to access",
+        "does not appear to be a git repository",
+    ]
+    .iter()
+    .any(|s| stderr.contains(s))
+}
+
+/// Result of `GitRepo::pull_rebase`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PullOutcome {
+    UpToDate,
+    /// HEAD moved forward over this many upstream commits.
+    FastForwarded(usize),
+    /// This many local commits were replayed onto the upstream.
+    Rebased(usize),
+    /// Replaying the local commits conflicts in these paths. The rebase
+    /// was abandoned, so HEAD and the working tree are as they were.
+    Conflicts(Vec<String>),
+}
+
+/// A commit as reported by `GitRepo::log`. `time` is in unix seconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub time: i64,
+}
+
+/// A changed worktree file as reported by `GitRepo::status`. `status` is
+/// one of `untracked`, `modified`, `added`, `deleted` or `unknown`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatus {
+    pub path: String,
+    pub status: &'static str,
+}
+
+/// Result of `GitRepo::merge`. `new_commit` is the commit HEAD moved to,
+/// `None` when there was nothing to merge or the merge stopped on
+/// conflicts, in which case `conflicts` lists the conflicting paths and the
+/// merge is left in progress.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MergeResult {
+    pub fast_forwarded: bool,
+    pub new_commit: Option<String>,
+    pub conflicts: Vec<String>,
+}
+
+/// A working tree as reported by `GitRepo::list_worktrees`. `branch` is
+/// the short branch name, or `(detached)`; the main working tree is listed
+/// first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeInfo {
+    pub path: PathBuf,
+    pub branch: String,
+    pub is_main: bool,
+}
+
+/// Parse `git worktree list --porcelain`: one blank-line separated block
+/// per worktree, main first.
+fn parse_worktree_list(porcelain: &str) -> Vec<WorktreeInfo> {
+    porcelain
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut path = None;
+            let mut branch = None;
+            for line in block.lines() {
+                if let Some(p) = line.strip_prefix("worktree ") {
+                    path = Some(PathBuf::from(p));
+                } else if let Some(b) = line.strip_prefix("branch ") {
+                    branch = Some(b.strip_prefix("refs/heads/").unwrap_or(b).to_string());
+                }
+            }
+            Some((path?, branch))
+        })
+        .enumerate()
+        .map(|(i, (path, branch))| WorktreeInfo {
+            path,
+            branch: branch.unwrap_or_else(|| "(detached)".to_string()),
+            is_main: i == 0,
+        })
+        .collect()
+}
+
 pub struct GitRepo {
     repo: Repository,
     path: std::path::PathBuf,
@@ -18,8 +250,14 @@ impl GitRepo {
         })
     }
 
-    pub fn init(path: &std::path::Path) -> Result<Self> {
-        let repo = Repository::init(path)
+    /// Create a repository at `path`. `initial_branch` names the branch HEAD
+    /// starts on; otherwise git's `init.defaultBranch` applies.
+    pub fn init(path: &std::path::Path, initial_branch: Option<&str>) -> Result<Self> {
+        let mut opts = RepositoryInitOptions::new();
+        if let Some(branch) = initial_branch {
+            opts.initial_head(branch);
+        }
+        let repo = Repository::init_opts(path, &opts)
             .with_context(|| format!("Failed to initialize git repository at {:?}", path))?;
         Ok(Self {
             repo,
@@ -27,29 +265,130 @@ impl GitRepo {
         })
     }
 
+    /// Clone `url` into `path` using the git binary, so the user's SSH agent
+    /// and credential helpers apply the same way they do for pull.
+    pub fn clone_from(url: &str, path: &std::path::Path) -> Result<Self> {
+        let output = Command::new("git")
+            .arg("clone")
+            .arg(url)
+            .arg(path)
+            .output()
+            .context("Failed to execute git clone")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git clone failed: {}", stderr);
+        }
+        Self::open(path)
+    }
+
+    /// `clone_from`, but with git's progress and error output shown
+    /// directly so slow clones and auth prompts/failures are visible.
+    pub fn clone_with_progress(url: &str, path: &std::path::Path) -> Result<Self> {
+        let status = Command::new("git")
+            .args(["clone", "--progress"])
+            .arg(url)
+            .arg(path)
+            .status()
+            .context("Failed to execute git clone")?;
+
+        if !status.success() {
+            anyhow::bail!(
+                "git clone of {} failed ({}); check the URL and your access to it",
+                url,
+                status
+            );
+        }
+        Self::open(path)
+    }
+
+    /// The working tree of the repository holding `file`, when `file` is
+    /// committed at its HEAD. Symlinks are resolved first, so a path through
+    /// a code repository's `thoughts/` link finds the thoughts repository.
+    pub fn committed_in(file: &Path) -> Option<PathBuf> {
+        let file = std::fs::canonicalize(file).ok()?;
+        let repo = Repository::discover(file.parent()?).ok()?;
+        let workdir = std::fs::canonicalize(repo.workdir()?).ok()?;
+        let relative = file.strip_prefix(&workdir).ok()?;
+        let tree = repo.head().ok()?.peel_to_tree().ok()?;
+        tree.get_path(relative).is_ok().then_some(workdir)
+    }
+
     pub fn is_repo(path: &std::path::Path) -> bool {
         Repository::open(path).is_ok()
     }
 
+    /// Whether `path` is a linked worktree (or submodule) checkout, whose
+    /// `.git` is a file pointing at the real git directory.
+    pub fn is_worktree(path: &Path) -> bool {
+        path.join(".git").is_file()
+    }
+
+    /// Every working tree of this repository, main first.
+    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        let output = Command::new("git")
+            .args(["worktree", "list", "--porcelain"])
+            .current_dir(&self.path)
+            .output()
+            .context("Failed to execute git worktree list")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git worktree list failed: {}", stderr.trim());
+        }
+        Ok(parse_worktree_list(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Check out a new linked worktree at `path`: on `branch` when given,
+    /// created from HEAD if no local branch has that name. Without one, git
+    /// creates a branch named after the directory.
+    pub fn add_worktree(&self, path: &Path, branch: Option<&str>) -> Result<()> {
+        let mut command = Command::new("git");
+        command.args(["worktree", "add"]).current_dir(&self.path);
+        match branch {
+            Some(branch)
+                if self
+                    .repo
+                    .find_branch(branch, git2::BranchType::Local)
+                    .is_err() =>
+            {
+                command.args(["-b", branch]).arg(path);
+            }
+            Some(branch) => {
+                command.arg(path).arg(branch);
+            }
+            None => {
+                command.arg(path);
+            }
+        }
+        let output = command
+            .output()
+            .context("Failed to execute git worktree add")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git worktree add failed: {}", stderr.trim());
+        }
+        Ok(())
+    }
+
     fn statuses(&self) -> Result<git2::Statuses<'_>> {
         let mut opts = StatusOptions::new();
         opts.include_untracked(true);
         Ok(self.repo.statuses(Some(&mut opts))?)
     }
 
-    pub fn status(&self) -> Result<String> {
+    /// Uncommitted changes, including untracked files.
+    pub fn status(&self) -> Result<Vec<FileStatus>> {
         let statuses = self.statuses()?;
-
-        if statuses.is_empty() {
-            return Ok("No changes to commit".to_string());
-        }
-
-        let result: String = statuses
+        let files = statuses
             .iter()
             .filter_map(|entry| {
-                let path = entry.path()?;
+                let path = entry.path()?.to_string();
                 let s = entry.status();
-                let label = match s {
+                let status = match s {
                     _ if s.contains(Status::WT_NEW) => "untracked",
                     _ if s.contains(Status::WT_MODIFIED) => "modified",
                     _ if s.contains(Status::INDEX_NEW) => "added",
@@ -58,11 +397,10 @@ impl GitRepo {
                     }
                     _ => "unknown",
                 };
-                Some(format!("  {:<10} {}\n", label, path))
+                Some(FileStatus { path, status })
             })
             .collect();
-
-        Ok(result)
+        Ok(files)
     }
 
     pub fn has_changes(&self) -> Result<bool> {
@@ -103,23 +441,121 @@ impl GitRepo {
         Ok(())
     }
 
-    pub fn get_last_commit(&self) -> Result<String> {
-        let head = self.repo.head().context("Repository has no HEAD commit")?;
+    /// Up to `limit` commits reachable from HEAD, newest first. Empty
+    /// before the first commit.
+    pub fn log(&self, limit: usize) -> Result<Vec<CommitInfo>> {
+        self.log_matching(limit, |_| true)
+    }
 
-        let commit = self
+    /// Like `log`, but only commits for which `filter` returns true count
+    /// towards `limit`.
+    pub fn log_matching(
+        &self,
+        limit: usize,
+        filter: impl Fn(&CommitInfo) -> bool,
+    ) -> Result<Vec<CommitInfo>> {
+        let Some(head) = self.repo.head().ok().and_then(|h| h.target()) else {
+            return Ok(Vec::new());
+        };
+        let mut walk = self.repo.revwalk()?;
+        walk.push(head)?;
+        let mut commits = Vec::new();
+        for oid in walk {
+            if commits.len() >= limit {
+                break;
+            }
+            let commit = self.repo.find_commit(oid?)?;
+            let info = CommitInfo {
+                id: commit.id().to_string(),
+                summary: commit.summary().unwrap_or("(no message)").to_string(),
+                author: commit.author().name().unwrap_or_default().to_string(),
+                time: commit.time().seconds(),
+            };
+            if filter(&info) {
+                commits.push(info);
+            }
+        }
+        Ok(commits)
+    }
+
+    /// Number of files commit `id` changed relative to its first parent
+    /// (or to an empty tree for a root commit).
+    pub fn files_changed(&self, id: &str) -> Result<usize> {
+        let commit = self.repo.find_commit(git2::Oid::from_str(id)?)?;
+        let tree = commit.tree()?;
+        let parent_tree = commit.parents().next().map(|p| p.tree()).transpose()?;
+        let diff = self
             .repo
-            .find_commit(head.target().context("HEAD has no target")?)
-            .context("Could not find HEAD commit")?;
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        Ok(diff.deltas().len())
+    }
+
+    /// The patch from `from` to `to` (anything `git rev-parse` accepts),
+    /// limited to files under `dir` and with `dir` shown as `shown_as` in
+    /// the file headers, e.g. `repos/app/` as `` so paths read like the
+    /// code repo's `thoughts/` directory. Empty when nothing changed.
+    pub fn diff_between(&self, from: &str, to: &str, dir: &str, shown_as: &str) -> Result<String> {
+        let tree = |spec: &str| -> Result<git2::Tree<'_>> {
+            self.repo
+                .revparse_single(spec)
+                .with_context(|| format!("Unknown commit: {spec}"))?
+                .peel_to_tree()
+                .with_context(|| format!("{spec} is not a commit"))
+        };
+        let mut options = git2::DiffOptions::new();
+        options.pathspec(dir.trim_end_matches('/'));
+        let diff = self.repo.diff_tree_to_tree(
+            Some(&tree(from)?),
+            Some(&tree(to)?),
+            Some(&mut options),
+        )?;
+
+        let (old, new) = (format!("a/{dir}"), format!("b/{dir}"));
+        let (old_shown, new_shown) = (format!("a/{shown_as}"), format!("b/{shown_as}"));
+        let mut patch = String::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            let content = String::from_utf8_lossy(line.content());
+            match line.origin() {
+                origin @ ('+' | '-' | ' ') => {
+                    patch.push(origin);
+                    patch.push_str(&content);
+                }
+                'F' => patch.push_str(&content.replace(&old, &old_shown).replace(&new, &new_shown)),
+                _ => patch.push_str(&content),
+            }
+            true
+        })?;
+        Ok(patch)
+    }
+
+    /// Whether commit `id` carries a signature `git verify-commit` accepts.
+    /// False for unsigned commits and for signatures git can't verify,
+    /// e.g. without the signer's key.
+    pub fn verify_commit(&self, id: &str) -> Result<bool> {
+        let status = Command::new("git")
+            .args(["verify-commit", id])
+            .current_dir(&self.path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .context("Failed to run git verify-commit")?;
+        Ok(status.success())
+    }
+
+    pub fn get_last_commit(&self) -> Result<String> {
+        let commit = self
+            .log(1)?
+            .pop()
+            .context("Repository has no HEAD commit")?;
 
-        let time = commit.time();
-        let seconds = time.seconds().unsigned_abs();
+        let seconds = commit.time.unsigned_abs();
         let datetime = UNIX_EPOCH + std::time::Duration::from_secs(seconds);
         let timestamp = chrono_humanize::HumanTime::from(datetime);
 
         Ok(format!(
             "{} {} ({})",
-            commit.id(),
-            commit.summary().unwrap_or("(no message)"),
+            commit.id,
+            commit.summary,
             timestamp.to_text_en(
                 chrono_humanize::Accuracy::Rough,
                 chrono_humanize::Tense::Present
@@ -127,50 +563,823 @@ impl GitRepo {
         ))
     }
 
+    /// Worktree paths of files touched by commits reachable from HEAD whose
+    /// commit time falls within `since..=until` (unix seconds). Empty for a
+    /// repository without commits.
+    pub fn files_modified_between(&self, since: i64, until: i64) -> Result<Vec<PathBuf>> {
+        let Some(head) = self.repo.head().ok().and_then(|h| h.target()) else {
+            return Ok(Vec::new());
+        };
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(head)?;
+
+        let mut paths = BTreeSet::new();
+        for oid in revwalk {
+            let commit = self.repo.find_commit(oid?)?;
+            let time = commit.time().seconds();
+            if time < since || time > until {
+                continue;
+            }
+            let tree = commit.tree()?;
+            let parent_tree = commit.parents().next().map(|p| p.tree()).transpose()?;
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+            paths.extend(
+                diff.deltas()
+                    .filter_map(|d| d.new_file().path().map(|p| self.path.join(p))),
+            );
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Commits reachable from HEAD that no remote-tracking branch contains.
+    /// Every commit counts when there are no remotes; an unborn HEAD has none.
+    pub fn unpushed_commit_count(&self) -> Result<usize> {
+        if self.repo.head().is_err() {
+            return Ok(0);
+        }
+        let mut walk = self.repo.revwalk()?;
+        walk.push_head()?;
+        for reference in self.repo.references_glob("refs/remotes/*")? {
+            if let Some(oid) = reference?.target() {
+                walk.hide(oid)?;
+            }
+        }
+        Ok(walk.count())
+    }
+
+    pub fn remote_add(&self, name: &str, url: &str) -> Result<()> {
+        self.repo
+            .remote(name, url)
+            .with_context(|| format!("Failed to add remote {} ({})", name, url))?;
+        Ok(())
+    }
+
+    /// Name of the branch HEAD points at, even before the first commit.
+    pub fn current_branch(&self) -> Option<String> {
+        let head = self.repo.find_reference("HEAD").ok()?;
+        let target = head.symbolic_target()?;
+        target.strip_prefix("refs/heads/").map(String::from)
+    }
+
     pub fn remote_url(&self) -> Option<String> {
         let remote = self.repo.find_remote("origin").ok()?;
         remote.url().map(String::from)
     }
 
-    /// Pull with rebase using git command (git2 doesn't support rebase well)
-    pub fn pull_rebase(&self) -> Result<()> {
+    /// Fetch the current branch's upstream and rebase local commits onto
+    /// it, fast-forwarding when there are none. Runs `git pull --rebase`
+    /// instead for what libgit2 can't do here: a detached or unborn HEAD,
+    /// or a rebase or `git am` already in progress.
+    pub fn pull_rebase(&self) -> Result<PullOutcome> {
+        if !PUSH_WITH_GIT_CLI.load(Ordering::Relaxed)
+            && let Some(outcome) = self.pull_native()?
+        {
+            return Ok(outcome);
+        }
+        self.pull_with_git_cli()
+    }
+
+    /// `None` when the pull needs the git binary (see `pull_rebase`).
+    fn pull_native(&self) -> Result<Option<PullOutcome>> {
+        if self.rebase_in_progress() || self.apply_in_progress() {
+            return Ok(None);
+        }
+        let (Some(branch), Ok(head)) = (self.current_branch(), self.repo.head()) else {
+            return Ok(None);
+        };
+        let head = head.peel_to_commit()?.id();
+        let local_ref = format!("refs/heads/{branch}");
+        let remote_name = self
+            .repo
+            .branch_upstream_remote(&local_ref)
+            .ok()
+            .and_then(|name| name.as_str().map(String::from))
+            .unwrap_or_else(|| "origin".to_string());
+
+        let mut remote = self
+            .repo
+            .find_remote(&remote_name)
+            .with_context(|| format!("No remote named {}", remote_name))?;
+        let url = remote.url().unwrap_or_default().to_string();
+        let mut credentials = CredentialChain::new(self.repo.config()?);
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| credentials.next(url, username, allowed));
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        remote
+            .fetch(&[] as &[&str], Some(&mut options), None)
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    remote_problem(&url, &e)
+                        .unwrap_or_else(|| format!("Fetching from {url} failed: {}", e.message()))
+                )
+            })?;
+
+        let upstream_ref = self
+            .repo
+            .branch_upstream_name(&local_ref)
+            .ok()
+            .and_then(|name| name.as_str().map(String::from))
+            .unwrap_or_else(|| format!("refs/remotes/{remote_name}/{branch}"));
+        // Nothing to pull from a remote that doesn't have the branch yet.
+        let Ok(upstream) = self.repo.refname_to_id(&upstream_ref) else {
+            return Ok(Some(PullOutcome::UpToDate));
+        };
+
+        let (ahead, behind) = self.repo.graph_ahead_behind(head, upstream)?;
+        if behind == 0 {
+            return Ok(Some(PullOutcome::UpToDate));
+        }
+        if ahead == 0 {
+            self.fast_forward(upstream, &upstream_ref)?;
+            return Ok(Some(PullOutcome::FastForwarded(behind)));
+        }
+        self.rebase_onto(upstream).map(Some)
+    }
+
+    /// Replay HEAD's commits that `upstream` lacks onto it, abandoning the
+    /// rebase at the first conflict.
+    fn rebase_onto(&self, upstream: git2::Oid) -> Result<PullOutcome> {
+        let onto = self.repo.find_annotated_commit(upstream)?;
+        let signature = self.repo.signature()?;
+        let mut rebase = self.repo.rebase(None, Some(&onto), None, None)?;
+        let mut replayed = 0;
+        while let Some(operation) = rebase.next() {
+            if let Err(e) = operation {
+                rebase.abort()?;
+                return Err(e.into());
+            }
+            let conflicts = self.conflicted_files()?;
+            if !conflicts.is_empty() {
+                rebase.abort()?;
+                return Ok(PullOutcome::Conflicts(conflicts));
+            }
+            match rebase.commit(None, &signature, None) {
+                Ok(_) => replayed += 1,
+                // The upstream already has this change.
+                Err(e) if e.code() == ErrorCode::Applied => {}
+                Err(e) => {
+                    rebase.abort()?;
+                    return Err(e.into());
+                }
+            }
+        }
+        rebase.finish(Some(&signature))?;
+        Ok(PullOutcome::Rebased(replayed))
+    }
+
+    /// `git pull --rebase`. A pull that stops on conflicts is aborted, as
+    /// the native path does.
+    fn pull_with_git_cli(&self) -> Result<PullOutcome> {
+        let before = self.repo.head().ok().and_then(|head| head.target());
         let output = Command::new("git")
             .args(["pull", "--rebase"])
             .current_dir(&self.path)
             .output()
             .context("Failed to execute git pull --rebase")?;
 
-        if output.status.success() {
-            return Ok(());
+        if !output.status.success() {
+            if self.rebase_in_progress() {
+                let conflicts = self.conflicted_files()?;
+                if !conflicts.is_empty() && self.rebase(&["--abort"])? {
+                    return Ok(PullOutcome::Conflicts(conflicts));
+                }
+            }
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git pull --rebase failed: {}", stderr.trim());
         }
 
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        let is_conflict = ["CONFLICT", "Automatic merge failed", "Patch failed"]
-            .iter()
-            .any(|s| stderr.contains(s));
+        let after = self.repo.head()?.peel_to_commit()?.id();
+        let Some(before) = before.filter(|before| *before != after) else {
+            return Ok(PullOutcome::UpToDate);
+        };
+        if self.repo.graph_descendant_of(after, before)? {
+            let (pulled, _) = self.repo.graph_ahead_behind(after, before)?;
+            return Ok(PullOutcome::FastForwarded(pulled));
+        }
+        Ok(PullOutcome::Rebased(self.unpushed_commit_count()?))
+    }
 
-        if is_conflict {
-            anyhow::bail!(
-                "Merge conflict detected. Please resolve conflicts manually in {:?}",
-                self.path
-            );
+    /// Apply `git format-patch` output (one or more patches, mbox style)
+    /// with `git am --3way` (git2 can't create commits from mail patches).
+    /// On failure the session is left in progress so conflicts can be
+    /// resolved and `git am --continue`d, or cleaned up with `abort_apply`.
+    pub fn apply_patch(&self, patch_content: &str) -> Result<()> {
+        let mut child = Command::new("git")
+            .args(["am", "--3way"])
+            .current_dir(&self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute git am")?;
+        child
+            .stdin
+            .take()
+            .context("Failed to open git am stdin")?
+            .write_all(patch_content.as_bytes())?;
+        let output = child.wait_with_output()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git am failed: {}", stderr.trim());
         }
+        Ok(())
+    }
 
-        anyhow::bail!("git pull --rebase failed: {}", stderr);
+    /// Abort an in-progress `apply_patch`, restoring the previous HEAD.
+    pub fn abort_apply(&self) -> Result<()> {
+        let output = Command::new("git")
+            .args(["am", "--abort"])
+            .current_dir(&self.path)
+            .output()
+            .context("Failed to execute git am --abort")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git am --abort failed: {}", stderr.trim());
+        }
+        Ok(())
     }
 
+    /// Whether a `git am` session is waiting to be continued or aborted.
+    pub fn apply_in_progress(&self) -> bool {
+        self.repo
+            .path()
+            .join("rebase-apply")
+            .join("applying")
+            .exists()
+    }
+
+    /// Whether a `git rebase` (including one started by `pull_rebase`) is
+    /// stopped, waiting to be continued, skipped or aborted.
+    pub fn rebase_in_progress(&self) -> bool {
+        let git_dir = self.repo.path();
+        git_dir.join("rebase-merge").exists()
+            || (git_dir.join("rebase-apply").exists() && !self.apply_in_progress())
+    }
+
+    /// Run `git rebase <args>` with the terminal attached, so editors (for
+    /// `--interactive` and `--continue`) and git's own messages work.
+    /// Returns whether git succeeded; a rebase stopping on conflicts is a
+    /// failure, with `rebase_in_progress` then true.
+    pub fn rebase(&self, args: &[&str]) -> Result<bool> {
+        let status = Command::new("git")
+            .arg("rebase")
+            .args(args)
+            .current_dir(&self.path)
+            .status()
+            .context("Failed to execute git rebase")?;
+        Ok(status.success())
+    }
+
+    /// Paths with unresolved merge conflicts in the index, sorted.
+    pub fn conflicted_files(&self) -> Result<Vec<String>> {
+        let mut index = self.repo.index()?;
+        // Pick up what a git subprocess wrote since the index was loaded.
+        index.read(false)?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+        let mut paths = BTreeSet::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                paths.insert(String::from_utf8_lossy(&entry.path).into_owned());
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+
+    /// Merge `branch` (a local or remote-tracking branch name) into HEAD,
+    /// fast-forwarding when possible. Otherwise the merge result is
+    /// committed with `message`, or "Merge branch '<branch>'".
+    pub fn merge(&self, branch: &str, message: Option<&str>) -> Result<MergeResult> {
+        self.merge_branch(branch, message, false)
+    }
+
+    /// Like `merge`, but never fast-forwards and records the result as a
+    /// single-parent commit on HEAD.
+    pub fn merge_squash(&self, branch: &str, message: Option<&str>) -> Result<MergeResult> {
+        self.merge_branch(branch, message, true)
+    }
+
+    fn merge_branch(
+        &self,
+        branch: &str,
+        message: Option<&str>,
+        squash: bool,
+    ) -> Result<MergeResult> {
+        let reference = self
+            .repo
+            .resolve_reference_from_short_name(branch)
+            .with_context(|| format!("Branch {} not found", branch))?;
+        let theirs = self.repo.reference_to_annotated_commit(&reference)?;
+        let (analysis, _) = self.repo.merge_analysis(&[&theirs])?;
+
+        if analysis.is_up_to_date() {
+            return Ok(MergeResult::default());
+        }
+        if analysis.is_unborn() || (analysis.is_fast_forward() && !squash) {
+            return self.fast_forward(theirs.id(), branch);
+        }
+
+        self.repo.merge(
+            &[&theirs],
+            Some(&mut MergeOptions::default()),
+            Some(&mut CheckoutBuilder::default()),
+        )?;
+        let conflicts = self.conflicted_files()?;
+        if !conflicts.is_empty() {
+            return Ok(MergeResult {
+                conflicts,
+                ..MergeResult::default()
+            });
+        }
+
+        let tree = {
+            let mut index = self.repo.index()?;
+            let tree_id = index.write_tree()?;
+            self.repo.find_tree(tree_id)?
+        };
+        let sig = self.repo.signature()?;
+        let head = self.repo.head()?.peel_to_commit()?;
+        let their_commit = self.repo.find_commit(theirs.id())?;
+        let parents = if squash {
+            vec![&head]
+        } else {
+            vec![&head, &their_commit]
+        };
+        let default_message = if squash {
+            format!("Squashed commit of branch '{}'", branch)
+        } else {
+            format!("Merge branch '{}'", branch)
+        };
+        let commit_id = self.repo.commit(
+            Some("HEAD"),
+            &sig,
+            &sig,
+            message.unwrap_or(&default_message),
+            &tree,
+            &parents,
+        )?;
+        self.repo.cleanup_state()?;
+
+        Ok(MergeResult {
+            new_commit: Some(commit_id.to_string()),
+            ..MergeResult::default()
+        })
+    }
+
+    /// Check out `target` and move the current branch to it.
+    fn fast_forward(&self, target: git2::Oid, branch: &str) -> Result<MergeResult> {
+        let commit = self.repo.find_commit(target)?;
+        self.repo
+            .checkout_tree(commit.as_object(), Some(CheckoutBuilder::default().safe()))?;
+        let head_ref = self
+            .repo
+            .find_reference("HEAD")?
+            .symbolic_target()
+            .context("HEAD is detached")?
+            .to_string();
+        self.repo.reference(
+            &head_ref,
+            target,
+            true,
+            &format!("merge {}: Fast-forward", branch),
+        )?;
+        Ok(MergeResult {
+            fast_forwarded: true,
+            new_commit: Some(target.to_string()),
+            conflicts: Vec::new(),
+        })
+    }
+
+    /// Push HEAD to `remote` and set it as the upstream, so later plain
+    /// `push`es work. Failures are reported as an outcome rather than an
+    /// error so callers can tell an unreachable remote from a rejection.
+    pub fn push_upstream(&self, remote: &str) -> Result<PushOutcome> {
+        if PUSH_WITH_GIT_CLI.load(Ordering::Relaxed) {
+            return self.push_with_git_cli(&["-u", remote, "HEAD"]);
+        }
+        let branch = self.current_branch().context("HEAD is detached")?;
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+        let outcome = self.push_native(remote, &refspec)?;
+        if outcome == PushOutcome::Pushed {
+            self.repo
+                .find_branch(&branch, BranchType::Local)?
+                .set_upstream(Some(&format!("{remote}/{branch}")))?;
+        }
+        Ok(outcome)
+    }
+
+    /// Push the current branch to its upstream, or to the same-named
+    /// branch of `origin` when it has none.
     pub fn push(&self) -> Result<()> {
+        let outcome = if PUSH_WITH_GIT_CLI.load(Ordering::Relaxed) {
+            self.push_with_git_cli(&[])?
+        } else {
+            let branch = self.current_branch().context("HEAD is detached")?;
+            let config = self.repo.config()?;
+            let remote = config
+                .get_string(&format!("branch.{branch}.remote"))
+                .unwrap_or_else(|_| "origin".to_string());
+            let target = config
+                .get_string(&format!("branch.{branch}.merge"))
+                .unwrap_or_else(|_| format!("refs/heads/{branch}"));
+            self.push_native(&remote, &format!("refs/heads/{branch}:{target}"))?
+        };
+        match outcome {
+            PushOutcome::Pushed => Ok(()),
+            PushOutcome::Unreachable(detail) | PushOutcome::Rejected(detail) => {
+                Err(anyhow::anyhow!("git push failed: {}", detail))
+            }
+        }
+    }
+
+    /// Push `refspec` to `remote` with libgit2, authenticating through
+    /// `CredentialChain`.
+    fn push_native(&self, remote: &str, refspec: &str) -> Result<PushOutcome> {
+        let mut remote = self
+            .repo
+            .find_remote(remote)
+            .with_context(|| format!("No remote named {}", remote))?;
+        let url = remote.url().unwrap_or_default().to_string();
+
+        let mut credentials = CredentialChain::new(self.repo.config()?);
+        let mut rejection = None;
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| credentials.next(url, username, allowed));
+        callbacks.push_update_reference(|reference, status| {
+            if let Some(status) = status {
+                rejection = Some(format!("{reference}: {status}"));
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+
+        let result = remote.push(&[refspec], Some(&mut options));
+        drop(options);
+        Ok(match (result, rejection) {
+            (Err(e), _) => push_failure(&url, &e),
+            (Ok(()), Some(rejection)) => PushOutcome::Rejected(rejection),
+            (Ok(()), None) => PushOutcome::Pushed,
+        })
+    }
+
+    /// `git push <args>`, for `thoughts.pushWithGitCli`.
+    fn push_with_git_cli(&self, args: &[&str]) -> Result<PushOutcome> {
         let output = Command::new("git")
-            .args(["push"])
+            .arg("push")
+            .args(args)
             .current_dir(&self.path)
             .output()
             .context("Failed to execute git push")?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("git push failed: {}", stderr));
+        if output.status.success() {
+            return Ok(PushOutcome::Pushed);
         }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Ok(if is_network_error(&stderr) {
+            PushOutcome::Unreachable(stderr)
+        } else {
+            PushOutcome::Rejected(stderr)
+        })
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A new repository at `path`, with an identity to commit as; CI
+    /// machines may have no global one.
+    pub(crate) fn repo_with_identity(path: &Path, initial_branch: Option<&str>) -> GitRepo {
+        let repo = GitRepo::init(path, initial_branch).unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        repo
+    }
+
+    #[test]
+    fn parse_worktree_list_marks_main_and_detached() {
+        let porcelain = "worktree /src/app\nHEAD 1111\nbranch refs/heads/main\n\n\
+                         worktree /src/app-feature\nHEAD 2222\nbranch refs/heads/feature/x\n\n\
+                         worktree /src/app-review\nHEAD 3333\ndetached\n";
+        let worktrees = parse_worktree_list(porcelain);
+        assert_eq!(
+            worktrees,
+            vec![
+                WorktreeInfo {
+                    path: PathBuf::from("/src/app"),
+                    branch: "main".to_string(),
+                    is_main: true,
+                },
+                WorktreeInfo {
+                    path: PathBuf::from("/src/app-feature"),
+                    branch: "feature/x".to_string(),
+                    is_main: false,
+                },
+                WorktreeInfo {
+                    path: PathBuf::from("/src/app-review"),
+                    branch: "(detached)".to_string(),
+                    is_main: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn list_worktrees_includes_linked_worktree() {
+        let root = tempfile::tempdir().unwrap();
+        let main = root.path().join("main");
+        let repo = repo_with_identity(&main, Some("main"));
+        repo.commit("base").unwrap();
+        let linked = root.path().join("linked");
+        let status = Command::new("git")
+            .args(["worktree", "add", "-q", "-b", "topic"])
+            .arg(&linked)
+            .current_dir(&main)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let worktrees = repo.list_worktrees().unwrap();
+        assert_eq!(worktrees.len(), 2);
+        assert!(worktrees[0].is_main);
+        assert_eq!(worktrees[1].branch, "topic");
+        assert!(!GitRepo::is_worktree(&main));
+        assert!(GitRepo::is_worktree(&linked));
+    }
+
+    #[test]
+    fn diff_between_limits_to_dir_and_renames_it() {
+        let root = tempfile::tempdir().unwrap();
+        let repo = repo_with_identity(root.path(), Some("main"));
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("repos/app/alice/plan.md", "one\n");
+        write("repos/other/notes.md", "one\n");
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+        write("repos/app/alice/plan.md", "two\n");
+        write("repos/other/notes.md", "two\n");
+        repo.add_all().unwrap();
+        repo.commit("second").unwrap();
+
+        let patch = repo
+            .diff_between("HEAD~1", "HEAD", "repos/app/", "")
+            .unwrap();
+        assert!(patch.contains("--- a/alice/plan.md\n+++ b/alice/plan.md\n"));
+        assert!(patch.contains("-one\n+two\n"));
+        assert!(!patch.contains("other"));
+
+        let shared = repo
+            .diff_between("HEAD~1", "HEAD", "repos/app/shared/", "shared/")
+            .unwrap();
+        assert!(shared.is_empty());
+        assert!(repo.diff_between("nope", "HEAD", "repos/", "").is_err());
+    }
+
+    #[test]
+    fn unpushed_commit_count_counts_commits_without_remotes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = repo_with_identity(dir.path(), None);
+        assert_eq!(repo.unpushed_commit_count().unwrap(), 0);
+
+        std::fs::write(dir.path().join("note.md"), "hi").unwrap();
+        repo.add_all().unwrap();
+        repo.commit("first").unwrap();
+        assert_eq!(repo.unpushed_commit_count().unwrap(), 1);
+    }
+
+    #[test]
+    fn rebase_in_progress_until_aborted() {
+        let root = tempfile::tempdir().unwrap();
+        let repo = repo_with_identity(root.path(), Some("main"));
+        let commit = |content: &str| {
+            std::fs::write(root.path().join("notes.md"), content).unwrap();
+            repo.add_all().unwrap();
+            repo.commit(content).unwrap();
+        };
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(root.path())
+                .output()
+                .unwrap()
+        };
+        commit("base");
+        git(&["checkout", "-qb", "topic"]);
+        commit("topic");
+        git(&["checkout", "-q", "main"]);
+        commit("main");
+        git(&["checkout", "-q", "topic"]);
+        assert!(!repo.rebase_in_progress());
+
+        assert!(!git(&["rebase", "main"]).status.success());
+        assert!(repo.rebase_in_progress());
+        assert!(repo.rebase(&["--abort"]).unwrap());
+        assert!(!repo.rebase_in_progress());
+    }
+
+    /// A repository at `path` on `main` with one commit, whose origin is a
+    /// new bare repository at `<path>.git`.
+    fn repo_with_bare_origin(path: &Path) -> (GitRepo, Repository) {
+        let repo = repo_with_identity(path, Some("main"));
+        repo.commit("first").unwrap();
+        let bare_path = path.with_extension("git");
+        let bare = Repository::init_bare(&bare_path).unwrap();
+        bare.set_head("refs/heads/main").unwrap();
+        repo.remote_add("origin", &bare_path.display().to_string())
+            .unwrap();
+        (repo, bare)
+    }
+
+    /// A clone of `origin`'s bare repository at `path`, with an identity
+    /// to commit as.
+    fn clone_of(bare: &Repository, path: &Path) -> GitRepo {
+        let url = bare.path().display().to_string();
+        Repository::clone(&url, path).unwrap();
+        let repo = GitRepo::open(path).unwrap();
+        let mut config = repo.repo.config().unwrap();
+        config.set_str("user.name", "Other").unwrap();
+        config.set_str("user.email", "other@example.com").unwrap();
+        repo
+    }
+
+    fn commit_file(repo: &GitRepo, name: &str, content: &str) {
+        std::fs::write(repo.path.join(name), content).unwrap();
+        repo.add_all().unwrap();
+        repo.commit(content).unwrap();
+    }
+
+    fn head_id(repo: &Repository, reference: &str) -> git2::Oid {
+        repo.revparse_single(reference).unwrap().id()
+    }
+
+    #[test]
+    fn native_push_sets_upstream_then_follows_it() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+
+        assert_eq!(repo.push_upstream("origin").unwrap(), PushOutcome::Pushed);
+        let head = head_id(&repo.repo, "HEAD");
+        assert_eq!(head_id(&bare, "refs/heads/main"), head);
+        let config = repo.repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(config.get_str("branch.main.remote").unwrap(), "origin");
+        assert_eq!(repo.unpushed_commit_count().unwrap(), 0);
+
+        repo.commit("second").unwrap();
+        assert_eq!(repo.unpushed_commit_count().unwrap(), 1);
+        repo.push().unwrap();
+        assert_eq!(
+            head_id(&bare, "refs/heads/main"),
+            head_id(&repo.repo, "HEAD")
+        );
+        assert_eq!(repo.unpushed_commit_count().unwrap(), 0);
+    }
+
+    #[test]
+    fn native_push_reports_rejected_and_unreachable_remotes() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+        repo.push_upstream("origin").unwrap();
+
+        // Someone else moves the remote branch on.
+        let signature = git2::Signature::now("Other", "other@example.com").unwrap();
+        let parent = bare.find_commit(head_id(&bare, "refs/heads/main")).unwrap();
+        bare.commit(
+            Some("refs/heads/main"),
+            &signature,
+            &signature,
+            "theirs",
+            &parent.tree().unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+        repo.commit("ours").unwrap();
+        let err = repo.push().unwrap_err().to_string();
+        assert!(err.contains("not present locally"), "{err}");
+
+        repo.repo
+            .remote_set_url(
+                "origin",
+                &root.path().join("missing.git").display().to_string(),
+            )
+            .unwrap();
+        assert!(matches!(
+            repo.push_upstream("origin").unwrap(),
+            PushOutcome::Unreachable(_)
+        ));
+    }
+
+    #[test]
+    fn git_cli_push_reaches_a_local_bare_repo() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+        assert_eq!(
+            repo.push_with_git_cli(&["-u", "origin", "HEAD"]).unwrap(),
+            PushOutcome::Pushed
+        );
+        assert_eq!(
+            head_id(&bare, "refs/heads/main"),
+            head_id(&repo.repo, "HEAD")
+        );
+    }
+
+    #[test]
+    fn push_failure_separates_auth_network_and_rejection() {
+        let url = "https://example.com/thoughts.git";
+        let failure = |code, class| push_failure(url, &git2::Error::new(code, class, "boom"));
+        match failure(ErrorCode::Auth, ErrorClass::Callback) {
+            PushOutcome::Unreachable(detail) => {
+                assert!(
+                    detail.starts_with("Authentication to https://example.com/thoughts.git failed")
+                );
+                assert!(detail.contains("thoughts.pushWithGitCli"));
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(
+            failure(ErrorCode::GenericError, ErrorClass::Net),
+            PushOutcome::Unreachable(format!("Could not reach {url}: boom"))
+        );
+        assert_eq!(
+            failure(ErrorCode::NotFastForward, ErrorClass::Reference),
+            PushOutcome::Rejected("boom".to_string())
+        );
+    }
+
+    #[test]
+    fn pull_rebase_fast_forwards_then_is_up_to_date() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+        repo.push_upstream("origin").unwrap();
+        let other = clone_of(&bare, &root.path().join("other"));
+        commit_file(&other, "a.md", "theirs");
+        commit_file(&other, "b.md", "theirs again");
+        other.push().unwrap();
+
+        assert_eq!(repo.pull_rebase().unwrap(), PullOutcome::FastForwarded(2));
+        assert_eq!(head_id(&repo.repo, "HEAD"), head_id(&other.repo, "HEAD"));
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("b.md")).unwrap(),
+            "theirs again"
+        );
+        assert_eq!(repo.pull_rebase().unwrap(), PullOutcome::UpToDate);
+    }
+
+    #[test]
+    fn pull_rebase_replays_local_commits_onto_the_upstream() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+        repo.push_upstream("origin").unwrap();
+        let other = clone_of(&bare, &root.path().join("other"));
+        commit_file(&other, "theirs.md", "theirs");
+        other.push().unwrap();
+        commit_file(&repo, "ours.md", "ours");
+
+        assert_eq!(repo.pull_rebase().unwrap(), PullOutcome::Rebased(1));
+        assert!(!repo.rebase_in_progress());
+        let head = repo.repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("ours"));
+        assert_eq!(head.parent_id(0).unwrap(), head_id(&other.repo, "HEAD"));
+        assert!(repo.path.join("theirs.md").is_file());
+        repo.push().unwrap();
+    }
+
+    #[test]
+    fn pull_rebase_reports_conflicts_and_leaves_the_repo_as_it_was() {
+        let root = tempfile::tempdir().unwrap();
+        let (repo, bare) = repo_with_bare_origin(&root.path().join("thoughts"));
+        repo.push_upstream("origin").unwrap();
+        let other = clone_of(&bare, &root.path().join("other"));
+        commit_file(&other, "notes.md", "theirs\n");
+        other.push().unwrap();
+        commit_file(&repo, "notes.md", "ours\n");
+        let before = head_id(&repo.repo, "HEAD");
+
+        for pull in [GitRepo::pull_rebase, GitRepo::pull_with_git_cli] {
+            assert_eq!(
+                pull(&repo).unwrap(),
+                PullOutcome::Conflicts(vec!["notes.md".to_string()])
+            );
+            assert_eq!(head_id(&repo.repo, "HEAD"), before);
+            assert!(!repo.rebase_in_progress());
+            assert!(!repo.has_changes().unwrap());
+            assert_eq!(
+                std::fs::read_to_string(repo.path.join("notes.md")).unwrap(),
+                "ours\n"
+            );
+        }
     }
 }
@@ -5,14 +5,43 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-const HOOK_VERSION: &str = "2";
+use crate::git_ops::GitRepo;
+
+const HOOK_VERSION: &str = "6";
+
+/// When set to a non-empty value, the post-commit hook and `thoughts sync`
+/// skip syncing, e.g. during a rebase that makes many commits.
+pub const NO_SYNC_ENV: &str = "HYPRLAYER_NO_SYNC";
+
+/// Whether `NO_SYNC_ENV` is set.
+pub fn sync_disabled() -> bool {
+    std::env::var_os(NO_SYNC_ENV).is_some_and(|v| !v.is_empty())
+}
+
+/// Lock file the post-commit hook holds while syncing. A sync that starts
+/// within a minute of another one is skipped.
+const SYNC_LOCK_FILE: &str = "$HOME/.hyprlayer.sync.lock";
+
+/// Every hook `setup_git_hooks` may install.
+const HOOK_NAMES: [&str; 2] = ["pre-commit", "post-commit"];
 
 /// Install the pre-commit hook (always) and, when `include_auto_sync` is true,
-/// the post-commit hook. With `include_auto_sync = false`, any previously-
+/// the post-commit hook, which waits a random 0..=`sync_jitter` seconds
+/// before syncing. With `include_auto_sync = false`, any previously-
 /// installed hyprlayer post-commit is removed so backend switches don't leave
 /// dead hooks firing on every commit. Returns `Ok(vec![])` if `repo_path`
-/// isn't inside a git working tree (safe to call from non-filesystem backends).
-pub fn setup_git_hooks(repo_path: &Path, include_auto_sync: bool) -> Result<Vec<String>> {
+/// isn't inside a git working tree (safe to call from non-filesystem backends)
+/// or is a linked worktree.
+pub fn setup_git_hooks(
+    repo_path: &Path,
+    include_auto_sync: bool,
+    sync_jitter: u64,
+) -> Result<Vec<String>> {
+    // A linked worktree shares the main checkout's hooks, which are
+    // installed from there.
+    if GitRepo::is_worktree(repo_path) {
+        return Ok(Vec::new());
+    }
     let Some(hooks_dir) = get_hooks_dir(repo_path)? else {
         return Ok(Vec::new());
     };
@@ -24,35 +53,68 @@ pub fn setup_git_hooks(repo_path: &Path, include_auto_sync: bool) -> Result<Vec<
         updated.push("pre-commit".to_string());
     }
     if include_auto_sync {
-        if install_hook(&hooks_dir, "post-commit", post_commit_content())? {
+        if install_hook(&hooks_dir, "post-commit", post_commit_content(sync_jitter))? {
             updated.push("post-commit".to_string());
         }
-    } else if remove_our_hook(&hooks_dir, "post-commit")? {
+    } else if remove_our_hook(&hooks_dir, "post-commit", true)? {
         updated.push("post-commit (removed)".to_string());
     }
 
     Ok(updated)
 }
 
+/// Remove hyprlayer's hooks (`names`, or all of them when `None`) from the
+/// repository at `repo_path`, leaving hooks hyprlayer didn't write alone.
+/// With `restore_backups`, a hook that was moved aside to `<name>.old` on
+/// install is put back. Returns the names removed; empty outside a git
+/// working tree.
+pub fn remove_git_hooks(
+    repo_path: &Path,
+    names: Option<&[&str]>,
+    restore_backups: bool,
+) -> Result<Vec<String>> {
+    let Some(hooks_dir) = get_hooks_dir(repo_path)? else {
+        return Ok(Vec::new());
+    };
+    let mut removed = Vec::new();
+    for name in names.unwrap_or(&HOOK_NAMES) {
+        if remove_our_hook(&hooks_dir, name, restore_backups)? {
+            removed.push(name.to_string());
+        }
+    }
+    Ok(removed)
+}
+
+/// Names of hyprlayer hooks currently installed in `repo_path`.
+pub fn installed_hooks(repo_path: &Path) -> Result<Vec<String>> {
+    let Some(hooks_dir) = get_hooks_dir(repo_path)? else {
+        return Ok(Vec::new());
+    };
+    Ok(HOOK_NAMES
+        .iter()
+        .filter(|name| is_our_hook(&hooks_dir.join(name)))
+        .map(|name| name.to_string())
+        .collect())
+}
+
+fn is_our_hook(hook_path: &Path) -> bool {
+    fs::read_to_string(hook_path).is_ok_and(|content| content.contains("hyprlayer thoughts"))
+}
+
 fn backup_path(hook_path: &Path) -> PathBuf {
     PathBuf::from(format!("{}.old", hook_path.display()))
 }
 
-fn remove_our_hook(hooks_dir: &Path, name: &str) -> Result<bool> {
+fn remove_our_hook(hooks_dir: &Path, name: &str, restore_backup: bool) -> Result<bool> {
     let hook_path = hooks_dir.join(name);
-    if !hook_path.exists() {
-        return Ok(false);
-    }
-
-    let content = fs::read_to_string(&hook_path).unwrap_or_default();
-    if !content.contains("hyprlayer thoughts") {
+    if !is_our_hook(&hook_path) {
         return Ok(false);
     }
 
     fs::remove_file(&hook_path)?;
 
     let backup = backup_path(&hook_path);
-    if backup.exists() {
+    if restore_backup && backup.exists() {
         fs::rename(&backup, &hook_path)?;
     }
     Ok(true)
@@ -87,7 +149,10 @@ fn get_hooks_dir(repo_path: &Path) -> Result<Option<PathBuf>> {
     Ok(Some(git_common_dir.join("hooks")))
 }
 
-fn hook_needs_update(hook_path: &Path) -> bool {
+/// Whether the hook at `hook_path` should be replaced by `new_content`:
+/// it's missing, or it's ours and either older or, at the same version,
+/// written with different settings (e.g. another sync jitter).
+fn hook_needs_update(hook_path: &Path, new_content: &str) -> bool {
     let Ok(content) = fs::read_to_string(hook_path) else {
         return true;
     };
@@ -101,14 +166,17 @@ fn hook_needs_update(hook_path: &Path) -> bool {
         .find(|l| l.contains("# Version:"))
         .and_then(|line| line.split(':').nth(1))
         .and_then(|v| v.trim().parse::<u32>().ok())
-        .map(|v| v < HOOK_VERSION.parse::<u32>().unwrap_or(1))
+        .map(|v| {
+            let current = HOOK_VERSION.parse::<u32>().unwrap_or(1);
+            v < current || (v == current && content != new_content)
+        })
         .unwrap_or(true)
 }
 
 fn install_hook(hooks_dir: &Path, name: &str, content: String) -> Result<bool> {
     let hook_path = hooks_dir.join(name);
 
-    if !hook_needs_update(&hook_path) {
+    if !hook_needs_update(&hook_path, &content) {
         return Ok(false);
     }
 
@@ -153,22 +221,47 @@ fi
     )
 }
 
-fn post_commit_content() -> String {
+fn post_commit_content(sync_jitter: u64) -> String {
+    let jitter = if sync_jitter > 0 {
+        format!(
+            "    # Spread out syncs from many machines sharing the thoughts repo\n    sleep $((RANDOM % {}))\n",
+            sync_jitter + 1
+        )
+    } else {
+        String::new()
+    };
     format!(
         r#"#!/bin/bash
 # hyprlayer thoughts auto-sync
 # Version: {HOOK_VERSION}
 
-# Check if we're in a worktree (skip auto-sync in worktrees)
-if [ -f .git ]; then
+# Skip auto-sync when {NO_SYNC_ENV} is set (e.g. during a rebase)
+if [ -n "${NO_SYNC_ENV}" ]; then
+    exit 0
+fi
+
+# Skip auto-sync in linked worktrees and submodules, whose common git
+# directory lives elsewhere
+if [ "$(git rev-parse --git-common-dir)" != ".git" ]; then
+    exit 0
+fi
+
+# Skip auto-sync when another one started less than a minute ago
+LOCK_FILE="{SYNC_LOCK_FILE}"
+if [ -n "$(find "$LOCK_FILE" -mmin -1 2>/dev/null)" ]; then
     exit 0
 fi
 
 # Get the commit message
 COMMIT_MSG=$(git log -1 --pretty=%B)
 
-# Auto-sync thoughts after each commit (only in non-worktree repos)
-hyprlayer thoughts sync --message "Auto-sync with commit: $COMMIT_MSG" >/dev/null 2>&1 &
+# Auto-sync thoughts after each commit (only in non-worktree repos),
+# holding the lock file while the sync runs
+(
+    touch "$LOCK_FILE"
+{jitter}    hyprlayer thoughts sync --no-interactive --message "Auto-sync with commit: $COMMIT_MSG"
+    rm -f "$LOCK_FILE"
+) >/dev/null 2>&1 &
 
 # Call any existing post-commit hook
 SCRIPT_PATH="$(realpath "$0")"
@@ -192,7 +285,7 @@ mod tests {
         let not_a_repo = tmp.path().join("plain");
         fs::create_dir_all(&not_a_repo).unwrap();
 
-        let updated = setup_git_hooks(&not_a_repo, false).unwrap();
+        let updated = setup_git_hooks(&not_a_repo, false, 0).unwrap();
         assert!(updated.is_empty());
         assert!(
             !not_a_repo.join("hooks").exists(),
@@ -217,13 +310,97 @@ mod tests {
             .output()
             .unwrap();
 
-        let updated = setup_git_hooks(&repo, true).unwrap();
+        let updated = setup_git_hooks(&repo, true, 0).unwrap();
         assert!(updated.contains(&"pre-commit".to_string()));
         assert!(updated.contains(&"post-commit".to_string()));
         assert!(repo.join(".git/hooks/pre-commit").exists());
         assert!(repo.join(".git/hooks/post-commit").exists());
     }
 
+    /// Project agent installs (`ai configure --project`) are meant to be
+    /// committed; only `thoughts/` is held back.
+    #[test]
+    fn pre_commit_blocks_thoughts_but_not_project_agent_files() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo)
+                .output()
+                .unwrap()
+        };
+        git(&["init", "--quiet"]);
+        setup_git_hooks(repo, false, 0).unwrap();
+
+        fs::create_dir_all(repo.join(".claude/agents")).unwrap();
+        fs::write(repo.join(".claude/agents/locator.md"), "# Locator").unwrap();
+        git(&["add", "."]);
+        assert!(
+            git(&["commit", "--quiet", "-m", "Add agents"])
+                .status
+                .success()
+        );
+
+        fs::create_dir_all(repo.join("thoughts")).unwrap();
+        fs::write(repo.join("thoughts/notes.md"), "# Notes").unwrap();
+        git(&["add", "."]);
+        assert!(
+            !git(&["commit", "--quiet", "-m", "Add notes"])
+                .status
+                .success()
+        );
+    }
+
+    #[test]
+    fn post_commit_skips_sync_when_no_sync_is_set() {
+        let content = post_commit_content(0);
+        assert!(content.contains("if [ -n \"$HYPRLAYER_NO_SYNC\" ]; then\n    exit 0\nfi"));
+        let no_sync = content.find("HYPRLAYER_NO_SYNC\"").unwrap();
+        assert!(no_sync < content.find("hyprlayer thoughts sync").unwrap());
+        assert!(content.contains(&format!("# Version: {HOOK_VERSION}")));
+    }
+
+    #[test]
+    fn post_commit_takes_lock_and_adds_jitter_only_when_set() {
+        let content = post_commit_content(0);
+        assert!(content.contains("LOCK_FILE=\"$HOME/.hyprlayer.sync.lock\""));
+        assert!(content.contains("find \"$LOCK_FILE\" -mmin -1"));
+        assert!(content.contains("touch \"$LOCK_FILE\""));
+        assert!(content.contains("rm -f \"$LOCK_FILE\""));
+        assert!(!content.contains("sleep"));
+
+        let content = post_commit_content(30);
+        let sleep = content.find("sleep $((RANDOM % 31))").unwrap();
+        assert!(sleep < content.find("hyprlayer thoughts sync").unwrap());
+    }
+
+    #[test]
+    fn setup_git_hooks_rewrites_post_commit_when_jitter_changes() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        let post_commit = repo.join(".git/hooks/post-commit");
+
+        setup_git_hooks(&repo, true, 0).unwrap();
+        assert!(setup_git_hooks(&repo, true, 0).unwrap().is_empty());
+
+        let updated = setup_git_hooks(&repo, true, 10).unwrap();
+        assert_eq!(updated, vec!["post-commit"]);
+        assert!(
+            fs::read_to_string(&post_commit)
+                .unwrap()
+                .contains("sleep $((RANDOM % 11))")
+        );
+    }
+
     #[test]
     fn setup_git_hooks_cleanup_removes_post_commit() {
         let tmp = TempDir::new().unwrap();
@@ -237,11 +414,11 @@ mod tests {
             .unwrap();
 
         // First install with auto-sync (both hooks).
-        setup_git_hooks(&repo, true).unwrap();
+        setup_git_hooks(&repo, true, 0).unwrap();
         assert!(repo.join(".git/hooks/post-commit").exists());
 
         // Second install without auto-sync — should remove the hyprlayer post-commit.
-        let updated = setup_git_hooks(&repo, false).unwrap();
+        let updated = setup_git_hooks(&repo, false, 0).unwrap();
         assert!(
             updated.iter().any(|s| s.contains("post-commit")),
             "expected cleanup to report post-commit removal: {:?}",
@@ -254,4 +431,34 @@ mod tests {
         // Pre-commit must still be present.
         assert!(repo.join(".git/hooks/pre-commit").exists());
     }
+
+    #[test]
+    fn remove_git_hooks_removes_ours_and_restores_backups() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        Command::new("git")
+            .arg("init")
+            .arg("--quiet")
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+        setup_git_hooks(&repo, true, 0).unwrap();
+        let hooks = repo.join(".git/hooks");
+        fs::write(hooks.join("pre-commit.old"), "#!/bin/sh\necho mine\n").unwrap();
+        assert_eq!(
+            installed_hooks(&repo).unwrap(),
+            vec!["pre-commit", "post-commit"]
+        );
+
+        let removed = remove_git_hooks(&repo, None, true).unwrap();
+        assert_eq!(removed, vec!["pre-commit", "post-commit"]);
+        assert!(installed_hooks(&repo).unwrap().is_empty());
+        assert_eq!(
+            fs::read_to_string(hooks.join("pre-commit")).unwrap(),
+            "#!/bin/sh\necho mine\n"
+        );
+        assert!(!hooks.join("post-commit").exists());
+        assert!(remove_git_hooks(&repo, None, true).unwrap().is_empty());
+    }
 }
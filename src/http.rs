@@ -0,0 +1,831 @@
+//! Blocking HTTP GETs for agent downloads, profile imports and the update
+//! check.
+//!
+//! Requests go through ureq, which honors `HTTPS_PROXY`/`HTTP_PROXY`/
+//! `ALL_PROXY` and `NO_PROXY`, unless `thoughts.proxy` in the config sets
+//! a proxy of its own (see `load_proxy`). Setting `HYPRLAYER_HTTP=curl` shells out to
+//! the system `curl` instead, for TLS setups only curl is configured for
+//! (e.g. a corporate root CA it picks up from the OS store).
+//!
+//! GitHub API requests carry `GITHUB_TOKEN` (or `GH_TOKEN`) when set, which
+//! lifts the 60 requests an hour GitHub allows per IP without one.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::TimeZone;
+
+/// Set to `curl` to make requests with the system `curl`.
+pub const BACKEND_ENV: &str = "HYPRLAYER_HTTP";
+
+const USER_AGENT: &str = "hyprlayer-cli";
+
+/// The GitHub API's JSON media type.
+const GITHUB_JSON: &str = "application/vnd.github.v3+json";
+
+/// Only requests to this prefix are sent the GitHub token.
+const GITHUB_API: &str = "https://api.github.com/";
+
+/// Checked in order for a GitHub token.
+const TOKEN_ENVS: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Large enough for GitHub's source tarball of this repository.
+const MAX_BODY_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Read by curl and ureq, in this order of precedence.
+const PROXY_ENVS: [&str; 5] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+];
+
+/// `thoughts.proxy` from the config, set once at startup by `load_proxy`.
+static PROXY: RwLock<Option<String>> = RwLock::new(None);
+
+/// Send every request through `proxy` instead of the one the environment
+/// names.
+pub fn set_proxy(proxy: Option<String>) {
+    *PROXY.write().unwrap_or_else(|e| e.into_inner()) = proxy;
+}
+
+/// Apply the `thoughts.proxy` of the config at `config_path`, or of the
+/// default config, if it sets one.
+pub fn load_proxy(config_path: Option<&Path>) {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => match crate::config::get_default_config_path() {
+            Ok(path) => path,
+            Err(_) => return,
+        },
+    };
+    let proxy = crate::config::HyprlayerConfig::load(&path)
+        .ok()
+        .and_then(|config| config.thoughts?.proxy)
+        .filter(|proxy| !proxy.trim().is_empty());
+    if proxy.is_some() {
+        set_proxy(proxy);
+    }
+}
+
+fn configured_proxy() -> Option<String> {
+    PROXY.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Whether requests may go through a proxy, from the config or the
+/// environment.
+fn proxy_in_use() -> bool {
+    configured_proxy().is_some()
+        || PROXY_ENVS
+            .iter()
+            .any(|name| env::var(name).is_ok_and(|v| !v.trim().is_empty()))
+}
+
+/// Tries `get_bytes_retrying` makes before giving up.
+const ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry, doubled before each one after.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug)]
+pub enum HttpError {
+    /// No response: DNS, TCP or TLS failure.
+    Connect { url: String, reason: String },
+    /// No response because the proxy couldn't be reached or refused the
+    /// connection.
+    Proxy { url: String, reason: String },
+    /// No complete response within the timeout.
+    Timeout { url: String },
+    /// A non-2xx response. `message` is the `message` field GitHub puts
+    /// in its JSON error bodies, when there is one.
+    Status {
+        url: String,
+        status: u16,
+        message: Option<String>,
+    },
+    /// GitHub refused the request for exceeding its API rate limit.
+    /// `reset` is when the limit resets, in Unix seconds.
+    RateLimited {
+        url: String,
+        reset: Option<i64>,
+        authenticated: bool,
+    },
+    /// The response body couldn't be read or isn't what the caller needs.
+    Decode { url: String, reason: String },
+    /// `HYPRLAYER_HTTP=curl` is set but `curl` isn't installed.
+    CurlNotFound,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Connect { url, reason } => write!(f, "Could not connect to {url}: {reason}"),
+            Self::Proxy { url, reason } => write!(
+                f,
+                "Could not reach {url} through the proxy: {reason}. Check thoughts.proxy in the config or HTTPS_PROXY/HTTP_PROXY"
+            ),
+            Self::Timeout { url } => write!(f, "Timed out fetching {url}"),
+            Self::Status {
+                url,
+                status,
+                message: Some(message),
+            } => write!(f, "HTTP {status} from {url}: {message}"),
+            Self::Status { url, status, .. } => write!(f, "HTTP {status} from {url}"),
+            Self::RateLimited {
+                url,
+                reset,
+                authenticated,
+            } => {
+                write!(f, "GitHub API rate limit exceeded fetching {url}")?;
+                let reset = reset.and_then(|ts| chrono::Local.timestamp_opt(ts, 0).single());
+                if let Some(reset) = reset {
+                    write!(f, " until {}", reset.format("%H:%M"))?;
+                }
+                if *authenticated {
+                    write!(f, " for the token in GITHUB_TOKEN/GH_TOKEN")
+                } else {
+                    write!(
+                        f,
+                        ". Set GITHUB_TOKEN (or GH_TOKEN) to a GitHub token to raise the limit"
+                    )
+                }
+            }
+            Self::Decode { url, reason } => write!(f, "Invalid response from {url}: {reason}"),
+            Self::CurlNotFound => write!(
+                f,
+                "curl not found — install curl or unset {BACKEND_ENV} to use the built-in client"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl HttpError {
+    /// Whether the same request may well succeed if tried again shortly.
+    /// Rate limits last too long to wait out.
+    fn is_transient(&self) -> bool {
+        match self {
+            Self::Connect { .. }
+            | Self::Proxy { .. }
+            | Self::Timeout { .. }
+            | Self::Decode { .. } => true,
+            Self::Status { status, .. } => *status >= 500 || *status == 429,
+            Self::RateLimited { .. } | Self::CurlNotFound => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Native,
+    Curl,
+}
+
+impl Backend {
+    fn from_env() -> Self {
+        match env::var(BACKEND_ENV) {
+            Ok(value) if value.eq_ignore_ascii_case("curl") => Self::Curl,
+            _ => Self::Native,
+        }
+    }
+}
+
+/// What a backend got back.
+struct Response {
+    status: u16,
+    body: Vec<u8>,
+    /// GitHub's `x-ratelimit-remaining` header.
+    rate_limit_remaining: Option<u64>,
+    /// GitHub's `x-ratelimit-reset` header, in Unix seconds.
+    rate_limit_reset: Option<i64>,
+}
+
+/// The GitHub token from the environment. Never printed.
+fn github_token() -> Option<String> {
+    TOKEN_ENVS
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|token| !token.trim().is_empty())
+}
+
+/// GET a GitHub API URL and return the JSON body.
+pub fn get_json(url: &str, timeout: Duration) -> Result<String, HttpError> {
+    let token = url.starts_with(GITHUB_API).then(github_token).flatten();
+    let body = get_with(
+        Backend::from_env(),
+        url,
+        Some(GITHUB_JSON),
+        token.as_deref(),
+        timeout,
+    )?;
+    String::from_utf8(body).map_err(|e| HttpError::Decode {
+        url: url.to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// GET a URL and return the raw body.
+pub fn get_bytes(url: &str, timeout: Duration) -> Result<Vec<u8>, HttpError> {
+    get_with(Backend::from_env(), url, None, None, timeout)
+}
+
+/// `get_bytes`, retried with exponential backoff when the failure may be
+/// temporary (connection drops, timeouts, 5xx), for downloads that should
+/// survive a flaky network.
+pub fn get_bytes_retrying(url: &str, timeout: Duration) -> Result<Vec<u8>, HttpError> {
+    retry(ATTEMPTS, RETRY_DELAY, || get_bytes(url, timeout))
+}
+
+/// Run `op` up to `attempts` times while it fails with a transient error,
+/// sleeping `delay` before the first retry and twice as long before each
+/// one after.
+fn retry<T>(
+    attempts: u32,
+    mut delay: Duration,
+    mut op: impl FnMut() -> Result<T, HttpError>,
+) -> Result<T, HttpError> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if e.is_transient() && attempt < attempts => {
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn get_with(
+    backend: Backend,
+    url: &str,
+    accept: Option<&str>,
+    token: Option<&str>,
+    timeout: Duration,
+) -> Result<Vec<u8>, HttpError> {
+    let response = match backend {
+        Backend::Native => native_get(url, accept, token, timeout)?,
+        Backend::Curl => curl_get(url, accept, token, timeout)?,
+    };
+    if (200..300).contains(&response.status) {
+        return Ok(response.body);
+    }
+
+    let message = github_message(&response.body);
+    // GitHub answers 403 (or 429) both for rate limits and for plain
+    // permission errors; only the headers or message tell them apart.
+    let rate_limited = matches!(response.status, 403 | 429)
+        && (response.rate_limit_remaining == Some(0)
+            || message
+                .as_deref()
+                .is_some_and(|m| m.to_lowercase().contains("rate limit")));
+    if rate_limited {
+        return Err(HttpError::RateLimited {
+            url: url.to_string(),
+            reset: response.rate_limit_reset,
+            authenticated: token.is_some(),
+        });
+    }
+    Err(HttpError::Status {
+        url: url.to_string(),
+        status: response.status,
+        message,
+    })
+}
+
+fn native_get(
+    url: &str,
+    accept: Option<&str>,
+    token: Option<&str>,
+    timeout: Duration,
+) -> Result<Response, HttpError> {
+    // The default config reads the proxy variables, `NO_PROXY` included.
+    let mut config = ureq::Agent::config_builder();
+    if let Some(proxy) = configured_proxy() {
+        let proxy = ureq::Proxy::new(&proxy).map_err(|e| HttpError::Proxy {
+            url: url.to_string(),
+            reason: e.to_string(),
+        })?;
+        config = config.proxy(Some(proxy));
+    }
+    let agent: ureq::Agent = config
+        .timeout_global(Some(timeout))
+        .http_status_as_error(false)
+        .user_agent(USER_AGENT)
+        .build()
+        .into();
+    let mut request = agent.get(url);
+    if let Some(accept) = accept {
+        request = request.header("Accept", accept);
+    }
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {token}"));
+    }
+    let via_proxy = agent.config().proxy().is_some_and(|proxy| {
+        !url.parse()
+            .is_ok_and(|uri: ureq::http::Uri| proxy.is_no_proxy(&uri))
+    });
+    let mut response = request.call().map_err(|e| match e {
+        ureq::Error::Timeout(_) => HttpError::Timeout {
+            url: url.to_string(),
+        },
+        other @ ureq::Error::ConnectProxyFailed(_) => HttpError::Proxy {
+            url: url.to_string(),
+            reason: other.to_string(),
+        },
+        other if via_proxy => HttpError::Proxy {
+            url: url.to_string(),
+            reason: other.to_string(),
+        },
+        other => HttpError::Connect {
+            url: url.to_string(),
+            reason: other.to_string(),
+        },
+    })?;
+
+    let status = response.status().as_u16();
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let rate_limit_remaining = header("x-ratelimit-remaining").and_then(|v| v.parse().ok());
+    let rate_limit_reset = header("x-ratelimit-reset").and_then(|v| v.parse().ok());
+    let body = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_BODY_BYTES)
+        .read_to_vec()
+        .map_err(|e| match e {
+            ureq::Error::Timeout(_) => HttpError::Timeout {
+                url: url.to_string(),
+            },
+            other => HttpError::Decode {
+                url: url.to_string(),
+                reason: other.to_string(),
+            },
+        })?;
+    Ok(Response {
+        status,
+        body,
+        rate_limit_remaining,
+        rate_limit_reset,
+    })
+}
+
+fn curl_get(
+    url: &str,
+    accept: Option<&str>,
+    token: Option<&str>,
+    timeout: Duration,
+) -> Result<Response, HttpError> {
+    // Body and headers go to files so stdout can carry just the status
+    // code. The files are created up front under random names, so nothing
+    // can plant a symlink where curl will write. Dropping them deletes them.
+    let temp_file = |suffix: &str| {
+        tempfile::Builder::new()
+            .prefix("hyprlayer-http-")
+            .suffix(suffix)
+            .tempfile()
+            .map_err(|e| HttpError::Connect {
+                url: url.to_string(),
+                reason: format!("couldn't create a temporary file: {e}"),
+            })
+    };
+    let body_file = temp_file(".body")?;
+    let headers_file = temp_file(".headers")?;
+    let (body_path, headers_path) = (body_file.path(), headers_file.path());
+
+    let mut command = Command::new("curl");
+    command
+        .args(["-sSL", "-A", USER_AGENT, "-w", "%{http_code}", "--max-time"])
+        .arg(timeout.as_secs().max(1).to_string())
+        .arg("-o")
+        .arg(body_path)
+        .arg("-D")
+        .arg(headers_path)
+        // Options on stdin, so the token never shows up in `ps`.
+        .args(["-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(accept) = accept {
+        command.args(["-H", &format!("Accept: {accept}")]);
+    }
+    let spawn_error = |e: std::io::Error| match e.kind() {
+        std::io::ErrorKind::NotFound => HttpError::CurlNotFound,
+        _ => HttpError::Connect {
+            url: url.to_string(),
+            reason: e.to_string(),
+        },
+    };
+    let mut child = command.arg(url).spawn().map_err(spawn_error)?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut config = token
+            .map(|token| format!("header = \"Authorization: Bearer {token}\"\n"))
+            .unwrap_or_default();
+        if let Some(proxy) = configured_proxy() {
+            // `noproxy` clears `NO_PROXY`, as for the built-in client.
+            config.push_str(&format!("proxy = \"{proxy}\"\nnoproxy = \"\"\n"));
+        }
+        stdin.write_all(config.as_bytes()).map_err(spawn_error)?;
+    }
+    let output = child.wait_with_output().map_err(spawn_error)?;
+
+    let body = fs::read(body_path).unwrap_or_default();
+    let headers = fs::read_to_string(headers_path).unwrap_or_default();
+    if let Some(err) = curl_error(url, output.status.code(), &output.stderr, proxy_in_use()) {
+        return Err(err);
+    }
+    let status = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .map_err(|_| HttpError::Decode {
+            url: url.to_string(),
+            reason: "curl reported no status code".to_string(),
+        })?;
+    let header = |name: &str| dumped_header(&headers, name);
+    Ok(Response {
+        status,
+        body,
+        rate_limit_remaining: header("x-ratelimit-remaining").and_then(|v| v.parse().ok()),
+        rate_limit_reset: header("x-ratelimit-reset").and_then(|v| v.parse().ok()),
+    })
+}
+
+/// The value of header `name` in the final response of a curl `-D`
+/// dump, which holds one block per redirect followed.
+fn dumped_header<'a>(dump: &'a str, name: &str) -> Option<&'a str> {
+    let last_response = dump.rsplit("HTTP/").next()?;
+    last_response.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+    })
+}
+
+/// The error for a curl exit code, `None` when curl got a response.
+/// Connection failures through a proxy (`via_proxy`) are blamed on it.
+fn curl_error(
+    url: &str,
+    exit_code: Option<i32>,
+    stderr: &[u8],
+    via_proxy: bool,
+) -> Option<HttpError> {
+    let url = url.to_string();
+    let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+    let reason = match exit_code {
+        _ if !stderr.is_empty() => stderr,
+        Some(code) => format!("curl exited with code {code}"),
+        None => "curl was interrupted".to_string(),
+    };
+    match exit_code {
+        Some(0) => None,
+        // CURLE_COULDNT_RESOLVE_PROXY, CURLE_PROXY
+        Some(5 | 97) => Some(HttpError::Proxy { url, reason }),
+        // CURLE_OPERATION_TIMEDOUT
+        Some(28) => Some(HttpError::Timeout { url }),
+        // CURLE_PARTIAL_FILE, CURLE_RECV_ERROR, CURLE_BAD_CONTENT_ENCODING
+        Some(18 | 56 | 61) => Some(HttpError::Decode { url, reason }),
+        // CURLE_SSL_CONNECT_ERROR, CURLE_SSL_CERTPROBLEM, CURLE_SSL_CIPHER,
+        // CURLE_PEER_FAILED_VERIFICATION, CURLE_SSL_CACERT_BADFILE
+        Some(35 | 58 | 59 | 60 | 77) => Some(HttpError::Connect {
+            url,
+            reason: format!("TLS error: {reason}"),
+        }),
+        _ if via_proxy => Some(HttpError::Proxy { url, reason }),
+        _ => Some(HttpError::Connect { url, reason }),
+    }
+}
+
+/// The `message` of a GitHub JSON error body.
+fn github_message(body: &[u8]) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct GitHubError {
+        message: Option<String>,
+    }
+    serde_json::from_slice::<GitHubError>(body).ok()?.message
+}
+
+/// A local HTTP server for tests of code that fetches URLs.
+#[cfg(test)]
+pub(crate) mod test_server {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A complete HTTP response with a JSON body.
+    pub fn json_response(status: &str, body: &str) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .into_bytes()
+    }
+
+    /// A request the server received.
+    pub struct Request {
+        pub path: String,
+        /// The header lines, as sent.
+        pub headers: Vec<String>,
+    }
+
+    /// Serve `responses`, written as-is, to successive requests on a local
+    /// port. Returns the base URL and a handle yielding the requests.
+    pub fn serve(responses: Vec<Vec<u8>>) -> (String, JoinHandle<Vec<Request>>) {
+        serve_with(|_| responses)
+    }
+
+    /// `serve`, for responses that link back to the server: `responses`
+    /// is given the base URL.
+    pub fn serve_with(
+        responses: impl FnOnce(&str) -> Vec<Vec<u8>>,
+    ) -> (String, JoinHandle<Vec<Request>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let responses = responses(&base);
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                // Read the headers before answering.
+                let mut headers = Vec::new();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    headers.push(line.trim_end().to_string());
+                    line.clear();
+                }
+                requests.push(Request {
+                    path: request_line.split(' ').nth(1).unwrap().to_string(),
+                    headers,
+                });
+                stream.write_all(&response).unwrap();
+            }
+            requests
+        });
+        (base, handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_server::{json_response, serve};
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn native_get_maps_responses_to_typed_errors() {
+        let (base, server) = serve(vec![
+            json_response("200 OK", r#"{"ok":true}"#),
+            json_response("404 Not Found", r#"{"message":"Not Found"}"#),
+            json_response("502 Bad Gateway", "<html>oops</html>"),
+            // Promises more body than it sends before closing.
+            b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\nshort".to_vec(),
+        ]);
+        let get = |path: &str| {
+            get_with(
+                Backend::Native,
+                &format!("{base}{path}"),
+                None,
+                None,
+                TIMEOUT,
+            )
+        };
+
+        assert_eq!(get("/ok").unwrap(), br#"{"ok":true}"#);
+        match get("/missing").unwrap_err() {
+            HttpError::Status {
+                status: 404,
+                message: Some(message),
+                ..
+            } => assert_eq!(message, "Not Found"),
+            other => panic!("unexpected {other:?}"),
+        }
+        let err = get("/proxy").unwrap_err();
+        assert!(matches!(
+            err,
+            HttpError::Status {
+                status: 502,
+                message: None,
+                ..
+            }
+        ));
+        assert!(err.to_string().starts_with("HTTP 502 from http://"));
+        assert!(matches!(
+            get("/truncated").unwrap_err(),
+            HttpError::Decode { .. }
+        ));
+        let paths: Vec<String> = server.join().unwrap().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, ["/ok", "/missing", "/proxy", "/truncated"]);
+    }
+
+    #[test]
+    fn native_get_reports_timeouts_and_refused_connections() {
+        // Accepts the connection but never answers.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let err = get_with(
+            Backend::Native,
+            &url,
+            None,
+            None,
+            Duration::from_millis(200),
+        )
+        .unwrap_err();
+        assert!(matches!(err, HttpError::Timeout { .. }), "{err:?}");
+        drop(listener);
+
+        let err = get_with(Backend::Native, &url, None, None, TIMEOUT).unwrap_err();
+        assert!(matches!(err, HttpError::Connect { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn github_token_is_sent_and_rate_limits_are_detected() {
+        let rate_limited = "HTTP/1.1 403 Forbidden\r\nx-ratelimit-remaining: 0\r\nx-ratelimit-reset: 1700000000\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+        let (base, server) = serve(vec![
+            json_response("200 OK", "[]"),
+            rate_limited.as_bytes().to_vec(),
+            // Secondary rate limits come without the headers.
+            json_response(
+                "403 Forbidden",
+                r#"{"message":"You have exceeded a secondary rate limit."}"#,
+            ),
+            json_response("403 Forbidden", r#"{"message":"Resource not accessible"}"#),
+        ]);
+        let get = |path: &str, token| {
+            get_with(
+                Backend::Native,
+                &format!("{base}{path}"),
+                Some(GITHUB_JSON),
+                token,
+                TIMEOUT,
+            )
+        };
+
+        get("/ok", Some("s3cret")).unwrap();
+        let err = get("/limited", None).unwrap_err();
+        assert!(matches!(
+            err,
+            HttpError::RateLimited {
+                reset: Some(1_700_000_000),
+                authenticated: false,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("Set GITHUB_TOKEN (or GH_TOKEN)"));
+        assert!(
+            err.to_string()
+                .contains(&format!("fetching {base}/limited"))
+        );
+
+        let err = get("/secondary", Some("s3cret")).unwrap_err();
+        assert!(matches!(
+            err,
+            HttpError::RateLimited {
+                reset: None,
+                authenticated: true,
+                ..
+            }
+        ));
+        assert!(!err.to_string().contains("s3cret"));
+        assert!(matches!(
+            get("/forbidden", None).unwrap_err(),
+            HttpError::Status { status: 403, .. }
+        ));
+
+        let requests = server.join().unwrap();
+        assert!(
+            requests[0]
+                .headers
+                .iter()
+                .any(|h| h.eq_ignore_ascii_case("authorization: bearer s3cret"))
+        );
+        assert!(
+            !requests[1]
+                .headers
+                .iter()
+                .any(|h| h.to_lowercase().starts_with("authorization"))
+        );
+    }
+
+    #[test]
+    fn retry_retries_transient_errors_only() {
+        let (base, server) = serve(vec![
+            json_response("502 Bad Gateway", "<html>oops</html>"),
+            json_response("503 Service Unavailable", ""),
+            json_response("200 OK", "done"),
+            json_response("404 Not Found", r#"{"message":"Not Found"}"#),
+            json_response("500 Internal Server Error", ""),
+            json_response("500 Internal Server Error", ""),
+        ]);
+        let get = |path: &str| {
+            retry(3, Duration::ZERO, || {
+                get_with(
+                    Backend::Native,
+                    &format!("{base}{path}"),
+                    None,
+                    None,
+                    TIMEOUT,
+                )
+            })
+        };
+
+        assert_eq!(get("/flaky").unwrap(), b"done");
+        assert!(matches!(
+            get("/missing").unwrap_err(),
+            HttpError::Status { status: 404, .. }
+        ));
+        let err = retry(2, Duration::ZERO, || {
+            get_with(
+                Backend::Native,
+                &format!("{base}/down"),
+                None,
+                None,
+                TIMEOUT,
+            )
+        })
+        .unwrap_err();
+        assert!(matches!(err, HttpError::Status { status: 500, .. }));
+
+        let paths: Vec<String> = server.join().unwrap().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            ["/flaky", "/flaky", "/flaky", "/missing", "/down", "/down"]
+        );
+    }
+
+    #[test]
+    fn dumped_header_reads_the_final_response() {
+        let dump = "HTTP/1.1 302 Found\r\nLocation: /x\r\nX-RateLimit-Remaining: 5\r\n\r\nHTTP/2 403\r\nx-ratelimit-remaining: 0\r\nx-ratelimit-reset: 1700000000\r\n\r\n";
+        assert_eq!(dumped_header(dump, "x-ratelimit-remaining"), Some("0"));
+        assert_eq!(dumped_header(dump, "X-RateLimit-Reset"), Some("1700000000"));
+        assert_eq!(dumped_header(dump, "location"), None);
+        assert_eq!(dumped_header("", "location"), None);
+    }
+
+    #[test]
+    fn curl_exit_codes_map_to_typed_errors() {
+        let url = "https://example.com/";
+        assert!(curl_error(url, Some(0), b"", false).is_none());
+        assert!(matches!(
+            curl_error(url, Some(28), b"", false),
+            Some(HttpError::Timeout { .. })
+        ));
+        assert!(matches!(
+            curl_error(url, Some(18), b"", false),
+            Some(HttpError::Decode { .. })
+        ));
+        match curl_error(url, Some(6), b"curl: (6) Could not resolve host\n", false) {
+            Some(HttpError::Connect { reason, .. }) => {
+                assert_eq!(reason, "curl: (6) Could not resolve host");
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(
+            curl_error(url, Some(5), b"", false),
+            Some(HttpError::Proxy { .. })
+        ));
+        let untrusted = b"curl: (60) SSL certificate problem: self-signed certificate\n";
+        match curl_error(url, Some(60), untrusted, true) {
+            Some(HttpError::Connect { reason, .. }) => assert_eq!(
+                reason,
+                "TLS error: curl: (60) SSL certificate problem: self-signed certificate"
+            ),
+            other => panic!("unexpected {other:?}"),
+        }
+        let refused = b"curl: (7) Failed to connect to proxy.corp port 3128\n";
+        assert!(matches!(
+            curl_error(url, Some(7), refused, true),
+            Some(HttpError::Proxy { .. })
+        ));
+        assert!(matches!(
+            curl_error(url, Some(7), refused, false),
+            Some(HttpError::Connect { .. })
+        ));
+    }
+
+    #[test]
+    fn github_message_reads_error_bodies_only() {
+        assert_eq!(
+            github_message(br#"{"message":"API rate limit exceeded","documentation_url":"x"}"#)
+                .as_deref(),
+            Some("API rate limit exceeded")
+        );
+        assert_eq!(github_message(br#"[{"sha":"abc"}]"#), None);
+        assert_eq!(github_message(b"<html>"), None);
+    }
+}
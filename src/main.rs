@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::Parser;
 
+mod agent_backup;
 pub mod agents;
 mod backends;
 mod cli;
@@ -8,19 +9,37 @@ mod commands;
 mod config;
 mod git_ops;
 mod hooks;
+mod http;
+mod platform;
+mod search_index;
+mod secrets;
+mod text_diff;
 mod version;
 
-use cli::{AiCommands, CodexCommands, ProfileCommands, StorageCommands, ThoughtsCommands};
-use commands::ai::{configure as ai_configure, reinstall as ai_reinstall, status as ai_status};
+use cli::{
+    AiCommands, CodexCommands, Commands, IndexCommands, ProfileCommands, StorageCommands,
+    ThoughtsCommands, WorktreeCommands,
+};
+use commands::ai::{
+    configure as ai_configure, diff as ai_diff, reinstall as ai_reinstall,
+    restore_backup as ai_restore_backup, set_model as ai_set_model, status as ai_status,
+    uninstall as ai_uninstall, update as ai_update,
+};
 use commands::codex::stream as codex_stream;
 use commands::storage::{
     info as storage_info, set_database_id as storage_set_database_id,
     set_type_id as storage_set_type_id,
 };
 use commands::thoughts::profile::{
-    create as profile_create, delete as profile_delete, list as profile_list, show as profile_show,
+    create as profile_create, delete as profile_delete, edit as profile_edit,
+    export as profile_export, import as profile_import, list as profile_list,
+    rename as profile_rename, set_default as profile_set_default, show as profile_show,
+    unset_default as profile_unset_default,
+};
+use commands::thoughts::{
+    apply_patch, audit, config_cmd, diff, encrypt, export, grep, import, index, init, merge, open,
+    rebase, search, status, sync, tui, uninit, use_profile, worktree,
 };
-use commands::thoughts::{config_cmd, init, status, sync, uninit};
 
 fn main() -> Result<()> {
     let cli = cli::Cli::parse();
@@ -28,36 +47,81 @@ fn main() -> Result<()> {
     // Parse first, then run startup checks against the config the
     // current command actually uses. Honors `--config-file` and the
     // per-config `disableUpdateCheck` flag for that file.
-    let config_path = cli.config_args().and_then(|a| a.path().ok());
-    version::run_startup_checks(config_path.as_deref());
+    let config_path = cli.command.config_args().and_then(|a| a.path().ok());
+    agents::load_dest_override(config_path.as_deref());
+    http::load_proxy(config_path.as_deref());
+    git_ops::load_push_with_git_cli(config_path.as_deref());
+    let release_check = version::run_startup_checks(config_path.as_deref(), cli.sync_version_check);
+
+    let result = run(cli.command);
+    // After the command's own output, so the notification comes last.
+    if let Some(release_check) = release_check {
+        release_check.finish();
+    }
+    result
+}
 
-    match cli {
-        cli::Cli::Thoughts { command } => match command {
-            ThoughtsCommands::Init(args) => init::init(args)?,
+fn run(command: Commands) -> Result<()> {
+    match command {
+        Commands::Thoughts { command } => match command {
+            ThoughtsCommands::Init(args) => init::init(*args)?,
             ThoughtsCommands::Uninit(args) => uninit::uninit(args)?,
             ThoughtsCommands::Sync(args) => sync::sync(args)?,
             ThoughtsCommands::Status(args) => status::status(args)?,
+            ThoughtsCommands::Tui(args) => tui::tui(args)?,
+            ThoughtsCommands::Open(args) => open::open(args)?,
+            ThoughtsCommands::Search(args) => search::search(args)?,
+            ThoughtsCommands::Grep(args) => grep::grep(args)?,
+            ThoughtsCommands::UseProfile(args) => use_profile::use_profile(args)?,
+            ThoughtsCommands::ApplyPatch(args) => apply_patch::apply_patch(args)?,
+            ThoughtsCommands::Merge(args) => merge::merge(args)?,
+            ThoughtsCommands::Encrypt(args) => encrypt::encrypt(args)?,
+            ThoughtsCommands::Audit(args) => audit::audit(args)?,
+            ThoughtsCommands::Diff(args) => diff::diff(args)?,
+            ThoughtsCommands::Rebase(args) => rebase::rebase(args)?,
+            ThoughtsCommands::Import(args) => import::import(args)?,
+            ThoughtsCommands::Export(args) => export::export(args)?,
+            ThoughtsCommands::Index { command } => match command {
+                IndexCommands::Build(args) => index::build(args)?,
+                IndexCommands::Search(args) => index::search(args)?,
+            },
             ThoughtsCommands::Config(args) => config_cmd::config(args)?,
+            ThoughtsCommands::Worktree { command } => match command {
+                WorktreeCommands::Add(args) => worktree::add(args)?,
+                WorktreeCommands::List(args) => worktree::list(args)?,
+            },
             ThoughtsCommands::Profile { command } => match command {
                 ProfileCommands::Create(args) => profile_create::create(args)?,
                 ProfileCommands::List(args) => profile_list::list(args)?,
                 ProfileCommands::Show(args) => profile_show::show(args)?,
                 ProfileCommands::Delete(args) => profile_delete::delete(args)?,
+                ProfileCommands::Rename(args) => profile_rename::rename(args)?,
+                ProfileCommands::Edit(args) => profile_edit::edit(args)?,
+                ProfileCommands::SetDefault(args) => profile_set_default::set_default(args)?,
+                ProfileCommands::UnsetDefault(args) => profile_unset_default::unset_default(args)?,
+                ProfileCommands::Export(args) => profile_export::export(args)?,
+                ProfileCommands::Import(args) => profile_import::import(args)?,
             },
         },
-        cli::Cli::Ai { command } => match command {
+        Commands::Ai { command } => match command {
             AiCommands::Configure(args) => ai_configure::configure(args)?,
             AiCommands::Status(args) => ai_status::status(args)?,
             AiCommands::Reinstall(args) => ai_reinstall::reinstall(args)?,
+            AiCommands::Update(args) => ai_update::update(args)?,
+            AiCommands::Diff(args) => ai_diff::diff(args)?,
+            AiCommands::SetModel(args) => ai_set_model::set_model(args)?,
+            AiCommands::RestoreBackup(args) => ai_restore_backup::restore_backup(args)?,
+            AiCommands::Uninstall(args) => ai_uninstall::uninstall(args)?,
         },
-        cli::Cli::Storage { command } => match command {
+        Commands::Storage { command } => match command {
             StorageCommands::Info(args) => storage_info::info(args)?,
             StorageCommands::SetDatabaseId(args) => storage_set_database_id::set_database_id(args)?,
             StorageCommands::SetTypeId(args) => storage_set_type_id::set_type_id(args)?,
         },
-        cli::Cli::Codex { command } => match command {
+        Commands::Codex { command } => match command {
             CodexCommands::Stream(args) => codex_stream::stream(args)?,
         },
+        Commands::Version(args) => commands::version::version(args)?,
     }
 
     Ok(())
@@ -0,0 +1,152 @@
+//! Platform-specific filesystem helpers.
+
+#[cfg(windows)]
+use anyhow::Context;
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+/// Create a directory link at `link` pointing to `target`.
+#[cfg(unix)]
+pub fn create_dir_link(target: &Path, link: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
+/// Create a directory link at `link` pointing to `target`.
+///
+/// Directory symlinks on Windows need either Administrator rights or
+/// Developer Mode. When `symlink_dir` is refused for lack of privilege we
+/// fall back to an NTFS junction, which any user can create. Junctions are
+/// close enough for editors and agents, but some tools treat them
+/// differently — notably `git status` does not traverse them.
+#[cfg(windows)]
+pub fn create_dir_link(target: &Path, link: &Path) -> Result<()> {
+    /// `ERROR_PRIVILEGE_NOT_HELD` — what `CreateSymbolicLinkW` returns
+    /// without Developer Mode. Older toolchains don't map it to
+    /// `PermissionDenied`, so match the raw code too.
+    const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+
+    let err = match std::os::windows::fs::symlink_dir(target, link) {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+    let privilege_error = err.kind() == std::io::ErrorKind::PermissionDenied
+        || err.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD);
+    if !privilege_error {
+        return Err(err).with_context(|| {
+            format!(
+                "Failed to create symlink.\nTarget: {}\nLink: {}",
+                target.display(),
+                link.display()
+            )
+        });
+    }
+
+    junction::create(target, link).with_context(|| {
+        format!(
+            "Failed to create symlink or junction. On Windows, symlinks require either:\n\
+             1. Run as Administrator, or\n\
+             2. Enable Developer Mode in Settings > Update & Security > For developers\n\n\
+             Target: {}\nLink: {}",
+            target.display(),
+            link.display()
+        )
+    })?;
+    print_junction_note();
+    Ok(())
+}
+
+/// Printed once per process — a single `init` creates three links.
+#[cfg(windows)]
+fn print_junction_note() {
+    use colored::Colorize;
+    use std::sync::Once;
+
+    static NOTE: Once = Once::new();
+    NOTE.call_once(|| {
+        eprintln!(
+            "{}",
+            "Note: symlinks are unavailable (no Administrator rights or Developer Mode), \
+             so NTFS junctions were created instead. Junctions behave slightly differently \
+             from symlinks for some tools, and `git status` does not traverse them."
+                .yellow()
+        );
+    });
+}
+
+/// `fs::rename`, falling back to copy-and-delete across filesystems.
+pub fn move_dir(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_dir(from, to)?;
+    fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+/// Recursively copy the directory `from` to `to`.
+pub fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), &dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn create_dir_link_resolves_to_target() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("note.md"), "hello").unwrap();
+
+        let link = tmp.path().join("link");
+        create_dir_link(&target, &link).unwrap();
+
+        assert!(link.is_dir());
+        assert_eq!(fs::read_to_string(link.join("note.md")).unwrap(), "hello");
+    }
+
+    #[test]
+    fn create_dir_link_errors_when_link_exists() {
+        let tmp = TempDir::new().unwrap();
+        let target = tmp.path().join("target");
+        let link = tmp.path().join("link");
+        fs::create_dir_all(&target).unwrap();
+        fs::create_dir_all(&link).unwrap();
+
+        assert!(create_dir_link(&target, &link).is_err());
+    }
+
+    #[test]
+    fn move_dir_moves_nested_tree_and_creates_parents() {
+        let tmp = TempDir::new().unwrap();
+        let from = tmp.path().join("a");
+        fs::create_dir_all(from.join("sub")).unwrap();
+        fs::write(from.join("sub").join("note.md"), "hi").unwrap();
+
+        let to = tmp.path().join("b").join("c");
+        move_dir(&from, &to).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(
+            fs::read_to_string(to.join("sub").join("note.md")).unwrap(),
+            "hi"
+        );
+    }
+}
@@ -0,0 +1,594 @@
+//! Persistent inverted index over thoughts files, used by
+//! `thoughts search --index` and maintained by `thoughts index build` and
+//! `thoughts sync`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::config::{get_data_dir, get_default_config_path};
+use crate::git_ops::GitRepo;
+
+const INDEX_FILE_NAME: &str = "search-index.json";
+
+/// Where the index for the config at `config_path` lives: the data
+/// directory for the default config, beside the file for any other
+/// `--config-file`.
+pub fn index_path(config_path: &Path) -> PathBuf {
+    let is_default = get_default_config_path().is_ok_and(|default| default == config_path);
+    match get_data_dir() {
+        Ok(data_dir) if is_default => data_dir.join(INDEX_FILE_NAME),
+        _ => config_path.with_file_name(INDEX_FILE_NAME),
+    }
+}
+
+/// Whether an index has been built at `path`, counting one `load` would
+/// still move there.
+pub fn index_exists(path: &Path) -> bool {
+    path.exists() || legacy_index_path(path).is_some_and(|legacy| legacy.exists())
+}
+
+/// Where the default config's index lived before it moved to the data
+/// directory, when `path` is where it lives now.
+fn legacy_index_path(path: &Path) -> Option<PathBuf> {
+    let legacy = get_default_config_path()
+        .ok()?
+        .with_file_name(INDEX_FILE_NAME);
+    let current = get_data_dir().ok()?.join(INDEX_FILE_NAME);
+    (current == path && legacy != path).then_some(legacy)
+}
+
+/// Move the index at `from` to `to`, unless there's none to move or `to`
+/// already has one. Returns `true` when the move failed, leaving the
+/// only index at `from`.
+fn move_index(from: &Path, to: &Path) -> bool {
+    if !from.exists() || to.exists() {
+        return false;
+    }
+    let moved = to
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::rename(from, to));
+    moved.is_err()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SearchHit {
+    pub path: PathBuf,
+    pub line: usize,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IndexUpdate {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// Lowercased word → every `(file, line)` it appears on, plus the mtime each
+/// file had when indexed so `update_root` can re-index only what changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchIndex {
+    words: BTreeMap<String, Vec<(String, usize)>>,
+    files: BTreeMap<String, u64>,
+}
+
+impl SearchIndex {
+    /// Load the index, or an empty one if it hasn't been built yet. The
+    /// default config's index is moved over from beside the config file,
+    /// where it used to live, the first time; if it can't be, it's read
+    /// from there.
+    pub fn load(path: &Path) -> Result<Self> {
+        let legacy = legacy_index_path(path);
+        let path = match &legacy {
+            Some(legacy) if move_index(legacy, path) => legacy,
+            _ => path,
+        };
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read search index: {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| {
+            format!(
+                "Search index is corrupt; rebuild it with 'hyprlayer thoughts index build': {}",
+                path.display()
+            )
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write search index: {}", path.display()))
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether any indexed file lives under `root`.
+    pub fn covers(&self, root: &Path) -> bool {
+        self.files.keys().any(|f| Path::new(f).starts_with(root))
+    }
+
+    /// Drop everything indexed under `root` and index it from scratch.
+    /// Returns the number of files indexed.
+    pub fn rebuild_root(&mut self, root: &Path) -> Result<usize> {
+        let stale: Vec<String> = self
+            .files
+            .keys()
+            .filter(|f| Path::new(f).starts_with(root))
+            .cloned()
+            .collect();
+        self.remove_files(&stale);
+
+        let files = list_files(root)?;
+        for file in &files {
+            self.add_file(file);
+        }
+        Ok(files.len())
+    }
+
+    /// Re-index only the files under `root` that were added, modified or
+    /// deleted since they were last indexed.
+    pub fn update_root(&mut self, root: &Path) -> Result<IndexUpdate> {
+        let mut update = IndexUpdate::default();
+        let on_disk: BTreeMap<String, PathBuf> = list_files(root)?
+            .into_iter()
+            .map(|p| (p.display().to_string(), p))
+            .collect();
+
+        let removed: Vec<String> = self
+            .files
+            .keys()
+            .filter(|f| Path::new(f).starts_with(root) && !on_disk.contains_key(*f))
+            .cloned()
+            .collect();
+        update.removed = removed.len();
+
+        let mut changed = Vec::new();
+        for (key, path) in &on_disk {
+            match self.files.get(key) {
+                None => update.added += 1,
+                Some(&indexed) if indexed != mtime_millis(path) => update.updated += 1,
+                Some(_) => continue,
+            }
+            changed.push((key, path));
+        }
+
+        // One pass over the postings for every stale file, not one per file.
+        let stale: Vec<String> = removed
+            .into_iter()
+            .chain(changed.iter().map(|(key, _)| (*key).clone()))
+            .collect();
+        self.remove_files(&stale);
+        for (_, path) in changed {
+            self.add_file(path);
+        }
+        Ok(update)
+    }
+
+    /// Lines containing every word of `query`, or with `regex`, lines with
+    /// any indexed word matching the pattern.
+    pub fn search(&self, query: &str, regex: bool) -> Result<Vec<SearchHit>> {
+        let hits: BTreeSet<(String, usize)> = match Matcher::new(query, regex)? {
+            Matcher::Words(words) => {
+                let mut sets = words.iter().map(|w| {
+                    self.words
+                        .get(w)
+                        .map(|occ| occ.iter().cloned().collect::<BTreeSet<_>>())
+                        .unwrap_or_default()
+                });
+                let first = sets.next().unwrap_or_default();
+                sets.fold(first, |acc, set| acc.intersection(&set).cloned().collect())
+            }
+            Matcher::Regex(re) => self
+                .words
+                .iter()
+                .filter(|(word, _)| re.is_match(word))
+                .flat_map(|(_, occ)| occ.iter().cloned())
+                .collect(),
+        };
+        Ok(hits
+            .into_iter()
+            .map(|(path, line)| SearchHit {
+                path: PathBuf::from(path),
+                line,
+            })
+            .collect())
+    }
+
+    fn add_file(&mut self, path: &Path) {
+        // Binary and non-UTF-8 files aren't thoughts; skip them quietly.
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+        let key = path.display().to_string();
+        for (idx, line) in content.lines().enumerate() {
+            let words: BTreeSet<String> = tokenize(line).collect();
+            for word in words {
+                self.words
+                    .entry(word)
+                    .or_default()
+                    .push((key.clone(), idx + 1));
+            }
+        }
+        self.files.insert(key, mtime_millis(path));
+    }
+
+    fn remove_files(&mut self, keys: &[String]) {
+        if keys.is_empty() {
+            return;
+        }
+        let keys: BTreeSet<&String> = keys.iter().collect();
+        self.words.retain(|_, occ| {
+            occ.retain(|(file, _)| !keys.contains(file));
+            !occ.is_empty()
+        });
+        self.files.retain(|file, _| !keys.contains(file));
+    }
+}
+
+/// Query semantics shared by indexed and unindexed search, so both return
+/// the same lines: plain queries need every word on the line; regexes are
+/// matched case-insensitively against individual words.
+pub enum Matcher {
+    Words(Vec<String>),
+    Regex(Regex),
+}
+
+impl Matcher {
+    pub fn new(query: &str, regex: bool) -> Result<Self> {
+        if regex {
+            let re = Regex::new(&format!("(?i){query}"))
+                .with_context(|| format!("Invalid regex: {query}"))?;
+            return Ok(Matcher::Regex(re));
+        }
+        let words: Vec<String> = tokenize(query).collect();
+        if words.is_empty() {
+            return Err(anyhow::anyhow!("Search query has no words"));
+        }
+        Ok(Matcher::Words(words))
+    }
+
+    pub fn matches_line(&self, line: &str) -> bool {
+        match self {
+            Matcher::Words(words) => {
+                let tokens: BTreeSet<String> = tokenize(line).collect();
+                words.iter().all(|w| tokens.contains(w))
+            }
+            Matcher::Regex(re) => tokenize(line).any(|t| re.is_match(&t)),
+        }
+    }
+}
+
+/// Scan `files` directly, without an index.
+pub fn scan(files: &[PathBuf], matcher: &Matcher) -> Result<Vec<SearchHit>> {
+    let mut hits = Vec::new();
+    for path in files {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        hits.extend(
+            content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| matcher.matches_line(line))
+                .map(|(idx, _)| SearchHit {
+                    path: path.clone(),
+                    line: idx + 1,
+                }),
+        );
+    }
+    Ok(hits)
+}
+
+/// `--after`/`--before` bounds in unix seconds; `after` is inclusive,
+/// `before` exclusive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+}
+
+impl DateRange {
+    pub fn is_unbounded(&self) -> bool {
+        self.after.is_none() && self.before.is_none()
+    }
+
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.after.is_none_or(|a| timestamp >= a) && self.before.is_none_or(|b| timestamp < b)
+    }
+
+    /// Keep the files under `root` modified within the range. When `root`
+    /// is a git repository, a file with commit history matches if any
+    /// commit in the range touched it; files without history (and roots
+    /// outside git) fall back to their mtime.
+    pub fn filter_files(&self, root: &Path, files: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+        if self.is_unbounded() {
+            return Ok(files);
+        }
+        let (in_range, with_history) = match GitRepo::open(root) {
+            Ok(repo) => {
+                let since = self.after.unwrap_or(i64::MIN);
+                let until = self.before.map_or(i64::MAX, |b| b - 1);
+                let in_range: BTreeSet<PathBuf> = repo
+                    .files_modified_between(since, until)?
+                    .into_iter()
+                    .collect();
+                let with_history: BTreeSet<PathBuf> = repo
+                    .files_modified_between(i64::MIN, i64::MAX)?
+                    .into_iter()
+                    .collect();
+                (in_range, with_history)
+            }
+            Err(_) => Default::default(),
+        };
+
+        Ok(files
+            .into_iter()
+            .filter(|f| {
+                if with_history.contains(f) {
+                    in_range.contains(f)
+                } else {
+                    let secs = mtime_millis(f) / 1000;
+                    self.contains(secs as i64)
+                }
+            })
+            .collect())
+    }
+}
+
+/// Parse a `--before`/`--after` value: `YYYY-MM-DD` (local midnight) or a
+/// duration ago such as `3days`, `1week`, `2months`, `1year`.
+pub fn parse_date(value: &str, now: DateTime<Local>) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let midnight = date.and_time(NaiveTime::MIN);
+        return Local
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|dt| dt.timestamp())
+            .ok_or_else(|| anyhow::anyhow!("Invalid local date: {value}"));
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (count, unit) = value.split_at(split);
+    let days_per_unit = match unit.trim().to_lowercase().as_str() {
+        "d" | "day" | "days" => 1,
+        "w" | "week" | "weeks" => 7,
+        "month" | "months" => 30,
+        "y" | "year" | "years" => 365,
+        _ => 0,
+    };
+    match count.parse::<i64>() {
+        Ok(count) if days_per_unit > 0 => Ok(now.timestamp() - count * days_per_unit * 86_400),
+        _ => Err(anyhow::anyhow!(
+            "Invalid date '{value}': use YYYY-MM-DD or a relative date like 3days, 1week, 2months"
+        )),
+    }
+}
+
+/// Read line `hit.line` of the hit's file for display.
+pub fn hit_text(hit: &SearchHit) -> Option<String> {
+    fs::read_to_string(&hit.path)
+        .ok()?
+        .lines()
+        .nth(hit.line.checked_sub(1)?)
+        .map(|l| l.trim().to_string())
+}
+
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Every file under `root`, sorted, skipping dot-entries (`.git`) and the
+/// generated `searchable/` hard-link tree.
+pub fn list_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if root.is_dir() {
+        walk(root, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "searchable" {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            walk(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+fn mtime_millis(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) -> PathBuf {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn build_indexes_words_with_line_numbers() {
+        let tmp = TempDir::new().unwrap();
+        let plan = write(
+            tmp.path(),
+            "repos/app/plan.md",
+            "# Plan\nMigrate the Database\n",
+        );
+        write(tmp.path(), ".git/HEAD", "database");
+        write(tmp.path(), "searchable/plan.md", "database");
+
+        let mut index = SearchIndex::default();
+        assert_eq!(index.rebuild_root(tmp.path()).unwrap(), 1);
+
+        let hits = index.search("database", false).unwrap();
+        assert_eq!(
+            hits,
+            vec![SearchHit {
+                path: plan,
+                line: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn files_added_after_build_are_found_only_after_rebuild() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "global/a.md", "alpha\n");
+        let mut index = SearchIndex::default();
+        index.rebuild_root(tmp.path()).unwrap();
+
+        write(tmp.path(), "global/b.md", "beta\n");
+        assert!(index.search("beta", false).unwrap().is_empty());
+
+        index.rebuild_root(tmp.path()).unwrap();
+        assert_eq!(index.search("beta", false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn update_root_reindexes_only_changes() {
+        let tmp = TempDir::new().unwrap();
+        let keep = write(tmp.path(), "keep.md", "steady\n");
+        let gone = write(tmp.path(), "gone.md", "ephemeral\n");
+        let mut index = SearchIndex::default();
+        index.rebuild_root(tmp.path()).unwrap();
+
+        fs::remove_file(&gone).unwrap();
+        write(tmp.path(), "new.md", "fresh\n");
+        let update = index.update_root(tmp.path()).unwrap();
+
+        assert_eq!(
+            update,
+            IndexUpdate {
+                added: 1,
+                updated: 0,
+                removed: 1
+            }
+        );
+        assert!(index.search("ephemeral", false).unwrap().is_empty());
+        assert_eq!(index.search("fresh", false).unwrap().len(), 1);
+        assert_eq!(index.search("steady", false).unwrap()[0].path, keep);
+    }
+
+    #[test]
+    fn search_requires_all_words_and_supports_regex() {
+        let tmp = TempDir::new().unwrap();
+        write(
+            tmp.path(),
+            "notes.md",
+            "cache invalidation bug\ncache warmup\nrate-limiter design\n",
+        );
+        let mut index = SearchIndex::default();
+        index.rebuild_root(tmp.path()).unwrap();
+
+        let lines = |hits: Vec<SearchHit>| hits.iter().map(|h| h.line).collect::<Vec<_>>();
+        assert_eq!(lines(index.search("Cache bug", false).unwrap()), vec![1]);
+        assert_eq!(lines(index.search("cache", false).unwrap()), vec![1, 2]);
+        assert_eq!(lines(index.search("^rate", true).unwrap()), vec![3]);
+        assert!(index.search("(", true).is_err());
+
+        let matcher = Matcher::new("cache bug", false).unwrap();
+        let files = list_files(tmp.path()).unwrap();
+        assert_eq!(lines(scan(&files, &matcher).unwrap()), vec![1]);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "a.md", "roundtrip\n");
+        let mut index = SearchIndex::default();
+        index.rebuild_root(tmp.path()).unwrap();
+
+        let path = index_path(&tmp.path().join("cfg").join("config.json"));
+        index.save(&path).unwrap();
+        let loaded = SearchIndex::load(&path).unwrap();
+        assert_eq!(loaded.file_count(), 1);
+        assert!(loaded.covers(tmp.path()));
+        assert_eq!(loaded.search("roundtrip", false).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn move_index_moves_once_and_keeps_an_existing_index() {
+        let tmp = TempDir::new().unwrap();
+        let from = tmp.path().join("config").join(INDEX_FILE_NAME);
+        let to = tmp.path().join("data").join(INDEX_FILE_NAME);
+        assert!(!move_index(&from, &to));
+
+        write(&tmp.path().join("config"), INDEX_FILE_NAME, "old");
+        assert!(!move_index(&from, &to));
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+
+        write(&tmp.path().join("config"), INDEX_FILE_NAME, "stale");
+        assert!(!move_index(&from, &to));
+        assert_eq!(fs::read_to_string(&to).unwrap(), "old");
+    }
+
+    #[test]
+    fn parse_date_accepts_absolute_and_relative_dates() {
+        let now = Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+        let midnight = Local.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        assert_eq!(parse_date("2024-03-01", now).unwrap(), midnight.timestamp());
+        assert_eq!(
+            parse_date("1week", now).unwrap(),
+            now.timestamp() - 7 * 86_400
+        );
+        assert_eq!(
+            parse_date("30days", now).unwrap(),
+            now.timestamp() - 30 * 86_400
+        );
+        assert!(parse_date("soon", now).is_err());
+        assert!(parse_date("3fortnights", now).is_err());
+    }
+
+    #[test]
+    fn date_range_filters_untracked_files_by_mtime() {
+        let tmp = TempDir::new().unwrap();
+        let file = write(tmp.path(), "a.md", "x\n");
+        let mtime = (mtime_millis(&file) / 1000) as i64;
+
+        let range = |after: Option<i64>, before: Option<i64>| DateRange { after, before };
+        let keep = |r: DateRange| r.filter_files(tmp.path(), vec![file.clone()]).unwrap();
+        assert_eq!(keep(range(Some(mtime - 60), None)), vec![file.clone()]);
+        assert!(keep(range(Some(mtime + 60), None)).is_empty());
+        assert!(keep(range(None, Some(mtime))).is_empty());
+        assert_eq!(keep(range(None, None)), vec![file.clone()]);
+    }
+}
@@ -0,0 +1,129 @@
+//! Line-level text diffing (Myers' O(ND) algorithm).
+
+/// One line of a diff between an old and a new text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Shortest edit script turning `old` into `new`, line by line.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = a.len() + b.len();
+    let offset = max as isize;
+    // `v[k + offset]` is the furthest x reached on diagonal k; `trace[d]`
+    // snapshots it before round d so the path can be walked back.
+    let mut v = vec![0isize; 2 * max + 2];
+    let mut trace = Vec::new();
+
+    'search: for d in 0..=offset {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+                v[i + 1]
+            } else {
+                v[i - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    let mut out = Vec::with_capacity(max);
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let i = (k + offset) as usize;
+        let prev_k = if k == -d || (k != d && v[i - 1] < v[i + 1]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[(prev_k + offset) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            out.push(DiffLine::Same(a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                out.push(DiffLine::Added(b[y as usize - 1]));
+                y -= 1;
+            } else {
+                out.push(DiffLine::Removed(a[x as usize - 1]));
+                x -= 1;
+            }
+        }
+    }
+    out.reverse();
+    out
+}
+
+/// Number of `(added, removed)` lines in a diff.
+pub fn change_counts(diff: &[DiffLine]) -> (usize, usize) {
+    diff.iter()
+        .fold((0, 0), |(added, removed), line| match line {
+            DiffLine::Added(_) => (added + 1, removed),
+            DiffLine::Removed(_) => (added, removed + 1),
+            DiffLine::Same(_) => (added, removed),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffLine::*;
+    use super::*;
+
+    #[test]
+    fn diff_lines_finds_minimal_edit() {
+        let diff = diff_lines("a\nb\nc\nd\n", "a\nc\nd\ne\n");
+        assert_eq!(
+            diff,
+            vec![Same("a"), Removed("b"), Same("c"), Same("d"), Added("e")]
+        );
+        assert_eq!(change_counts(&diff), (1, 1));
+    }
+
+    #[test]
+    fn diff_lines_handles_empty_sides() {
+        assert!(diff_lines("", "").is_empty());
+        assert_eq!(diff_lines("", "x\ny"), vec![Added("x"), Added("y")]);
+        assert_eq!(diff_lines("x", ""), vec![Removed("x")]);
+        assert_eq!(diff_lines("same", "same"), vec![Same("same")]);
+    }
+
+    #[test]
+    fn diff_lines_replaces_every_line() {
+        let diff = diff_lines("a\nb", "c\nd");
+        assert_eq!(change_counts(&diff), (2, 2));
+        let old: Vec<&str> = diff
+            .iter()
+            .filter_map(|l| match l {
+                Same(s) | Removed(s) => Some(*s),
+                Added(_) => None,
+            })
+            .collect();
+        let new: Vec<&str> = diff
+            .iter()
+            .filter_map(|l| match l {
+                Same(s) | Added(s) => Some(*s),
+                Removed(_) => None,
+            })
+            .collect();
+        assert_eq!((old, new), (vec!["a", "b"], vec!["c", "d"]));
+    }
+}
@@ -1,17 +1,35 @@
 //! Version checking and update notification.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::env;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::agents;
 use crate::config;
+use crate::http;
 
-/// Throttle interval shared between the GitHub release check and the agent
-/// auto-reinstall check.
+/// Default throttle interval shared between the GitHub release check and
+/// the agent auto-reinstall check.
 const CHECK_INTERVAL_SECS: i64 = 24 * 60 * 60;
 
+/// Overrides `updateCheckIntervalHours` for one invocation.
+pub const CHECK_INTERVAL_ENV: &str = "HYPRLAYER_UPDATE_CHECK_INTERVAL_HOURS";
+
+/// Intervals above this (30 days) get a warning when set.
+const MAX_RECOMMENDED_INTERVAL_HOURS: u64 = 720;
+
+const RELEASES_API: &str = "https://api.github.com/repos/BrightBlock/hyprlayer-cli/releases";
+
+/// How many lines of release notes an update notification shows.
+const CHANGELOG_SUMMARY_LINES: usize = 5;
+
+/// How long the end of `main` waits for a release check still in flight.
+const RELEASE_CHECK_GRACE: Duration = Duration::from_millis(100);
+
 fn unix_now() -> i64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -19,8 +37,30 @@ fn unix_now() -> i64 {
         .unwrap_or(0)
 }
 
-fn should_skip_due_to_throttle(last_check: i64, now: i64) -> bool {
-    now - last_check < CHECK_INTERVAL_SECS
+/// `interval_secs = 0` checks on every run.
+fn should_skip_due_to_throttle(last_check: i64, now: i64, interval_secs: i64) -> bool {
+    interval_secs > 0 && now - last_check < interval_secs
+}
+
+/// The check interval in seconds: `HYPRLAYER_UPDATE_CHECK_INTERVAL_HOURS`
+/// when it holds a number of hours, else `configured_hours`, else a day.
+fn check_interval_secs(configured_hours: Option<u64>, env_hours: Option<&str>) -> i64 {
+    env_hours
+        .and_then(|hours| hours.trim().parse().ok())
+        .or(configured_hours)
+        .map_or(CHECK_INTERVAL_SECS, |hours: u64| {
+            i64::try_from(hours.saturating_mul(60 * 60)).unwrap_or(i64::MAX)
+        })
+}
+
+/// Warning for an update check interval long enough to miss releases
+/// that matter, such as security fixes.
+pub fn interval_warning(hours: u64) -> Option<String> {
+    (hours > MAX_RECOMMENDED_INTERVAL_HOURS).then(|| {
+        format!(
+            "Checking for updates only every {hours} hours (over 30 days) might miss important security updates."
+        )
+    })
 }
 
 /// GitHub Release API response (minimal fields needed)
@@ -28,6 +68,9 @@ fn should_skip_due_to_throttle(last_check: i64, now: i64) -> bool {
 struct GitHubRelease {
     tag_name: String,
     html_url: String,
+    /// The release notes, in markdown. `null` for a release without any.
+    #[serde(default)]
+    body: Option<String>,
 }
 
 /// How hyprlayer was installed - determines upgrade instructions
@@ -89,6 +132,8 @@ impl InstallMethod {
 pub struct UpdateInfo {
     pub current: String,
     pub latest: String,
+    /// The release's git tag, e.g. `v1.6.0` for `latest` 1.6.0.
+    pub tag: String,
     #[allow(dead_code)]
     pub download_url: String,
     pub install_method: InstallMethod,
@@ -104,11 +149,7 @@ pub fn check_for_updates() -> Option<UpdateInfo> {
 fn check_for_updates_inner() -> Result<Option<UpdateInfo>> {
     let current = env!("CARGO_PKG_VERSION");
 
-    // Fetch latest release from GitHub
-    let url = "https://api.github.com/repos/BrightBlock/hyprlayer-cli/releases/latest";
-    let json = agents::curl_get_json(url, Some(5))?;
-
-    let release: GitHubRelease = serde_json::from_str(&json)?;
+    let release = fetch_latest_release()?;
 
     // Strip 'v' prefix if present (e.g., "v1.5.0" -> "1.5.0")
     let latest = release.tag_name.trim_start_matches('v');
@@ -117,6 +158,7 @@ fn check_for_updates_inner() -> Result<Option<UpdateInfo>> {
         Ok(Some(UpdateInfo {
             current: current.to_string(),
             latest: latest.to_string(),
+            tag: release.tag_name.clone(),
             download_url: release.html_url,
             install_method: InstallMethod::detect(),
         }))
@@ -125,10 +167,69 @@ fn check_for_updates_inner() -> Result<Option<UpdateInfo>> {
     }
 }
 
-/// Compare two semver version strings numerically.
-/// Returns true if `a` is newer than `b`.
-/// Pre-release suffixes (e.g., "-beta.1") are stripped before comparison.
+fn fetch_latest_release() -> Result<GitHubRelease> {
+    let json = http::get_json(&format!("{RELEASES_API}/latest"), Duration::from_secs(5))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// The release notes of the GitHub release tagged `tag` (e.g. `v1.6.0`).
+pub fn fetch_changelog(tag: &str) -> Result<String> {
+    fetch_changelog_from(RELEASES_API, tag)
+}
+
+fn fetch_changelog_from(releases_api: &str, tag: &str) -> Result<String> {
+    let json = http::get_json(
+        &format!("{releases_api}/tags/{tag}"),
+        Duration::from_secs(5),
+    )
+    .with_context(|| format!("No GitHub release for {tag}"))?;
+    let release: GitHubRelease = serde_json::from_str(&json)?;
+    Ok(release.body.unwrap_or_default())
+}
+
+/// The first few non-blank lines of `changelog`, with `…` when there is
+/// more.
+fn changelog_summary(changelog: &str) -> Vec<&str> {
+    let mut lines = changelog
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty());
+    let mut summary: Vec<&str> = lines.by_ref().take(CHANGELOG_SUMMARY_LINES).collect();
+    if lines.next().is_some() {
+        summary.push("…");
+    }
+    summary
+}
+
+/// Warning to show when agent files are pinned to a ref other than the
+/// latest release. `None` when the pin is current or GitHub is unreachable.
+pub fn pin_warning(pin_ref: &str) -> Option<String> {
+    let release = fetch_latest_release().ok()?;
+    pin_is_outdated(pin_ref, &release.tag_name).then(|| {
+        format!(
+            "Agent files are pinned to {} but the latest release is {}. \
+             Run 'hyprlayer thoughts config set agent-pin-ref null' to follow updates again.",
+            pin_ref, release.tag_name
+        )
+    })
+}
+
+/// `v1.2.3` and `1.2.3` name the same release.
+fn pin_is_outdated(pin_ref: &str, latest_tag: &str) -> bool {
+    pin_ref.trim_start_matches('v') != latest_tag.trim_start_matches('v')
+}
+
+/// Returns true if version `a` is newer than `b`, by semver precedence:
+/// `1.5.0-beta.1 < 1.5.0-beta.2 < 1.5.0`, and build metadata
+/// (`+build123`) is ignored.
+///
+/// Strings that aren't valid semver (e.g. `1.4`, `nightly-2025-07`) fall
+/// back to comparing their numeric segments, with any pre-release suffix
+/// stripped.
 fn is_newer_version(a: &str, b: &str) -> bool {
+    if let (Ok(a), Ok(b)) = (semver::Version::parse(a), semver::Version::parse(b)) {
+        return a.cmp_precedence(&b).is_gt();
+    }
     let parse = |v: &str| -> Vec<u64> {
         // Strip pre-release suffix: "1.5.0-beta.1" -> "1.5.0"
         let base = v.split('-').next().unwrap_or(v);
@@ -147,11 +248,17 @@ fn should_reinstall(installed_sha: Option<&str>, latest_sha: &str) -> bool {
 /// bundle auto-reinstall). Loads the config once and saves it at most once,
 /// only when a check actually mutated something.
 ///
+/// The release check runs on a background thread so its network round
+/// trip doesn't delay the command; the returned handle prints its
+/// notification once the command is done. `sync` waits for it here
+/// instead. The agent reinstall stays on this thread: it writes files the
+/// command may read, and must not be cut short when `main` returns.
+///
 /// `config_path = None` means "use the default path." The caller passes
 /// the parsed `--config-file` value when present so a user with a custom
 /// config (and their custom `disableUpdateCheck` setting) gets the
 /// expected startup behavior.
-pub fn run_startup_checks(config_path: Option<&std::path::Path>) {
+pub fn run_startup_checks(config_path: Option<&Path>, sync: bool) -> Option<PendingReleaseCheck> {
     let default_path;
     let config_path = match config_path {
         Some(p) => p,
@@ -160,100 +267,241 @@ pub fn run_startup_checks(config_path: Option<&std::path::Path>) {
                 default_path = p;
                 &default_path
             }
-            Err(_) => return,
+            Err(_) => return None,
         },
     };
     let Ok(mut cfg) = config::HyprlayerConfig::load(config_path) else {
-        return;
+        return None;
     };
     if cfg.disable_update_check {
-        return;
+        return None;
     }
 
     let now = unix_now();
-    let release_changed = check_release_in(&mut cfg, now);
-    let agents_changed = reinstall_agents_in(&mut cfg, now);
-
-    if release_changed || agents_changed {
-        let _ = cfg.save(config_path);
+    let interval = check_interval_secs(
+        cfg.update_check_interval_hours,
+        env::var(CHECK_INTERVAL_ENV).ok().as_deref(),
+    );
+    let release_check = start_release_check(&mut cfg, now, interval, sync);
+    let agents_changed = reinstall_agents_in(&mut cfg, now, interval);
+
+    if release_check.is_some() || agents_changed {
+        // Only the check bookkeeping is written back, onto the config as it
+        // is now: a command may have saved it while the agents reinstalled.
+        let _ = config::HyprlayerConfig::update(config_path, |current| {
+            current.last_version_check = cfg.last_version_check;
+            current.last_agent_check = cfg.last_agent_check;
+            current.agents_installed_sha = cfg.agents_installed_sha.clone();
+            Ok(())
+        });
     }
+    release_check.map(|result| PendingReleaseCheck {
+        config_path: config_path.to_path_buf(),
+        result,
+    })
 }
 
-fn check_release_in(cfg: &mut config::HyprlayerConfig, now: i64) -> bool {
-    if should_skip_due_to_throttle(cfg.last_version_check.unwrap_or(0), now) {
-        return false;
+/// A release found newer than this binary, with its notes when they're
+/// due to be shown.
+struct ReleaseCheck {
+    info: UpdateInfo,
+    changelog: Option<String>,
+}
+
+/// The release check `run_startup_checks` started, to be reported with
+/// `finish` after the command has run.
+pub struct PendingReleaseCheck {
+    config_path: PathBuf,
+    result: mpsc::Receiver<Option<ReleaseCheck>>,
+}
+
+impl PendingReleaseCheck {
+    /// Print the update notification if the check is done, waiting at most
+    /// `RELEASE_CHECK_GRACE` for it. A check still running is dropped: the
+    /// notification is cosmetic and comes back after the next interval.
+    pub fn finish(self) {
+        let Ok(Some(check)) = self.result.recv_timeout(RELEASE_CHECK_GRACE) else {
+            return;
+        };
+        print_update_notification(&check.info, check.changelog.as_deref());
+        if check.changelog.is_some() {
+            // Reloaded, since the command may have saved the config since
+            // startup.
+            let _ = config::HyprlayerConfig::update(&self.config_path, |cfg| {
+                cfg.last_changelog_shown = Some(check.info.tag);
+                Ok(())
+            });
+        }
     }
-    if let Some(update_info) = check_for_updates() {
-        print_update_notification(&update_info);
+}
+
+/// Start the release check unless it ran within `interval`. The check is
+/// recorded as done up front, so one that doesn't finish in time isn't
+/// retried on every run until the next interval.
+fn start_release_check(
+    cfg: &mut config::HyprlayerConfig,
+    now: i64,
+    interval: i64,
+    sync: bool,
+) -> Option<mpsc::Receiver<Option<ReleaseCheck>>> {
+    if should_skip_due_to_throttle(cfg.last_version_check.unwrap_or(0), now, interval) {
+        return None;
     }
     cfg.last_version_check = Some(now);
-    true
+
+    let last_changelog_shown = cfg.last_changelog_shown.clone();
+    let (tx, rx) = mpsc::channel();
+    let check = move || {
+        let _ = tx.send(check_release(last_changelog_shown.as_deref()));
+    };
+    if sync {
+        check();
+    } else {
+        thread::spawn(check);
+    }
+    Some(rx)
+}
+
+fn check_release(last_changelog_shown: Option<&str>) -> Option<ReleaseCheck> {
+    let info = check_for_updates()?;
+    // Release notes are shown once per release, not on every daily
+    // reminder.
+    let changelog = (last_changelog_shown != Some(&info.tag))
+        .then(|| fetch_changelog(&info.tag).ok())
+        .flatten();
+    Some(ReleaseCheck { info, changelog })
 }
 
-fn reinstall_agents_in(cfg: &mut config::HyprlayerConfig, now: i64) -> bool {
+fn reinstall_agents_in(cfg: &mut config::HyprlayerConfig, now: i64, interval: i64) -> bool {
     // Auto-reinstall only refreshes an existing install — it never bootstraps
     // a new one for a user who has not run `hyprlayer ai configure`.
     let Some(ai) = cfg.ai.as_ref() else {
         return false;
     };
-    let Some(tool) = ai.agent_tool else {
-        return false;
-    };
     // `has_existing_install` (looser than `is_installed`) is correct here:
     // the strict sentinel check rejects exactly the stale installs that
     // most need refreshing.
-    if !tool.has_existing_install() {
+    let tools: Vec<agents::AgentTool> = ai
+        .agent_tools
+        .iter()
+        .copied()
+        .filter(|tool| tool.has_existing_install())
+        .collect();
+    if tools.is_empty() {
         return false;
     }
-    let opencode_provider = ai.opencode_provider.clone();
+    let placeholders = match ai.opencode_provider.as_ref() {
+        Some(p) => agents::build_placeholder_set(cfg.thoughts.as_ref(), p).with_models(
+            ai.opencode_sonnet_model.as_deref(),
+            ai.opencode_opus_model.as_deref(),
+        ),
+        None => agents::thoughts_placeholder_set(cfg.thoughts.as_ref()),
+    };
 
-    if should_skip_due_to_throttle(cfg.last_agent_check.unwrap_or(0), now) {
+    if should_skip_due_to_throttle(cfg.last_agent_check.unwrap_or(0), now, interval) {
         return false;
     }
     cfg.last_agent_check = Some(now);
 
-    let Ok(latest_sha) = agents::fetch_repo_dir_sha(tool.repo_dir()) else {
+    // A pinned install only changes when the pin itself does; the pinned
+    // ref is what `install` records as the installed SHA. Unpinned, the
+    // target moves with the release tag, so upgrading the CLI reinstalls.
+    let pin_ref = ai.agent_pin_ref.clone();
+    let target = agents::combined_sha(
+        tools
+            .iter()
+            .map(|tool| tool.resolve_ref(pin_ref.as_deref()).sha),
+    );
+    let Some(target) = target else {
         return true;
     };
-    if !should_reinstall(cfg.agents_installed_sha.as_deref(), &latest_sha) {
+    if !should_reinstall(cfg.agents_installed_sha.as_deref(), &target) {
+        // A pinned install is never refreshed here, so say when it's
+        // behind instead.
+        if let Some(pin) = pin_ref.as_deref()
+            && tools
+                .iter()
+                .any(|tool| tool.has_updates_since(pin) == Some(true))
+        {
+            print_agent_update_nudge(pin);
+        }
         return true;
     }
 
-    eprintln!("Updating agent files for {}…", tool);
-    match tool.install(opencode_provider.as_ref(), true) {
-        Ok(sha) => {
-            if sha.is_some() {
-                cfg.agents_installed_sha = sha;
+    let mut shas = Vec::new();
+    for tool in &tools {
+        eprintln!("Updating agent files for {}…", tool);
+        match tool.install(
+            &agents::AgentSource::GitHub,
+            Some(&placeholders),
+            pin_ref.as_deref(),
+            agents::InstructionsTarget::Existing,
+            agents::InstallOptions {
+                quiet: true,
+                backup: true,
+            },
+        ) {
+            Ok(sha) => shas.push(sha),
+            Err(e) => {
+                eprintln!(
+                    "Failed to update agent files: {}. Run 'hyprlayer ai reinstall' to retry.",
+                    e
+                );
+                shas.push(None);
             }
         }
-        Err(e) => eprintln!(
-            "Failed to update agent files: {}. Run 'hyprlayer ai reinstall' to retry.",
-            e
-        ),
+    }
+    if let Some(sha) = agents::combined_sha(shas) {
+        cfg.agents_installed_sha = Some(sha);
     }
     true
 }
 
-/// Print update notification with install-method-specific hint.
+/// One line saying agent files pinned to `pin` are behind `master`.
+fn print_agent_update_nudge(pin: &str) {
+    use colored::Colorize;
+    eprintln!(
+        "{} Run 'hyprlayer thoughts config set agent-pin-ref null', then 'hyprlayer ai update'.",
+        format!("Agent files have updates since {pin}.").yellow()
+    );
+}
+
+/// Print update notification with install-method-specific hint, followed
+/// by the start of the release's `changelog` when given.
 ///
 /// Writes to stderr so it never pollutes stdout-piped output (e.g.
 /// `codex exec ... --json | hyprlayer codex stream`).
-fn print_update_notification(info: &UpdateInfo) {
+fn print_update_notification(info: &UpdateInfo, changelog: Option<&str>) {
     use colored::Colorize;
 
     let hint = info.install_method.upgrade_hint();
     eprintln!(
-        "\n{} {} → {} ({})\n",
+        "\n{} {} → {} ({})",
         "Update available:".yellow(),
         info.current,
         info.latest.green(),
         hint
     );
+    if let Some(changelog) = changelog {
+        for line in changelog_summary(changelog) {
+            eprintln!("  {}", line.dimmed());
+        }
+    }
+    eprintln!();
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::http::test_server::{json_response, serve};
+
+    #[test]
+    fn pin_is_outdated_ignores_v_prefix() {
+        assert!(!pin_is_outdated("v1.5.3", "v1.5.3"));
+        assert!(!pin_is_outdated("1.5.3", "v1.5.3"));
+        assert!(pin_is_outdated("v1.4.0", "v1.5.3"));
+        assert!(pin_is_outdated("my-branch", "v1.5.3"));
+    }
 
     #[test]
     fn version_comparison_works() {
@@ -267,12 +515,24 @@ mod tests {
 
     #[test]
     fn version_comparison_prerelease() {
-        // Pre-release of same version is not newer
+        // Pre-release of same version is older than the release
         assert!(!is_newer_version("1.5.0-beta.1", "1.5.0"));
+        assert!(is_newer_version("1.5.0", "1.5.0-beta.1"));
         // Pre-release of newer version is still newer
         assert!(is_newer_version("1.6.0-rc.1", "1.5.0"));
-        // Two pre-releases of same version are equal (suffix stripped)
+        // Pre-releases of the same version are ordered
+        assert!(is_newer_version("1.5.0-beta.2", "1.5.0-beta.1"));
         assert!(!is_newer_version("1.5.0-beta.1", "1.5.0-beta.2"));
+        assert!(is_newer_version("1.5.0-rc.1", "1.5.0-beta.11"));
+        assert!(is_newer_version("1.5.0-beta.11", "1.5.0-beta.2"));
+        assert!(is_newer_version("1.5.0-alpha.beta", "1.5.0-alpha.1"));
+    }
+
+    #[test]
+    fn version_comparison_ignores_build_metadata() {
+        assert!(!is_newer_version("1.5.0+build123", "1.5.0"));
+        assert!(!is_newer_version("1.5.0", "1.5.0+build123"));
+        assert!(is_newer_version("1.5.1+build1", "1.5.0+build2"));
     }
 
     #[test]
@@ -290,6 +550,11 @@ mod tests {
         assert!(!is_newer_version("", ""));
         assert!(is_newer_version("1.0.0", ""));
         assert!(!is_newer_version("nightly", "1.0.0"));
+        // Non-semver tags fall back to their numeric segments
+        assert!(!is_newer_version("nightly-2025-07", "1.0.0"));
+        assert!(is_newer_version("1.0.0", "nightly-2025-07"));
+        assert!(is_newer_version("1.5.0-beta.1", "1.4"));
+        assert!(!is_newer_version("1.4.0.1-beta", "1.4.0.1"));
     }
 
     #[test]
@@ -315,22 +580,53 @@ mod tests {
     #[test]
     fn throttle_math() {
         let now: i64 = 2_000_000_000;
-        assert!(should_skip_due_to_throttle(now, now));
-        assert!(should_skip_due_to_throttle(now - 1, now));
-        assert!(should_skip_due_to_throttle(
-            now - (CHECK_INTERVAL_SECS - 1),
-            now
-        ));
-        assert!(!should_skip_due_to_throttle(now - CHECK_INTERVAL_SECS, now));
-        assert!(!should_skip_due_to_throttle(
-            now - (CHECK_INTERVAL_SECS + 1),
-            now
-        ));
-        assert!(!should_skip_due_to_throttle(0, now));
+        let day = CHECK_INTERVAL_SECS;
+        assert!(should_skip_due_to_throttle(now, now, day));
+        assert!(should_skip_due_to_throttle(now - 1, now, day));
+        assert!(should_skip_due_to_throttle(now - (day - 1), now, day));
+        assert!(!should_skip_due_to_throttle(now - day, now, day));
+        assert!(!should_skip_due_to_throttle(now - (day + 1), now, day));
+        assert!(!should_skip_due_to_throttle(0, now, day));
         // Clock skew: last_check in the future → still skip (negative
         // delta is < interval). Conservative: avoids hammering on a
         // misconfigured clock that's about to be fixed.
-        assert!(should_skip_due_to_throttle(now + 5, now));
+        assert!(should_skip_due_to_throttle(now + 5, now, day));
+        // 0 always checks, clock skew or not.
+        assert!(!should_skip_due_to_throttle(now, now, 0));
+        assert!(!should_skip_due_to_throttle(now + 5, now, 0));
+    }
+
+    #[test]
+    fn check_interval_prefers_env_then_config_then_a_day() {
+        assert_eq!(check_interval_secs(None, None), CHECK_INTERVAL_SECS);
+        assert_eq!(check_interval_secs(Some(6), None), 6 * 3600);
+        assert_eq!(check_interval_secs(Some(6), Some("1")), 3600);
+        assert_eq!(check_interval_secs(Some(6), Some("0")), 0);
+        // An unparseable override is ignored.
+        assert_eq!(check_interval_secs(Some(6), Some("soon")), 6 * 3600);
+        assert_eq!(check_interval_secs(Some(u64::MAX), None), i64::MAX);
+    }
+
+    #[test]
+    fn configured_interval_throttles_release_check() {
+        let now: i64 = 2_000_000_000;
+        let mut cfg = config::HyprlayerConfig {
+            last_version_check: Some(now - 2 * 3600),
+            update_check_interval_hours: Some(3),
+            ..Default::default()
+        };
+        let interval = check_interval_secs(cfg.update_check_interval_hours, None);
+        // Checked two hours ago with a three hour interval: skipped
+        // without touching the network or the timestamp.
+        assert!(start_release_check(&mut cfg, now, interval, true).is_none());
+        assert_eq!(cfg.last_version_check, Some(now - 2 * 3600));
+    }
+
+    #[test]
+    fn interval_warning_above_thirty_days() {
+        assert!(interval_warning(720).is_none());
+        assert!(interval_warning(0).is_none());
+        assert!(interval_warning(721).unwrap().contains("security"));
     }
 
     #[test]
@@ -356,4 +652,88 @@ mod tests {
             "Download the latest release from GitHub"
         );
     }
+
+    #[test]
+    fn fetch_changelog_reads_the_release_body() {
+        let (base, server) = serve(vec![
+            json_response(
+                "200 OK",
+                r###"{"tag_name":"v1.6.0","html_url":"https://example.com","body":"## Added\n- thoughts export"}"###,
+            ),
+            json_response(
+                "200 OK",
+                r#"{"tag_name":"v1.6.1","html_url":"https://example.com","body":null}"#,
+            ),
+            json_response("404 Not Found", r#"{"message":"Not Found"}"#),
+        ]);
+        let releases = format!("{base}/releases");
+
+        assert_eq!(
+            fetch_changelog_from(&releases, "v1.6.0").unwrap(),
+            "## Added\n- thoughts export"
+        );
+        assert_eq!(fetch_changelog_from(&releases, "v1.6.1").unwrap(), "");
+        let err = fetch_changelog_from(&releases, "v9.9.9").unwrap_err();
+        assert!(err.to_string().contains("v9.9.9"));
+
+        let paths: Vec<String> = server.join().unwrap().into_iter().map(|r| r.path).collect();
+        assert_eq!(
+            paths,
+            [
+                "/releases/tags/v1.6.0",
+                "/releases/tags/v1.6.1",
+                "/releases/tags/v9.9.9"
+            ]
+        );
+    }
+
+    #[test]
+    fn changelog_summary_keeps_the_first_lines() {
+        assert_eq!(
+            changelog_summary("## Added\n\n- a\n- b  \n\n## Fixed\n- c\n- d"),
+            ["## Added", "- a", "- b", "## Fixed", "- c", "…"]
+        );
+        assert_eq!(changelog_summary("- only\n"), ["- only"]);
+        assert!(changelog_summary("").is_empty());
+    }
+
+    #[test]
+    fn finish_gives_up_on_a_slow_check_and_records_shown_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.json");
+        config::HyprlayerConfig::default()
+            .save(&config_path)
+            .unwrap();
+
+        // Still running: `finish` returns after the grace period.
+        let (tx, rx) = mpsc::channel();
+        let started = std::time::Instant::now();
+        PendingReleaseCheck {
+            config_path: config_path.clone(),
+            result: rx,
+        }
+        .finish();
+        assert!(started.elapsed() < Duration::from_secs(2));
+        drop(tx);
+
+        let (tx, rx) = mpsc::channel();
+        tx.send(Some(ReleaseCheck {
+            info: UpdateInfo {
+                current: "1.0.0".to_string(),
+                latest: "1.1.0".to_string(),
+                tag: "v1.1.0".to_string(),
+                download_url: String::new(),
+                install_method: InstallMethod::Unknown,
+            },
+            changelog: Some("- new".to_string()),
+        }))
+        .unwrap();
+        PendingReleaseCheck {
+            config_path: config_path.clone(),
+            result: rx,
+        }
+        .finish();
+        let cfg = config::HyprlayerConfig::load(&config_path).unwrap();
+        assert_eq!(cfg.last_changelog_shown.as_deref(), Some("v1.1.0"));
+    }
 }
@@ -0,0 +1,217 @@
+//! End-to-end runs of `hyprlayer thoughts` against real git repositories in
+//! a temp directory. Unix only: `init` links `thoughts/` with symlinks,
+//! which Windows only allows with developer mode or elevation.
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+/// A code repository, thoughts repository path and config file under one
+/// temp directory, which is also `HOME` so nothing outside it is touched.
+struct Sandbox {
+    root: TempDir,
+}
+
+impl Sandbox {
+    fn new() -> Self {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join(".gitconfig"),
+            "[user]\n\tname = Test\n\temail = test@example.com\n",
+        )
+        .unwrap();
+        fs::write(
+            root.path().join("config.json"),
+            r#"{"version": 3, "disableUpdateCheck": true, "ai": {"agentTools": ["claude"]}}"#,
+        )
+        .unwrap();
+        let sandbox = Self { root };
+        fs::create_dir(sandbox.code_repo()).unwrap();
+        sandbox.git(&sandbox.code_repo(), &["init", "--quiet"]);
+        sandbox.git(
+            &sandbox.code_repo(),
+            &["commit", "--quiet", "--allow-empty", "-m", "Initial commit"],
+        );
+        sandbox
+    }
+
+    fn code_repo(&self) -> PathBuf {
+        self.root.path().join("code")
+    }
+
+    fn thoughts_repo(&self) -> PathBuf {
+        self.root.path().join("thoughts")
+    }
+
+    fn config_path(&self) -> PathBuf {
+        self.root.path().join("config.json")
+    }
+
+    fn config(&self) -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(self.config_path()).unwrap()).unwrap()
+    }
+
+    fn isolate<'a>(&self, command: &'a mut Command) -> &'a mut Command {
+        command
+            .env("HOME", self.root.path())
+            .env("XDG_CONFIG_HOME", self.root.path().join(".config"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env_remove("GIT_DIR")
+            .env_remove("HYPRLAYER_USER")
+            .env_remove("RUST_BACKTRACE")
+            .stdin(std::process::Stdio::null())
+    }
+
+    fn git(&self, dir: &Path, args: &[&str]) -> String {
+        let output = self
+            .isolate(&mut Command::new("git"))
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert_success(&output, &format!("git {}", args.join(" ")));
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    /// Runs `hyprlayer thoughts <args>` in the code repository.
+    fn thoughts(&self, args: &[&str]) -> Output {
+        self.isolate(&mut Command::new(env!("CARGO_BIN_EXE_hyprlayer")))
+            .arg("thoughts")
+            .args(args)
+            .arg("--config-file")
+            .arg(self.config_path())
+            .current_dir(self.code_repo())
+            .output()
+            .unwrap()
+    }
+}
+
+fn assert_success(output: &Output, what: &str) {
+    assert!(
+        output.status.success(),
+        "{what} failed\nstdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn init_sync_uninit_round_trip() {
+    let sandbox = Sandbox::new();
+    let thoughts_repo = sandbox.thoughts_repo().display().to_string();
+
+    let output = sandbox.thoughts(&[
+        "init",
+        "--yes",
+        "--user",
+        "alice",
+        "--thoughts-repo",
+        &thoughts_repo,
+        "--directory",
+        "app",
+    ]);
+    assert_success(&output, "thoughts init");
+    let thoughts_dir = sandbox.code_repo().join("thoughts");
+    for (link, target) in [
+        ("alice", "repos/app/alice"),
+        ("shared", "repos/app/shared"),
+        ("global", "global"),
+    ] {
+        let link = thoughts_dir.join(link);
+        assert!(link.is_symlink(), "{} is not a symlink", link.display());
+        assert_eq!(
+            fs::canonicalize(&link).unwrap(),
+            fs::canonicalize(sandbox.thoughts_repo().join(target)).unwrap()
+        );
+    }
+
+    fs::write(thoughts_dir.join("alice/note.md"), "# Note\n").unwrap();
+    assert!(
+        sandbox
+            .thoughts_repo()
+            .join("repos/app/alice/note.md")
+            .is_file()
+    );
+
+    let output = sandbox.thoughts(&["sync", "--message", "Add a note", "--no-interactive"]);
+    assert_success(&output, "thoughts sync");
+    let log = sandbox.git(
+        &sandbox.thoughts_repo(),
+        &["log", "-1", "--format=%s", "--name-only"],
+    );
+    assert_eq!(
+        log.lines().filter(|l| !l.is_empty()).collect::<Vec<_>>(),
+        ["Add a note", "repos/app/alice/note.md"]
+    );
+
+    let output = sandbox.thoughts(&["uninit", "--remove-hooks"]);
+    assert_success(&output, "thoughts uninit");
+    assert!(!thoughts_dir.exists());
+    assert!(
+        sandbox
+            .thoughts_repo()
+            .join("repos/app/alice/note.md")
+            .is_file(),
+        "uninit removed the thoughts themselves"
+    );
+    let mappings = &sandbox.config()["thoughts"]["repoMappings"];
+    assert_eq!(*mappings, serde_json::json!({}));
+
+    let output = sandbox.thoughts(&["status"]);
+    assert_success(&output, "thoughts status");
+    assert!(
+        String::from_utf8_lossy(&output.stdout)
+            .contains("Current repository not mapped to thoughts")
+    );
+}
+
+#[test]
+fn sync_before_init_says_to_run_init() {
+    let sandbox = Sandbox::new();
+    let output = sandbox.thoughts(&["sync", "--no-interactive"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("hyprlayer thoughts init"));
+}
+
+#[test]
+fn sync_thoughts_repo_commits_without_a_code_repository() {
+    let sandbox = Sandbox::new();
+    let thoughts = sandbox.thoughts_repo();
+    fs::create_dir(&thoughts).unwrap();
+    sandbox.git(&thoughts, &["init", "--quiet"]);
+    fs::write(thoughts.join("notes.md"), "# Notes\n").unwrap();
+
+    let output = sandbox
+        .isolate(&mut Command::new(env!("CARGO_BIN_EXE_hyprlayer")))
+        .args([
+            "thoughts",
+            "sync",
+            "--no-interactive",
+            "--message",
+            "Backup",
+        ])
+        .arg("--thoughts-repo")
+        .arg(&thoughts)
+        .arg("--config-file")
+        .arg(sandbox.config_path())
+        .current_dir(sandbox.root.path())
+        .output()
+        .unwrap();
+    assert_success(&output, "thoughts sync --thoughts-repo");
+    let log = sandbox.git(&thoughts, &["log", "--format=%s"]);
+    assert_eq!(log.trim(), "Backup");
+    assert!(!sandbox.code_repo().join("thoughts").exists());
+}
+
+#[test]
+fn sync_thoughts_repo_rejects_a_directory_that_is_not_a_repository() {
+    let sandbox = Sandbox::new();
+    fs::create_dir(sandbox.thoughts_repo()).unwrap();
+    let thoughts = sandbox.thoughts_repo().display().to_string();
+    let output = sandbox.thoughts(&["sync", "--no-interactive", "--thoughts-repo", &thoughts]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not a git repository"));
+}